@@ -1,14 +1,20 @@
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::io::{BufRead, Write};
+use std::io::{BufRead, Read, Write};
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
+use std::process::{Command, Output, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, bail, Context, Result};
 use directories::BaseDirs;
 use serde::de::Deserializer;
 use serde::Deserialize;
 
+use crate::indexer::NamelessDisplay;
+use crate::vcard_io::{ClearedField, EmailCase, PhoneOptions};
+use crate::vdir::IgnoreList;
+
 const CONFIG_FILE_NAME: &str = "config.toml";
 const APP_NAME: &str = "rldx";
 
@@ -16,12 +22,50 @@ const APP_NAME: &str = "rldx";
 pub struct Config {
     pub config_path: PathBuf,
     pub vdir: PathBuf,
+    /// Files and directories under `vdir` left out of indexing and sync
+    pub vdir_ignore: IgnoreList,
     pub db_path: PathBuf,
     pub fields_first_pane: Vec<String>,
     pub phone_region: Option<String>,
+    pub phone_normalize: bool,
+    pub review_duplicates_on_startup: bool,
+    /// Minimum Jaro-Winkler name similarity for the startup duplicate review
+    pub duplicate_similarity: f64,
+    pub nameless_display: NamelessDisplay,
+    /// Write the FN built from N back into cards that lack one on reindex
+    pub fill_missing_fn: bool,
+    /// Tag values added by imports and sync with `X-RLDX-SOURCE`
+    pub track_field_sources: bool,
+    /// Address book for new contacts written without an explicit book
+    pub default_book: Option<String>,
+    /// Confirm on a search with no matches creates a contact named after it
+    pub create_on_no_match: bool,
+    /// Photos larger than this (encoded JPEG bytes) are kept in a file under
+    /// the vdir instead of being embedded in the card
+    pub max_embedded_photo_bytes: Option<usize>,
+    /// Copy a contact file to `<vdir>/.backups` before it is overwritten or deleted
+    pub edit_backup: bool,
+    /// How many backups `edit_backup` keeps per contact
+    pub edit_backup_keep: usize,
+    /// Letter case policy for cleaning up EMAIL values
+    pub email_case: EmailCase,
+    /// What confirm does on an email, phone or address with a single value
+    pub single_value_enter: SingleValueEnter,
+    /// Order of the entries in the email, phone, address and alias lists
+    pub multivalue_sort: MultivalueSort,
+    /// What the TUI shows when it starts
+    pub startup: Startup,
+    /// What reindexing does with a contact file that fails to decrypt
+    pub on_decrypt_error: OnDecryptError,
+    /// Fields whose absence marks a contact in the search list
+    pub flag_incomplete: Vec<ExpectedField>,
+    /// Fields left blank on a contact copied with the duplicate key
+    pub duplicate_clear: Vec<ClearedField>,
     pub keys: Keys,
     pub ui: UiConfig,
     pub commands: Commands,
+    pub copy_transform: CopyTransformConfig,
+    pub impp_templates: ImppTemplates,
     pub top_bar: TopBarConfig,
     pub maildir_import: MaildirImportConfig,
     pub encryption: EncryptionConfig,
@@ -30,6 +74,129 @@ pub struct Config {
     pub remotes: Vec<RemoteConfig>,
 }
 
+impl Config {
+    /// Phone handling options derived from `phone_region` and `phone_normalize`.
+    pub fn phone_options(&self) -> PhoneOptions<'_> {
+        PhoneOptions::new(self.phone_region.as_deref(), self.phone_normalize)
+    }
+
+    /// Directory new contacts are written to: `book` under the vdir, else
+    /// `default_book`, else the vdir root. Callers create it on demand.
+    pub fn book_dir(&self, book: Option<&str>) -> PathBuf {
+        match book.or(self.default_book.as_deref()) {
+            Some(name) => self.vdir.join(name),
+            None => self.vdir.clone(),
+        }
+    }
+}
+
+/// Action for the confirm key on a field that has only one value, where
+/// there is no list to open
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SingleValueEnter {
+    /// Copy the value, like the copy key
+    Copy,
+    /// Start editing the value, like the edit key
+    Edit,
+    /// Do nothing
+    #[default]
+    Nothing,
+}
+
+impl SingleValueEnter {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "copy" => Some(SingleValueEnter::Copy),
+            "edit" => Some(SingleValueEnter::Edit),
+            "nothing" | "none" => Some(SingleValueEnter::Nothing),
+            _ => None,
+        }
+    }
+}
+
+/// Order of the entries in the multi-value list modal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MultivalueSort {
+    /// Card order
+    #[default]
+    Seq,
+    /// Entries with the same TYPE together, groups in order of first appearance
+    Type,
+    /// The PREF-marked entry first, the rest in card order
+    DefaultFirst,
+}
+
+impl MultivalueSort {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "seq" => Some(MultivalueSort::Seq),
+            "type" => Some(MultivalueSort::Type),
+            "default_first" => Some(MultivalueSort::DefaultFirst),
+            _ => None,
+        }
+    }
+}
+
+/// What the TUI shows when it starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Startup {
+    /// The search pane, ready for a query
+    #[default]
+    Search,
+    /// The card of the contact selected when rldx last quit
+    Last,
+    /// The card of the first contact in the list
+    First,
+}
+
+impl Startup {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "search" => Some(Startup::Search),
+            "last" => Some(Startup::Last),
+            "first" => Some(Startup::First),
+            _ => None,
+        }
+    }
+}
+
+/// What reindexing does with a contact file that fails to decrypt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OnDecryptError {
+    /// Stop the reindex with the error
+    #[default]
+    Abort,
+    /// Leave the file out of the index, report it and carry on
+    Skip,
+}
+
+impl OnDecryptError {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "abort" => Some(OnDecryptError::Abort),
+            "skip" => Some(OnDecryptError::Skip),
+            _ => None,
+        }
+    }
+}
+
+/// A field `flag_incomplete` expects every contact to have
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpectedField {
+    Email,
+    Phone,
+}
+
+impl ExpectedField {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "email" => Some(ExpectedField::Email),
+            "phone" | "tel" => Some(ExpectedField::Phone),
+            _ => None,
+        }
+    }
+}
+
 // =============================================================================
 // Encryption Configuration
 // =============================================================================
@@ -41,6 +208,19 @@ pub enum EncryptionType {
     Gpg,
     /// Age encryption (modern, simpler alternative to GPG)
     Age,
+    /// No encryption: cleartext files and an unencrypted index
+    None,
+}
+
+impl EncryptionType {
+    /// Value of `encryption.type` selecting this backend
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EncryptionType::Gpg => "gpg",
+            EncryptionType::Age => "age",
+            EncryptionType::None => "none",
+        }
+    }
 }
 
 
@@ -52,8 +232,10 @@ pub struct EncryptionConfig {
     pub encryption_type: EncryptionType,
     /// GPG key ID for encryption (required if type = "gpg")
     pub gpg_key_id: Option<String>,
-    /// Path to age identity file (required if type = "age")
+    /// Path to age identity file (required if type = "age" and no command is set)
     pub age_identity: Option<PathBuf>,
+    /// Command that prints the age identity (e.g., "pass show age-key")
+    pub age_identity_cmd: Option<String>,
     /// Age recipient public key (required if type = "age")
     pub age_recipient: Option<String>,
 }
@@ -69,13 +251,16 @@ impl EncryptionConfig {
                 Ok(())
             }
             EncryptionType::Age => {
-                if self.age_identity.is_none() {
-                    bail!("encryption.age_identity is required when encryption.type = \"age\"");
+                if self.age_identity.is_none() && self.age_identity_cmd.is_none() {
+                    bail!("encryption.age_identity or encryption.age_identity_cmd is required when encryption.type = \"age\"");
                 }
                 if self.age_recipient.is_none() {
                     bail!("encryption.age_recipient is required when encryption.type = \"age\"");
                 }
-                // Validate identity file exists
+                // Validate identity file exists (the command takes precedence)
+                if self.age_identity_cmd.is_some() {
+                    return Ok(());
+                }
                 if let Some(ref path) = self.age_identity {
                     let expanded = expand_tilde(path);
                     if !expanded.exists() {
@@ -87,6 +272,7 @@ impl EncryptionConfig {
                 }
                 Ok(())
             }
+            EncryptionType::None => Ok(()),
         }
     }
 }
@@ -101,6 +287,101 @@ pub fn expand_tilde(path: &Path) -> PathBuf {
     path.to_path_buf()
 }
 
+/// Expand ~ and read a relative path as relative to `config_dir`, so a
+/// config kept next to its vdir and identity can be moved as one folder
+fn resolve_config_path(config_dir: &Path, path: &Path) -> PathBuf {
+    let expanded = expand_tilde(path);
+    if expanded.is_relative() {
+        config_dir.join(expanded)
+    } else {
+        expanded
+    }
+}
+
+// =============================================================================
+// Copy Transforms
+// =============================================================================
+
+/// How a value is rewritten before it is handed to `commands.copy`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CopyTransform {
+    /// Copy the value as displayed
+    #[default]
+    Display,
+    /// Phone: digits only, keeping a leading `+`
+    Digits,
+    /// Phone: E.164 when the number parses
+    E164,
+    /// Email: lowercased
+    Lowercase,
+}
+
+impl CopyTransform {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "display" => Some(CopyTransform::Display),
+            "digits" => Some(CopyTransform::Digits),
+            "e164" => Some(CopyTransform::E164),
+            "lowercase" => Some(CopyTransform::Lowercase),
+            _ => None,
+        }
+    }
+}
+
+/// Per-field copy transforms (`[copy_transform]`)
+#[derive(Debug, Clone, Default)]
+pub struct CopyTransformConfig {
+    /// Applied to TEL values
+    pub phone: CopyTransform,
+    /// Applied to EMAIL values
+    pub email: CopyTransform,
+}
+
+/// Display URLs for IMPP values by service (`[impp_templates]`), e.g.
+/// `telegram = "https://t.me/{handle}"`
+#[derive(Debug, Clone, Default)]
+pub struct ImppTemplates {
+    /// Template by lowercase service name
+    templates: HashMap<String, String>,
+}
+
+impl ImppTemplates {
+    /// The service name and filled-in URL for an IMPP value. The service is
+    /// `service_type` (Apple's X-SERVICE-TYPE) when given, else the URI
+    /// scheme; None when there is no template for it.
+    pub fn link(&self, value: &str, service_type: Option<&str>) -> Option<(String, String)> {
+        let value = value.trim();
+        let (scheme, handle) = match value.split_once(':') {
+            Some((scheme, rest)) => (Some(scheme), rest),
+            None => (None, value),
+        };
+        let service = service_type.or(scheme)?;
+        let handle = handle.trim_start_matches('/').trim_start_matches('@');
+        if handle.is_empty() {
+            return None;
+        }
+        let template = self.templates.get(&service.to_ascii_lowercase())?;
+        Some((service.to_string(), template.replace("{handle}", handle)))
+    }
+}
+
+impl From<HashMap<String, String>> for ImppTemplates {
+    fn from(file: HashMap<String, String>) -> Self {
+        let mut templates = HashMap::new();
+        for (service, template) in file {
+            if !template.contains("{handle}") {
+                eprintln!(
+                    "warning: impp_templates.{} has no {{handle}} placeholder, ignoring it",
+                    service
+                );
+                continue;
+            }
+            templates.insert(service.to_ascii_lowercase(), template);
+        }
+        Self { templates }
+    }
+}
+
 // =============================================================================
 // Sync Configuration
 // =============================================================================
@@ -129,6 +410,8 @@ impl Default for SyncConfig {
 pub struct DetailsSectionsConfig {
     /// Ordered list of sections to display
     pub sections: Vec<SectionMapping>,
+    /// Section that also takes untyped values of its type-qualified fields
+    pub untyped_section: Option<String>,
 }
 
 /// A single section mapping
@@ -136,10 +419,58 @@ pub struct DetailsSectionsConfig {
 pub struct SectionMapping {
     /// Display name for the section (e.g., "Notes", "Contacts", "Job")
     pub name: String,
-    /// vCard field names that belong to this section (e.g., ["NOTE"], ["TEL", "EMAIL"])
+    /// vCard field names that belong to this section (e.g., ["NOTE"], ["TEL", "EMAIL"]).
+    /// A field may be narrowed to one TYPE, e.g. "EMAIL;TYPE=WORK".
     pub fields: Vec<String>,
 }
 
+/// TYPE values that a type-qualified section field treats as equivalent
+const TYPE_SYNONYMS: &[&[&str]] = &[&["WORK", "BUSINESS"], &["HOME", "PERSONAL"]];
+
+impl SectionMapping {
+    /// Whether a property with this field name and TYPE values belongs here.
+    ///
+    /// Fields match by name, by prefix (`X-*`), or by name and TYPE
+    /// (`EMAIL;TYPE=WORK`, which also accepts synonyms such as BUSINESS).
+    /// Untyped values only match a type-qualified field if `take_untyped` is set.
+    pub fn matches(&self, field: &str, types: &[String], take_untyped: bool) -> bool {
+        let field = field.to_uppercase();
+        self.fields.iter().any(|pattern| {
+            let (name, wanted) = match pattern.split_once(";TYPE=") {
+                Some((name, wanted)) => (name, Some(wanted)),
+                None => (pattern.as_str(), None),
+            };
+            let name_matches = match name.strip_suffix('*') {
+                Some(prefix) => field.starts_with(prefix),
+                None => field == name,
+            };
+            name_matches
+                && match wanted {
+                    None => true,
+                    Some(_) if types.is_empty() => take_untyped,
+                    Some(wanted) => types.iter().any(|ty| same_type(ty, wanted)),
+                }
+        })
+    }
+}
+
+/// Compare TYPE values case-insensitively, ignoring an `X-` prefix and
+/// treating [`TYPE_SYNONYMS`] as equal
+fn same_type(a: &str, b: &str) -> bool {
+    let strip = |ty: &str| {
+        let ty = ty.trim().to_uppercase();
+        match ty.strip_prefix("X-") {
+            Some(rest) => rest.to_string(),
+            None => ty,
+        }
+    };
+    let (a, b) = (strip(a), strip(b));
+    a == b
+        || TYPE_SYNONYMS
+            .iter()
+            .any(|group| group.contains(&a.as_str()) && group.contains(&b.as_str()))
+}
+
 impl Default for DetailsSectionsConfig {
     fn default() -> Self {
         Self {
@@ -180,6 +511,7 @@ impl Default for DetailsSectionsConfig {
                     ],
                 },
             ],
+            untyped_section: None,
         }
     }
 }
@@ -228,6 +560,98 @@ impl RemoteType {
     }
 }
 
+/// `cmd` run through the platform shell, with stdout and stderr captured
+fn shell_command(cmd: &str) -> Command {
+    let mut command = if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        command.args(["/C", cmd]);
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.args(["-c", cmd]);
+        command
+    };
+    command.stdout(Stdio::piped()).stderr(Stdio::piped());
+    command
+}
+
+/// Run `cmd` through the platform shell, capturing stdout and stderr
+pub fn run_shell_command(cmd: &str) -> std::io::Result<Output> {
+    shell_command(cmd).output()
+}
+
+/// Like [`run_shell_command`], but kill the command and fail once `timeout`
+/// has passed
+pub fn run_shell_command_with_timeout(cmd: &str, timeout: Duration) -> Result<Output> {
+    let mut command = shell_command(cmd);
+    // A group of its own, so a timeout also kills what the shell started
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    let mut child = command.spawn()?;
+    // Drain both pipes while waiting, or a chatty command blocks on a full pipe
+    let stdout = child.stdout.take().map(read_to_end_in_background);
+    let stderr = child.stderr.take().map(read_to_end_in_background);
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            kill_command(&mut child);
+            bail!("timed out after {}s", timeout.as_secs_f32());
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+
+    // A background process the command left behind can hold the pipes open
+    match (join_by(stdout, deadline), join_by(stderr, deadline)) {
+        (Some(stdout), Some(stderr)) => Ok(Output { status, stdout, stderr }),
+        _ => {
+            kill_command(&mut child);
+            bail!("timed out after {}s", timeout.as_secs_f32());
+        }
+    }
+}
+
+/// Kill a command started by [`run_shell_command_with_timeout`] along with
+/// the rest of its process group. Best effort: the command is abandoned
+/// either way.
+fn kill_command(child: &mut std::process::Child) {
+    #[cfg(unix)]
+    // SAFETY: kill(2) only takes plain integers
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// What a reader thread collected, or None when it is still reading at `deadline`
+fn join_by(reader: Option<thread::JoinHandle<Vec<u8>>>, deadline: Instant) -> Option<Vec<u8>> {
+    let Some(handle) = reader else {
+        return Some(Vec::new());
+    };
+    while !handle.is_finished() {
+        if Instant::now() >= deadline {
+            return None;
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    Some(handle.join().unwrap_or_default())
+}
+
+fn read_to_end_in_background(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = pipe.read_to_end(&mut buf);
+        buf
+    })
+}
+
 /// Password retrieval method
 #[derive(Debug, Clone)]
 pub enum PasswordSource {
@@ -235,8 +659,12 @@ pub enum PasswordSource {
     Plain(String),
     /// Read password from a file
     File(PathBuf),
-    /// Execute a command to get the password (e.g., "pass show x")
-    Command(String),
+    /// Execute a command to get the password (e.g., "pass show x"), giving
+    /// up after `timeout` when one is set
+    Command {
+        cmd: String,
+        timeout: Option<Duration>,
+    },
 }
 
 impl PasswordSource {
@@ -246,37 +674,38 @@ impl PasswordSource {
             PasswordSource::Plain(s) => Ok(s.clone()),
             PasswordSource::File(path) => {
                 let expanded = expand_tilde(path);
+                log::debug!("reading password from {}", expanded.display());
                 let content = fs::read_to_string(&expanded)
                     .with_context(|| format!("failed to read password file: {}", expanded.display()))?;
                 // Take first line, trim whitespace
                 Ok(content.lines().next().unwrap_or("").trim().to_string())
             }
-            PasswordSource::Command(cmd) => {
-                let output = if cfg!(target_os = "windows") {
-                    Command::new("cmd")
-                        .args(["/C", cmd])
-                        .stdout(Stdio::piped())
-                        .stderr(Stdio::piped())
-                        .output()
-                } else {
-                    Command::new("sh")
-                        .args(["-c", cmd])
-                        .stdout(Stdio::piped())
-                        .stderr(Stdio::piped())
-                        .output()
+            PasswordSource::Command { cmd, timeout } => {
+                // The command may embed a secret path; its output certainly is one
+                log::debug!("running password command");
+                let output = match timeout {
+                    Some(timeout) => run_shell_command_with_timeout(cmd, *timeout).with_context(|| {
+                        format!("password command `{}` did not finish (see password_cmd_timeout)", cmd)
+                    })?,
+                    None => run_shell_command(cmd)
+                        .with_context(|| format!("failed to execute password command: {}", cmd))?,
                 };
 
-                let output = output
-                    .with_context(|| format!("failed to execute password command: {}", cmd))?;
-
+                let stderr = String::from_utf8_lossy(&output.stderr);
                 if !output.status.success() {
-                    let stderr = String::from_utf8_lossy(&output.stderr);
-                    bail!("password command failed: {}", stderr.trim());
+                    if stderr.trim().is_empty() {
+                        bail!("password command `{}` failed ({}) without output on stderr", cmd, output.status);
+                    }
+                    bail!("password command `{}` failed ({}): {}", cmd, output.status, stderr.trim());
                 }
 
                 let stdout = String::from_utf8_lossy(&output.stdout);
                 // Take first line, trim whitespace
-                Ok(stdout.lines().next().unwrap_or("").trim().to_string())
+                let password = stdout.lines().next().unwrap_or("").trim().to_string();
+                if password.is_empty() {
+                    bail!("password command `{}` printed no password: {}", cmd, stderr.trim());
+                }
+                Ok(password)
             }
         }
     }
@@ -312,6 +741,9 @@ pub struct RemoteConfig {
     pub conflict_prefer: Option<ConflictPreference>,
     /// Local subdirectory within vdir for this remote's contacts (None = root vdir)
     pub local_book: Option<String>,
+    /// Map address books to CATEGORIES: uploads carry their book as the first
+    /// category, and new downloads are filed into the book named by theirs
+    pub categories_as_books: bool,
 }
 
 impl RemoteConfig {
@@ -397,11 +829,10 @@ impl TopBarButton {
     /// Get the function key number (1-12) or None if invalid
     pub fn function_key_number(&self) -> Option<u8> {
         let upper = self.key.to_ascii_uppercase();
-        if upper.starts_with('F') {
-            upper[1..].parse::<u8>().ok().filter(|&n| n >= 1 && n <= 12)
-        } else {
-            None
-        }
+        upper
+            .strip_prefix('F')
+            .and_then(|rest| rest.parse::<u8>().ok())
+            .filter(|&n| (1..=12).contains(&n))
     }
 }
 
@@ -570,6 +1001,7 @@ pub struct UiConfig {
     pub colors: UiColors,
     pub icons: UiIcons,
     pub pane: UiPane,
+    pub share: UiShare,
 }
 
 #[derive(Debug, Clone)]
@@ -598,6 +1030,80 @@ pub struct UiPane {
 pub struct UiPaneImage {
     pub width: u16,
     pub height: u16,
+    pub mode: ImageMode,
+}
+
+/// How the image pane draws contact photos
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImageMode {
+    /// Use the terminal's image protocol (Kitty, Sixel, iTerm2) when one is
+    /// detected, half-block characters otherwise
+    #[default]
+    Auto,
+    /// Always draw half-block characters
+    Blocks,
+    /// Never draw photos, only a text placeholder
+    None,
+}
+
+impl ImageMode {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "auto" => Some(ImageMode::Auto),
+            "blocks" => Some(ImageMode::Blocks),
+            "none" => Some(ImageMode::None),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UiShare {
+    pub qr_error_correction: QrErrorCorrection,
+}
+
+/// QR error-correction level for the share modal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QrErrorCorrection {
+    /// ~7% recovery, largest capacity
+    L,
+    /// ~15% recovery (qrcode default)
+    M,
+    /// ~25% recovery
+    Q,
+    /// ~30% recovery, smallest capacity
+    H,
+}
+
+impl QrErrorCorrection {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_uppercase().as_str() {
+            "L" => Some(QrErrorCorrection::L),
+            "M" => Some(QrErrorCorrection::M),
+            "Q" => Some(QrErrorCorrection::Q),
+            "H" => Some(QrErrorCorrection::H),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            QrErrorCorrection::L => "L",
+            QrErrorCorrection::M => "M",
+            QrErrorCorrection::Q => "Q",
+            QrErrorCorrection::H => "H",
+        }
+    }
+
+    /// Largest byte-mode payload that fits a version 40 symbol at this level
+    pub fn max_bytes(self) -> usize {
+        match self {
+            QrErrorCorrection::L => 2953,
+            QrErrorCorrection::M => 2331,
+            QrErrorCorrection::Q => 1663,
+            QrErrorCorrection::H => 1273,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -610,6 +1116,8 @@ pub struct RgbColor {
 #[derive(Debug, Clone)]
 pub struct Commands {
     pub copy: Option<CommandExec>,
+    /// Run with the current contact's file path (see `CommandExec::with_path`)
+    pub open_path: Option<CommandExec>,
 }
 
 #[derive(Debug, Clone)]
@@ -623,7 +1131,7 @@ pub struct CommandExec {
 // =============================================================================
 
 /// All key bindings organized by context
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Keys {
     /// Global keys (work in most contexts)
     pub global: GlobalKeys,
@@ -644,6 +1152,7 @@ pub struct GlobalKeys {
     pub quit: Vec<String>,
     pub search: Vec<String>,
     pub help: Vec<String>,
+    pub recent: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -665,6 +1174,9 @@ pub struct SearchResultsKeys {
     pub mark: Vec<String>,
     pub merge: Vec<String>,
     pub toggle_marked: Vec<String>,
+    pub tag_marked: Vec<String>,
+    pub compare: Vec<String>,
+    pub skip_group: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -673,9 +1185,27 @@ pub struct NavigationKeys {
     pub prev: Vec<String>,
     pub edit: Vec<String>,
     pub copy: Vec<String>,
+    pub copy_uri: Vec<String>,
+    pub edit_label: Vec<String>,
     pub confirm: Vec<String>,
     pub add_field: Vec<String>,
     pub delete_field: Vec<String>,
+    pub split_name: Vec<String>,
+    pub clear_filter: Vec<String>,
+    pub toggle_filter: Vec<String>,
+    pub toggle_empty: Vec<String>,
+    pub toggle_nosync: Vec<String>,
+    pub open_path: Vec<String>,
+    pub copy_uid: Vec<String>,
+    pub copy_path: Vec<String>,
+    pub clean_emails: Vec<String>,
+    pub toggle_notes: Vec<String>,
+    pub expand: Vec<String>,
+    pub toggle_book: Vec<String>,
+    pub toggle_sources: Vec<String>,
+    pub move_contact_up: Vec<String>,
+    pub move_contact_down: Vec<String>,
+    pub duplicate: Vec<String>,
     pub photo_fetch: Vec<String>,
     pub lang_cycle: Vec<String>,
 }
@@ -688,7 +1218,11 @@ pub struct ModalKeys {
     pub prev: Vec<String>,
     pub edit: Vec<String>,
     pub copy: Vec<String>,
+    pub copy_uri: Vec<String>,
+    pub edit_label: Vec<String>,
     pub set_default: Vec<String>,
+    pub move_up: Vec<String>,
+    pub move_down: Vec<String>,
     pub delete: Vec<String>,
     pub add: Vec<String>,
 }
@@ -703,25 +1237,13 @@ pub struct EditorKeys {
 // Default implementations
 // =============================================================================
 
-impl Default for Keys {
-    fn default() -> Self {
-        Self {
-            global: GlobalKeys::default(),
-            search_input: SearchInputKeys::default(),
-            search_results: SearchResultsKeys::default(),
-            navigation: NavigationKeys::default(),
-            modal: ModalKeys::default(),
-            editor: EditorKeys::default(),
-        }
-    }
-}
-
 impl Default for GlobalKeys {
     fn default() -> Self {
         Self {
             quit: vec!["q".into()],
             search: vec!["/".into()],
             help: vec!["F1".into(), "?".into()],
+            recent: vec!["R".into()],
         }
     }
 }
@@ -749,6 +1271,9 @@ impl Default for SearchResultsKeys {
             mark: vec!["Space".into()],
             merge: vec!["m".into()],
             toggle_marked: vec!["M".into()],
+            tag_marked: vec!["T".into()],
+            compare: vec!["=".into()],
+            skip_group: vec![">".into()],
         }
     }
 }
@@ -760,9 +1285,27 @@ impl Default for NavigationKeys {
             prev: vec!["Backspace".into(), "k".into(), "Up".into()],
             edit: vec!["e".into()],
             copy: vec!["Space".into()],
+            copy_uri: vec!["Y".into()],
+            edit_label: vec!["T".into()],
             confirm: vec!["Enter".into()],
             add_field: vec!["a".into()],
             delete_field: vec!["d".into()],
+            split_name: vec!["S".into()],
+            clear_filter: vec!["c".into()],
+            toggle_filter: vec!["F".into()],
+            toggle_empty: vec!["z".into()],
+            toggle_nosync: vec!["N".into()],
+            open_path: vec!["O".into()],
+            copy_uid: vec!["U".into()],
+            copy_path: vec!["C".into()],
+            clean_emails: vec!["E".into()],
+            toggle_notes: vec!["v".into()],
+            expand: vec!["f".into()],
+            toggle_book: vec!["B".into()],
+            toggle_sources: vec!["I".into()],
+            move_contact_up: vec!["K".into()],
+            move_contact_down: vec!["J".into()],
+            duplicate: vec!["D".into()],
             photo_fetch: vec!["i".into()],
             lang_cycle: vec!["L".into()],
         }
@@ -773,12 +1316,16 @@ impl Default for ModalKeys {
     fn default() -> Self {
         Self {
             cancel: vec!["Escape".into(), "q".into()],
-            confirm: vec!["Enter".into(), "y".into()],
+            confirm: vec!["Enter".into()],
             next: vec!["j".into(), "Down".into(), "Tab".into()],
             prev: vec!["k".into(), "Up".into(), "Backtab".into()],
             edit: vec!["e".into()],
             copy: vec!["y".into(), "Space".into()],
+            copy_uri: vec!["Y".into()],
+            edit_label: vec!["T".into()],
             set_default: vec!["d".into()],
+            move_up: vec!["K".into()],
+            move_down: vec!["J".into()],
             delete: vec!["x".into()],
             add: vec!["a".into()],
         }
@@ -794,6 +1341,96 @@ impl Default for EditorKeys {
     }
 }
 
+// =============================================================================
+// Key map presets
+// =============================================================================
+
+/// Names accepted by the top-level `keymap` setting
+const KEYMAP_PRESETS: &[&str] = &["default", "vim", "emacs"];
+
+impl Keys {
+    /// Build the bindings for a named preset (`keymap = "..."`)
+    pub fn preset(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "default" => Some(Keys::default()),
+            "vim" => Some(vim_keymap()),
+            "emacs" => Some(emacs_keymap()),
+            _ => None,
+        }
+    }
+}
+
+fn keys(bindings: &[&str]) -> Vec<String> {
+    bindings.iter().map(|b| b.to_string()).collect()
+}
+
+/// Vim-flavoured bindings: hjkl-style movement only, `i` to edit, `o`/`x` to add/delete
+fn vim_keymap() -> Keys {
+    let defaults = Keys::default();
+    Keys {
+        search_results: SearchResultsKeys {
+            next: keys(&["j", "Down"]),
+            prev: keys(&["k", "Up"]),
+            ..defaults.search_results
+        },
+        navigation: NavigationKeys {
+            next: keys(&["j", "Down"]),
+            prev: keys(&["k", "Up"]),
+            edit: keys(&["i", "e"]),
+            copy: keys(&["y"]),
+            copy_uri: keys(&["Y"]),
+            add_field: keys(&["o"]),
+            delete_field: keys(&["x"]),
+            photo_fetch: keys(&["P"]),
+            ..defaults.navigation
+        },
+        modal: ModalKeys {
+            next: keys(&["j", "Down"]),
+            prev: keys(&["k", "Up"]),
+            edit: keys(&["i", "e"]),
+            copy: keys(&["y"]),
+            add: keys(&["o"]),
+            ..defaults.modal
+        },
+        ..defaults
+    }
+}
+
+/// Emacs/dired-flavoured bindings: `n`/`p` to move, `w` to copy, `m`/`t` to mark
+fn emacs_keymap() -> Keys {
+    let defaults = Keys::default();
+    Keys {
+        global: GlobalKeys {
+            search: keys(&["/", "s"]),
+            ..defaults.global
+        },
+        search_results: SearchResultsKeys {
+            next: keys(&["n", "Down", "Tab"]),
+            prev: keys(&["p", "Up", "Backtab"]),
+            mark: keys(&["m", "Space"]),
+            merge: keys(&["M"]),
+            toggle_marked: keys(&["t"]),
+            ..defaults.search_results
+        },
+        navigation: NavigationKeys {
+            next: keys(&["n", "Down", "Tab"]),
+            prev: keys(&["p", "Up", "Backspace"]),
+            copy: keys(&["w", "Space"]),
+            copy_uri: keys(&["W"]),
+            ..defaults.navigation
+        },
+        modal: ModalKeys {
+            cancel: keys(&["Escape", "q", "g"]),
+            next: keys(&["n", "Down", "Tab"]),
+            prev: keys(&["p", "Up", "Backtab"]),
+            copy: keys(&["w", "Space"]),
+            copy_uri: keys(&["W"]),
+            ..defaults.modal
+        },
+        ..defaults
+    }
+}
+
 // =============================================================================
 // Serde deserialization types (support both single string and array)
 // =============================================================================
@@ -814,10 +1451,9 @@ impl KeyBinding {
     }
 }
 
-impl Default for KeyBinding {
-    fn default() -> Self {
-        KeyBinding::Multiple(vec![])
-    }
+/// Use an explicit binding from the config file, or fall back to the preset
+fn resolve_binding(binding: Option<KeyBinding>, preset: Vec<String>) -> Vec<String> {
+    binding.map(KeyBinding::into_vec).unwrap_or(preset)
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -831,247 +1467,216 @@ struct KeysFile {
     editor: EditorKeysFile,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Default)]
 #[serde(default)]
 struct GlobalKeysFile {
-    quit: KeyBinding,
-    search: KeyBinding,
-    help: KeyBinding,
-}
-
-impl Default for GlobalKeysFile {
-    fn default() -> Self {
-        let defaults = GlobalKeys::default();
-        Self {
-            quit: KeyBinding::Multiple(defaults.quit),
-            search: KeyBinding::Multiple(defaults.search),
-            help: KeyBinding::Multiple(defaults.help),
-        }
-    }
+    quit: Option<KeyBinding>,
+    search: Option<KeyBinding>,
+    help: Option<KeyBinding>,
+    recent: Option<KeyBinding>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Default)]
 #[serde(default)]
 struct SearchInputKeysFile {
-    cancel: KeyBinding,
-    confirm: KeyBinding,
-    next: KeyBinding,
-    prev: KeyBinding,
-}
-
-impl Default for SearchInputKeysFile {
-    fn default() -> Self {
-        let defaults = SearchInputKeys::default();
-        Self {
-            cancel: KeyBinding::Multiple(defaults.cancel),
-            confirm: KeyBinding::Multiple(defaults.confirm),
-            next: KeyBinding::Multiple(defaults.next),
-            prev: KeyBinding::Multiple(defaults.prev),
-        }
-    }
+    cancel: Option<KeyBinding>,
+    confirm: Option<KeyBinding>,
+    next: Option<KeyBinding>,
+    prev: Option<KeyBinding>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Default)]
 #[serde(default)]
 struct SearchResultsKeysFile {
-    cancel: KeyBinding,
-    confirm: KeyBinding,
-    next: KeyBinding,
-    prev: KeyBinding,
-    page_down: KeyBinding,
-    page_up: KeyBinding,
-    mark: KeyBinding,
-    merge: KeyBinding,
-    toggle_marked: KeyBinding,
-}
-
-impl Default for SearchResultsKeysFile {
-    fn default() -> Self {
-        let defaults = SearchResultsKeys::default();
-        Self {
-            cancel: KeyBinding::Multiple(defaults.cancel),
-            confirm: KeyBinding::Multiple(defaults.confirm),
-            next: KeyBinding::Multiple(defaults.next),
-            prev: KeyBinding::Multiple(defaults.prev),
-            page_down: KeyBinding::Multiple(defaults.page_down),
-            page_up: KeyBinding::Multiple(defaults.page_up),
-            mark: KeyBinding::Multiple(defaults.mark),
-            merge: KeyBinding::Multiple(defaults.merge),
-            toggle_marked: KeyBinding::Multiple(defaults.toggle_marked),
-        }
-    }
+    cancel: Option<KeyBinding>,
+    confirm: Option<KeyBinding>,
+    next: Option<KeyBinding>,
+    prev: Option<KeyBinding>,
+    page_down: Option<KeyBinding>,
+    page_up: Option<KeyBinding>,
+    mark: Option<KeyBinding>,
+    merge: Option<KeyBinding>,
+    toggle_marked: Option<KeyBinding>,
+    tag_marked: Option<KeyBinding>,
+    compare: Option<KeyBinding>,
+    skip_group: Option<KeyBinding>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Default)]
 #[serde(default)]
 struct NavigationKeysFile {
-    next: KeyBinding,
-    prev: KeyBinding,
-    edit: KeyBinding,
-    copy: KeyBinding,
-    confirm: KeyBinding,
-    add_field: KeyBinding,
-    delete_field: KeyBinding,
-    photo_fetch: KeyBinding,
-    lang_cycle: KeyBinding,
-}
-
-impl Default for NavigationKeysFile {
-    fn default() -> Self {
-        let defaults = NavigationKeys::default();
-        Self {
-            next: KeyBinding::Multiple(defaults.next),
-            prev: KeyBinding::Multiple(defaults.prev),
-            edit: KeyBinding::Multiple(defaults.edit),
-            copy: KeyBinding::Multiple(defaults.copy),
-            confirm: KeyBinding::Multiple(defaults.confirm),
-            add_field: KeyBinding::Multiple(defaults.add_field),
-            delete_field: KeyBinding::Multiple(defaults.delete_field),
-            photo_fetch: KeyBinding::Multiple(defaults.photo_fetch),
-            lang_cycle: KeyBinding::Multiple(defaults.lang_cycle),
-        }
-    }
+    next: Option<KeyBinding>,
+    prev: Option<KeyBinding>,
+    edit: Option<KeyBinding>,
+    copy: Option<KeyBinding>,
+    copy_uri: Option<KeyBinding>,
+    edit_label: Option<KeyBinding>,
+    confirm: Option<KeyBinding>,
+    add_field: Option<KeyBinding>,
+    delete_field: Option<KeyBinding>,
+    split_name: Option<KeyBinding>,
+    clear_filter: Option<KeyBinding>,
+    toggle_filter: Option<KeyBinding>,
+    toggle_empty: Option<KeyBinding>,
+    toggle_nosync: Option<KeyBinding>,
+    open_path: Option<KeyBinding>,
+    copy_uid: Option<KeyBinding>,
+    copy_path: Option<KeyBinding>,
+    clean_emails: Option<KeyBinding>,
+    toggle_notes: Option<KeyBinding>,
+    expand: Option<KeyBinding>,
+    toggle_book: Option<KeyBinding>,
+    toggle_sources: Option<KeyBinding>,
+    move_contact_up: Option<KeyBinding>,
+    move_contact_down: Option<KeyBinding>,
+    duplicate: Option<KeyBinding>,
+    photo_fetch: Option<KeyBinding>,
+    lang_cycle: Option<KeyBinding>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Default)]
 #[serde(default)]
 struct ModalKeysFile {
-    cancel: KeyBinding,
-    confirm: KeyBinding,
-    next: KeyBinding,
-    prev: KeyBinding,
-    edit: KeyBinding,
-    copy: KeyBinding,
-    set_default: KeyBinding,
-    delete: KeyBinding,
-    add: KeyBinding,
-}
-
-impl Default for ModalKeysFile {
-    fn default() -> Self {
-        let defaults = ModalKeys::default();
-        Self {
-            cancel: KeyBinding::Multiple(defaults.cancel),
-            confirm: KeyBinding::Multiple(defaults.confirm),
-            next: KeyBinding::Multiple(defaults.next),
-            prev: KeyBinding::Multiple(defaults.prev),
-            edit: KeyBinding::Multiple(defaults.edit),
-            copy: KeyBinding::Multiple(defaults.copy),
-            set_default: KeyBinding::Multiple(defaults.set_default),
-            delete: KeyBinding::Multiple(defaults.delete),
-            add: KeyBinding::Multiple(defaults.add),
-        }
-    }
+    cancel: Option<KeyBinding>,
+    confirm: Option<KeyBinding>,
+    next: Option<KeyBinding>,
+    prev: Option<KeyBinding>,
+    edit: Option<KeyBinding>,
+    copy: Option<KeyBinding>,
+    copy_uri: Option<KeyBinding>,
+    edit_label: Option<KeyBinding>,
+    set_default: Option<KeyBinding>,
+    move_up: Option<KeyBinding>,
+    move_down: Option<KeyBinding>,
+    delete: Option<KeyBinding>,
+    add: Option<KeyBinding>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Default)]
 #[serde(default)]
 struct EditorKeysFile {
-    cancel: KeyBinding,
-    confirm: KeyBinding,
-}
-
-impl Default for EditorKeysFile {
-    fn default() -> Self {
-        let defaults = EditorKeys::default();
-        Self {
-            cancel: KeyBinding::Multiple(defaults.cancel),
-            confirm: KeyBinding::Multiple(defaults.confirm),
-        }
-    }
+    cancel: Option<KeyBinding>,
+    confirm: Option<KeyBinding>,
 }
 
 // =============================================================================
 // Conversion from file types to runtime types
 // =============================================================================
 
-impl From<KeysFile> for Keys {
-    fn from(file: KeysFile) -> Self {
-        Self {
-            global: file.global.into(),
-            search_input: file.search_input.into(),
-            search_results: file.search_results.into(),
-            navigation: file.navigation.into(),
-            modal: file.modal.into(),
-            editor: file.editor.into(),
+impl KeysFile {
+    /// Overlay the bindings set in the config file on top of a preset
+    fn into_keys(self, preset: Keys) -> Keys {
+        Keys {
+            global: self.global.into_keys(preset.global),
+            search_input: self.search_input.into_keys(preset.search_input),
+            search_results: self.search_results.into_keys(preset.search_results),
+            navigation: self.navigation.into_keys(preset.navigation),
+            modal: self.modal.into_keys(preset.modal),
+            editor: self.editor.into_keys(preset.editor),
+        }
+    }
+}
+
+impl GlobalKeysFile {
+    fn into_keys(self, preset: GlobalKeys) -> GlobalKeys {
+        GlobalKeys {
+            quit: resolve_binding(self.quit, preset.quit),
+            search: resolve_binding(self.search, preset.search),
+            help: resolve_binding(self.help, preset.help),
+            recent: resolve_binding(self.recent, preset.recent),
         }
     }
 }
 
-impl From<GlobalKeysFile> for GlobalKeys {
-    fn from(file: GlobalKeysFile) -> Self {
-        Self {
-            quit: file.quit.into_vec(),
-            search: file.search.into_vec(),
-            help: file.help.into_vec(),
-        }
-    }
-}
-
-impl From<SearchInputKeysFile> for SearchInputKeys {
-    fn from(file: SearchInputKeysFile) -> Self {
-        Self {
-            cancel: file.cancel.into_vec(),
-            confirm: file.confirm.into_vec(),
-            next: file.next.into_vec(),
-            prev: file.prev.into_vec(),
+impl SearchInputKeysFile {
+    fn into_keys(self, preset: SearchInputKeys) -> SearchInputKeys {
+        SearchInputKeys {
+            cancel: resolve_binding(self.cancel, preset.cancel),
+            confirm: resolve_binding(self.confirm, preset.confirm),
+            next: resolve_binding(self.next, preset.next),
+            prev: resolve_binding(self.prev, preset.prev),
         }
     }
 }
 
-impl From<SearchResultsKeysFile> for SearchResultsKeys {
-    fn from(file: SearchResultsKeysFile) -> Self {
-        Self {
-            cancel: file.cancel.into_vec(),
-            confirm: file.confirm.into_vec(),
-            next: file.next.into_vec(),
-            prev: file.prev.into_vec(),
-            page_down: file.page_down.into_vec(),
-            page_up: file.page_up.into_vec(),
-            mark: file.mark.into_vec(),
-            merge: file.merge.into_vec(),
-            toggle_marked: file.toggle_marked.into_vec(),
+impl SearchResultsKeysFile {
+    fn into_keys(self, preset: SearchResultsKeys) -> SearchResultsKeys {
+        SearchResultsKeys {
+            cancel: resolve_binding(self.cancel, preset.cancel),
+            confirm: resolve_binding(self.confirm, preset.confirm),
+            next: resolve_binding(self.next, preset.next),
+            prev: resolve_binding(self.prev, preset.prev),
+            page_down: resolve_binding(self.page_down, preset.page_down),
+            page_up: resolve_binding(self.page_up, preset.page_up),
+            mark: resolve_binding(self.mark, preset.mark),
+            merge: resolve_binding(self.merge, preset.merge),
+            toggle_marked: resolve_binding(self.toggle_marked, preset.toggle_marked),
+            tag_marked: resolve_binding(self.tag_marked, preset.tag_marked),
+            compare: resolve_binding(self.compare, preset.compare),
+            skip_group: resolve_binding(self.skip_group, preset.skip_group),
         }
     }
 }
 
-impl From<NavigationKeysFile> for NavigationKeys {
-    fn from(file: NavigationKeysFile) -> Self {
-        Self {
-            next: file.next.into_vec(),
-            prev: file.prev.into_vec(),
-            edit: file.edit.into_vec(),
-            copy: file.copy.into_vec(),
-            confirm: file.confirm.into_vec(),
-            add_field: file.add_field.into_vec(),
-            delete_field: file.delete_field.into_vec(),
-            photo_fetch: file.photo_fetch.into_vec(),
-            lang_cycle: file.lang_cycle.into_vec(),
+impl NavigationKeysFile {
+    fn into_keys(self, preset: NavigationKeys) -> NavigationKeys {
+        NavigationKeys {
+            next: resolve_binding(self.next, preset.next),
+            prev: resolve_binding(self.prev, preset.prev),
+            edit: resolve_binding(self.edit, preset.edit),
+            copy: resolve_binding(self.copy, preset.copy),
+            copy_uri: resolve_binding(self.copy_uri, preset.copy_uri),
+            edit_label: resolve_binding(self.edit_label, preset.edit_label),
+            confirm: resolve_binding(self.confirm, preset.confirm),
+            add_field: resolve_binding(self.add_field, preset.add_field),
+            delete_field: resolve_binding(self.delete_field, preset.delete_field),
+            split_name: resolve_binding(self.split_name, preset.split_name),
+            clear_filter: resolve_binding(self.clear_filter, preset.clear_filter),
+            toggle_filter: resolve_binding(self.toggle_filter, preset.toggle_filter),
+            toggle_empty: resolve_binding(self.toggle_empty, preset.toggle_empty),
+            toggle_nosync: resolve_binding(self.toggle_nosync, preset.toggle_nosync),
+            open_path: resolve_binding(self.open_path, preset.open_path),
+            copy_uid: resolve_binding(self.copy_uid, preset.copy_uid),
+            copy_path: resolve_binding(self.copy_path, preset.copy_path),
+            clean_emails: resolve_binding(self.clean_emails, preset.clean_emails),
+            toggle_notes: resolve_binding(self.toggle_notes, preset.toggle_notes),
+            expand: resolve_binding(self.expand, preset.expand),
+            toggle_book: resolve_binding(self.toggle_book, preset.toggle_book),
+            toggle_sources: resolve_binding(self.toggle_sources, preset.toggle_sources),
+            move_contact_up: resolve_binding(self.move_contact_up, preset.move_contact_up),
+            move_contact_down: resolve_binding(self.move_contact_down, preset.move_contact_down),
+            duplicate: resolve_binding(self.duplicate, preset.duplicate),
+            photo_fetch: resolve_binding(self.photo_fetch, preset.photo_fetch),
+            lang_cycle: resolve_binding(self.lang_cycle, preset.lang_cycle),
         }
     }
 }
 
-impl From<ModalKeysFile> for ModalKeys {
-    fn from(file: ModalKeysFile) -> Self {
-        Self {
-            cancel: file.cancel.into_vec(),
-            confirm: file.confirm.into_vec(),
-            next: file.next.into_vec(),
-            prev: file.prev.into_vec(),
-            edit: file.edit.into_vec(),
-            copy: file.copy.into_vec(),
-            set_default: file.set_default.into_vec(),
-            delete: file.delete.into_vec(),
-            add: file.add.into_vec(),
+impl ModalKeysFile {
+    fn into_keys(self, preset: ModalKeys) -> ModalKeys {
+        ModalKeys {
+            cancel: resolve_binding(self.cancel, preset.cancel),
+            confirm: resolve_binding(self.confirm, preset.confirm),
+            next: resolve_binding(self.next, preset.next),
+            prev: resolve_binding(self.prev, preset.prev),
+            edit: resolve_binding(self.edit, preset.edit),
+            copy: resolve_binding(self.copy, preset.copy),
+            copy_uri: resolve_binding(self.copy_uri, preset.copy_uri),
+            edit_label: resolve_binding(self.edit_label, preset.edit_label),
+            set_default: resolve_binding(self.set_default, preset.set_default),
+            move_up: resolve_binding(self.move_up, preset.move_up),
+            move_down: resolve_binding(self.move_down, preset.move_down),
+            delete: resolve_binding(self.delete, preset.delete),
+            add: resolve_binding(self.add, preset.add),
         }
     }
 }
 
-impl From<EditorKeysFile> for EditorKeys {
-    fn from(file: EditorKeysFile) -> Self {
-        Self {
-            cancel: file.cancel.into_vec(),
-            confirm: file.confirm.into_vec(),
+impl EditorKeysFile {
+    fn into_keys(self, preset: EditorKeys) -> EditorKeys {
+        EditorKeys {
+            cancel: resolve_binding(self.cancel, preset.cancel),
+            confirm: resolve_binding(self.confirm, preset.confirm),
         }
     }
 }
@@ -1080,18 +1685,51 @@ impl From<EditorKeysFile> for EditorKeys {
 // Key binding validation
 // =============================================================================
 
+/// Modifier prefixes carried by a binding such as "Ctrl+e" or "Alt+x"
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BindingModifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+}
+
+/// Split "Ctrl+" and "Alt+" prefixes (any case, any order) off a binding,
+/// returning them with the remaining key name. "Shift+Tab" is a key name.
+pub fn split_binding_modifiers(binding: &str) -> (BindingModifiers, &str) {
+    let mut modifiers = BindingModifiers::default();
+    let mut rest = binding.trim();
+    while let Some((prefix, key)) = rest.split_once('+') {
+        if key.is_empty() {
+            break;
+        }
+        match prefix.trim().to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => modifiers.ctrl = true,
+            "alt" | "meta" => modifiers.alt = true,
+            _ => break,
+        }
+        rest = key.trim();
+    }
+    (modifiers, rest)
+}
+
 /// Normalize a key binding string to a canonical form for collision detection.
 /// Single characters preserve case (since 'M' means Shift+m, different from 'm').
 /// Multi-character key names are case-insensitive (Enter, ENTER, enter are the same).
+/// Modifiers come first in a fixed order ("Alt+Ctrl+x" is "ctrl+alt+x").
 fn normalize_binding(binding: &str) -> String {
-    let trimmed = binding.trim();
-    if trimmed.len() == 1 {
+    let (modifiers, key) = split_binding_modifiers(binding);
+    let key = if key.len() == 1 {
         // Single character: preserve case (m != M)
-        trimmed.to_string()
+        key.to_string()
     } else {
         // Special key names: case-insensitive
-        trimmed.to_ascii_lowercase()
+        key.to_ascii_lowercase()
+    };
+    if key.is_empty() {
+        return key;
     }
+    let ctrl = if modifiers.ctrl { "ctrl+" } else { "" };
+    let alt = if modifiers.alt { "alt+" } else { "" };
+    format!("{ctrl}{alt}{key}")
 }
 
 /// Check for collisions within a single context
@@ -1107,6 +1745,14 @@ fn check_context_collisions(
             if normalized.is_empty() {
                 continue;
             }
+            if normalized == "ctrl+c" || normalized == "ctrl+C" {
+                bail!(
+                    "key binding '{}' for '{}' in [keys.{}] is reserved: Ctrl+C always quits",
+                    key,
+                    action_name,
+                    context_name
+                );
+            }
             if let Some(existing_action) = seen.get(&normalized) {
                 bail!(
                     "key binding collision in [keys.{}]: '{}' is bound to both '{}' and '{}'",
@@ -1131,6 +1777,7 @@ fn validate_key_bindings(keys: &Keys) -> Result<()> {
             ("quit", &keys.global.quit),
             ("search", &keys.global.search),
             ("help", &keys.global.help),
+            ("recent", &keys.global.recent),
         ],
         "global",
     )?;
@@ -1156,6 +1803,9 @@ fn validate_key_bindings(keys: &Keys) -> Result<()> {
             ("mark", &keys.search_results.mark),
             ("merge", &keys.search_results.merge),
             ("toggle_marked", &keys.search_results.toggle_marked),
+            ("tag_marked", &keys.search_results.tag_marked),
+            ("compare", &keys.search_results.compare),
+            ("skip_group", &keys.search_results.skip_group),
         ],
         "search_results",
     )?;
@@ -1167,9 +1817,27 @@ fn validate_key_bindings(keys: &Keys) -> Result<()> {
             ("prev", &keys.navigation.prev),
             ("edit", &keys.navigation.edit),
             ("copy", &keys.navigation.copy),
+            ("copy_uri", &keys.navigation.copy_uri),
+            ("edit_label", &keys.navigation.edit_label),
             ("confirm", &keys.navigation.confirm),
             ("add_field", &keys.navigation.add_field),
             ("delete_field", &keys.navigation.delete_field),
+            ("split_name", &keys.navigation.split_name),
+            ("clear_filter", &keys.navigation.clear_filter),
+            ("toggle_filter", &keys.navigation.toggle_filter),
+            ("toggle_empty", &keys.navigation.toggle_empty),
+            ("toggle_nosync", &keys.navigation.toggle_nosync),
+            ("open_path", &keys.navigation.open_path),
+            ("copy_uid", &keys.navigation.copy_uid),
+            ("copy_path", &keys.navigation.copy_path),
+            ("clean_emails", &keys.navigation.clean_emails),
+            ("toggle_notes", &keys.navigation.toggle_notes),
+            ("expand", &keys.navigation.expand),
+            ("toggle_book", &keys.navigation.toggle_book),
+            ("toggle_sources", &keys.navigation.toggle_sources),
+            ("move_contact_up", &keys.navigation.move_contact_up),
+            ("move_contact_down", &keys.navigation.move_contact_down),
+            ("duplicate", &keys.navigation.duplicate),
             ("photo_fetch", &keys.navigation.photo_fetch),
             ("lang_cycle", &keys.navigation.lang_cycle),
         ],
@@ -1185,7 +1853,11 @@ fn validate_key_bindings(keys: &Keys) -> Result<()> {
             ("prev", &keys.modal.prev),
             ("edit", &keys.modal.edit),
             ("copy", &keys.modal.copy),
+            ("copy_uri", &keys.modal.copy_uri),
+            ("edit_label", &keys.modal.edit_label),
             ("set_default", &keys.modal.set_default),
+            ("move_up", &keys.modal.move_up),
+            ("move_down", &keys.modal.move_down),
         ],
         "modal",
     )?;
@@ -1210,10 +1882,36 @@ fn validate_key_bindings(keys: &Keys) -> Result<()> {
 #[serde(default)]
 struct ConfigFile {
     vdir: Option<PathBuf>,
+    #[serde(default)]
+    vdir_ignore: Vec<String>,
     db_path: Option<PathBuf>,
     #[serde(default = "default_fields_first_pane")]
     fields_first_pane: Vec<String>,
     phone_region: Option<String>,
+    #[serde(default = "default_phone_normalize")]
+    phone_normalize: bool,
+    review_duplicates_on_startup: bool,
+    #[serde(default = "default_duplicate_similarity")]
+    duplicate_similarity: f64,
+    nameless_display: Option<String>,
+    fill_missing_fn: bool,
+    track_field_sources: bool,
+    default_book: Option<String>,
+    create_on_no_match: bool,
+    max_embedded_photo_bytes: Option<usize>,
+    edit_backup: bool,
+    #[serde(default = "default_edit_backup_keep")]
+    edit_backup_keep: usize,
+    email_case: Option<String>,
+    single_value_enter: Option<String>,
+    multivalue_sort: Option<String>,
+    startup: Option<String>,
+    on_decrypt_error: Option<String>,
+    #[serde(default)]
+    flag_incomplete: Vec<String>,
+    #[serde(default)]
+    duplicate_clear: Vec<String>,
+    keymap: Option<String>,
     #[serde(default)]
     keys: KeysFile,
     #[serde(default)]
@@ -1227,9 +1925,14 @@ struct ConfigFile {
     #[serde(default)]
     encryption: EncryptionFile,
     #[serde(default)]
+    copy_transform: CopyTransformFile,
+    #[serde(default)]
+    impp_templates: HashMap<String, String>,
+    #[serde(default)]
     sync: SyncFile,
     #[serde(default)]
     details_sections: DetailsSectionsFile,
+    untyped_details_section: Option<String>,
     #[serde(default)]
     remotes: Vec<RemoteFile>,
 }
@@ -1238,17 +1941,40 @@ impl Default for ConfigFile {
     fn default() -> Self {
         Self {
             vdir: None,
+            vdir_ignore: Vec::new(),
             db_path: None,
             fields_first_pane: default_fields_first_pane(),
             phone_region: None,
+            phone_normalize: default_phone_normalize(),
+            review_duplicates_on_startup: false,
+            duplicate_similarity: default_duplicate_similarity(),
+            nameless_display: None,
+            fill_missing_fn: false,
+            track_field_sources: false,
+            default_book: None,
+            create_on_no_match: false,
+            max_embedded_photo_bytes: None,
+            edit_backup: false,
+            edit_backup_keep: default_edit_backup_keep(),
+            email_case: None,
+            single_value_enter: None,
+            multivalue_sort: None,
+            startup: None,
+            on_decrypt_error: None,
+            flag_incomplete: Vec::new(),
+            duplicate_clear: Vec::new(),
+            keymap: None,
             keys: KeysFile::default(),
             ui: UiFile::default(),
             commands: CommandsFile::default(),
             top_bar: TopBarFile::default(),
             maildir_import: MaildirImportFile::default(),
             encryption: EncryptionFile::default(),
+            copy_transform: CopyTransformFile::default(),
+            impp_templates: HashMap::new(),
             sync: SyncFile::default(),
             details_sections: DetailsSectionsFile::default(),
+            untyped_details_section: None,
             remotes: Vec::new(),
         }
     }
@@ -1282,7 +2008,7 @@ impl From<TopBarFile> for TopBarConfig {
             }
             let num: Option<u8> = upper_key[1..].parse().ok();
             match num {
-                Some(n) if n >= 1 && n <= 12 => {}
+                Some(n) if (1..=12).contains(&n) => {}
                 _ => {
                     eprintln!("warning: invalid top_bar key '{}', expected F1-F12", key);
                     continue;
@@ -1361,34 +2087,27 @@ impl From<MaildirImportFile> for MaildirImportConfig {
 // Encryption File Deserialization
 // =============================================================================
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Default)]
 #[serde(default)]
 struct EncryptionFile {
     #[serde(rename = "type")]
     encryption_type: Option<String>,
     gpg_key_id: Option<String>,
+    gpg_key_ids: Option<Vec<String>>,
     age_identity: Option<String>,
+    age_identity_cmd: Option<String>,
     age_recipient: Option<String>,
 }
 
-impl Default for EncryptionFile {
-    fn default() -> Self {
-        Self {
-            encryption_type: None,
-            gpg_key_id: None,
-            age_identity: None,
-            age_recipient: None,
-        }
-    }
-}
-
 impl EncryptionFile {
-    fn into_config(self) -> Result<EncryptionConfig> {
+    /// Relative paths are read against `config_dir`
+    fn into_config(self, config_dir: &Path) -> Result<EncryptionConfig> {
         let encryption_type = match self.encryption_type.as_deref() {
             Some("gpg") => EncryptionType::Gpg,
             Some("age") => EncryptionType::Age,
+            Some("none") => EncryptionType::None,
             Some(other) => bail!(
-                "invalid encryption.type '{}', expected one of: gpg, age",
+                "invalid encryption.type '{}', expected one of: gpg, age, none",
                 other
             ),
             None => bail!(
@@ -1396,15 +2115,28 @@ impl EncryptionFile {
             ),
         };
 
-        let age_identity = self.age_identity.map(|s| {
-            let path = PathBuf::from(&s);
-            expand_tilde(&path)
-        });
+        let age_identity = self
+            .age_identity
+            .map(|s| resolve_config_path(config_dir, Path::new(&s)));
+
+        // The book's keys are derived from one GPG key, so a second recipient
+        // could not read it; refuse lists rather than pretend to share
+        if self.gpg_key_ids.is_some() {
+            bail!(
+                "encryption.gpg_key_ids is not supported: a book is encrypted with a key \
+                 derived from a single GPG key, so set encryption.gpg_key_id instead"
+            );
+        }
+        let gpg_key_id = self
+            .gpg_key_id
+            .map(|key| key.trim().to_string())
+            .filter(|key| !key.is_empty());
 
         let config = EncryptionConfig {
             encryption_type,
-            gpg_key_id: self.gpg_key_id,
+            gpg_key_id,
             age_identity,
+            age_identity_cmd: self.age_identity_cmd,
             age_recipient: self.age_recipient,
         };
 
@@ -1417,20 +2149,54 @@ impl EncryptionFile {
 // Sync File Deserialization
 // =============================================================================
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Default)]
 #[serde(default)]
-struct SyncFile {
-    conflict_prefer: Option<String>,
+struct CopyTransformFile {
+    phone: Option<String>,
+    email: Option<String>,
+}
+
+impl From<CopyTransformFile> for CopyTransformConfig {
+    fn from(file: CopyTransformFile) -> Self {
+        CopyTransformConfig {
+            phone: parse_copy_transform(
+                "phone",
+                file.phone.as_deref(),
+                &[CopyTransform::Display, CopyTransform::Digits, CopyTransform::E164],
+            ),
+            email: parse_copy_transform(
+                "email",
+                file.email.as_deref(),
+                &[CopyTransform::Display, CopyTransform::Lowercase],
+            ),
+        }
+    }
 }
 
-impl Default for SyncFile {
-    fn default() -> Self {
-        Self {
-            conflict_prefer: None,
+/// Parse a `copy_transform` entry, falling back to `display` with a warning
+/// when the value is unknown or does not apply to the field
+fn parse_copy_transform(field: &str, value: Option<&str>, allowed: &[CopyTransform]) -> CopyTransform {
+    let Some(value) = value else {
+        return CopyTransform::Display;
+    };
+    match CopyTransform::from_str(value) {
+        Some(transform) if allowed.contains(&transform) => transform,
+        _ => {
+            eprintln!(
+                "warning: copy_transform.{} `{}` is not supported, copying values as displayed",
+                field, value
+            );
+            CopyTransform::Display
         }
     }
 }
 
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+struct SyncFile {
+    conflict_prefer: Option<String>,
+}
+
 impl From<SyncFile> for SyncConfig {
     fn from(file: SyncFile) -> Self {
         let conflict_prefer = file
@@ -1477,7 +2243,10 @@ impl From<DetailsSectionsFile> for DetailsSectionsConfig {
             })
             .collect();
 
-        DetailsSectionsConfig { sections }
+        DetailsSectionsConfig {
+            sections,
+            untyped_section: None,
+        }
     }
 }
 
@@ -1499,26 +2268,35 @@ struct RemoteFile {
     password_file: Option<String>,
     #[serde(default)]
     password_cmd: Option<String>,
+    /// Seconds before `password_cmd` is killed; 0 waits forever
+    #[serde(default = "default_password_cmd_timeout")]
+    password_cmd_timeout: u64,
     #[serde(default)]
     conflict_prefer: Option<String>,
     #[serde(default)]
     local_book: Option<String>,
+    #[serde(default)]
+    categories_as_books: bool,
 }
 
 impl RemoteFile {
-    fn into_config(self) -> Result<RemoteConfig> {
+    /// Relative paths are read against `config_dir`
+    fn into_config(self, config_dir: &Path) -> Result<RemoteConfig> {
         let remote_type = RemoteType::from_str(&self.remote_type)
             .ok_or_else(|| anyhow!("invalid remote type '{}', expected: carddav", self.remote_type))?;
 
         // Determine password source (priority: password_cmd > password_file > password)
         let password_source = if let Some(cmd) = self.password_cmd {
-            Some(PasswordSource::Command(cmd))
+            let timeout = (self.password_cmd_timeout > 0)
+                .then(|| Duration::from_secs(self.password_cmd_timeout));
+            Some(PasswordSource::Command { cmd, timeout })
         } else if let Some(file) = self.password_file {
-            Some(PasswordSource::File(expand_tilde(&PathBuf::from(file))))
-        } else if let Some(plain) = self.password {
-            Some(PasswordSource::Plain(plain))
+            Some(PasswordSource::File(resolve_config_path(
+                config_dir,
+                Path::new(&file),
+            )))
         } else {
-            None // Will prompt interactively
+            self.password.map(PasswordSource::Plain)
         };
 
         let conflict_prefer = self
@@ -1535,6 +2313,7 @@ impl RemoteFile {
             password_source,
             conflict_prefer,
             local_book: self.local_book,
+            categories_as_books: self.categories_as_books,
         };
 
         config.validate()?;
@@ -1542,6 +2321,21 @@ impl RemoteFile {
     }
 }
 
+/// Field keys understood by the first (card) pane
+const FIRST_PANE_FIELDS: &[&str] = &["fname", "mname", "lname", "alias", "phone", "email", "address"];
+
+/// Entries of `fields_first_pane` that the card pane does not recognize
+fn unknown_first_pane_fields(fields: &[String]) -> Vec<&str> {
+    fields
+        .iter()
+        .map(|field| field.as_str())
+        .filter(|field| {
+            let key = field.trim().to_ascii_lowercase();
+            !FIRST_PANE_FIELDS.contains(&key.as_str())
+        })
+        .collect()
+}
+
 fn default_fields_first_pane() -> Vec<String> {
     vec![
         "fname".to_string(),
@@ -1550,9 +2344,26 @@ fn default_fields_first_pane() -> Vec<String> {
         "alias".to_string(),
         "phone".to_string(),
         "email".to_string(),
+        "address".to_string(),
     ]
 }
 
+fn default_password_cmd_timeout() -> u64 {
+    60
+}
+
+fn default_phone_normalize() -> bool {
+    true
+}
+
+fn default_duplicate_similarity() -> f64 {
+    0.9
+}
+
+fn default_edit_backup_keep() -> usize {
+    5
+}
+
 fn config_root() -> Result<PathBuf> {
     let base = BaseDirs::new().context("unable to determine base directories")?;
     let dir = base.config_dir().join(APP_NAME);
@@ -1608,20 +2419,27 @@ pub fn load_from(custom_path: Option<&Path>) -> Result<Config> {
         .try_into()
         .with_context(|| format!("failed to deserialize config from {}", path.display()))?;
 
+    for field in unknown_first_pane_fields(&cfg_file.fields_first_pane) {
+        eprintln!(
+            "warning: unknown fields_first_pane entry `{}` (expected one of: {})",
+            field,
+            FIRST_PANE_FIELDS.join(", ")
+        );
+    }
+
     let vdir = cfg_file
         .vdir
         .ok_or_else(|| anyhow!("`vdir` must be specified in configuration"))?;
 
-    // Expand tilde in vdir path
-    let vdir = expand_tilde(&vdir);
+    // Relative paths are read against the config file's directory
+    let config_dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+    let vdir = resolve_config_path(&config_dir, &vdir);
 
-    if !vdir.exists() {
-        bail!("configured vdir does not exist: {}", vdir.display());
-    }
+    let vdir_ignore = IgnoreList::new(&vdir, &cfg_file.vdir_ignore)?;
 
     // Handle db_path: use configured value or default
     let db_path = match cfg_file.db_path {
-        Some(p) => expand_tilde(&p),
+        Some(p) => resolve_config_path(&config_dir, &p),
         None => default_db_path()?,
     };
 
@@ -1632,7 +2450,112 @@ pub fn load_from(custom_path: Option<&Path>) -> Result<Config> {
         .filter(|value| !value.is_empty())
         .map(|value| value.to_ascii_uppercase());
 
-    let keys: Keys = cfg_file.keys.into();
+    let nameless_display = match cfg_file.nameless_display.as_deref() {
+        Some(value) => NamelessDisplay::from_str(value).unwrap_or_else(|| {
+            eprintln!(
+                "warning: unknown nameless_display `{}` (expected email, org or placeholder)",
+                value
+            );
+            NamelessDisplay::default()
+        }),
+        None => NamelessDisplay::default(),
+    };
+
+    let default_book = cfg_file
+        .default_book
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .and_then(|name| match crate::vdir::book_path(name) {
+            Some(dir) => Some(dir),
+            None => {
+                eprintln!(
+                    "warning: default_book `{}` is not a valid address book directory name",
+                    name
+                );
+                None
+            }
+        });
+
+    let email_case = match cfg_file.email_case.as_deref() {
+        Some(value) => EmailCase::from_str(value).unwrap_or_else(|| {
+            eprintln!("warning: unknown email_case `{}` (expected all or domain)", value);
+            EmailCase::default()
+        }),
+        None => EmailCase::default(),
+    };
+
+    let single_value_enter = match cfg_file.single_value_enter.as_deref() {
+        Some(value) => SingleValueEnter::from_str(value).unwrap_or_else(|| {
+            eprintln!(
+                "warning: unknown single_value_enter `{}` (expected copy, edit or nothing)",
+                value
+            );
+            SingleValueEnter::default()
+        }),
+        None => SingleValueEnter::default(),
+    };
+
+    let multivalue_sort = match cfg_file.multivalue_sort.as_deref() {
+        Some(value) => MultivalueSort::from_str(value).unwrap_or_else(|| {
+            eprintln!(
+                "warning: unknown multivalue_sort `{}` (expected seq, type or default_first)",
+                value
+            );
+            MultivalueSort::default()
+        }),
+        None => MultivalueSort::default(),
+    };
+
+    let startup = match cfg_file.startup.as_deref() {
+        Some(value) => Startup::from_str(value).unwrap_or_else(|| {
+            eprintln!("warning: unknown startup `{}` (expected search, last or first)", value);
+            Startup::default()
+        }),
+        None => Startup::default(),
+    };
+
+    let on_decrypt_error = match cfg_file.on_decrypt_error.as_deref() {
+        Some(value) => OnDecryptError::from_str(value).unwrap_or_else(|| {
+            eprintln!("warning: unknown on_decrypt_error `{}` (expected abort or skip)", value);
+            OnDecryptError::default()
+        }),
+        None => OnDecryptError::default(),
+    };
+
+    let mut flag_incomplete = Vec::new();
+    for name in &cfg_file.flag_incomplete {
+        match ExpectedField::from_str(name) {
+            Some(field) if !flag_incomplete.contains(&field) => flag_incomplete.push(field),
+            Some(_) => {}
+            None => eprintln!(
+                "warning: unknown flag_incomplete field `{}` (expected email or phone)",
+                name
+            ),
+        }
+    }
+
+    let mut duplicate_clear = Vec::new();
+    for name in &cfg_file.duplicate_clear {
+        match ClearedField::from_str(name) {
+            Some(field) if !duplicate_clear.contains(&field) => duplicate_clear.push(field),
+            Some(_) => {}
+            None => eprintln!(
+                "warning: unknown duplicate_clear field `{}` (expected name, email or phone)",
+                name
+            ),
+        }
+    }
+
+    let keymap = cfg_file.keymap.as_deref().unwrap_or("default");
+    let preset = Keys::preset(keymap).ok_or_else(|| {
+        anyhow!(
+            "invalid keymap '{}', expected one of: {}",
+            keymap,
+            KEYMAP_PRESETS.join(", ")
+        )
+    })?;
+    let keys = cfg_file.keys.into_keys(preset);
 
     // Validate key bindings for collisions
     validate_key_bindings(&keys)?;
@@ -1640,9 +2563,12 @@ pub fn load_from(custom_path: Option<&Path>) -> Result<Config> {
     // Parse and validate encryption config
     let encryption = cfg_file
         .encryption
-        .into_config()
+        .into_config(&config_dir)
         .with_context(|| "failed to parse encryption configuration")?;
 
+    let copy_transform: CopyTransformConfig = cfg_file.copy_transform.into();
+    let impp_templates: ImppTemplates = cfg_file.impp_templates.into();
+
     // Parse sync config
     let sync: SyncConfig = cfg_file.sync.into();
 
@@ -1650,7 +2576,7 @@ pub fn load_from(custom_path: Option<&Path>) -> Result<Config> {
     let mut remotes = Vec::new();
     for (i, remote_file) in cfg_file.remotes.into_iter().enumerate() {
         let remote = remote_file
-            .into_config()
+            .into_config(&config_dir)
             .with_context(|| format!("failed to parse remote configuration at index {}", i))?;
         remotes.push(remote);
     }
@@ -1663,21 +2589,59 @@ pub fn load_from(custom_path: Option<&Path>) -> Result<Config> {
         }
     }
 
+    if !(0.0..=1.0).contains(&cfg_file.duplicate_similarity) {
+        bail!("duplicate_similarity must be between 0.0 and 1.0");
+    }
+
     // Parse details sections config
-    let details_sections: DetailsSectionsConfig = cfg_file.details_sections.into();
+    let mut details_sections: DetailsSectionsConfig = cfg_file.details_sections.into();
+    if let Some(name) = cfg_file
+        .untyped_details_section
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+    {
+        if !details_sections.sections.iter().any(|s| s.name == name) {
+            eprintln!(
+                "warning: untyped_details_section `{}` does not name a details section",
+                name
+            );
+        }
+        details_sections.untyped_section = Some(name);
+    }
 
     Ok(Config {
         config_path: path,
         vdir,
+        vdir_ignore,
         db_path,
         fields_first_pane: cfg_file.fields_first_pane,
         phone_region,
+        phone_normalize: cfg_file.phone_normalize,
+        review_duplicates_on_startup: cfg_file.review_duplicates_on_startup,
+        duplicate_similarity: cfg_file.duplicate_similarity,
+        nameless_display,
+        fill_missing_fn: cfg_file.fill_missing_fn,
+        track_field_sources: cfg_file.track_field_sources,
+        default_book,
+        create_on_no_match: cfg_file.create_on_no_match,
+        max_embedded_photo_bytes: cfg_file.max_embedded_photo_bytes,
+        edit_backup: cfg_file.edit_backup,
+        edit_backup_keep: cfg_file.edit_backup_keep.max(1),
+        email_case,
+        single_value_enter,
+        multivalue_sort,
+        startup,
+        on_decrypt_error,
+        flag_incomplete,
+        duplicate_clear,
         keys,
         ui: cfg_file.ui.into(),
         commands: cfg_file.commands.into(),
         top_bar: cfg_file.top_bar.into(),
         maildir_import: cfg_file.maildir_import.into(),
         encryption,
+        copy_transform,
+        impp_templates,
         sync,
         details_sections,
         remotes,
@@ -1695,17 +2659,40 @@ fn warn_unknown_keys(value: &toml::Value) {
 
     let known = HashSet::from([
         "vdir".to_string(),
+        "vdir_ignore".to_string(),
         "db_path".to_string(),
         "fields_first_pane".to_string(),
         "phone_region".to_string(),
+        "phone_normalize".to_string(),
+        "keymap".to_string(),
+        "review_duplicates_on_startup".to_string(),
+        "duplicate_similarity".to_string(),
+        "nameless_display".to_string(),
+        "fill_missing_fn".to_string(),
+        "track_field_sources".to_string(),
+        "default_book".to_string(),
+        "create_on_no_match".to_string(),
+        "max_embedded_photo_bytes".to_string(),
+        "edit_backup".to_string(),
+        "edit_backup_keep".to_string(),
+        "email_case".to_string(),
+        "single_value_enter".to_string(),
+        "multivalue_sort".to_string(),
+        "startup".to_string(),
+        "on_decrypt_error".to_string(),
+        "flag_incomplete".to_string(),
+        "duplicate_clear".to_string(),
         "keys".to_string(),
         "ui".to_string(),
         "commands".to_string(),
+        "copy_transform".to_string(),
+        "impp_templates".to_string(),
         "top_bar".to_string(),
         "maildir_import".to_string(),
         "encryption".to_string(),
         "sync".to_string(),
         "details_sections".to_string(),
+        "untyped_details_section".to_string(),
         "remotes".to_string(),
     ]);
 
@@ -1735,6 +2722,10 @@ fn warn_unknown_keys(value: &toml::Value) {
         warn_unknown_encryption_keys(encryption_val);
     }
 
+    if let Some(copy_transform_val) = table.get("copy_transform") {
+        warn_unknown_copy_transform_keys(copy_transform_val);
+    }
+
     if let Some(sync_val) = table.get("sync") {
         warn_unknown_sync_keys(sync_val);
     }
@@ -1765,7 +2756,7 @@ fn warn_unknown_keys_section(value: &toml::Value) {
     }
 
     if let Some(v) = table.get("global") {
-        warn_unknown_in_context(v, "global", &["quit", "search", "help"]);
+        warn_unknown_in_context(v, "global", &["quit", "search", "help", "recent"]);
     }
     if let Some(v) = table.get("search_input") {
         warn_unknown_in_context(v, "search_input", &["cancel", "confirm"]);
@@ -1784,6 +2775,9 @@ fn warn_unknown_keys_section(value: &toml::Value) {
                 "mark",
                 "merge",
                 "toggle_marked",
+                "tag_marked",
+                "compare",
+                "skip_group",
             ],
         );
     }
@@ -1796,9 +2790,27 @@ fn warn_unknown_keys_section(value: &toml::Value) {
                 "prev",
                 "edit",
                 "copy",
+                "copy_uri",
+                "edit_label",
                 "confirm",
                 "add_field",
                 "delete_field",
+                "split_name",
+                "clear_filter",
+                "toggle_filter",
+                "toggle_empty",
+                "toggle_nosync",
+                "open_path",
+                "copy_uid",
+                "copy_path",
+                "clean_emails",
+                "toggle_notes",
+                "expand",
+                "toggle_book",
+                "toggle_sources",
+                "move_contact_up",
+                "move_contact_down",
+                "duplicate",
                 "photo_fetch",
                 "lang_cycle",
             ],
@@ -1808,7 +2820,19 @@ fn warn_unknown_keys_section(value: &toml::Value) {
         warn_unknown_in_context(
             v,
             "modal",
-            &["cancel", "confirm", "next", "prev", "edit", "copy", "set_default"],
+            &[
+                "cancel",
+                "confirm",
+                "next",
+                "prev",
+                "edit",
+                "copy",
+                "copy_uri",
+                "edit_label",
+                "set_default",
+                "move_up",
+                "move_down",
+            ],
         );
     }
     if let Some(v) = table.get("editor") {
@@ -1837,6 +2861,7 @@ fn warn_unknown_ui_keys(value: &toml::Value) {
         "colors".to_string(),
         "icons".to_string(),
         "pane".to_string(),
+        "share".to_string(),
     ]);
 
     for key in table.keys() {
@@ -1854,6 +2879,9 @@ fn warn_unknown_ui_keys(value: &toml::Value) {
     if let Some(pane_val) = table.get("pane") {
         warn_unknown_ui_pane(pane_val);
     }
+    if let Some(share_val) = table.get("share") {
+        warn_unknown_ui_share(share_val);
+    }
 }
 
 fn warn_unknown_ui_colors(value: &toml::Value) {
@@ -1911,7 +2939,7 @@ fn warn_unknown_ui_pane_image(value: &toml::Value) {
     let Some(table) = value.as_table() else {
         return;
     };
-    let known = HashSet::from(["width".to_string(), "height".to_string()]);
+    let known = HashSet::from(["width".to_string(), "height".to_string(), "mode".to_string()]);
     for key in table.keys() {
         if !known.contains(key) {
             eprintln!("warning: unknown ui.pane.image entry `{}`", key);
@@ -1919,11 +2947,23 @@ fn warn_unknown_ui_pane_image(value: &toml::Value) {
     }
 }
 
+fn warn_unknown_ui_share(value: &toml::Value) {
+    let Some(table) = value.as_table() else {
+        return;
+    };
+    let known = HashSet::from(["qr_error_correction".to_string()]);
+    for key in table.keys() {
+        if !known.contains(key) {
+            eprintln!("warning: unknown ui.share entry `{}`", key);
+        }
+    }
+}
+
 fn warn_unknown_commands_keys(value: &toml::Value) {
     let Some(table) = value.as_table() else {
         return;
     };
-    let known = HashSet::from(["copy".to_string()]);
+    let known = HashSet::from(["copy".to_string(), "open_path".to_string()]);
     for key in table.keys() {
         if !known.contains(key) {
             eprintln!("warning: unknown commands entry `{}`", key);
@@ -1957,7 +2997,9 @@ fn warn_unknown_encryption_keys(value: &toml::Value) {
     let known = HashSet::from([
         "type".to_string(),
         "gpg_key_id".to_string(),
+        "gpg_key_ids".to_string(),
         "age_identity".to_string(),
+        "age_identity_cmd".to_string(),
         "age_recipient".to_string(),
     ]);
     for key in table.keys() {
@@ -1967,6 +3009,18 @@ fn warn_unknown_encryption_keys(value: &toml::Value) {
     }
 }
 
+fn warn_unknown_copy_transform_keys(value: &toml::Value) {
+    let Some(table) = value.as_table() else {
+        return;
+    };
+    let known = HashSet::from(["phone".to_string(), "email".to_string()]);
+    for key in table.keys() {
+        if !known.contains(key) {
+            eprintln!("warning: unknown copy_transform entry `{}`", key);
+        }
+    }
+}
+
 fn warn_unknown_sync_keys(value: &toml::Value) {
     let Some(table) = value.as_table() else {
         return;
@@ -1993,8 +3047,10 @@ fn warn_unknown_remotes_keys(value: &toml::Value) {
         "password".to_string(),
         "password_file".to_string(),
         "password_cmd".to_string(),
+        "password_cmd_timeout".to_string(),
         "conflict_prefer".to_string(),
         "local_book".to_string(),
+        "categories_as_books".to_string(),
     ]);
 
     for (i, item) in arr.iter().enumerate() {
@@ -2012,22 +3068,13 @@ fn warn_unknown_remotes_keys(value: &toml::Value) {
 // UI config types
 // =============================================================================
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Default)]
 #[serde(default)]
 struct UiFile {
     colors: UiColorsFile,
     icons: UiIconsFile,
     pane: UiPaneFile,
-}
-
-impl Default for UiFile {
-    fn default() -> Self {
-        Self {
-            colors: UiColorsFile::default(),
-            icons: UiIconsFile::default(),
-            pane: UiPaneFile::default(),
-        }
-    }
+    share: UiShareFile,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -2072,25 +3119,18 @@ impl Default for UiIconsFile {
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Default)]
 #[serde(default)]
 struct UiPaneFile {
     image: UiPaneImageFile,
 }
 
-impl Default for UiPaneFile {
-    fn default() -> Self {
-        Self {
-            image: UiPaneImageFile::default(),
-        }
-    }
-}
-
 #[derive(Debug, Clone, Deserialize)]
 #[serde(default)]
 struct UiPaneImageFile {
     width: u16,
     height: u16,
+    mode: Option<String>,
 }
 
 impl Default for UiPaneImageFile {
@@ -2098,10 +3138,17 @@ impl Default for UiPaneImageFile {
         Self {
             width: 40,
             height: 12,
+            mode: None,
         }
     }
 }
 
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+struct UiShareFile {
+    qr_error_correction: Option<String>,
+}
+
 impl From<UiFile> for UiConfig {
     fn from(file: UiFile) -> Self {
         let image_width = if file.pane.image.width == 0 {
@@ -2114,6 +3161,26 @@ impl From<UiFile> for UiConfig {
         } else {
             file.pane.image.height
         };
+        let image_mode = match file.pane.image.mode.as_deref() {
+            None => ImageMode::Auto,
+            Some(mode) => ImageMode::from_str(mode).unwrap_or_else(|| {
+                eprintln!(
+                    "warning: unknown ui.pane.image.mode `{}` (expected auto, blocks or none)",
+                    mode
+                );
+                ImageMode::Auto
+            }),
+        };
+        let qr_error_correction = match file.share.qr_error_correction.as_deref() {
+            None => QrErrorCorrection::M,
+            Some(level) => QrErrorCorrection::from_str(level).unwrap_or_else(|| {
+                eprintln!(
+                    "warning: unknown ui.share.qr_error_correction `{}` (expected L, M, Q or H)",
+                    level
+                );
+                QrErrorCorrection::M
+            }),
+        };
         Self {
             colors: UiColors {
                 border: file.colors.border,
@@ -2132,8 +3199,10 @@ impl From<UiFile> for UiConfig {
                 image: UiPaneImage {
                     width: image_width,
                     height: image_height,
+                    mode: image_mode,
                 },
             },
+            share: UiShare { qr_error_correction },
         }
     }
 }
@@ -2142,16 +3211,11 @@ impl From<UiFile> for UiConfig {
 // Commands config
 // =============================================================================
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Default)]
 #[serde(default)]
 struct CommandsFile {
     copy: Option<CommandDef>,
-}
-
-impl Default for CommandsFile {
-    fn default() -> Self {
-        Self { copy: None }
-    }
+    open_path: Option<CommandDef>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -2165,11 +3229,29 @@ impl From<CommandsFile> for Commands {
     fn from(file: CommandsFile) -> Self {
         Self {
             copy: file.copy.and_then(CommandExec::from_def),
+            open_path: file.open_path.and_then(CommandExec::from_def),
         }
     }
 }
 
 impl CommandExec {
+    /// Arguments with `{path}` and `{dir}` replaced by `path` and its parent
+    /// directory; when neither placeholder appears, `path` is appended.
+    pub fn with_path(&self, path: &Path) -> Vec<String> {
+        let file = path.to_string_lossy();
+        let dir = path.parent().unwrap_or(path).to_string_lossy();
+        let placeholder = |arg: &String| arg.contains("{path}") || arg.contains("{dir}");
+        let mut args: Vec<String> = self
+            .args
+            .iter()
+            .map(|arg| arg.replace("{path}", &file).replace("{dir}", &dir))
+            .collect();
+        if !self.args.iter().any(placeholder) {
+            args.push(file.into_owned());
+        }
+        args
+    }
+
     fn from_def(def: CommandDef) -> Option<Self> {
         match def {
             CommandDef::Simple(cmd) => {
@@ -2343,4 +3425,232 @@ mod tests {
         assert!(!config.is_valid_nickname_for_merge("John")); // too short
         assert!(!config.is_valid_nickname_for_merge("Joe")); // too short
     }
+
+    #[test]
+    fn test_unknown_first_pane_fields() {
+        let fields: Vec<String> = ["fname", " Email ", "emial", "PHONE", "org"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(unknown_first_pane_fields(&fields), vec!["emial", "org"]);
+        assert!(unknown_first_pane_fields(&default_fields_first_pane()).is_empty());
+    }
+
+    #[test]
+    fn test_keymap_presets_are_collision_free() {
+        for name in KEYMAP_PRESETS {
+            let keys = Keys::preset(name).unwrap();
+            validate_key_bindings(&keys).unwrap_or_else(|e| panic!("{name}: {e}"));
+        }
+        assert!(Keys::preset("VIM").is_some());
+        assert!(Keys::preset("helix").is_none());
+    }
+
+    #[test]
+    fn test_modifier_bindings_normalize_and_collide() {
+        assert_eq!(normalize_binding("Ctrl+e"), "ctrl+e");
+        assert_eq!(normalize_binding("alt+CTRL+X"), "ctrl+alt+X");
+        assert_eq!(normalize_binding("Control+Enter"), "ctrl+enter");
+        assert_eq!(normalize_binding("Shift+Tab"), "shift+tab");
+        assert_eq!(split_binding_modifiers("Ctrl++"), (BindingModifiers { ctrl: true, alt: false }, "+"));
+        assert_eq!(split_binding_modifiers("+"), (BindingModifiers::default(), "+"));
+
+        let edit = vec!["Ctrl+e".to_string()];
+        let other = vec!["ctrl+e".to_string()];
+        let plain = vec!["e".to_string()];
+        assert!(check_context_collisions(&[("edit", &edit), ("copy", &plain)], "navigation").is_ok());
+        assert!(check_context_collisions(&[("edit", &edit), ("copy", &other)], "navigation").is_err());
+
+        let quit = vec!["Ctrl+c".to_string()];
+        let err = check_context_collisions(&[("quit", &quit)], "global").unwrap_err();
+        assert!(err.to_string().contains("Ctrl+C always quits"));
+    }
+
+    #[test]
+    fn test_explicit_keys_override_preset() {
+        let file: KeysFile = toml::from_str(
+            r#"
+            [navigation]
+            edit = "E"
+            "#,
+        )
+        .unwrap();
+        let keys = file.into_keys(Keys::preset("vim").unwrap());
+        assert_eq!(keys.navigation.edit, vec!["E".to_string()]);
+        // Untouched entries keep the preset bindings
+        assert_eq!(keys.navigation.add_field, vec!["o".to_string()]);
+        assert_eq!(keys.global.quit, vec!["q".to_string()]);
+    }
+
+    #[test]
+    fn test_ui_share_error_correction() {
+        let file: UiFile = toml::from_str("[share]\nqr_error_correction = \"q\"").unwrap();
+        let ui = UiConfig::from(file);
+        assert_eq!(ui.share.qr_error_correction, QrErrorCorrection::Q);
+        assert_eq!(ui.share.qr_error_correction.max_bytes(), 1663);
+
+        let ui = UiConfig::from(UiFile::default());
+        assert_eq!(ui.share.qr_error_correction, QrErrorCorrection::M);
+    }
+
+    #[test]
+    fn test_ui_pane_image_mode() {
+        let file: UiFile = toml::from_str("[pane.image]\nmode = \"None\"").unwrap();
+        assert_eq!(UiConfig::from(file).pane.image.mode, ImageMode::None);
+
+        let file: UiFile = toml::from_str("[pane.image]\nmode = \"sixel\"").unwrap();
+        assert_eq!(UiConfig::from(file).pane.image.mode, ImageMode::Auto);
+    }
+
+    #[test]
+    fn test_section_type_qualifiers() {
+        let file: DetailsSectionsFile = toml::from_str(
+            r#"
+            Work = ["email;type=work", "TEL;TYPE=work"]
+            Home = ["EMAIL;TYPE=home", "X-*"]
+            "#,
+        )
+        .unwrap();
+        let config = DetailsSectionsConfig::from(file);
+        let home = &config.sections[0];
+        let work = &config.sections[1];
+        let types = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+
+        assert!(work.matches("EMAIL", &types(&["WORK"]), false));
+        assert!(work.matches("email", &types(&["X-BUSINESS"]), false));
+        assert!(work.matches("TEL", &types(&["CELL", "business"]), false));
+        assert!(!work.matches("EMAIL", &types(&["HOME"]), false));
+        assert!(home.matches("EMAIL", &types(&["PERSONAL"]), false));
+        assert!(home.matches("X-SIGNAL", &[], false));
+
+        // Untyped values only land in the section chosen for them
+        assert!(!work.matches("EMAIL", &[], false));
+        assert!(work.matches("EMAIL", &[], true));
+        assert!(!work.matches("ADR", &[], true));
+    }
+
+    #[test]
+    fn test_relative_paths_follow_config_dir() {
+        let dir = tempfile::TempDir::new().unwrap();
+        fs::write(dir.path().join("identity.txt"), "AGE-SECRET-KEY-1").unwrap();
+        let file: EncryptionFile = toml::from_str(
+            "type = \"age\"\nage_identity = \"identity.txt\"\nage_recipient = \"age1x\"",
+        )
+        .unwrap();
+        let config = file.into_config(dir.path()).unwrap();
+        assert_eq!(config.age_identity, Some(dir.path().join("identity.txt")));
+
+        assert_eq!(
+            resolve_config_path(Path::new("/etc/rldx"), Path::new("contacts")),
+            PathBuf::from("/etc/rldx/contacts")
+        );
+        assert_eq!(
+            resolve_config_path(Path::new("/etc/rldx"), Path::new("/srv/contacts")),
+            PathBuf::from("/srv/contacts")
+        );
+    }
+
+    #[test]
+    fn test_gpg_key_id_forms() {
+        let single: EncryptionFile =
+            toml::from_str("type = \"gpg\"\ngpg_key_id = \" A \"").unwrap();
+        assert_eq!(single.into_config(Path::new("/")).unwrap().gpg_key_id.as_deref(), Some("A"));
+
+        // Several recipients could not decrypt a book keyed from one of them
+        let list: EncryptionFile =
+            toml::from_str("type = \"gpg\"\ngpg_key_ids = [\"A\", \"B\"]").unwrap();
+        let err = list.into_config(Path::new("/")).unwrap_err();
+        assert!(err.to_string().contains("gpg_key_ids is not supported"), "{err}");
+
+        let empty: EncryptionFile = toml::from_str("type = \"gpg\"\ngpg_key_id = \"\"").unwrap();
+        assert!(empty.into_config(Path::new("/")).is_err());
+    }
+
+    #[test]
+    fn test_impp_templates_fill_handles() {
+        let file: HashMap<String, String> = toml::from_str(
+            r#"
+            Telegram = "https://t.me/{handle}"
+            xmpp = "xmpp:{handle}?message"
+            skype = "https://example.com/skype"
+            "#,
+        )
+        .unwrap();
+        let templates = ImppTemplates::from(file);
+
+        assert_eq!(
+            templates.link("telegram:@jane", None),
+            Some(("telegram".to_string(), "https://t.me/jane".to_string()))
+        );
+        assert_eq!(
+            templates.link("xmpp:jane@example.org", None),
+            Some(("xmpp".to_string(), "xmpp:jane@example.org?message".to_string()))
+        );
+        // Apple names the service in a parameter and may store a bare handle
+        assert_eq!(
+            templates.link("x-apple:jane", Some("Telegram")),
+            Some(("Telegram".to_string(), "https://t.me/jane".to_string()))
+        );
+        assert_eq!(
+            templates.link("jane", Some("telegram")),
+            Some(("telegram".to_string(), "https://t.me/jane".to_string()))
+        );
+
+        // Unknown services, templates without a placeholder and bare handles
+        // without a service are shown raw
+        assert_eq!(templates.link("sip:jane@example.org", None), None);
+        assert_eq!(templates.link("skype:jane", None), None);
+        assert_eq!(templates.link("jane", None), None);
+    }
+
+    #[test]
+    fn test_password_cmd_timeout_and_stderr() {
+        let source = |cmd: &str, secs: u64| PasswordSource::Command {
+            cmd: cmd.to_string(),
+            timeout: Some(Duration::from_secs(secs)),
+        };
+
+        assert_eq!(source("echo hunter2", 5).get_password().unwrap(), "hunter2");
+
+        let started = Instant::now();
+        let err = source("sleep 10", 1).get_password().unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(format!("{:#}", err).contains("timed out after 1s"));
+
+        // What the shell started goes with it, and a background process
+        // holding the output open does not keep the caller waiting
+        let temp = tempfile::TempDir::new().unwrap();
+        let marker = temp.path().join("marker");
+        let started = Instant::now();
+        let cmd = format!("(sleep 2; touch '{}') & wait", marker.display());
+        assert!(source(&cmd, 1).get_password().is_err());
+        let err = source("sleep 10 & echo hunter2", 1).get_password().unwrap_err();
+        assert!(format!("{:#}", err).contains("timed out after 1s"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+        thread::sleep(Duration::from_secs(2));
+        assert!(!marker.exists());
+
+        let err = source("echo 'no tty for pinentry' >&2; exit 2", 5).get_password().unwrap_err();
+        assert!(err.to_string().contains("no tty for pinentry"));
+
+        let err = source("true", 5).get_password().unwrap_err();
+        assert!(err.to_string().contains("printed no password"));
+    }
+
+    #[test]
+    fn test_open_path_arguments() {
+        let path = Path::new("/vdir/Work/jane.vcf.age");
+        let commands: CommandsFile =
+            toml::from_str("open_path = [\"xdg-open\", \"{dir}\"]").unwrap();
+        let command = Commands::from(commands).open_path.unwrap();
+        assert_eq!(command.program, "xdg-open");
+        assert_eq!(command.with_path(path), vec!["/vdir/Work".to_string()]);
+
+        let commands: CommandsFile = toml::from_str("open_path = [\"open\", \"-R\"]").unwrap();
+        let command = Commands::from(commands).open_path.unwrap();
+        assert_eq!(
+            command.with_path(path),
+            vec!["-R".to_string(), "/vdir/Work/jane.vcf.age".to_string()]
+        );
+    }
 }
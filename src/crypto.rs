@@ -2,9 +2,11 @@
 //!
 //! Supports two backends:
 //! - GPG: Derives a symmetric key from the GPG key fingerprint using HKDF-SHA256,
-//!        then uses orion's AEAD (XChaCha20-Poly1305) for fast file encryption.
-//!        Files are stored as .vcf.age (text format with magic header).
+//!   then uses orion's AEAD (XChaCha20-Poly1305) for fast file encryption.
+//!   Files are stored as .vcf.age (text format with magic header).
 //! - Age: Modern encryption using X25519 keys, stores files as .vcf.age
+//! - None: No encryption at all, for tests and throwaway books. Files are
+//!   cleartext .vcf vCards, and the index is plain SQLite.
 
 use std::io::{Read, Write};
 use std::path::PathBuf;
@@ -14,7 +16,8 @@ use anyhow::{anyhow, bail, Context, Result};
 use hkdf::Hkdf;
 use sha2::Sha256;
 
-use crate::config::{EncryptionConfig, EncryptionType};
+use crate::config::{run_shell_command, EncryptionConfig, EncryptionType};
+use crate::vdir::EditBackup;
 
 // =============================================================================
 // CryptoProvider Trait
@@ -29,11 +32,18 @@ pub trait CryptoProvider: Send + Sync {
     fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>>;
 
     /// Derive a deterministic key for SQLCipher database encryption
-    /// The key should be derived from the encryption key material
-    fn derive_db_key(&self) -> Result<String>;
+    /// The key should be derived from the encryption key material.
+    /// `None` leaves the database unencrypted.
+    fn derive_db_key(&self) -> Result<Option<String>>;
 
     /// Get the encryption type
     fn encryption_type(&self) -> EncryptionType;
+
+    /// Where files written through this provider are backed up before they
+    /// change (the `edit_backup` setting), if anywhere
+    fn edit_backup(&self) -> Option<&EditBackup> {
+        None
+    }
 }
 
 // =============================================================================
@@ -60,15 +70,15 @@ const GPG_CHACHA_MAGIC: &[u8] = b"RLDX-GPG-CHACHA20\n";
 pub struct GpgProvider {
     /// The derived secret key for orion AEAD (cached at construction)
     secret_key: orion::aead::SecretKey,
-    /// The GPG key fingerprint (cached for DB key derivation)
+    /// The recipient key material (cached for DB key derivation)
     fingerprint: String,
 }
 
 impl GpgProvider {
-    pub fn new(key_id: String) -> Result<Self> {
+    pub fn new(key_id: &str) -> Result<Self> {
         // Verify GPG is available and key exists
         let output = Command::new("gpg")
-            .args(["--list-keys", &key_id])
+            .args(["--list-keys", key_id])
             .output()
             .context("failed to execute gpg - is GPG installed?")?;
 
@@ -81,8 +91,8 @@ impl GpgProvider {
         }
 
         // Get and cache the fingerprint
-        let fingerprint = Self::fetch_key_fingerprint(&key_id)?;
-        
+        let fingerprint = Self::fetch_key_fingerprint(key_id)?;
+
         // Derive the encryption key using HKDF-SHA256
         let secret_key = Self::derive_secret_key(&fingerprint)?;
 
@@ -151,6 +161,7 @@ impl CryptoProvider for GpgProvider {
     fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
         // Check for our magic header
         if !ciphertext.starts_with(GPG_CHACHA_MAGIC) {
+            log::debug!("ciphertext lacks the GPG-ChaCha20 header ({} bytes)", ciphertext.len());
             bail!("invalid file format: missing GPG-ChaCha20 header");
         }
         
@@ -167,7 +178,7 @@ impl CryptoProvider for GpgProvider {
             .map_err(|_| anyhow!("decryption failed - invalid key or corrupted data"))
     }
 
-    fn derive_db_key(&self) -> Result<String> {
+    fn derive_db_key(&self) -> Result<Option<String>> {
         // Use HKDF-SHA256 for DB key derivation (separate from file encryption key)
         let hk = Hkdf::<Sha256>::new(Some(HKDF_SALT_DB), self.fingerprint.as_bytes());
         
@@ -177,7 +188,7 @@ impl CryptoProvider for GpgProvider {
             .map_err(|_| anyhow!("HKDF expansion failed for DB key"))?;
 
         // Convert to hex string for SQLCipher PRAGMA key
-        Ok(format!("x'{}'", hex_encode(&okm)))
+        Ok(Some(format!("x'{}'", hex_encode(&okm))))
     }
 
     fn encryption_type(&self) -> EncryptionType {
@@ -189,9 +200,17 @@ impl CryptoProvider for GpgProvider {
 // Age Provider
 // =============================================================================
 
+/// Where the age provider obtains its identities
+enum IdentitySource {
+    /// Identity file, read on each use
+    File(PathBuf),
+    /// Identities obtained once from `age_identity_cmd`
+    Loaded(Vec<age::x25519::Identity>),
+}
+
 /// Age encryption provider using the age library
 pub struct AgeProvider {
-    identity_path: PathBuf,
+    identity: IdentitySource,
     recipient: String,
 }
 
@@ -205,18 +224,44 @@ impl AgeProvider {
             );
         }
 
-        // Validate recipient format
+        Self::validate_recipient(&recipient)?;
+
+        Ok(Self {
+            identity: IdentitySource::File(identity_path),
+            recipient,
+        })
+    }
+
+    /// Create a provider whose identity is printed by `cmd` (e.g., "pass show age-key").
+    /// The command runs once here so the key never touches disk.
+    pub fn from_command(cmd: &str, recipient: String) -> Result<Self> {
+        Self::validate_recipient(&recipient)?;
+
+        let output = run_shell_command(cmd)
+            .with_context(|| format!("failed to execute age identity command: {}", cmd))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("age identity command failed: {}", stderr.trim());
+        }
+
+        let content = String::from_utf8(output.stdout)
+            .context("age identity command produced invalid UTF-8")?;
+        let identities = parse_identities(&content, &format!("output of '{}'", cmd))?;
+
+        Ok(Self {
+            identity: IdentitySource::Loaded(identities),
+            recipient,
+        })
+    }
+
+    fn validate_recipient(recipient: &str) -> Result<()> {
         if !recipient.starts_with("age1") {
             bail!(
                 "invalid age recipient '{}' - must start with 'age1'",
                 recipient
             );
         }
-
-        Ok(Self {
-            identity_path,
-            recipient,
-        })
+        Ok(())
     }
 
     /// Create a new ephemeral AgeProvider for testing.
@@ -237,40 +282,21 @@ impl AgeProvider {
         std::fs::write(&identity_path, identity_content)?;
         
         Ok(Self {
-            identity_path,
+            identity: IdentitySource::File(identity_path),
             recipient,
         })
     }
 
-    /// Read the identity file and parse identities
+    /// Read the identity file (or cached command output) and parse identities
     fn read_identities(&self) -> Result<Vec<age::x25519::Identity>> {
-        use std::fs;
-        let content = fs::read_to_string(&self.identity_path)
-            .with_context(|| format!("failed to read identity file: {}", self.identity_path.display()))?;
-
-        let mut identities = Vec::new();
-        for line in content.lines() {
-            let line = line.trim();
-            if line.is_empty() || line.starts_with('#') {
-                continue;
-            }
-            // Parse X25519 identity
-            if line.starts_with("AGE-SECRET-KEY-") {
-                let identity: age::x25519::Identity = line
-                    .parse()
-                    .map_err(|e| anyhow!("failed to parse age identity: {}", e))?;
-                identities.push(identity);
+        match &self.identity {
+            IdentitySource::File(path) => {
+                let content = std::fs::read_to_string(path)
+                    .with_context(|| format!("failed to read identity file: {}", path.display()))?;
+                parse_identities(&content, &path.display().to_string())
             }
+            IdentitySource::Loaded(identities) => Ok(identities.clone()),
         }
-
-        if identities.is_empty() {
-            bail!(
-                "no valid age identities found in {}",
-                self.identity_path.display()
-            );
-        }
-
-        Ok(identities)
     }
 
     /// Parse recipient public key
@@ -344,7 +370,7 @@ impl CryptoProvider for AgeProvider {
         Ok(decrypted)
     }
 
-    fn derive_db_key(&self) -> Result<String> {
+    fn derive_db_key(&self) -> Result<Option<String>> {
         // Derive DB key from the first identity's public key using HKDF-SHA256
         let identities = self.read_identities()?;
         let first_identity = identities.first().context("no identities available")?;
@@ -364,7 +390,7 @@ impl CryptoProvider for AgeProvider {
             .map_err(|_| anyhow!("HKDF expansion failed for DB key"))?;
 
         // Convert to hex string for SQLCipher PRAGMA key
-        Ok(format!("x'{}'", hex_encode(&okm)))
+        Ok(Some(format!("x'{}'", hex_encode(&okm))))
     }
 
     fn encryption_type(&self) -> EncryptionType {
@@ -372,6 +398,96 @@ impl CryptoProvider for AgeProvider {
     }
 }
 
+/// Parse X25519 identities from identity file content, skipping comments.
+/// `origin` names where the content came from for error messages.
+fn parse_identities(content: &str, origin: &str) -> Result<Vec<age::x25519::Identity>> {
+    let mut identities = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        // Parse X25519 identity
+        if line.starts_with("AGE-SECRET-KEY-") {
+            let identity: age::x25519::Identity = line
+                .parse()
+                .map_err(|e| anyhow!("failed to parse age identity: {}", e))?;
+            identities.push(identity);
+        }
+    }
+
+    if identities.is_empty() {
+        bail!("no valid age identities found in {}", origin);
+    }
+
+    Ok(identities)
+}
+
+// =============================================================================
+// Plaintext Provider
+// =============================================================================
+
+/// Pass-through provider for `type = "none"`: contacts are stored in cleartext
+/// and the index database is not encrypted.
+pub struct PlainProvider;
+
+impl CryptoProvider for PlainProvider {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        Ok(plaintext.to_vec())
+    }
+
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        Ok(ciphertext.to_vec())
+    }
+
+    fn derive_db_key(&self) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    fn encryption_type(&self) -> EncryptionType {
+        EncryptionType::None
+    }
+}
+
+// =============================================================================
+// Backing-up Provider
+// =============================================================================
+
+/// Wraps another provider for `edit_backup`: encryption is left to `inner`,
+/// and writers back files up to `backup` before changing them.
+pub struct BackupProvider {
+    inner: Box<dyn CryptoProvider>,
+    backup: EditBackup,
+}
+
+impl BackupProvider {
+    pub fn new(inner: Box<dyn CryptoProvider>, backup: EditBackup) -> Self {
+        Self { inner, backup }
+    }
+}
+
+impl CryptoProvider for BackupProvider {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        self.inner.encrypt(plaintext)
+    }
+
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        self.inner.decrypt(ciphertext)
+    }
+
+    fn derive_db_key(&self) -> Result<Option<String>> {
+        self.inner.derive_db_key()
+    }
+
+    fn encryption_type(&self) -> EncryptionType {
+        self.inner.encryption_type()
+    }
+
+    fn edit_backup(&self) -> Option<&EditBackup> {
+        Some(&self.backup)
+    }
+}
+
 // =============================================================================
 // Factory function
 // =============================================================================
@@ -382,21 +498,50 @@ pub fn create_provider(config: &EncryptionConfig) -> Result<Box<dyn CryptoProvid
         EncryptionType::Gpg => {
             let key_id = config
                 .gpg_key_id
-                .clone()
-                .context("gpg_key_id is required for GPG encryption")?;
+                .as_ref()
+                .ok_or_else(|| anyhow!("gpg_key_id is required for GPG encryption"))?;
+            log::debug!("using GPG provider with key {}", key_id);
             Ok(Box::new(GpgProvider::new(key_id)?))
         }
         EncryptionType::Age => {
-            let identity_path = config
-                .age_identity
-                .clone()
-                .context("age_identity is required for age encryption")?;
             let recipient = config
                 .age_recipient
                 .clone()
                 .context("age_recipient is required for age encryption")?;
+            if let Some(ref cmd) = config.age_identity_cmd {
+                log::debug!("using age provider, identity from age_identity_cmd");
+                return Ok(Box::new(AgeProvider::from_command(cmd, recipient)?));
+            }
+            let identity_path = config
+                .age_identity
+                .clone()
+                .context("age_identity or age_identity_cmd is required for age encryption")?;
+            log::debug!("using age provider, identity from {}", identity_path.display());
             Ok(Box::new(AgeProvider::new(identity_path, recipient)?))
         }
+        EncryptionType::None => {
+            log::debug!("using plaintext provider, contacts are not encrypted");
+            Ok(Box::new(PlainProvider))
+        }
+    }
+}
+
+/// The backend whose files start like `data`: the GPG provider's magic, an
+/// age header (armored or binary) or a cleartext vCard. None for anything else.
+pub fn detect_format(data: &[u8]) -> Option<EncryptionType> {
+    let text = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+    let start = text.iter().position(|b| !b.is_ascii_whitespace()).unwrap_or(text.len());
+    let text = &text[start..];
+    if text.starts_with(GPG_CHACHA_MAGIC) {
+        Some(EncryptionType::Gpg)
+    } else if text.starts_with(b"-----BEGIN AGE ENCRYPTED FILE-----")
+        || text.starts_with(b"age-encryption.org/")
+    {
+        Some(EncryptionType::Age)
+    } else if text.len() >= 11 && text[..11].eq_ignore_ascii_case(b"BEGIN:VCARD") {
+        Some(EncryptionType::None)
+    } else {
+        None
     }
 }
 
@@ -449,6 +594,20 @@ mod tests {
         assert_eq!(decoded, data);
     }
 
+    #[test]
+    fn test_detect_format() {
+        let gpg = test_provider("TEST_FINGERPRINT_12345").encrypt(b"BEGIN:VCARD").unwrap();
+        assert_eq!(detect_format(&gpg), Some(EncryptionType::Gpg));
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let age = AgeProvider::new_ephemeral(temp_dir.path()).unwrap().encrypt(b"x").unwrap();
+        assert_eq!(detect_format(&age), Some(EncryptionType::Age));
+
+        assert_eq!(detect_format(b"\xEF\xBB\xBF\r\nbegin:vcard\r\n"), Some(EncryptionType::None));
+        assert_eq!(detect_format(b"-----BEGIN PGP MESSAGE-----"), None);
+        assert_eq!(detect_format(b""), None);
+    }
+
     /// Helper to create a test GpgProvider without requiring actual GPG
     fn test_provider(fingerprint: &str) -> GpgProvider {
         let secret_key = GpgProvider::derive_secret_key(fingerprint).unwrap();
@@ -489,6 +648,38 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_age_identity_from_command() {
+        use age::secrecy::ExposeSecret;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let identity = age::x25519::Identity::generate();
+        let recipient = identity.to_public().to_string();
+        let key_path = temp_dir.path().join("key.txt");
+        std::fs::write(
+            &key_path,
+            format!("# comment\n{}\n", identity.to_string().expose_secret()),
+        )
+        .unwrap();
+
+        let from_cmd =
+            AgeProvider::from_command(&format!("cat {}", key_path.display()), recipient.clone())
+                .unwrap();
+        let from_file = AgeProvider::new(key_path, recipient).unwrap();
+
+        // Both sources yield the same key material
+        let ciphertext = from_file.encrypt(b"BEGIN:VCARD").unwrap();
+        assert_eq!(from_cmd.decrypt(&ciphertext).unwrap(), b"BEGIN:VCARD");
+        assert_eq!(from_cmd.derive_db_key().unwrap(), from_file.derive_db_key().unwrap());
+    }
+
+    #[test]
+    fn test_age_identity_command_failure() {
+        let recipient = "age1ql3z7hjy54pw3hyww5ayyfg7zqgvc7w3j2elw8zmrj2kg5sfn9aqmcac8p";
+        assert!(AgeProvider::from_command("exit 1", recipient.to_string()).is_err());
+        assert!(AgeProvider::from_command("echo not-a-key", recipient.to_string()).is_err());
+    }
+
     #[test]
     fn test_gpg_deterministic_key_derivation() {
         // Same fingerprint should always produce the same key
@@ -502,4 +693,14 @@ mod tests {
         let decrypted = provider2.decrypt(&ciphertext).unwrap();
         assert_eq!(decrypted, plaintext);
     }
+
+    #[test]
+    fn test_plain_provider_passes_through() {
+        let provider = PlainProvider;
+        let card = b"BEGIN:VCARD\r\nVERSION:4.0\r\nEND:VCARD\r\n";
+
+        assert_eq!(provider.encrypt(card).unwrap(), card);
+        assert_eq!(provider.decrypt(card).unwrap(), card);
+        assert!(provider.derive_db_key().unwrap().is_none());
+    }
 }
@@ -1,12 +1,33 @@
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use anyhow::{Context, Result};
-use rusqlite::{params, Connection, Row, TransactionBehavior};
+use bktree::BkTree;
+use rusqlite::{params, Connection, OptionalExtension, Row, Transaction, TransactionBehavior};
 use serde_json::Value;
+use strsim::jaro_winkler;
 
+use crate::indexer;
 use crate::search;
+use crate::vcard_io;
+
+/// How long a handle waits for another process's lock before giving up
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Create `dir` and any missing parents; new directories are owner-only on Unix
+/// since the index holds contact data.
+fn create_private_dir_all(dir: &Path) -> std::io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder.create(dir)
+}
 
 /// Compute SimHash for a normalized string (for fuzzy matching)
 pub fn compute_simhash(text: &str) -> u64 {
@@ -24,6 +45,10 @@ pub struct IndexedItem {
     pub sha1: Vec<u8>,
     pub mtime: i64,
     pub lang_pref: Option<String>,
+    /// Card carries `X-RLDX-NOSYNC` and stays out of CardDAV sync
+    pub no_sync: bool,
+    /// ORG, TITLE and ROLE as one normalized search string
+    pub work_norm: String,
 }
 
 #[derive(Debug, Clone)]
@@ -47,6 +72,30 @@ pub struct ContactListEntry {
     pub path: PathBuf,
     pub primary_org: Option<String>,
     pub kind: Option<String>,
+    pub has_email: bool,
+    pub has_phone: bool,
+    /// Manual position within the address book from `X-RLDX-SORT`
+    pub sort_position: Option<i64>,
+}
+
+/// Row returned by `list_contacts_full`: a contact with its primary EMAIL and TEL
+#[derive(Debug, Clone)]
+pub struct ContactSummary {
+    pub uuid: String,
+    pub display_fn: String,
+    pub path: PathBuf,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+}
+
+/// Row returned by `list_contacts_with_props`: a contact with the indexed
+/// values of the requested fields, in card order
+#[derive(Debug, Clone)]
+pub struct ContactProps {
+    pub uuid: String,
+    pub display_fn: String,
+    pub path: PathBuf,
+    pub props: Vec<PropRow>,
 }
 
 #[derive(Debug, Clone)]
@@ -65,6 +114,48 @@ pub struct PropRow {
     pub seq: i64,
 }
 
+impl PropRow {
+    /// PREF parameter recorded by the indexer, if any
+    pub fn pref(&self) -> Option<u64> {
+        self.params.get("pref").and_then(Value::as_u64)
+    }
+}
+
+/// The primary value of `field`: the entry with the lowest PREF, or the first
+/// one when none is marked
+pub fn primary_prop<'a>(props: &'a [PropRow], field: &str) -> Option<&'a PropRow> {
+    let entries = || props.iter().filter(move |p| p.field == field);
+    entries()
+        .filter_map(|p| p.pref().map(|pref| (pref, p)))
+        .min_by_key(|(pref, _)| *pref)
+        .map(|(_, p)| p)
+        .or_else(|| entries().next())
+}
+
+/// Columns read by `row_to_list_entry`, selected from `items`
+const LIST_ENTRY_COLUMNS: &str = "uuid, fn, path,
+       (SELECT value FROM props p WHERE p.uuid = items.uuid AND p.field = 'ORG' ORDER BY seq LIMIT 1),
+       (SELECT value FROM props p WHERE p.uuid = items.uuid AND p.field = 'KIND' ORDER BY seq LIMIT 1),
+       EXISTS (SELECT 1 FROM props p WHERE p.uuid = items.uuid AND p.field = 'EMAIL'),
+       EXISTS (SELECT 1 FROM props p WHERE p.uuid = items.uuid AND p.field = 'TEL'),
+       (SELECT CASE WHEN trim(value) GLOB '[0-9]*' AND trim(value) NOT GLOB '*[^0-9]*'
+                    THEN CAST(trim(value) AS INTEGER) END
+          FROM props p WHERE p.uuid = items.uuid AND p.field = 'X-RLDX-SORT' ORDER BY seq LIMIT 1) AS sort_position";
+
+/// Order of the contact list over `LIST_ENTRY_COLUMNS`: by address book
+/// directory, then pinned contacts by `X-RLDX-SORT`, then the rest by name.
+/// The uuid keeps pages stable between contacts of the same name.
+const LIST_ORDER: &str = "lower(rtrim(path, replace(path, '/', ''))), sort_position IS NULL, sort_position,
+       fn COLLATE NOCASE, uuid";
+
+/// Contacts matched by the query command: FN, or NICKNAME/ORG/EMAIL/TEL values
+const QUERY_MATCH: &str = "(i.fn_norm LIKE ?1
+               OR EXISTS (
+                   SELECT 1 FROM props WHERE props.uuid = i.uuid
+                     AND props.field IN ('NICKNAME', 'ORG', 'EMAIL', 'TEL')
+                     AND props.value_norm LIKE ?1
+               ))";
+
 /// Result from query_emails() for abook-compatible output
 #[derive(Debug, Clone)]
 pub struct QueryResult {
@@ -73,6 +164,9 @@ pub struct QueryResult {
     pub notes: Option<String>,
 }
 
+/// Row returned by `list_all_simhashes`: (path, display_fn, value_norm, simhash, source)
+pub type SimhashRow = (PathBuf, String, String, u64, String);
+
 /// Metadata for tracking sync state of a contact with a remote
 #[derive(Debug, Clone)]
 pub struct SyncMetadata {
@@ -82,6 +176,23 @@ pub struct SyncMetadata {
     pub remote_etag: Option<String>,
     pub last_synced: Option<i64>,
     pub local_modified: bool,
+    /// SHA1 of the local file as of the last sync (None for rows predating tracking)
+    pub synced_sha1: Option<Vec<u8>>,
+}
+
+/// What the index knows about one remote's sync state
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SyncSummary {
+    /// Contacts linked to a card on the remote
+    pub tracked: usize,
+    /// When the last sync with the remote finished (Unix seconds)
+    pub last_synced: Option<i64>,
+    /// Linked contacts edited locally since they were last synced
+    pub local_modified: usize,
+    /// Linked contacts excluded with `X-RLDX-NOSYNC`
+    pub no_sync: usize,
+    /// Contacts queued for upload
+    pub pending_uploads: usize,
 }
 
 pub struct Database {
@@ -100,13 +211,24 @@ impl Database {
 
     /// Internal: open database at path with optional encryption
     fn open_at(db_path: &Path, encryption_key: Option<&str>) -> Result<Self> {
-        // Ensure parent directory exists
-        if let Some(parent) = db_path.parent() {
-            fs::create_dir_all(parent)?;
+        if let Some(parent) = db_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            create_private_dir_all(parent).with_context(|| {
+                format!("failed to create index directory {}", parent.display())
+            })?;
         }
-        let conn = Connection::open(db_path)?;
+        let conn = Connection::open(db_path)
+            .with_context(|| format!("failed to open index {}", db_path.display()))?;
+        // Another rldx process (e.g. a sync next to the TUI) may hold the write
+        // lock; wait for it instead of failing with "database is locked".
+        // This is the C-level busy handler, so it is safe before PRAGMA key.
+        conn.busy_timeout(BUSY_TIMEOUT)?;
 
         let mut db = Self { conn };
+        log::debug!(
+            "opening index {} (encrypted: {})",
+            db_path.display(),
+            encryption_key.is_some_and(|key| !key.is_empty())
+        );
 
         // Apply SQLCipher encryption key if provided
         if let Some(key) = encryption_key {
@@ -164,7 +286,9 @@ impl Database {
               has_logo  INTEGER NOT NULL DEFAULT 0,
               sha1 BLOB NOT NULL,
               mtime INTEGER NOT NULL,
-              lang_pref TEXT
+              lang_pref TEXT,
+              no_sync INTEGER NOT NULL DEFAULT 0,
+              work_norm TEXT
             );
 
             CREATE TABLE IF NOT EXISTS props (
@@ -201,16 +325,51 @@ impl Database {
               remote_etag TEXT,
               last_synced INTEGER,
               local_modified INTEGER DEFAULT 0,
+              synced_sha1 BLOB,
+              no_sync INTEGER NOT NULL DEFAULT 0,
               PRIMARY KEY (contact_path, remote_name)
             );
             CREATE INDEX IF NOT EXISTS idx_sync_metadata_remote ON sync_metadata(remote_name);
 
+            -- Pending uploads: local changes waiting to be pushed to a remote
+            CREATE TABLE IF NOT EXISTS pending_uploads (
+              contact_path TEXT NOT NULL,
+              remote_name TEXT NOT NULL,
+              queued_at INTEGER,
+              PRIMARY KEY (contact_path, remote_name)
+            );
+            CREATE INDEX IF NOT EXISTS idx_pending_uploads_remote ON pending_uploads(remote_name);
+
+            -- When each remote last finished a sync, whether or not any card changed
+            CREATE TABLE IF NOT EXISTS sync_runs (
+              remote_name TEXT PRIMARY KEY,
+              finished_at INTEGER NOT NULL
+            );
+
+            -- Import manifest: source records an earlier import already processed
+            CREATE TABLE IF NOT EXISTS import_manifest (
+              format TEXT NOT NULL,
+              source TEXT NOT NULL,
+              record_sha1 BLOB NOT NULL,
+              imported_at INTEGER,
+              PRIMARY KEY (format, source, record_sha1)
+            );
+
+            -- UI state kept between runs (e.g. the last selected contact);
+            -- not part of the index, so reset_schema leaves it alone
+            CREATE TABLE IF NOT EXISTS app_state (
+              key   TEXT PRIMARY KEY,
+              value TEXT NOT NULL
+            );
+
         "#,
         )?;
 
         // Migration for existing DBs: ensure columns exist and backfill
         self.ensure_norm_columns()?;
+        self.ensure_sync_columns()?;
         self.backfill_norm_columns()?;
+        self.backfill_rev_sort_keys()?;
         self.backfill_simhashes()?;
         // Create indexes that depend on newly added columns
         self.conn.execute_batch(
@@ -223,12 +382,16 @@ impl Database {
     }
 
     pub fn reset_schema(&mut self) -> Result<()> {
+        log::info!("resetting index schema");
         // Drop and recreate schema from scratch
         let tx = self
             .conn
             .transaction_with_behavior(TransactionBehavior::Immediate)?;
         tx.execute_batch(
             r#"
+            DROP TABLE IF EXISTS import_manifest;
+            DROP TABLE IF EXISTS pending_uploads;
+            DROP TABLE IF EXISTS sync_runs;
             DROP TABLE IF EXISTS sync_metadata;
             DROP TABLE IF EXISTS remote_state;  -- Legacy table, keep in drop for migration
             DROP TABLE IF EXISTS simhashes;
@@ -243,6 +406,25 @@ impl Database {
         Ok(())
     }
 
+    /// A value saved with `set_state`
+    pub fn get_state(&self, key: &str) -> Result<Option<String>> {
+        let mut stmt = self.conn.prepare("SELECT value FROM app_state WHERE key = ?1")?;
+        let mut rows = stmt.query([key])?;
+        match rows.next()? {
+            Some(row) => Ok(Some(row.get(0)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn set_state(&mut self, key: &str, value: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO app_state (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+
     fn column_exists(&self, table: &str, column: &str) -> Result<bool> {
         let mut stmt = self
             .conn
@@ -265,6 +447,14 @@ impl Database {
             self.conn
                 .execute_batch("ALTER TABLE items ADD COLUMN fn_simhash INTEGER;")?;
         }
+        if !self.column_exists("items", "no_sync")? {
+            self.conn
+                .execute_batch("ALTER TABLE items ADD COLUMN no_sync INTEGER NOT NULL DEFAULT 0;")?;
+        }
+        if !self.column_exists("items", "work_norm")? {
+            self.conn
+                .execute_batch("ALTER TABLE items ADD COLUMN work_norm TEXT;")?;
+        }
         if !self.column_exists("props", "value_norm")? {
             self.conn
                 .execute_batch("ALTER TABLE props ADD COLUMN value_norm TEXT;")?;
@@ -272,6 +462,19 @@ impl Database {
         Ok(())
     }
 
+    fn ensure_sync_columns(&mut self) -> Result<()> {
+        if !self.column_exists("sync_metadata", "synced_sha1")? {
+            self.conn
+                .execute_batch("ALTER TABLE sync_metadata ADD COLUMN synced_sha1 BLOB;")?;
+        }
+        if !self.column_exists("sync_metadata", "no_sync")? {
+            self.conn.execute_batch(
+                "ALTER TABLE sync_metadata ADD COLUMN no_sync INTEGER NOT NULL DEFAULT 0;",
+            )?;
+        }
+        Ok(())
+    }
+
     fn backfill_norm_columns(&mut self) -> Result<()> {
         // items.fn_norm and fn_simhash backfill
         let items_to_update: Vec<(String, String)> = {
@@ -335,6 +538,64 @@ impl Database {
         }
         tx2.commit()?;
 
+        // items.work_norm backfill from the indexed ORG, TITLE and ROLE
+        let work_to_update: Vec<(String, String, String)> = {
+            let mut stmt3 = self.conn.prepare(
+                "SELECT i.uuid, p.field, p.value FROM items i
+                 LEFT JOIN props p ON p.uuid = i.uuid AND p.field IN ('ORG','TITLE','ROLE')
+                 WHERE i.work_norm IS NULL
+                 ORDER BY i.uuid, p.field, p.seq",
+            )?;
+            let rows3 = stmt3.query_map([], |row| {
+                let uuid: String = row.get(0)?;
+                let field: Option<String> = row.get(1)?;
+                let value: Option<String> = row.get(2)?;
+                Ok((uuid, field.unwrap_or_default(), value.unwrap_or_default()))
+            })?;
+            let mut acc = Vec::new();
+            for r in rows3 {
+                acc.push(r?);
+            }
+            acc
+        };
+        let tx3 = self
+            .conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)?;
+        {
+            let mut upd = tx3.prepare("UPDATE items SET work_norm = ?1 WHERE uuid = ?2")?;
+            for chunk in work_to_update.chunk_by(|a, b| a.0 == b.0) {
+                let norm = indexer::work_norm(chunk.iter().map(|(_, field, value)| (field.as_str(), value.as_str())));
+                upd.execute(params![norm, chunk[0].0])?;
+            }
+        }
+        tx3.commit()?;
+
+        Ok(())
+    }
+
+    /// Rewrite REVs indexed as written in the card (before they were stored
+    /// in UTC) so old and new rows sort together
+    fn backfill_rev_sort_keys(&mut self) -> Result<()> {
+        let revs: Vec<(String, String)> = {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT uuid, rev FROM items WHERE rev IS NOT NULL AND rev NOT LIKE '%Z'")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+        if revs.is_empty() {
+            return Ok(());
+        }
+        let tx = self
+            .conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)?;
+        {
+            let mut upd = tx.prepare("UPDATE items SET rev = ?1 WHERE uuid = ?2")?;
+            for (uuid, rev) in revs {
+                upd.execute(params![vcard_io::rev_text_sort_key(&rev), uuid])?;
+            }
+        }
+        tx.commit()?;
         Ok(())
     }
 
@@ -405,83 +666,24 @@ impl Database {
             .conn
             .transaction_with_behavior(TransactionBehavior::Immediate)?;
 
-        let fn_norm = search::normalize(&item.display_fn);
-        let fn_simhash = compute_simhash(&fn_norm);
-
-        tx.execute(
-            r#"
-            INSERT INTO items (uuid, path, fn, fn_norm, fn_simhash, rev, has_photo, has_logo, sha1, mtime, lang_pref)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
-            ON CONFLICT(uuid) DO UPDATE SET
-              path=excluded.path,
-              fn=excluded.fn,
-              fn_norm=excluded.fn_norm,
-              fn_simhash=excluded.fn_simhash,
-              rev=excluded.rev,
-              has_photo=excluded.has_photo,
-              has_logo=excluded.has_logo,
-              sha1=excluded.sha1,
-              mtime=excluded.mtime,
-              lang_pref=excluded.lang_pref
-        "#,
-            params![
-                item.uuid,
-                item.path.to_string_lossy(),
-                item.display_fn,
-                fn_norm,
-                fn_simhash as i64,
-                item.rev,
-                if item.has_photo { 1 } else { 0 },
-                if item.has_logo { 1 } else { 0 },
-                item.sha1,
-                item.mtime,
-                item.lang_pref,
-            ],
-        )?;
-
-        tx.execute("DELETE FROM props WHERE uuid = ?1", params![item.uuid])?;
-
-        {
-            let mut stmt = tx.prepare(
-                r#"INSERT INTO props (uuid, fn, field, value, value_norm, params, seq)
-                   VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"#,
-            )?;
-
-            for prop in props {
-                let params_json =
-                    serde_json::to_string(&prop.params).unwrap_or_else(|_| "{}".to_string());
-                stmt.execute(params![
-                    item.uuid,
-                    item.display_fn,
-                    prop.field,
-                    prop.value,
-                    search::normalize(&prop.value),
-                    params_json,
-                    prop.seq,
-                ])?;
-            }
-        }
-
-        // Update simhashes table
-        tx.execute("DELETE FROM simhashes WHERE uuid = ?1", params![item.uuid])?;
-        {
-            let mut stmt = tx.prepare(
-                "INSERT OR IGNORE INTO simhashes (uuid, simhash, source, value_norm) VALUES (?1, ?2, ?3, ?4)",
-            )?;
-
-            // Insert FN simhash
-            stmt.execute(params![item.uuid, fn_simhash as i64, "FN", fn_norm])?;
-
-            // Insert NICKNAME simhashes
-            for prop in props {
-                if prop.field == "NICKNAME" {
-                    let nick_norm = search::normalize(&prop.value);
-                    let nick_simhash = compute_simhash(&nick_norm);
-                    stmt.execute(params![item.uuid, nick_simhash as i64, "NICKNAME", nick_norm])?;
-                }
-            }
-        }
+        upsert_in(&tx, item, props)?;
+        tx.commit()?;
+        Ok(())
+    }
 
+    /// Replace the items at `old_paths` with `item` in one transaction, so a
+    /// failure leaves the index as it was (used when merging contacts)
+    pub fn replace_items(
+        &mut self,
+        old_paths: &[PathBuf],
+        item: &IndexedItem,
+        props: &[IndexedProp],
+    ) -> Result<()> {
+        let tx = self
+            .conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)?;
+        delete_paths_in(&tx, old_paths.iter().cloned())?;
+        upsert_in(&tx, item, props)?;
         tx.commit()?;
         Ok(())
     }
@@ -519,6 +721,7 @@ impl Database {
             }
         }
 
+        log::debug!("removing {} missing file(s) from the index", to_delete.len());
         for path in to_delete {
             self.conn.execute(
                 "DELETE FROM items WHERE path = ?1",
@@ -535,45 +738,137 @@ impl Database {
         let tx = self
             .conn
             .transaction_with_behavior(TransactionBehavior::Immediate)?;
-        {
-            let mut stmt = tx.prepare("DELETE FROM items WHERE path = ?1")?;
-            for path in paths {
-                let _ = stmt.execute(params![path.to_string_lossy()])?;
-            }
-        }
+        delete_paths_in(&tx, paths)?;
         tx.commit()?;
         Ok(())
     }
 
-    pub fn list_contacts(&self, filter: Option<&str>) -> Result<Vec<ContactListEntry>> {
-        let mut sql = String::from(
-            "SELECT uuid, fn, path,
-                    (SELECT value FROM props p WHERE p.uuid = items.uuid AND p.field = 'ORG' ORDER BY seq LIMIT 1),
-                    (SELECT value FROM props p WHERE p.uuid = items.uuid AND p.field = 'KIND' ORDER BY seq LIMIT 1)
-             FROM items",
+    /// Up to `limit` contacts matching `filter` (all of them without a
+    /// limit), skipping the first `offset`, in the order of the contact list
+    pub fn list_contacts(
+        &self,
+        filter: Option<&str>,
+        offset: usize,
+        limit: Option<usize>,
+    ) -> Result<Vec<ContactListEntry>> {
+        let (clause, args) = contact_filter_clause(filter);
+        let limit = limit.map_or(-1, |limit| limit as i64);
+        let sql = format!(
+            "SELECT * FROM (SELECT {LIST_ENTRY_COLUMNS} FROM items{clause})
+             ORDER BY {LIST_ORDER}
+             LIMIT {limit} OFFSET {offset}"
         );
 
-        let mut args: Vec<String> = Vec::new();
-        if let Some(filter) = filter {
-            let pattern = search::like_pattern(filter);
-            sql.push_str(
-                " WHERE fn_norm LIKE ?1 OR EXISTS (
-                    SELECT 1 FROM props WHERE props.uuid = items.uuid
-                      AND props.field IN ('NICKNAME','ORG','EMAIL','TEL')
-                      AND props.value_norm LIKE ?1
-                 )",
-            );
-            args.push(pattern);
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(&args), row_to_list_entry)?;
+
+        let mut out = Vec::new();
+        for row in rows {
+            out.push(row?);
         }
+        Ok(out)
+    }
 
-        sql.push_str(" ORDER BY fn COLLATE NOCASE");
+    /// Index of contact `uuid` in the list of contacts matching `filter`
+    pub fn contact_position(&self, filter: Option<&str>, uuid: &str) -> Result<Option<usize>> {
+        let (clause, args) = contact_filter_clause(filter);
+        let sql = format!(
+            "SELECT uuid FROM (SELECT {LIST_ENTRY_COLUMNS} FROM items{clause})
+             ORDER BY {LIST_ORDER}"
+        );
 
         let mut stmt = self.conn.prepare(&sql)?;
-        let rows = if args.is_empty() {
-            stmt.query_map([], row_to_list_entry)?
-        } else {
-            stmt.query_map([args[0].as_str()], row_to_list_entry)?
-        };
+        let rows = stmt.query_map(rusqlite::params_from_iter(&args), |row| row.get::<_, String>(0))?;
+        for (index, row) in rows.enumerate() {
+            if row? == uuid {
+                return Ok(Some(index));
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn count_contacts(&self) -> Result<usize> {
+        let count: i64 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM items", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    /// Every contact with its first EMAIL and TEL, ordered by name
+    pub fn list_contacts_full(&self) -> Result<Vec<ContactSummary>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT uuid, fn, path,
+                    (SELECT value FROM props p WHERE p.uuid = items.uuid AND p.field = 'EMAIL' ORDER BY seq LIMIT 1),
+                    (SELECT value FROM props p WHERE p.uuid = items.uuid AND p.field = 'TEL' ORDER BY seq LIMIT 1)
+             FROM items
+             ORDER BY fn COLLATE NOCASE",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(ContactSummary {
+                uuid: row.get(0)?,
+                display_fn: row.get(1)?,
+                path: PathBuf::from(row.get::<_, String>(2)?),
+                email: row.get(3)?,
+                phone: row.get(4)?,
+            })
+        })?;
+
+        let mut out = Vec::new();
+        for row in rows {
+            out.push(row?);
+        }
+        Ok(out)
+    }
+
+    /// Every contact with its indexed values of `fields`, ordered by name
+    pub fn list_contacts_with_props(&self, fields: &[&str]) -> Result<Vec<ContactProps>> {
+        let placeholders = vec!["?"; fields.len()].join(", ");
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT i.uuid, i.fn, i.path, p.field, p.value, p.params, p.seq
+             FROM items i
+             LEFT JOIN props p ON p.uuid = i.uuid AND p.field IN ({placeholders})
+             ORDER BY i.fn COLLATE NOCASE, i.uuid, p.field, p.seq"
+        ))?;
+        let mut rows = stmt.query(rusqlite::params_from_iter(fields))?;
+
+        let mut out: Vec<ContactProps> = Vec::new();
+        while let Some(row) = rows.next()? {
+            let uuid: String = row.get(0)?;
+            if out.last().map(|contact| &contact.uuid) != Some(&uuid) {
+                out.push(ContactProps {
+                    uuid,
+                    display_fn: row.get(1)?,
+                    path: PathBuf::from(row.get::<_, String>(2)?),
+                    props: Vec::new(),
+                });
+            }
+            let Some(field) = row.get::<_, Option<String>>(3)? else {
+                continue;
+            };
+            let raw: String = row.get(5)?;
+            let params = serde_json::from_str(&raw).unwrap_or_else(|_| Value::Object(Default::default()));
+            if let Some(contact) = out.last_mut() {
+                contact.props.push(PropRow {
+                    field,
+                    value: row.get(4)?,
+                    params,
+                    seq: row.get(6)?,
+                });
+            }
+        }
+        Ok(out)
+    }
+
+    /// The `limit` most recently revised contacts, newest REV first.
+    /// Cards without a REV sort last, most recently indexed file first.
+    pub fn list_recent_contacts(&self, limit: usize) -> Result<Vec<ContactListEntry>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {LIST_ENTRY_COLUMNS}
+             FROM items
+             ORDER BY rev IS NULL, rev DESC, mtime DESC
+             LIMIT ?1"
+        ))?;
+        let rows = stmt.query_map([limit as i64], row_to_list_entry)?;
 
         let mut out = Vec::new();
         for row in rows {
@@ -633,22 +928,19 @@ impl Database {
 
         // Query: for each contact matching the filter, get primary EMAIL (lowest seq),
         // the FN from items table, and optionally NOTE from props
-        let sql = r#"
+        let sql = format!(
+            r#"
             SELECT
                 i.fn,
                 (SELECT p.value FROM props p WHERE p.uuid = i.uuid AND p.field = 'EMAIL' ORDER BY p.seq LIMIT 1) AS email,
                 (SELECT p.value FROM props p WHERE p.uuid = i.uuid AND p.field = 'NOTE' ORDER BY p.seq LIMIT 1) AS notes
             FROM items i
-            WHERE i.fn_norm LIKE ?1
-               OR EXISTS (
-                   SELECT 1 FROM props WHERE props.uuid = i.uuid
-                     AND props.field IN ('NICKNAME', 'ORG', 'EMAIL', 'TEL')
-                     AND props.value_norm LIKE ?1
-               )
+            WHERE {QUERY_MATCH}
             ORDER BY i.fn COLLATE NOCASE
-        "#;
+        "#
+        );
 
-        let mut stmt = self.conn.prepare(sql)?;
+        let mut stmt = self.conn.prepare(&sql)?;
         let rows = stmt.query_map([&pattern], |row| {
             Ok((
                 row.get::<_, String>(0)?,
@@ -674,9 +966,29 @@ impl Database {
         Ok(out)
     }
 
+    /// Files of every contact `query_emails` would match, with or without an email
+    pub fn query_paths(&self, filter: &str) -> Result<Vec<PathBuf>> {
+        let normalized = search::normalize_query(filter);
+        let pattern = normalized
+            .as_ref()
+            .map(|n| search::like_pattern(n))
+            .unwrap_or_else(|| "%".to_string());
+
+        let sql = format!(
+            "SELECT i.path FROM items i WHERE {QUERY_MATCH} ORDER BY i.fn COLLATE NOCASE"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map([&pattern], |row| row.get::<_, String>(0))?;
+        let mut out = Vec::new();
+        for row in rows {
+            out.push(PathBuf::from(row?));
+        }
+        Ok(out)
+    }
+
     /// List all simhashes for fuzzy matching (includes FN and nicknames)
     /// Returns: (path, display_fn, value_norm, simhash, source)
-    pub fn list_all_simhashes(&self) -> Result<Vec<(PathBuf, String, String, u64, String)>> {
+    pub fn list_all_simhashes(&self) -> Result<Vec<SimhashRow>> {
         let mut stmt = self.conn.prepare(
             r#"
             SELECT i.path, i.fn, s.value_norm, s.simhash, s.source
@@ -702,6 +1014,160 @@ impl Database {
         Ok(out)
     }
 
+    /// Find groups of likely duplicate contacts.
+    ///
+    /// Contacts are linked when they share a normalized EMAIL value, or when
+    /// one's FN or nickname is similar to another's by the same test import
+    /// automerge uses: a SimHash within `simhash_threshold` bits, confirmed by
+    /// a Jaro-Winkler score of at least `min_similarity`. Linked contacts are
+    /// collected transitively into groups. Returns UUID groups with at least
+    /// two members, ordered by display name.
+    pub fn find_duplicate_groups(
+        &self,
+        simhash_threshold: u32,
+        min_similarity: f64,
+    ) -> Result<Vec<Vec<String>>> {
+        let mut links: Vec<(String, String)> = Vec::new();
+
+        let mut stmt = self
+            .conn
+            .prepare("SELECT uuid, value_norm, simhash FROM simhashes WHERE value_norm != ''")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)? as u64,
+            ))
+        })?;
+        let mut names: BkTree<(String, String, u64)> =
+            BkTree::new(|a: &(String, String, u64), b: &(String, String, u64)| {
+                (a.2 ^ b.2).count_ones() as isize
+            });
+        let mut entries = Vec::new();
+        for row in rows {
+            let entry = row?;
+            names.insert(entry.clone());
+            entries.push(entry);
+        }
+        for entry in &entries {
+            let (uuid, value_norm, _) = entry;
+            for ((other_uuid, other_norm, _), _) in
+                names.find(entry.clone(), simhash_threshold as isize)
+            {
+                if other_uuid != uuid && jaro_winkler(value_norm, other_norm) >= min_similarity {
+                    links.push((format!("name:{uuid}"), uuid.clone()));
+                    links.push((format!("name:{uuid}"), other_uuid.clone()));
+                }
+            }
+        }
+
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT DISTINCT value_norm, uuid FROM props
+            WHERE field = 'EMAIL' AND value_norm IN (
+                SELECT value_norm FROM props
+                WHERE field = 'EMAIL' AND value_norm IS NOT NULL AND value_norm != ''
+                GROUP BY value_norm HAVING COUNT(DISTINCT uuid) > 1
+            )
+            "#,
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+        for row in rows {
+            let (key, uuid) = row?;
+            links.push((format!("email:{key}"), uuid));
+        }
+
+        // Union-find over UUIDs, joining every UUID that shares a key
+        let mut parent: HashMap<String, String> = HashMap::new();
+
+        let mut first_by_key: HashMap<String, String> = HashMap::new();
+        for (key, uuid) in &links {
+            let root = find_root(&mut parent, uuid);
+            match first_by_key.get(key) {
+                Some(first) => {
+                    let other = find_root(&mut parent, first);
+                    if other != root {
+                        parent.insert(root, other);
+                    }
+                }
+                None => {
+                    first_by_key.insert(key.clone(), uuid.clone());
+                }
+            }
+        }
+
+        let uuids: Vec<String> = parent.keys().cloned().collect();
+        let mut groups: HashMap<String, Vec<String>> = HashMap::new();
+        for uuid in uuids {
+            let root = find_root(&mut parent, &uuid);
+            groups.entry(root).or_default().push(uuid);
+        }
+
+        let mut names: HashMap<String, String> = HashMap::new();
+        let mut stmt = self.conn.prepare("SELECT uuid, fn FROM items")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+        for row in rows {
+            let (uuid, display_fn) = row?;
+            names.insert(uuid, display_fn.to_lowercase());
+        }
+        let sort_key = |uuid: &String| (names.get(uuid).cloned().unwrap_or_default(), uuid.clone());
+
+        let mut out: Vec<Vec<String>> = groups
+            .into_values()
+            .filter(|group| group.len() > 1)
+            .map(|mut group| {
+                group.sort_by_key(sort_key);
+                group
+            })
+            .collect();
+        out.sort_by_key(|group| sort_key(&group[0]));
+        Ok(out)
+    }
+
+    /// Resolve a contact path from a UID or the contact's primary (first) email
+    pub fn find_path_by_uid_or_primary_email(&self, key: &str) -> Result<Option<PathBuf>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT path FROM items WHERE uuid = ?1 COLLATE NOCASE LIMIT 1")?;
+        let mut rows = stmt.query([key])?;
+        if let Some(row) = rows.next()? {
+            let path: String = row.get(0)?;
+            return Ok(Some(PathBuf::from(path)));
+        }
+
+        let email_norm = search::normalize(key);
+        let mut stmt = self.conn.prepare(
+            "SELECT items.path FROM props p JOIN items ON items.uuid = p.uuid
+             WHERE p.field = 'EMAIL' AND p.value_norm = ?1
+               AND p.seq = (SELECT MIN(seq) FROM props q WHERE q.uuid = p.uuid AND q.field = 'EMAIL')
+             ORDER BY items.fn COLLATE NOCASE
+             LIMIT 1",
+        )?;
+        let mut rows = stmt.query([email_norm])?;
+        if let Some(row) = rows.next()? {
+            let path: String = row.get(0)?;
+            return Ok(Some(PathBuf::from(path)));
+        }
+        Ok(None)
+    }
+
+    /// Path and FN of a contact that has `email` among its EMAIL values
+    pub fn find_contact_by_email(&self, email: &str) -> Result<Option<(PathBuf, String)>> {
+        let email_norm = search::normalize(email);
+        let mut stmt = self.conn.prepare(
+            "SELECT items.path, items.fn FROM props p JOIN items ON items.uuid = p.uuid
+             WHERE p.field = 'EMAIL' AND p.value_norm = ?1
+             ORDER BY items.path
+             LIMIT 1",
+        )?;
+        let mut rows = stmt.query([email_norm])?;
+        if let Some(row) = rows.next()? {
+            let path: String = row.get(0)?;
+            return Ok(Some((PathBuf::from(path), row.get(1)?)));
+        }
+        Ok(None)
+    }
+
     /// Check if an email already exists in the database
     pub fn email_exists(&self, email: &str) -> Result<bool> {
         let email_norm = search::normalize(email);
@@ -718,7 +1184,7 @@ impl Database {
     /// Get all sync metadata for a remote
     pub fn get_sync_metadata_for_remote(&self, remote_name: &str) -> Result<Vec<SyncMetadata>> {
         let mut stmt = self.conn.prepare(
-            "SELECT contact_path, remote_name, remote_href, remote_etag, last_synced, local_modified
+            "SELECT contact_path, remote_name, remote_href, remote_etag, last_synced, local_modified, synced_sha1
              FROM sync_metadata WHERE remote_name = ?1"
         )?;
 
@@ -730,6 +1196,7 @@ impl Database {
                 remote_etag: row.get(3)?,
                 last_synced: row.get(4)?,
                 local_modified: row.get::<_, i64>(5)? != 0,
+                synced_sha1: row.get(6)?,
             })
         })?;
 
@@ -740,17 +1207,64 @@ impl Database {
         Ok(out)
     }
 
+    /// Summarize the sync metadata and upload queue for a remote
+    pub fn sync_summary(&self, remote_name: &str) -> Result<SyncSummary> {
+        let (tracked, local_modified, no_sync) = self.conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(local_modified != 0), 0), COALESCE(SUM(no_sync != 0), 0)
+             FROM sync_metadata WHERE remote_name = ?1",
+            params![remote_name],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, i64>(2)?)),
+        )?;
+        let last_synced: Option<i64> = self
+            .conn
+            .query_row(
+                "SELECT finished_at FROM sync_runs WHERE remote_name = ?1",
+                params![remote_name],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let pending_uploads: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM pending_uploads WHERE remote_name = ?1",
+            params![remote_name],
+            |row| row.get(0),
+        )?;
+        Ok(SyncSummary {
+            tracked: tracked as usize,
+            last_synced,
+            local_modified: local_modified as usize,
+            no_sync: no_sync as usize,
+            pending_uploads: pending_uploads as usize,
+        })
+    }
+
+    /// Note that a sync with `remote_name` finished at `finished_at` (Unix seconds)
+    pub fn record_sync_run(&mut self, remote_name: &str, finished_at: i64) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO sync_runs (remote_name, finished_at) VALUES (?1, ?2)
+             ON CONFLICT(remote_name) DO UPDATE SET finished_at = excluded.finished_at",
+            params![remote_name, finished_at],
+        )?;
+        Ok(())
+    }
+
     /// Upsert sync metadata for a contact
     pub fn upsert_sync_metadata(&mut self, meta: &SyncMetadata) -> Result<()> {
+        log::trace!(
+            "sync link {} <-> {}:{}",
+            meta.contact_path.display(),
+            meta.remote_name,
+            meta.remote_href
+        );
         self.conn.execute(
             r#"
-            INSERT INTO sync_metadata (contact_path, remote_name, remote_href, remote_etag, last_synced, local_modified)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            INSERT INTO sync_metadata (contact_path, remote_name, remote_href, remote_etag, last_synced, local_modified, synced_sha1, no_sync)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, COALESCE((SELECT no_sync FROM items WHERE path = ?1), 0))
             ON CONFLICT(contact_path, remote_name) DO UPDATE SET
               remote_href = excluded.remote_href,
               remote_etag = excluded.remote_etag,
               last_synced = excluded.last_synced,
-              local_modified = excluded.local_modified
+              local_modified = excluded.local_modified,
+              synced_sha1 = excluded.synced_sha1
             "#,
             params![
                 meta.contact_path.to_string_lossy(),
@@ -759,6 +1273,7 @@ impl Database {
                 meta.remote_etag,
                 meta.last_synced,
                 if meta.local_modified { 1 } else { 0 },
+                meta.synced_sha1,
             ],
         )?;
         Ok(())
@@ -773,15 +1288,292 @@ impl Database {
         Ok(())
     }
 
-    /// Delete all sync metadata for a remote
-    pub fn delete_all_sync_metadata_for_remote(&mut self, remote_name: &str) -> Result<()> {
-        self.conn.execute(
+    /// Delete all sync metadata (and pending uploads) for a remote.
+    /// Returns the number of sync metadata rows removed.
+    pub fn delete_all_sync_metadata_for_remote(&mut self, remote_name: &str) -> Result<usize> {
+        let deleted = self.conn.execute(
             "DELETE FROM sync_metadata WHERE remote_name = ?1",
             params![remote_name],
         )?;
+        self.conn.execute(
+            "DELETE FROM pending_uploads WHERE remote_name = ?1",
+            params![remote_name],
+        )?;
+        self.conn.execute("DELETE FROM sync_runs WHERE remote_name = ?1", params![remote_name])?;
+        Ok(deleted)
+    }
+
+    // =========================================================================
+    // Pending upload queue
+    // =========================================================================
+
+    /// Indexed contacts under `dir` whose content changed since their last sync
+    /// with `remote_name` (or that were never synced). Read-only counterpart of
+    /// `queue_pending_uploads`, used by dry runs.
+    pub fn changed_since_sync(&self, remote_name: &str, dir: &Path) -> Result<Vec<PathBuf>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT i.path FROM items i
+             LEFT JOIN sync_metadata sm ON sm.contact_path = i.path AND sm.remote_name = ?1
+             WHERE i.no_sync = 0
+               AND (sm.contact_path IS NULL
+                OR sm.local_modified = 1
+                OR (sm.synced_sha1 IS NOT NULL AND sm.synced_sha1 != i.sha1)
+                OR (sm.synced_sha1 IS NULL AND (sm.last_synced IS NULL OR i.mtime > sm.last_synced)))",
+        )?;
+        let rows = stmt.query_map(params![remote_name], |row| row.get::<_, String>(0))?;
+        let mut out = Vec::new();
+        for row in rows {
+            let path = PathBuf::from(row?);
+            if path.starts_with(dir) {
+                out.push(path);
+            }
+        }
+        Ok(out)
+    }
+
+    /// Queue every indexed contact under `dir` whose content changed since its last
+    /// sync with `remote_name` (or that was never synced). Uses only the index, so
+    /// it works offline. Returns the number of newly queued contacts.
+    pub fn queue_pending_uploads(&mut self, remote_name: &str, dir: &Path, now: i64) -> Result<usize> {
+        let candidates = self.changed_since_sync(remote_name, dir)?;
+
+        let tx = self
+            .conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let mut queued = 0;
+        {
+            let mut insert = tx.prepare(
+                "INSERT OR IGNORE INTO pending_uploads (contact_path, remote_name, queued_at)
+                 VALUES (?1, ?2, ?3)",
+            )?;
+            for path in candidates {
+                queued += insert.execute(params![path.to_string_lossy(), remote_name, now])?;
+            }
+        }
+        tx.commit()?;
+        Ok(queued)
+    }
+
+    /// List queued uploads for a remote, oldest first
+    pub fn pending_uploads(&self, remote_name: &str) -> Result<Vec<PathBuf>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT contact_path FROM pending_uploads WHERE remote_name = ?1
+             ORDER BY queued_at, contact_path",
+        )?;
+        let rows = stmt.query_map(params![remote_name], |row| row.get::<_, String>(0))?;
+        let mut out = Vec::new();
+        for row in rows {
+            out.push(PathBuf::from(row?));
+        }
+        Ok(out)
+    }
+
+    /// Local paths linked to a remote whose card carries `X-RLDX-NOSYNC`
+    pub fn no_sync_paths(&self, remote_name: &str) -> Result<HashSet<PathBuf>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT contact_path FROM sync_metadata WHERE remote_name = ?1 AND no_sync = 1
+             UNION
+             SELECT pu.contact_path FROM pending_uploads pu
+             JOIN items i ON i.path = pu.contact_path
+             WHERE pu.remote_name = ?1 AND i.no_sync = 1",
+        )?;
+        let rows = stmt.query_map(params![remote_name], |row| row.get::<_, String>(0))?;
+        let mut out = HashSet::new();
+        for row in rows {
+            out.insert(PathBuf::from(row?));
+        }
+        Ok(out)
+    }
+
+    /// Drop a contact from a remote's upload queue
+    pub fn remove_pending_upload(&mut self, contact_path: &Path, remote_name: &str) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM pending_uploads WHERE contact_path = ?1 AND remote_name = ?2",
+            params![contact_path.to_string_lossy(), remote_name],
+        )?;
+        Ok(())
+    }
+
+    // =========================================================================
+    // Import manifest
+    // =========================================================================
+
+    /// Hashes of the records already imported from `source` with `format`
+    pub fn import_manifest(&self, format: &str, source: &str) -> Result<HashSet<Vec<u8>>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT record_sha1 FROM import_manifest WHERE format = ?1 AND source = ?2",
+        )?;
+        let rows = stmt.query_map(params![format, source], |row| row.get::<_, Vec<u8>>(0))?;
+        let mut out = HashSet::new();
+        for row in rows {
+            out.insert(row?);
+        }
+        Ok(out)
+    }
+
+    /// Remember records imported from `source` so the next run can skip them
+    pub fn record_import_manifest(
+        &mut self,
+        format: &str,
+        source: &str,
+        hashes: &[Vec<u8>],
+        now: i64,
+    ) -> Result<()> {
+        let tx = self
+            .conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)?;
+        {
+            let mut insert = tx.prepare(
+                "INSERT OR REPLACE INTO import_manifest (format, source, record_sha1, imported_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            for hash in hashes {
+                insert.execute(params![format, source, hash, now])?;
+            }
+        }
+        tx.commit()?;
         Ok(())
     }
 
+    /// Forget everything imported from `source`. Returns the number of records dropped.
+    pub fn clear_import_manifest(&mut self, format: &str, source: &str) -> Result<usize> {
+        let deleted = self.conn.execute(
+            "DELETE FROM import_manifest WHERE format = ?1 AND source = ?2",
+            params![format, source],
+        )?;
+        Ok(deleted)
+    }
+
+}
+
+fn upsert_in(tx: &Transaction<'_>, item: &IndexedItem, props: &[IndexedProp]) -> Result<()> {
+    log::trace!("indexing {} ({})", item.uuid, item.path.display());
+    let fn_norm = search::normalize(&item.display_fn);
+    let fn_simhash = compute_simhash(&fn_norm);
+
+    tx.execute(
+        r#"
+        INSERT INTO items (uuid, path, fn, fn_norm, fn_simhash, rev, has_photo, has_logo, sha1, mtime, lang_pref, no_sync, work_norm)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+        ON CONFLICT(uuid) DO UPDATE SET
+          path=excluded.path,
+          fn=excluded.fn,
+          fn_norm=excluded.fn_norm,
+          fn_simhash=excluded.fn_simhash,
+          rev=excluded.rev,
+          has_photo=excluded.has_photo,
+          has_logo=excluded.has_logo,
+          sha1=excluded.sha1,
+          mtime=excluded.mtime,
+          lang_pref=excluded.lang_pref,
+          no_sync=excluded.no_sync,
+          work_norm=excluded.work_norm
+    "#,
+        params![
+            item.uuid,
+            item.path.to_string_lossy(),
+            item.display_fn,
+            fn_norm,
+            fn_simhash as i64,
+            item.rev,
+            if item.has_photo { 1 } else { 0 },
+            if item.has_logo { 1 } else { 0 },
+            item.sha1,
+            item.mtime,
+            item.lang_pref,
+            if item.no_sync { 1 } else { 0 },
+            item.work_norm,
+        ],
+    )?;
+    // Remember the flag on the sync links too, so a flagged card that
+    // disappears locally is not deleted remotely either
+    tx.execute(
+        "UPDATE sync_metadata SET no_sync = ?1 WHERE contact_path = ?2",
+        params![if item.no_sync { 1 } else { 0 }, item.path.to_string_lossy()],
+    )?;
+
+    tx.execute("DELETE FROM props WHERE uuid = ?1", params![item.uuid])?;
+
+    {
+        let mut stmt = tx.prepare(
+            r#"INSERT INTO props (uuid, fn, field, value, value_norm, params, seq)
+               VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"#,
+        )?;
+
+        for prop in props {
+            let params_json =
+                serde_json::to_string(&prop.params).unwrap_or_else(|_| "{}".to_string());
+            stmt.execute(params![
+                item.uuid,
+                item.display_fn,
+                prop.field,
+                prop.value,
+                search::normalize(&prop.value),
+                params_json,
+                prop.seq,
+            ])?;
+        }
+    }
+
+    // Update simhashes table
+    tx.execute("DELETE FROM simhashes WHERE uuid = ?1", params![item.uuid])?;
+    {
+        let mut stmt = tx.prepare(
+            "INSERT OR IGNORE INTO simhashes (uuid, simhash, source, value_norm) VALUES (?1, ?2, ?3, ?4)",
+        )?;
+
+        // Insert FN simhash
+        stmt.execute(params![item.uuid, fn_simhash as i64, "FN", fn_norm])?;
+
+        // Insert NICKNAME simhashes
+        for prop in props {
+            if prop.field == "NICKNAME" {
+                let nick_norm = search::normalize(&prop.value);
+                let nick_simhash = compute_simhash(&nick_norm);
+                stmt.execute(params![item.uuid, nick_simhash as i64, "NICKNAME", nick_norm])?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn delete_paths_in<I>(tx: &Transaction<'_>, paths: I) -> Result<()>
+where
+    I: IntoIterator<Item = PathBuf>,
+{
+    let mut stmt = tx.prepare("DELETE FROM items WHERE path = ?1")?;
+    for path in paths {
+        log::trace!("dropping {} from the index", path.display());
+        let _ = stmt.execute(params![path.to_string_lossy()])?;
+    }
+    Ok(())
+}
+
+/// WHERE clause over `items` and its arguments for the contacts matching
+/// `filter`: FN, NICKNAME/ORG/EMAIL/TEL values, or every word somewhere in
+/// ORG, TITLE or ROLE ("acme cfo")
+fn contact_filter_clause(filter: Option<&str>) -> (String, Vec<String>) {
+    let Some(filter) = filter else {
+        return (String::new(), Vec::new());
+    };
+    let mut clause = " WHERE fn_norm LIKE ?1 OR EXISTS (
+            SELECT 1 FROM props WHERE props.uuid = items.uuid
+              AND props.field IN ('NICKNAME','ORG','EMAIL','TEL')
+              AND props.value_norm LIKE ?1
+         )"
+    .to_string();
+    let mut args = vec![search::like_pattern(filter)];
+
+    // A query that normalizes to nothing has no words to match
+    let words: Vec<&str> = filter.split_whitespace().collect();
+    if !words.is_empty() {
+        let conditions: Vec<String> = (0..words.len())
+            .map(|i| format!("work_norm LIKE ?{}", i + 2))
+            .collect();
+        clause.push_str(&format!(" OR ({})", conditions.join(" AND ")));
+        args.extend(words.into_iter().map(search::like_pattern));
+    }
+    (clause, args)
 }
 
 fn row_to_list_entry(row: &rusqlite::Row<'_>) -> rusqlite::Result<ContactListEntry> {
@@ -792,5 +1584,592 @@ fn row_to_list_entry(row: &rusqlite::Row<'_>) -> rusqlite::Result<ContactListEnt
         path: PathBuf::from(path),
         primary_org: row.get(3)?,
         kind: row.get(4)?,
+        has_email: row.get(5)?,
+        has_phone: row.get(6)?,
+        sort_position: row.get(7)?,
     })
 }
+
+/// Union-find lookup with path compression, used by `find_duplicate_groups`.
+fn find_root(parent: &mut HashMap<String, String>, uuid: &str) -> String {
+    let next = parent
+        .entry(uuid.to_string())
+        .or_insert_with(|| uuid.to_string())
+        .clone();
+    if next == uuid {
+        return next;
+    }
+    let root = find_root(parent, &next);
+    parent.insert(uuid.to_string(), root.clone());
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn insert_contact(db: &mut Database, uuid: &str, display_fn: &str, emails: &[&str]) {
+        let item = IndexedItem {
+            uuid: uuid.to_string(),
+            path: PathBuf::from(format!("/vdir/{uuid}.vcf.age")),
+            display_fn: display_fn.to_string(),
+            rev: None,
+            has_photo: false,
+            has_logo: false,
+            sha1: Vec::new(),
+            mtime: 0,
+            lang_pref: None,
+            no_sync: false,
+            work_norm: String::new(),
+        };
+        let props: Vec<IndexedProp> = emails
+            .iter()
+            .enumerate()
+            .map(|(seq, email)| IndexedProp {
+                field: "EMAIL".to_string(),
+                value: email.to_string(),
+                params: Value::Object(Default::default()),
+                seq: seq as i64,
+            })
+            .collect();
+        db.upsert(&item, &props).unwrap();
+    }
+
+    #[test]
+    fn test_list_contacts_with_props_groups_by_contact() {
+        let temp = TempDir::new().unwrap();
+        let mut db = Database::open_with_key(&temp.path().join("index.db"), None).unwrap();
+        insert_contact(&mut db, "b", "Zed", &[]);
+        insert_contact(&mut db, "a", "amy", &["amy@example.com", "amy@work.example"]);
+
+        let contacts = db.list_contacts_with_props(&["EMAIL", "TEL"]).unwrap();
+        assert_eq!(contacts.len(), 2);
+        assert_eq!(db.count_contacts().unwrap(), 2);
+        assert_eq!(contacts[0].display_fn, "amy");
+        let emails: Vec<_> = contacts[0].props.iter().map(|p| p.value.as_str()).collect();
+        assert_eq!(emails, ["amy@example.com", "amy@work.example"]);
+        assert_eq!(contacts[1].display_fn, "Zed");
+        assert!(contacts[1].props.is_empty());
+
+        assert!(db.list_contacts_with_props(&["TITLE"]).unwrap().iter().all(|c| c.props.is_empty()));
+    }
+
+    #[test]
+    fn test_primary_prop_prefers_lowest_pref() {
+        let row = |value: &str, params: Value, seq| PropRow {
+            field: "TEL".to_string(),
+            value: value.to_string(),
+            params,
+            seq,
+        };
+        let mut props = vec![
+            row("+1 555 0100", serde_json::json!({}), 0),
+            row("+1 555 0101", serde_json::json!({"pref": 2}), 1),
+            row("+1 555 0102", serde_json::json!({"pref": 1}), 2),
+        ];
+        assert_eq!(primary_prop(&props, "TEL").unwrap().seq, 2);
+        assert!(primary_prop(&props, "EMAIL").is_none());
+
+        // Without PREF the first entry is primary
+        for prop in &mut props {
+            prop.params = serde_json::json!({});
+        }
+        assert_eq!(primary_prop(&props, "TEL").unwrap().seq, 0);
+    }
+
+    #[test]
+    fn test_find_duplicate_groups_by_fn_and_email() {
+        let temp = TempDir::new().unwrap();
+        let mut db = Database::open_with_key(&temp.path().join("index.db"), None).unwrap();
+
+        insert_contact(&mut db, "a", "Jane Doe", &["jane@example.com"]);
+        insert_contact(&mut db, "b", "jane doe", &[]);
+        insert_contact(&mut db, "c", "J. Doe", &["JANE@example.com", "jd@work.com"]);
+        insert_contact(&mut db, "d", "Zed", &["zed@example.com"]);
+        insert_contact(&mut db, "e", "Bob", &["bob@example.com"]);
+        insert_contact(&mut db, "f", "Robert", &["bob@example.com"]);
+
+        let groups = db.find_duplicate_groups(4, 0.9).unwrap();
+        assert_eq!(groups.len(), 2);
+        // Sorted by display name of the first member
+        assert_eq!(groups[0], vec!["e".to_string(), "f".to_string()]);
+        assert_eq!(
+            groups[1],
+            vec!["c".to_string(), "a".to_string(), "b".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_find_duplicate_groups_empty_when_unique() {
+        let temp = TempDir::new().unwrap();
+        let mut db = Database::open_with_key(&temp.path().join("index.db"), None).unwrap();
+
+        insert_contact(&mut db, "a", "Jane Doe", &["jane@example.com"]);
+        insert_contact(&mut db, "b", "John Doe", &["john@example.com"]);
+
+        assert!(db.find_duplicate_groups(4, 0.9).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_find_duplicate_groups_matches_similar_names() {
+        let temp = TempDir::new().unwrap();
+        let mut db = Database::open_with_key(&temp.path().join("index.db"), None).unwrap();
+
+        insert_contact(&mut db, "a", "Jonathan Smith", &[]);
+        insert_contact(&mut db, "b", "Jonathon Smith", &[]);
+        insert_contact(&mut db, "c", "Margaret Jones", &[]);
+
+        let groups = db.find_duplicate_groups(64, 0.9).unwrap();
+        assert_eq!(groups, vec![vec!["a".to_string(), "b".to_string()]]);
+
+        // A strict similarity leaves only exact name matches
+        assert!(db.find_duplicate_groups(64, 1.0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_find_path_by_uid_or_primary_email() {
+        let temp = TempDir::new().unwrap();
+        let mut db = Database::open_with_key(&temp.path().join("index.db"), None).unwrap();
+
+        insert_contact(&mut db, "uid-a", "Jane Doe", &["jane@example.com", "jd@work.com"]);
+
+        let expected = Some(PathBuf::from("/vdir/uid-a.vcf.age"));
+        assert_eq!(db.find_path_by_uid_or_primary_email("uid-a").unwrap(), expected);
+        assert_eq!(db.find_path_by_uid_or_primary_email("UID-A").unwrap(), expected);
+        assert_eq!(db.find_path_by_uid_or_primary_email("Jane@Example.com").unwrap(), expected);
+        // Secondary emails do not count as primary
+        assert_eq!(db.find_path_by_uid_or_primary_email("jd@work.com").unwrap(), None);
+    }
+
+    #[test]
+    fn test_queue_pending_uploads_tracks_changed_sha1() {
+        let temp = TempDir::new().unwrap();
+        let mut db = Database::open_with_key(&temp.path().join("index.db"), None).unwrap();
+
+        insert_contact(&mut db, "a", "Synced", &[]);
+        insert_contact(&mut db, "b", "Edited", &[]);
+        insert_contact(&mut db, "c", "New", &[]);
+        for (uuid, sha1) in [("a", Vec::new()), ("b", vec![1u8])] {
+            db.upsert_sync_metadata(&SyncMetadata {
+                contact_path: PathBuf::from(format!("/vdir/{uuid}.vcf.age")),
+                remote_name: "work".to_string(),
+                remote_href: format!("/dav/{uuid}.vcf"),
+                remote_etag: None,
+                last_synced: Some(0),
+                local_modified: false,
+                synced_sha1: Some(sha1),
+            })
+            .unwrap();
+        }
+
+        // Listing the changes (as a dry run does) leaves the queue empty
+        assert_eq!(db.changed_since_sync("work", Path::new("/vdir")).unwrap().len(), 2);
+        assert!(db.pending_uploads("work").unwrap().is_empty());
+
+        assert_eq!(db.queue_pending_uploads("work", Path::new("/vdir"), 1).unwrap(), 2);
+        // Queueing again does not duplicate entries
+        assert_eq!(db.queue_pending_uploads("work", Path::new("/vdir"), 2).unwrap(), 0);
+        // Paths outside the remote's directory are ignored
+        assert_eq!(db.queue_pending_uploads("home", Path::new("/other"), 1).unwrap(), 0);
+
+        let pending = db.pending_uploads("work").unwrap();
+        assert_eq!(
+            pending,
+            vec![PathBuf::from("/vdir/b.vcf.age"), PathBuf::from("/vdir/c.vcf.age")]
+        );
+
+        db.remove_pending_upload(&pending[0], "work").unwrap();
+        assert_eq!(db.pending_uploads("work").unwrap(), vec![PathBuf::from("/vdir/c.vcf.age")]);
+    }
+
+    #[test]
+    fn test_sync_summary_counts_one_remote() {
+        let temp = TempDir::new().unwrap();
+        let mut db = Database::open_with_key(&temp.path().join("index.db"), None).unwrap();
+        assert_eq!(db.sync_summary("work").unwrap(), SyncSummary::default());
+
+        for (uuid, remote, last_synced, local_modified) in
+            [("a", "work", 100, false), ("b", "work", 250, true), ("c", "home", 900, false)]
+        {
+            insert_contact(&mut db, uuid, uuid, &[]);
+            db.upsert_sync_metadata(&SyncMetadata {
+                contact_path: PathBuf::from(format!("/vdir/{uuid}.vcf.age")),
+                remote_name: remote.to_string(),
+                remote_href: format!("/dav/{uuid}.vcf"),
+                remote_etag: Some("\"1\"".to_string()),
+                last_synced: Some(last_synced),
+                local_modified,
+                synced_sha1: None,
+            })
+            .unwrap();
+        }
+        insert_contact(&mut db, "d", "New", &[]);
+        db.queue_pending_uploads("work", Path::new("/vdir"), 1).unwrap();
+
+        let summary = db.sync_summary("work").unwrap();
+        assert_eq!(summary.tracked, 2);
+        assert_eq!(summary.last_synced, None);
+        assert_eq!(summary.local_modified, 1);
+        assert_eq!(summary.no_sync, 0);
+        assert_eq!(summary.pending_uploads, db.pending_uploads("work").unwrap().len());
+
+        // A sync that changed no card still counts as the last sync
+        db.record_sync_run("work", 1000).unwrap();
+        db.record_sync_run("work", 2000).unwrap();
+        assert_eq!(db.sync_summary("work").unwrap().last_synced, Some(2000));
+        assert_eq!(db.sync_summary("home").unwrap().last_synced, None);
+        db.delete_all_sync_metadata_for_remote("work").unwrap();
+        assert_eq!(db.sync_summary("work").unwrap().last_synced, None);
+    }
+
+    #[test]
+    fn test_list_contacts_full_takes_primary_values() {
+        let temp = TempDir::new().unwrap();
+        let mut db = Database::open_with_key(&temp.path().join("index.db"), None).unwrap();
+
+        insert_contact(&mut db, "b", "Zed", &[]);
+        insert_contact(&mut db, "a", "amy", &["amy@example.com", "amy@work.example"]);
+
+        let rows = db.list_contacts_full().unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].uuid, "a");
+        assert_eq!(rows[0].email.as_deref(), Some("amy@example.com"));
+        assert_eq!(rows[0].phone, None);
+        assert_eq!(rows[1].display_fn, "Zed");
+        assert_eq!(rows[1].email, None);
+
+        let entries = db.list_contacts(None, 0, None).unwrap();
+        assert!(entries[0].has_email && !entries[0].has_phone);
+        assert!(!entries[1].has_email);
+    }
+
+    #[test]
+    fn test_list_contacts_reads_sort_position() {
+        let temp = TempDir::new().unwrap();
+        let mut db = Database::open_with_key(&temp.path().join("index.db"), None).unwrap();
+
+        insert_contact(&mut db, "a", "Ada", &[]);
+        let item = IndexedItem {
+            uuid: "b".to_string(),
+            path: PathBuf::from("/vdir/b.vcf.age"),
+            display_fn: "Bob".to_string(),
+            rev: None,
+            has_photo: false,
+            has_logo: false,
+            sha1: Vec::new(),
+            mtime: 0,
+            lang_pref: None,
+            no_sync: false,
+            work_norm: String::new(),
+        };
+        let sort = IndexedProp {
+            field: "X-RLDX-SORT".to_string(),
+            value: " 2 ".to_string(),
+            params: Value::Object(Default::default()),
+            seq: 0,
+        };
+        db.upsert(&item, &[sort]).unwrap();
+
+        // Pinned contacts come first in their book
+        let entries = db.list_contacts(None, 0, None).unwrap();
+        assert_eq!(entries[0].sort_position, Some(2));
+        assert_eq!(entries[1].sort_position, None);
+    }
+
+    #[test]
+    fn test_list_contacts_pages_in_list_order() {
+        let temp = TempDir::new().unwrap();
+        let mut db = Database::open_with_key(&temp.path().join("index.db"), None).unwrap();
+        for (uuid, name, book) in [("a", "Amy", "work"), ("b", "bob", "home"), ("c", "Cal", "home"), ("d", "Amy", "home")] {
+            let item = IndexedItem {
+                uuid: uuid.to_string(),
+                path: PathBuf::from(format!("/vdir/{book}/{uuid}.vcf.age")),
+                display_fn: name.to_string(),
+                rev: None,
+                has_photo: false,
+                has_logo: false,
+                sha1: Vec::new(),
+                mtime: 0,
+                lang_pref: None,
+                no_sync: false,
+                work_norm: String::new(),
+            };
+            db.upsert(&item, &[]).unwrap();
+        }
+        let uuids = |entries: Vec<ContactListEntry>| -> Vec<String> {
+            entries.into_iter().map(|entry| entry.uuid).collect()
+        };
+
+        assert_eq!(uuids(db.list_contacts(None, 0, None).unwrap()), ["d", "b", "c", "a"]);
+        assert_eq!(uuids(db.list_contacts(None, 0, Some(2)).unwrap()), ["d", "b"]);
+        assert_eq!(uuids(db.list_contacts(None, 2, Some(2)).unwrap()), ["c", "a"]);
+        assert!(db.list_contacts(None, 4, Some(2)).unwrap().is_empty());
+        assert_eq!(uuids(db.list_contacts(Some("amy"), 1, Some(5)).unwrap()), ["a"]);
+
+        assert_eq!(db.contact_position(None, "c").unwrap(), Some(2));
+        assert_eq!(db.contact_position(Some("amy"), "a").unwrap(), Some(1));
+        assert_eq!(db.contact_position(Some("amy"), "b").unwrap(), None);
+    }
+
+    #[test]
+    fn test_list_contacts_matches_org_and_role_together() {
+        let temp = TempDir::new().unwrap();
+        let db_path = temp.path().join("index.db");
+        let mut db = Database::open_with_key(&db_path, None).unwrap();
+        let prop = |field: &str, value: &str| IndexedProp {
+            field: field.to_string(),
+            value: value.to_string(),
+            params: Value::Object(Default::default()),
+            seq: 0,
+        };
+        for (uuid, name, org, title) in [("a", "Ann", "Acme Corp;Finance", "CFO"), ("b", "Bo", "Beta", "CFO")] {
+            let item = IndexedItem {
+                uuid: uuid.to_string(),
+                path: PathBuf::from(format!("/vdir/{uuid}.vcf.age")),
+                display_fn: name.to_string(),
+                rev: None,
+                has_photo: false,
+                has_logo: false,
+                sha1: Vec::new(),
+                mtime: 0,
+                lang_pref: None,
+                no_sync: false,
+                work_norm: indexer::work_norm([("ORG", org), ("TITLE", title)].into_iter()),
+            };
+            db.upsert(&item, &[prop("ORG", org), prop("TITLE", title)]).unwrap();
+        }
+        let names = |db: &Database, query: &str| -> Vec<String> {
+            let filter = search::normalize_query(query).unwrap();
+            db.list_contacts(Some(&filter), 0, None).unwrap().into_iter().map(|c| c.display_fn).collect()
+        };
+
+        assert_eq!(names(&db, "acme cfo"), ["Ann"]);
+        assert_eq!(names(&db, "CFO  finance"), ["Ann"]);
+        assert_eq!(names(&db, "cfo"), ["Ann", "Bo"]);
+        assert!(names(&db, "acme cto").is_empty());
+        // Queries of only invisible or combining characters normalize to no words
+        assert_eq!(names(&db, "\u{200B}"), ["Ann", "Bo"]);
+        assert_eq!(names(&db, "\u{0301}"), ["Ann", "Bo"]);
+        assert_eq!(db.list_contacts(Some(""), 0, None).unwrap().len(), 2);
+
+        // An index from before the column existed is filled in on open
+        db.conn.execute("UPDATE items SET work_norm = NULL", []).unwrap();
+        drop(db);
+        let db = Database::open_with_key(&db_path, None).unwrap();
+        assert_eq!(names(&db, "acme cfo"), ["Ann"]);
+    }
+
+    #[test]
+    fn test_app_state_survives_reset_schema() {
+        let temp = TempDir::new().unwrap();
+        let mut db = Database::open_with_key(&temp.path().join("index.db"), None).unwrap();
+        assert_eq!(db.get_state("last_contact").unwrap(), None);
+        db.set_state("last_contact", "a").unwrap();
+        db.set_state("last_contact", "b").unwrap();
+        db.reset_schema().unwrap();
+        assert_eq!(db.get_state("last_contact").unwrap().as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn test_replace_items_rolls_back_on_failure() {
+        let temp = TempDir::new().unwrap();
+        let mut db = Database::open_with_key(&temp.path().join("index.db"), None).unwrap();
+
+        insert_contact(&mut db, "a", "Amy", &["amy@example.com"]);
+        insert_contact(&mut db, "b", "Amy B", &["amy@work.example"]);
+        insert_contact(&mut db, "c", "Cal", &[]);
+        let old_paths = [PathBuf::from("/vdir/a.vcf.age"), PathBuf::from("/vdir/b.vcf.age")];
+        let merged = |path: &str| IndexedItem {
+            uuid: "m".to_string(),
+            path: PathBuf::from(path),
+            display_fn: "Amy".to_string(),
+            rev: None,
+            has_photo: false,
+            has_logo: false,
+            sha1: Vec::new(),
+            mtime: 0,
+            lang_pref: None,
+            no_sync: false,
+            work_norm: String::new(),
+        };
+
+        // The merged file is written, but indexing it fails (path clash):
+        // nothing may have been deleted
+        assert!(db.replace_items(&old_paths, &merged("/vdir/c.vcf.age"), &[]).is_err());
+        let uuids: Vec<String> =
+            db.list_contacts(None, 0, None).unwrap().into_iter().map(|entry| entry.uuid).collect();
+        assert_eq!(uuids, ["a", "b", "c"]);
+
+        db.replace_items(&old_paths, &merged("/vdir/m.vcf.age"), &[]).unwrap();
+        let uuids: Vec<String> =
+            db.list_contacts(None, 0, None).unwrap().into_iter().map(|entry| entry.uuid).collect();
+        assert_eq!(uuids, ["m", "c"]);
+    }
+
+    #[test]
+    fn test_no_sync_contacts_stay_out_of_sync() {
+        let temp = TempDir::new().unwrap();
+        let mut db = Database::open_with_key(&temp.path().join("index.db"), None).unwrap();
+
+        insert_contact(&mut db, "a", "Synced", &[]);
+        insert_contact(&mut db, "b", "Local", &[]);
+        db.upsert_sync_metadata(&SyncMetadata {
+            contact_path: PathBuf::from("/vdir/a.vcf.age"),
+            remote_name: "work".to_string(),
+            remote_href: "/dav/a.vcf".to_string(),
+            remote_etag: None,
+            last_synced: Some(0),
+            local_modified: true,
+            synced_sha1: None,
+        })
+        .unwrap();
+
+        // Flag both cards after the fact
+        for uuid in ["a", "b"] {
+            let item = IndexedItem {
+                uuid: uuid.to_string(),
+                path: PathBuf::from(format!("/vdir/{uuid}.vcf.age")),
+                display_fn: uuid.to_string(),
+                rev: None,
+                has_photo: false,
+                has_logo: false,
+                sha1: vec![1u8],
+                mtime: 1,
+                lang_pref: None,
+                no_sync: true,
+                work_norm: String::new(),
+            };
+            db.upsert(&item, &[]).unwrap();
+        }
+
+        assert_eq!(db.queue_pending_uploads("work", Path::new("/vdir"), 1).unwrap(), 0);
+        // The synced link keeps the flag even once the card is gone locally
+        db.delete_items_by_paths([PathBuf::from("/vdir/a.vcf.age")]).unwrap();
+        assert_eq!(
+            db.no_sync_paths("work").unwrap(),
+            HashSet::from([PathBuf::from("/vdir/a.vcf.age")])
+        );
+    }
+
+    #[test]
+    fn test_list_recent_contacts_orders_by_rev() {
+        let temp = TempDir::new().unwrap();
+        let mut db = Database::open_with_key(&temp.path().join("index.db"), None).unwrap();
+
+        for (uuid, rev) in [
+            ("old", Some("20230101T000000Z")),
+            ("none", None),
+            ("new", Some("20250301T120000Z")),
+            ("mid", Some("20240615T080000Z")),
+        ] {
+            let item = IndexedItem {
+                uuid: uuid.to_string(),
+                path: PathBuf::from(format!("/vdir/{uuid}.vcf.age")),
+                display_fn: uuid.to_string(),
+                rev: rev.map(str::to_string),
+                has_photo: false,
+                has_logo: false,
+                sha1: Vec::new(),
+                mtime: 0,
+                lang_pref: None,
+                no_sync: false,
+                work_norm: String::new(),
+            };
+            db.upsert(&item, &[]).unwrap();
+        }
+
+        let uuids = |limit| -> Vec<String> {
+            db.list_recent_contacts(limit)
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.uuid)
+                .collect()
+        };
+        assert_eq!(uuids(10), vec!["new", "mid", "old", "none"]);
+        assert_eq!(uuids(2), vec!["new", "mid"]);
+    }
+
+    #[test]
+    fn test_open_converts_old_rev_to_utc() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("index.db");
+        {
+            let mut db = Database::open_with_key(&path, None).unwrap();
+            for (uuid, rev) in [("offset", "20250301T130000+0200"), ("utc", "20250301T120000Z")] {
+                let item = IndexedItem {
+                    uuid: uuid.to_string(),
+                    path: PathBuf::from(format!("/vdir/{uuid}.vcf.age")),
+                    display_fn: uuid.to_string(),
+                    rev: Some(rev.to_string()),
+                    has_photo: false,
+                    has_logo: false,
+                    sha1: Vec::new(),
+                    mtime: 0,
+                    lang_pref: None,
+                    no_sync: false,
+                    work_norm: String::new(),
+                };
+                db.upsert(&item, &[]).unwrap();
+            }
+        }
+
+        // 13:00+02:00 is earlier than 12:00Z once both are in UTC
+        let db = Database::open_with_key(&path, None).unwrap();
+        let uuids: Vec<String> = db
+            .list_recent_contacts(10)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.uuid)
+            .collect();
+        assert_eq!(uuids, vec!["utc", "offset"]);
+    }
+
+    #[test]
+    fn test_encrypted_handles_wait_for_write_lock() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("index.db");
+        let key = "x'2dd29ca851e7b56e4697b0e1f08507293d761a05ce4d1b628663f411a8086d99'";
+        let writer = Database::open_with_key(&path, Some(key)).unwrap();
+        let mut other = Database::open_with_key(&path, Some(key)).unwrap();
+
+        // SQLCipher honours the WAL pragma like plain SQLite
+        let mode: String = other
+            .conn
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(mode.to_ascii_lowercase(), "wal");
+
+        let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+        let holder = std::thread::spawn(move || {
+            writer.conn.execute_batch("BEGIN IMMEDIATE").unwrap();
+            locked_tx.send(()).unwrap();
+            std::thread::sleep(Duration::from_millis(300));
+            writer.conn.execute_batch("COMMIT").unwrap();
+        });
+        locked_rx.recv().unwrap();
+
+        // Readers are not blocked by the open write transaction...
+        assert!(other.list_contacts(None, 0, None).unwrap().is_empty());
+        // ...and a second writer waits for it instead of failing
+        insert_contact(&mut other, "a", "Ada Lovelace", &["ada@example.com"]);
+        holder.join().unwrap();
+        assert_eq!(other.list_contacts(None, 0, None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_open_creates_missing_parent_dirs() {
+        let temp = TempDir::new().unwrap();
+        let db_path = temp.path().join("volume/rldx/index.db");
+        let mut db = Database::open_with_key(&db_path, None).unwrap();
+        insert_contact(&mut db, "a", "Ada Lovelace", &[]);
+        assert!(db_path.exists());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let volume = fs::metadata(temp.path().join("volume")).unwrap();
+            assert_eq!(volume.permissions().mode() & 0o777, 0o700);
+        }
+    }
+}
@@ -1,29 +1,46 @@
-use std::collections::HashSet;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use anyhow::{anyhow, Context, Result};
+use rayon::prelude::*;
+use sha1::{Digest, Sha1};
 use strsim::jaro_winkler;
 
 use vcard4::property::TextProperty;
 use vcard4::Vcard;
 
 use super::simhash_index::{NameSource, SimHashIndex};
+use super::{ImportOptions, OnDuplicate};
 use crate::config::Config;
 use crate::crypto::CryptoProvider;
 use crate::db::{compute_simhash, Database};
 use crate::search;
-use crate::vcard_io;
+use crate::vcard_io::{self, PhoneOptions};
 use crate::vdir;
 
 const BEGIN_VCARD: &str = "BEGIN:VCARD";
 const END_VCARD: &str = "END:VCARD";
 
+/// Labels Google attaches to every (or every starred) contact; never used as books
+const GOOGLE_SYSTEM_GROUPS: &[&str] = &["mycontacts", "starred"];
+
+/// vCard 3.0 properties with no 4.0 counterpart; cards using them are
+/// left for a manual upgrade
+const V3_ONLY_PROPERTIES: &[&str] = &["AGENT", "CLASS", "LABEL", "MAILER", "NAME", "PROFILE", "SORT-STRING"];
+
+/// Import manifest format key for Google exports
+pub const MANIFEST_FORMAT: &str = "google";
+
 /// Result of Google import operation
 pub struct ImportResult {
     pub imported: usize,
     pub merged: Vec<MergeInfo>,
     pub skipped: usize,
+    /// Records already processed by an earlier import of the same file
+    pub unchanged: usize,
 }
 
 /// Information about a merged contact
@@ -37,8 +54,8 @@ pub struct MergeInfo {
 pub fn import_google_contacts(
     input: &Path,
     config: &Config,
-    book: Option<&str>,
-    automerge_threshold: Option<f64>,
+    options: &ImportOptions<'_>,
+    categories_as_books: bool,
     db: &mut Database,
     provider: &dyn CryptoProvider,
 ) -> Result<ImportResult> {
@@ -54,7 +71,11 @@ pub fn import_google_contacts(
         return Err(anyhow!("no vCards found in Google export"));
     }
 
-    let target_dir = match book {
+    let book = options.book;
+    let automerge_threshold = options.automerge;
+    let target_dir = config.book_dir(book);
+    // Category books sit beside the default book, not inside it
+    let category_root = match book {
         Some(name) => config.vdir.join(name),
         None => config.vdir.clone(),
     };
@@ -66,10 +87,16 @@ pub fn import_google_contacts(
         )
     })?;
 
-    let mut used_names = vdir::existing_stems(&target_dir)?;
+    let mut used_names: HashMap<PathBuf, HashSet<String>> = HashMap::new();
+    used_names.insert(target_dir.clone(), vdir::existing_stems(&target_dir)?);
     let mut imported = 0usize;
     let mut merged = Vec::new();
     let mut skipped = 0usize;
+    let mut unchanged = 0usize;
+
+    let source = manifest_source(input)?;
+    let seen = db.import_manifest(MANIFEST_FORMAT, &source)?;
+    let mut processed: Vec<Vec<u8>> = Vec::new();
 
     // Build SimHash index for automerge if enabled
     let simhash_index = if automerge_threshold.is_some() {
@@ -80,9 +107,48 @@ pub fn import_google_contacts(
     };
     let simhash_threshold = config.maildir_import.simhash_threshold;
 
+    let mut pending = Vec::new();
     for (index, card_lines) in cards.iter().enumerate() {
-        match convert_google_card(card_lines, config.phone_region.as_deref()) {
+        let record_sha1 = record_sha1(card_lines);
+        if seen.contains(&record_sha1) {
+            unchanged += 1;
+            continue;
+        }
+        pending.push((index, card_lines, record_sha1));
+    }
+
+    log::info!(
+        "{} record(s) in {}, {} already imported",
+        cards.len(),
+        input.display(),
+        unchanged
+    );
+
+    // Convert records in parallel; they touch neither files nor the DB
+    let phone = config.phone_options();
+    let pb = super::progress_bar(pending.len(), "Converting contacts...");
+    let converted: Vec<_> = pending
+        .into_par_iter()
+        .map(|(index, card_lines, record_sha1)| {
+            let card = convert_google_card(card_lines, phone);
+            pb.inc(1);
+            (index, record_sha1, card)
+        })
+        .collect();
+    pb.finish_with_message("Done converting contacts");
+
+    // Merge planning, file writes and DB lookups stay serial, in export order
+    let pb = super::progress_bar(converted.len(), "Importing contacts...");
+    for (index, record_sha1, card) in converted {
+        pb.inc(1);
+        match card {
             Ok(mut card) => {
+                // Conversion failures are retried on the next run; anything else is settled
+                processed.push(record_sha1);
+                if config.track_field_sources {
+                    vcard_io::tag_source(&mut card, "google");
+                }
+
                 // Get the FN and primary email for automerge check
                 let fn_value = card
                     .formatted_name
@@ -99,6 +165,24 @@ pub fn import_google_contacts(
                     .map(|n| n.value.as_str())
                     .collect();
 
+                // An exact email match is merged into the existing contact
+                if options.on_duplicate == OnDuplicate::Merge {
+                    if let Some((email, path, display_fn)) = find_email_match(db, &card)? {
+                        log::debug!("merging {} into {} (email {})", fn_value, path.display(), email);
+                        if merge_card_into_existing(&path, &card, config.phone_options(), provider)? {
+                            merged.push(MergeInfo {
+                                email,
+                                name: fn_value,
+                                merged_into: display_fn,
+                                score: 1.0,
+                            });
+                        } else {
+                            skipped += 1;
+                        }
+                        continue;
+                    }
+                }
+
                 // Skip if primary email already exists
                 if let Some(ref email) = primary_email {
                     if db.email_exists(email)? {
@@ -120,10 +204,11 @@ pub fn import_google_contacts(
                                 &config.maildir_import,
                             ) {
                                 // Merge into existing contact
+                                log::debug!("merging {} into {} ({:.2})", fn_value, path.display(), score);
                                 if merge_card_into_existing(
                                     &path,
                                     &card,
-                                    config.phone_region.as_deref(),
+                                    config.phone_options(),
                                     provider,
                                 )? {
                                     merged.push(MergeInfo {
@@ -143,31 +228,79 @@ pub fn import_google_contacts(
                 let uuid = vcard_io::ensure_uuid_uid(&mut card)?;
                 vcard_io::touch_rev(&mut card);
 
-                let filename = vdir::select_filename(&uuid, &mut used_names, None);
-                let path = vdir::vcf_target_path(&target_dir, &filename, provider.encryption_type());
+                let card_dir = match categories_as_books.then(|| category_book(&card)).flatten() {
+                    Some(book) => category_root.join(book),
+                    None => target_dir.clone(),
+                };
+                // Added after the book is picked so they never choose it
+                for category in options.categories {
+                    vcard_io::add_category(&mut card, category);
+                }
+
+                let names = match used_names.entry(card_dir.clone()) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => {
+                        fs::create_dir_all(&card_dir).with_context(|| {
+                            format!("failed to create address book directory {}", card_dir.display())
+                        })?;
+                        entry.insert(vdir::existing_stems(&card_dir)?)
+                    }
+                };
+
+                let filename = vdir::select_filename(&uuid, names, None);
+                let path = vdir::vcf_target_path(&card_dir, &filename, provider.encryption_type());
                 let bytes = vcard_io::card_to_bytes(&card);
                 let encrypted = provider.encrypt(&bytes)
                     .with_context(|| format!("failed to encrypt vCard for {}", path.display()))?;
-                vdir::write_atomic(&path, &encrypted)?;
+                vdir::write_atomic(&path, &encrypted, provider.edit_backup())?;
                 imported += 1;
             }
             Err(err) => {
-                eprintln!(
-                    "warning: skipping contact #{}, conversion failed: {err}",
-                    index + 1
-                );
+                log::warn!("skipping contact #{}, conversion failed: {err}", index + 1);
                 skipped += 1;
             }
         }
     }
+    pb.finish_with_message("Done importing contacts");
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+    db.record_import_manifest(MANIFEST_FORMAT, &source, &processed, now)?;
 
     Ok(ImportResult {
         imported,
         merged,
         skipped,
+        unchanged,
     })
 }
 
+/// Identity of an export file in the import manifest: its canonical path
+pub fn manifest_source(input: &Path) -> Result<String> {
+    let path = fs::canonicalize(input)
+        .with_context(|| format!("failed to resolve {}", input.display()))?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// Hash of one exported vCard, as split from the input
+fn record_sha1(card_lines: &[String]) -> Vec<u8> {
+    let mut hasher = Sha1::new();
+    for line in card_lines {
+        hasher.update(line.as_bytes());
+        hasher.update(b"\n");
+    }
+    hasher.finalize().to_vec()
+}
+
+/// Address book named after the card's first non-system category
+fn category_book(card: &Vcard) -> Option<String> {
+    vcard_io::categories(card)
+        .find(|category| !GOOGLE_SYSTEM_GROUPS.contains(&category.to_ascii_lowercase().as_str()))
+        .and_then(vdir::book_dir_name)
+}
+
 /// Match result with source information for preferencing
 struct MatchCandidate {
     path: PathBuf,
@@ -273,14 +406,25 @@ fn collect_candidates(
     }
 }
 
+/// First email on `card` that an indexed contact already has, with that
+/// contact's path and FN
+fn find_email_match(db: &Database, card: &Vcard) -> Result<Option<(String, PathBuf, String)>> {
+    for email in &card.email {
+        if let Some((path, display_fn)) = db.find_contact_by_email(&email.value)? {
+            return Ok(Some((email.value.clone(), path, display_fn)));
+        }
+    }
+    Ok(None)
+}
+
 /// Merge a card's data into an existing vCard
 fn merge_card_into_existing(
     path: &Path,
     source: &Vcard,
-    default_region: Option<&str>,
+    phone: PhoneOptions<'_>,
     provider: &dyn CryptoProvider,
 ) -> Result<bool> {
-    let parsed = vcard_io::parse_file(path, default_region, provider)?;
+    let parsed = vcard_io::parse_file(path, phone, provider)?;
     let Some(mut card) = parsed.cards.into_iter().next() else {
         return Ok(false);
     };
@@ -336,6 +480,26 @@ fn merge_card_into_existing(
         }
     }
 
+    // Merge organizations and titles
+    for org in &source.org {
+        if !card.org.iter().any(|o| o.value == org.value) {
+            card.org.push(org.clone());
+            changed = true;
+        }
+    }
+
+    for title in &source.title {
+        if !card.title.iter().any(|t| t.value.eq_ignore_ascii_case(&title.value)) {
+            card.title.push(title.clone());
+            changed = true;
+        }
+    }
+
+    if card.bday.is_none() && source.bday.is_some() {
+        card.bday = source.bday.clone();
+        changed = true;
+    }
+
     // Merge additional FN values as nicknames (if different from primary)
     let card_fn = card
         .formatted_name
@@ -360,13 +524,13 @@ fn merge_card_into_existing(
         let bytes = vcard_io::card_to_bytes(&card);
         let encrypted = provider.encrypt(&bytes)
             .with_context(|| format!("failed to encrypt vCard for {}", path.display()))?;
-        vdir::write_atomic(path, &encrypted)?;
+        vdir::write_atomic(path, &encrypted, provider.edit_backup())?;
     }
 
     Ok(changed)
 }
 
-fn split_cards(content: &str) -> Vec<Vec<String>> {
+pub(crate) fn split_cards(content: &str) -> Vec<Vec<String>> {
     let mut cards: Vec<Vec<String>> = Vec::new();
     let mut current: Vec<String> = Vec::new();
     let mut inside = false;
@@ -396,7 +560,28 @@ fn split_cards(content: &str) -> Vec<Vec<String>> {
     cards
 }
 
-fn convert_google_card(lines: &[String], default_region: Option<&str>) -> Result<Vcard> {
+/// Convert a vCard 3.0 card from the vdir to 4.0 with the same rules as the
+/// Google import. Fails, naming them, when the card uses properties 4.0 dropped.
+pub(crate) fn upgrade_v3_card(lines: &[String], phone: PhoneOptions<'_>) -> Result<Vcard> {
+    let mut dropped: Vec<String> = Vec::new();
+    for line in unfold_lines(lines) {
+        let Some((lhs, _)) = line.split_once(':') else {
+            continue;
+        };
+        let property = lhs.split(';').next().unwrap_or_default();
+        let (_, name) = split_group(property);
+        let name = name.to_ascii_uppercase();
+        if V3_ONLY_PROPERTIES.contains(&name.as_str()) && !dropped.contains(&name) {
+            dropped.push(name);
+        }
+    }
+    if !dropped.is_empty() {
+        return Err(anyhow!("uses properties vCard 4.0 dropped: {}", dropped.join(", ")));
+    }
+    convert_google_card(lines, phone)
+}
+
+fn convert_google_card(lines: &[String], phone: PhoneOptions<'_>) -> Result<Vcard> {
     let unfolded = unfold_lines(lines);
 
     let mut output: Vec<String> = Vec::new();
@@ -423,7 +608,7 @@ fn convert_google_card(lines: &[String], default_region: Option<&str>) -> Result
     output.push(END_VCARD.to_string());
     let joined = output.join("\r\n");
 
-    let parsed = vcard_io::parse_str(&joined, default_region)?;
+    let parsed = vcard_io::parse_str(&joined, phone)?;
     parsed
         .cards
         .into_iter()
@@ -508,7 +693,7 @@ fn convert_property(lhs: &str, value: &str) -> Result<Option<String>> {
     Ok(Some(line))
 }
 
-fn split_group<'a>(property: &'a str) -> (Option<&'a str>, &'a str) {
+fn split_group(property: &str) -> (Option<&str>, &str) {
     if let Some(pos) = property.find('.') {
         let (group, name) = property.split_at(pos);
         (Some(group), &name[1..])
@@ -532,10 +717,10 @@ impl Parameter {
     }
 }
 
-impl ToString for Parameter {
-    fn to_string(&self) -> String {
+impl std::fmt::Display for Parameter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if self.values.is_empty() {
-            return self.name.clone();
+            return f.write_str(&self.name);
         }
 
         let formatted_values = if self.values.len() == 1 {
@@ -548,7 +733,7 @@ impl ToString for Parameter {
                 .join(",")
         };
 
-        format!("{}={}", self.name, formatted_values)
+        write!(f, "{}={}", self.name, formatted_values)
     }
 }
 
@@ -776,8 +961,7 @@ fn clean_quotes(value: &str) -> String {
 fn format_param_value(value: &str) -> String {
     let trimmed = value.trim();
     if trimmed.contains(',') || trimmed.contains(';') || trimmed.contains(':') {
-        let escaped = trimmed.replace('"', "\"");
-        format!("\"{}\"", escaped)
+        format!("\"{}\"", trimmed)
     } else {
         trimmed.to_string()
     }
@@ -4,7 +4,6 @@ use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
 use anyhow::{Context, Result};
-use indicatif::{ProgressBar, ProgressStyle};
 use mailparse::{addrparse, parse_mail, MailAddr, MailHeader, MailHeaderMap};
 use rayon::prelude::*;
 use strsim::jaro_winkler;
@@ -13,11 +12,13 @@ use vcard4::property::TextProperty;
 use vcard4::Vcard;
 
 use super::simhash_index::{NameSource, SimHashIndex};
+use super::ImportOptions;
 use crate::config::Config;
 use crate::crypto::CryptoProvider;
 use crate::db::{compute_simhash, Database};
+use crate::output;
 use crate::search;
-use crate::vcard_io;
+use crate::vcard_io::{self, PhoneOptions};
 use crate::vdir;
 
 /// Result of maildir import operation
@@ -51,22 +52,14 @@ const CHUNK_SIZE: usize = 10000;
 pub fn import_maildir(
     input: &Path,
     config: &Config,
-    book: Option<&str>,
-    automerge_threshold: Option<f64>,
-    threads: Option<usize>,
+    options: &ImportOptions<'_>,
     db: &mut Database,
     provider: &dyn CryptoProvider,
 ) -> Result<ImportResult> {
-    // Configure thread pool if specified
-    if let Some(num_threads) = threads {
-        rayon::ThreadPoolBuilder::new()
-            .num_threads(num_threads)
-            .build_global()
-            .ok(); // Ignore error if pool already initialized
-    }
-
     // Phase 1: Collect all mail file paths
-    eprintln!("Scanning maildir for email files...");
+    if !output::is_quiet() {
+        eprintln!("Scanning maildir for email files...");
+    }
     let mail_files = collect_all_mail_files(input)?;
     
     if mail_files.is_empty() {
@@ -77,7 +70,9 @@ pub fn import_maildir(
         });
     }
 
-    eprintln!("Found {} email files", mail_files.len());
+    if !output::is_quiet() {
+        eprintln!("Found {} email files", mail_files.len());
+    }
 
     // Phase 2: Parse emails in parallel (chunked for memory efficiency)
     let contacts = parse_emails_parallel(&mail_files)?;
@@ -90,10 +85,12 @@ pub fn import_maildir(
         });
     }
 
-    eprintln!("Extracted {} unique contacts", contacts.len());
+    if !output::is_quiet() {
+        eprintln!("Extracted {} unique contacts", contacts.len());
+    }
 
     // Phase 3: Import contacts (sequential - involves file I/O and DB)
-    import_contacts(contacts, config, book, automerge_threshold, db, provider)
+    import_contacts(contacts, config, options, db, provider)
 }
 
 /// Collect all mail file paths from maildir structure
@@ -163,14 +160,7 @@ fn parse_emails_parallel(mail_files: &[PathBuf]) -> Result<HashMap<String, Extra
     let total = mail_files.len();
     
     // Create progress bar
-    let pb = ProgressBar::new(total as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({percent}%) {msg}")
-            .unwrap()
-            .progress_chars("#>-"),
-    );
-    pb.set_message("Parsing emails...");
+    let pb = super::progress_bar(total, "Parsing emails...");
 
     // Process in chunks to limit memory usage
     let contacts: Mutex<HashMap<String, ExtractedContact>> = Mutex::new(HashMap::new());
@@ -322,15 +312,12 @@ fn merge_contact_entry(
 fn import_contacts(
     contacts: HashMap<String, ExtractedContact>,
     config: &Config,
-    book: Option<&str>,
-    automerge_threshold: Option<f64>,
+    options: &ImportOptions<'_>,
     db: &mut Database,
     provider: &dyn CryptoProvider,
 ) -> Result<ImportResult> {
-    let target_dir = match book {
-        Some(name) => config.vdir.join(name),
-        None => config.vdir.clone(),
-    };
+    let automerge_threshold = options.automerge;
+    let target_dir = config.book_dir(options.book);
 
     fs::create_dir_all(&target_dir).with_context(|| {
         format!(
@@ -354,14 +341,7 @@ fn import_contacts(
     let simhash_threshold = config.maildir_import.simhash_threshold;
 
     // Progress bar for import phase
-    let pb = ProgressBar::new(contacts.len() as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({percent}%) {msg}")
-            .unwrap()
-            .progress_chars("#>-"),
-    );
-    pb.set_message("Importing contacts...");
+    let pb = super::progress_bar(contacts.len(), "Importing contacts...");
 
     for contact in contacts.values() {
         pb.inc(1);
@@ -395,9 +375,9 @@ fn import_contacts(
                     // Merge into existing contact
                     if merge_into_existing(
                         &path,
-                        &contact.email,
-                        &contact.aliases,
-                        config.phone_region.as_deref(),
+                        contact,
+                        config.track_field_sources.then_some("maildir"),
+                        config.phone_options(),
                         provider,
                     )? {
                         merged.push(MergeInfo {
@@ -413,9 +393,15 @@ fn import_contacts(
         }
 
         // Create new vCard
-        match create_vcard(contact, config.phone_region.as_deref()) {
+        match create_vcard(contact, config.phone_options()) {
             Ok(mut card) => {
                 let uuid = vcard_io::ensure_uuid_uid(&mut card)?;
+                if config.track_field_sources {
+                    vcard_io::tag_source(&mut card, "maildir");
+                }
+                for category in options.categories {
+                    vcard_io::add_category(&mut card, category);
+                }
                 vcard_io::touch_rev(&mut card);
 
                 let filename = vdir::select_filename(&uuid, &mut used_names, None);
@@ -423,12 +409,12 @@ fn import_contacts(
                 let bytes = vcard_io::card_to_bytes(&card);
                 let encrypted = provider.encrypt(&bytes)
                     .with_context(|| format!("failed to encrypt vCard for {}", path.display()))?;
-                vdir::write_atomic(&path, &encrypted)?;
+                vdir::write_atomic(&path, &encrypted, provider.edit_backup())?;
                 imported += 1;
             }
             Err(err) => {
-                eprintln!(
-                    "warning: skipping contact <{}>, conversion failed: {err}",
+                log::warn!(
+                    "skipping contact <{}>, conversion failed: {err}",
                     contact.email
                 );
                 skipped += 1;
@@ -558,12 +544,12 @@ fn collect_candidates(
 /// Merge email and aliases into an existing vCard
 fn merge_into_existing(
     path: &Path,
-    email: &str,
-    aliases: &HashSet<String>,
-    default_region: Option<&str>,
+    contact: &ExtractedContact,
+    source: Option<&str>,
+    phone: PhoneOptions<'_>,
     provider: &dyn CryptoProvider,
 ) -> Result<bool> {
-    let parsed = vcard_io::parse_file(path, default_region, provider)?;
+    let parsed = vcard_io::parse_file(path, phone, provider)?;
     let Some(mut card) = parsed.cards.into_iter().next() else {
         return Ok(false);
     };
@@ -571,20 +557,20 @@ fn merge_into_existing(
     let mut changed = false;
 
     // Add email if not present
-    let email_lower = email.to_lowercase();
+    let email_lower = contact.email.to_lowercase();
     let has_email = card.email.iter().any(|e| e.value.to_lowercase() == email_lower);
 
     if !has_email {
         card.email.push(TextProperty {
             group: None,
-            value: email.to_string(),
-            parameters: None,
+            value: contact.email.clone(),
+            parameters: source.map(vcard_io::source_parameters),
         });
         changed = true;
     }
 
     // Add aliases as nicknames if not present
-    for alias in aliases {
+    for alias in &contact.aliases {
         let alias_lower = alias.to_lowercase();
         let has_alias = card.nickname.iter().any(|n| n.value.to_lowercase() == alias_lower);
 
@@ -592,7 +578,7 @@ fn merge_into_existing(
             card.nickname.push(TextProperty {
                 group: None,
                 value: alias.clone(),
-                parameters: None,
+                parameters: source.map(vcard_io::source_parameters),
             });
             changed = true;
         }
@@ -603,14 +589,14 @@ fn merge_into_existing(
         let bytes = vcard_io::card_to_bytes(&card);
         let encrypted = provider.encrypt(&bytes)
             .with_context(|| format!("failed to encrypt vCard for {}", path.display()))?;
-        vdir::write_atomic(path, &encrypted)?;
+        vdir::write_atomic(path, &encrypted, provider.edit_backup())?;
     }
 
     Ok(changed)
 }
 
 /// Create a new vCard from extracted contact
-fn create_vcard(contact: &ExtractedContact, default_region: Option<&str>) -> Result<Vcard> {
+fn create_vcard(contact: &ExtractedContact, phone: PhoneOptions<'_>) -> Result<Vcard> {
     let mut lines = vec![
         "BEGIN:VCARD".to_string(),
         "VERSION:4.0".to_string(),
@@ -626,7 +612,7 @@ fn create_vcard(contact: &ExtractedContact, default_region: Option<&str>) -> Res
     lines.push("END:VCARD".to_string());
 
     let vcard_str = lines.join("\r\n");
-    let parsed = vcard_io::parse_str(&vcard_str, default_region)?;
+    let parsed = vcard_io::parse_str(&vcard_str, phone)?;
 
     parsed
         .cards
@@ -1,3 +1,54 @@
 pub mod google;
 pub mod maildir;
+pub mod photos;
 pub mod simhash_index;
+
+use indicatif::{ProgressBar, ProgressStyle};
+
+use crate::output;
+
+/// What an import does with an incoming contact whose email is already indexed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OnDuplicate {
+    /// Drop the incoming contact
+    #[default]
+    Skip,
+    /// Add the incoming contact's missing fields to the existing one
+    Merge,
+}
+
+/// Settings shared by the import formats
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImportOptions<'a> {
+    /// Address book to import into; the default book when unset
+    pub book: Option<&'a str>,
+    /// FN similarity above which a contact is merged into an existing one
+    pub automerge: Option<f64>,
+    pub on_duplicate: OnDuplicate,
+    /// CATEGORIES values added to every newly created contact
+    pub categories: &'a [String],
+}
+
+/// Size rayon's global pool for the parallel import phases.
+/// Defaults to one thread per CPU core; only the first call takes effect.
+pub fn configure_threads(threads: Option<usize>) {
+    if let Some(num_threads) = threads {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build_global()
+            .ok(); // Ignore error if pool already initialized
+    }
+}
+
+/// Progress bar for an import phase ("pos/len (percent%) message")
+fn progress_bar(len: usize, message: &'static str) -> ProgressBar {
+    let pb = output::progress_bar(len as u64);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({percent}%) {msg}")
+            .unwrap()
+            .progress_chars("#>-"),
+    );
+    pb.set_message(message);
+    pb
+}
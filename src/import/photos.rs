@@ -0,0 +1,101 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::config::Config;
+use crate::crypto::CryptoProvider;
+use crate::db::Database;
+use crate::indexer;
+use crate::vcard_io;
+use crate::vdir;
+
+/// Result of a batch photo import
+pub struct PhotoImportResult {
+    /// Files applied to a contact, with the contact path they were matched to
+    pub matched: Vec<(PathBuf, PathBuf)>,
+    /// Files whose stem matched no UID or primary email
+    pub unmatched: Vec<PathBuf>,
+    /// Files that matched but could not be applied, with the reason
+    pub failed: Vec<(PathBuf, String)>,
+    /// How many of the matched photos exceeded `max_embedded_photo_bytes` and
+    /// were stored beside the cards instead of in them
+    pub external: usize,
+}
+
+/// Set contact photos from a directory of images named by UID or primary email
+/// (e.g. `jane@example.com.jpg`). Images are resized and encoded the same way
+/// as photos set from the TUI.
+pub fn import_photos(
+    dir: &Path,
+    config: &Config,
+    db: &mut Database,
+    provider: &dyn CryptoProvider,
+) -> Result<PhotoImportResult> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("failed to read directory {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && is_supported_image(path))
+        .collect();
+    files.sort();
+
+    let mut result = PhotoImportResult {
+        matched: Vec::new(),
+        unmatched: Vec::new(),
+        failed: Vec::new(),
+        external: 0,
+    };
+
+    for file in files {
+        let Some(stem) = file.file_stem().and_then(|s| s.to_str()) else {
+            result.unmatched.push(file);
+            continue;
+        };
+        let Some(contact_path) = db.find_path_by_uid_or_primary_email(stem.trim())? else {
+            result.unmatched.push(file);
+            continue;
+        };
+
+        match apply_photo(&file, &contact_path, config, db, provider) {
+            Ok(placement) => {
+                if matches!(placement, vcard_io::PhotoPlacement::External(_)) {
+                    result.external += 1;
+                }
+                result.matched.push((file, contact_path));
+            }
+            Err(err) => result.failed.push((file, format!("{:#}", err))),
+        }
+    }
+
+    Ok(result)
+}
+
+fn apply_photo(
+    image_path: &Path,
+    contact_path: &Path,
+    config: &Config,
+    db: &mut Database,
+    provider: &dyn CryptoProvider,
+) -> Result<vcard_io::PhotoPlacement> {
+    let jpeg = vcard_io::photo_jpeg_from_path(image_path)?;
+
+    let parsed = vcard_io::parse_file(contact_path, config.phone_options(), provider)?;
+    let mut cards = parsed.cards;
+    let card = cards.first_mut().context("contact has no cards")?;
+    let (placement, stale) =
+        vcard_io::store_photo(card, &jpeg, &config.vdir, config.max_embedded_photo_bytes, provider)?;
+    vcard_io::write_cards(contact_path, &cards, provider)?;
+    vdir::remove_photo_files(&config.vdir, &stale, provider.encryption_type());
+
+    let state = vdir::compute_file_state(contact_path)?;
+    let record = indexer::build_record(contact_path, &cards[0], &state, None, config.nameless_display)?;
+    db.upsert(&record.item, &record.props)?;
+    Ok(placement)
+}
+
+fn is_supported_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| matches!(ext.to_ascii_lowercase().as_str(), "jpg" | "jpeg" | "png"))
+        .unwrap_or(false)
+}
@@ -1,16 +1,46 @@
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
 use anyhow::{anyhow, Result};
 use serde_json::{json, Map, Value};
 use uuid::Uuid;
 use vcard4::parameter::{Parameters, TimeZoneParameter, TypeParameter};
-use vcard4::property::{DateTimeProperty, Property, TextOrUriProperty};
+use vcard4::property::{Property, TextOrUriProperty};
 use vcard4::Vcard;
 
-use crate::db::{IndexedItem, IndexedProp};
+use crate::config::Config;
+use crate::crypto::CryptoProvider;
+use crate::db::{Database, IndexedItem, IndexedProp};
+use crate::search;
 use crate::vcard_io;
-use crate::vdir::FileState;
+use crate::vdir::{self, FileState};
+
+/// Shown in the list for a card with a blank FN and nothing to fall back on
+const NAMELESS_PLACEHOLDER: &str = "(no name)";
+
+/// Where the indexed display name comes from when a card's FN is blank.
+/// Only the value stored in the index changes; the card keeps its FN.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NamelessDisplay {
+    /// Local part of the primary email, then ORG
+    #[default]
+    Email,
+    /// ORG, then the local part of the primary email
+    Org,
+    /// Always the placeholder
+    Placeholder,
+}
+
+impl NamelessDisplay {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "email" => Some(NamelessDisplay::Email),
+            "org" => Some(NamelessDisplay::Org),
+            "placeholder" => Some(NamelessDisplay::Placeholder),
+            _ => None,
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct IndexedRecord {
@@ -23,18 +53,20 @@ pub fn build_record(
     card: &Vcard,
     state: &FileState,
     preferred_language: Option<&str>,
+    nameless: NamelessDisplay,
 ) -> Result<IndexedRecord> {
     let uuid_str =
         vcard_io::card_uid(card).ok_or_else(|| anyhow!("card missing UID: {}", path.display()))?;
     let uuid =
         Uuid::parse_str(&uuid_str).map_err(|_| anyhow!("card UID is not a UUID: {}", uuid_str))?;
 
-    let (display_fn, display_lang) = select_display_fn(card, preferred_language);
+    let (mut display_fn, display_lang) = select_display_fn(card, preferred_language);
+    if display_fn.trim().is_empty() {
+        display_fn = vcard_io::name_from_n(card).unwrap_or_else(|| nameless_display_fn(card, nameless));
+    }
 
-    let rev = card
-        .rev
-        .as_ref()
-        .map(|rev| date_time_property_to_string(rev));
+    // Stored in UTC so the recently-modified list sorts by time, not by offset
+    let rev = vcard_io::rev_sort_key(card);
     let has_photo = !card.photo.is_empty();
     let has_logo = !card.logo.is_empty();
 
@@ -56,6 +88,7 @@ pub fn build_record(
     collect_logo_props(card, &mut counters, &mut props);
     collect_misc_props(card, &mut counters, &mut props);
     collect_extension_props(card, &mut counters, &mut props);
+    attach_ab_labels(card, &mut props);
 
     let item = IndexedItem {
         uuid: uuid.to_string(),
@@ -67,11 +100,113 @@ pub fn build_record(
         sha1: state.sha1.clone(),
         mtime: state.mtime,
         lang_pref: display_lang,
+        no_sync: vcard_io::is_no_sync(card),
+        work_norm: work_norm(props.iter().map(|prop| (prop.field.as_str(), prop.value.as_str()))),
     };
 
     Ok(IndexedRecord { item, props })
 }
 
+/// ORG units, TITLE and ROLE normalized into one string, so a search like
+/// "acme cfo" can match across them
+pub(crate) fn work_norm<'a>(props: impl Iterator<Item = (&'a str, &'a str)>) -> String {
+    let parts: Vec<&str> = props
+        .filter(|(field, _)| matches!(*field, "ORG" | "TITLE" | "ROLE"))
+        .flat_map(|(_, value)| value.split(';'))
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect();
+    search::normalize(&parts.join(" "))
+}
+
+/// How a reindex settles two files that carry the same UID. Either way the
+/// most recently modified file keeps the UID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UidCollision {
+    /// Index only the newer file
+    KeepNewest,
+    /// Write a fresh UID into the older file and index both
+    NewUid,
+}
+
+/// Two files found holding the same UID during a reindex
+#[derive(Debug, Clone)]
+pub struct UidClash {
+    pub uid: String,
+    pub older: PathBuf,
+    pub newer: PathBuf,
+    /// The UID written into the older file under `UidCollision::NewUid`
+    pub renewed: Option<String>,
+}
+
+/// Store `record` in the index. Another file in `on_disk` that the index
+/// already holds under the same UID would otherwise be silently replaced, so
+/// the clash is settled per `uid_collision` and returned for the caller to
+/// report.
+pub fn store_record(
+    db: &mut Database,
+    mut record: IndexedRecord,
+    on_disk: &HashSet<PathBuf>,
+    uid_collision: UidCollision,
+    config: &Config,
+    provider: &dyn CryptoProvider,
+) -> Result<Option<UidClash>> {
+    let path = record.item.path.clone();
+    let holder = db
+        .get_contact(&record.item.uuid)?
+        .map(|contact| contact.path)
+        .filter(|other| *other != path && on_disk.contains(other));
+    let Some(other) = holder else {
+        db.upsert(&record.item, &record.props)?;
+        return Ok(None);
+    };
+
+    let other_mtime = vdir::compute_file_state(&other)?.mtime;
+    // Newer file wins; on a tie the path that sorts first
+    let this_wins = (record.item.mtime, std::cmp::Reverse(&path))
+        > (other_mtime, std::cmp::Reverse(&other));
+    let (newer, older) = if this_wins { (path, other) } else { (other, path) };
+    let mut clash = UidClash { uid: record.item.uuid.clone(), older, newer, renewed: None };
+    match uid_collision {
+        UidCollision::KeepNewest => {
+            if this_wins {
+                db.upsert(&record.item, &record.props)?;
+            }
+        }
+        UidCollision::NewUid => {
+            let renewed = assign_new_uid(&clash.older, config, provider)?;
+            clash.renewed = Some(renewed.item.uuid.clone());
+            let renewed_other = if this_wins {
+                Some(renewed)
+            } else {
+                record = renewed;
+                None
+            };
+            db.upsert(&record.item, &record.props)?;
+            // Only once the UID has moved to this file, or the paths would clash
+            if let Some(renewed) = renewed_other {
+                db.upsert(&renewed.item, &renewed.props)?;
+            }
+        }
+    }
+    Ok(Some(clash))
+}
+
+/// Replace the UID of the contact in `path` with a fresh one, write the file
+/// back and return its index record
+fn assign_new_uid(path: &Path, config: &Config, provider: &dyn CryptoProvider) -> Result<IndexedRecord> {
+    let mut cards = vcard_io::parse_file(path, config.phone_options(), provider)?.cards;
+    let Some(card) = cards.first_mut() else {
+        return Err(anyhow!("{} contains no vCards", path.display()));
+    };
+    card.uid = None;
+    vcard_io::ensure_uuid_uid(card)?;
+    vcard_io::touch_rev(card);
+    vcard_io::write_cards(path, &cards, provider)?;
+    let state = vdir::compute_file_state(path)?;
+    build_record(path, &cards[0], &state, None, config.nameless_display)
+}
+
 fn select_display_fn(card: &Vcard, preferred_language: Option<&str>) -> (String, Option<String>) {
     let mut best_index: Option<usize> = None;
     let mut best_pref: u8 = u8::MAX;
@@ -106,16 +241,38 @@ fn select_display_fn(card: &Vcard, preferred_language: Option<&str>) -> (String,
         }
     }
 
-    let default_name = "Unnamed".to_string();
     if let Some(index) = best_index {
         let prop = &card.formatted_name[index];
         let language = prop.parameters.as_ref().and_then(|p| p.language.clone());
         (prop.value.clone(), language)
     } else {
-        (default_name, None)
+        (String::new(), None)
     }
 }
 
+fn nameless_display_fn(card: &Vcard, nameless: NamelessDisplay) -> String {
+    let email = || {
+        card.email
+            .first()
+            .map(|prop| prop.value.split('@').next().unwrap_or("").trim().to_string())
+            .filter(|local| !local.is_empty())
+    };
+    let org = || {
+        card.org
+            .first()
+            .and_then(|prop| prop.value.first())
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+    };
+
+    let name = match nameless {
+        NamelessDisplay::Email => email().or_else(org),
+        NamelessDisplay::Org => org().or_else(email),
+        NamelessDisplay::Placeholder => None,
+    };
+    name.unwrap_or_else(|| NAMELESS_PLACEHOLDER.to_string())
+}
+
 fn collect_fn_props(
     card: &Vcard,
     display_fn: &str,
@@ -471,6 +628,58 @@ fn collect_extension_props(
     }
 }
 
+/// Fold Apple `itemN.X-ABLabel` labels into the params of the TEL/EMAIL/ADR/URL
+/// sharing their group (as `x-ablabel`) and drop the label rows they explain
+fn attach_ab_labels(card: &Vcard, props: &mut Vec<IndexedProp>) {
+    let labels = vcard_io::ab_labels(card);
+    if labels.is_empty() {
+        return;
+    }
+
+    let tel_groups = card.tel.iter().map(|prop| match prop {
+        TextOrUriProperty::Text(prop) => prop.group.as_ref(),
+        TextOrUriProperty::Uri(prop) => prop.group.as_ref(),
+    });
+    let grouped = tel_groups
+        .enumerate()
+        .map(|(seq, group)| ("TEL", seq, group))
+        .chain(card.email.iter().enumerate().map(|(seq, p)| ("EMAIL", seq, p.group.as_ref())))
+        .chain(card.address.iter().enumerate().map(|(seq, p)| ("ADR", seq, p.group.as_ref())))
+        .chain(card.url.iter().enumerate().map(|(seq, p)| ("URL", seq, p.group.as_ref())));
+
+    let mut claimed = Vec::new();
+    for (field, seq, group) in grouped {
+        let Some(group) = group.map(|g| g.to_ascii_lowercase()) else {
+            continue;
+        };
+        let Some(label) = labels.get(&group) else {
+            continue;
+        };
+        let prop = props
+            .iter_mut()
+            .find(|prop| prop.field == field && prop.seq == seq as i64);
+        if let Some(params) = prop.and_then(|prop| prop.params.as_object_mut()) {
+            params.insert("x-ablabel".to_string(), Value::String(label.clone()));
+            claimed.push(group);
+        }
+    }
+
+    // X-ABLabel rows were numbered in card order by collect_extension_props
+    let claimed_seqs: Vec<i64> = card
+        .extensions
+        .iter()
+        .filter(|ext| ext.name.eq_ignore_ascii_case(vcard_io::AB_LABEL_PROPERTY))
+        .enumerate()
+        .filter(|(_, ext)| {
+            ext.group
+                .as_ref()
+                .is_some_and(|g| claimed.contains(&g.to_ascii_lowercase()))
+        })
+        .map(|(seq, _)| seq as i64)
+        .collect();
+    props.retain(|prop| prop.field != "X-ABLABEL" || !claimed_seqs.contains(&prop.seq));
+}
+
 fn push_prop(
     props: &mut Vec<IndexedProp>,
     counters: &mut HashMap<String, i64>,
@@ -578,6 +787,98 @@ fn timezone_to_string(param: &TimeZoneParameter) -> String {
     }
 }
 
-fn date_time_property_to_string(prop: &DateTimeProperty) -> String {
-    prop.to_string()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vcard_io::{parse_str, PhoneOptions};
+
+    const UID: &str = "UID:urn:uuid:7f8a4b3e-2c1d-4e5f-9a8b-1c2d3e4f5a6b\r\n";
+
+    fn display_fn(props: &str, nameless: NamelessDisplay) -> String {
+        let vcard = format!("BEGIN:VCARD\r\nVERSION:4.0\r\n{UID}FN:\r\n{props}END:VCARD\r\n");
+        let card = parse_str(&vcard, PhoneOptions::default())
+            .unwrap()
+            .cards
+            .remove(0);
+        let state = FileState { sha1: Vec::new(), mtime: 0 };
+        build_record(Path::new("/vdir/a.vcf"), &card, &state, None, nameless)
+            .unwrap()
+            .item
+            .display_fn
+    }
+
+    #[test]
+    fn test_ab_labels_attach_to_grouped_props() {
+        let vcard = format!(
+            "BEGIN:VCARD\r\nVERSION:4.0\r\n{UID}FN:Jane\r\n\
+item1.EMAIL:jane@home.example\r\nitem1.X-ABLabel:_$!<Home>!$_\r\n\
+item2.X-ABLabel:Orphan\r\nEND:VCARD\r\n"
+        );
+        let card = parse_str(&vcard, PhoneOptions::default()).unwrap().cards.remove(0);
+        let state = FileState { sha1: Vec::new(), mtime: 0 };
+        let props = build_record(Path::new("/vdir/a.vcf"), &card, &state, None, NamelessDisplay::Email)
+            .unwrap()
+            .props;
+
+        let email = props.iter().find(|p| p.field == "EMAIL").unwrap();
+        assert_eq!(email.params["x-ablabel"], "Home");
+        // A label without a matching property stays visible as-is
+        let labels: Vec<_> = props.iter().filter(|p| p.field == "X-ABLABEL").collect();
+        assert_eq!(labels.len(), 1);
+        assert_eq!(labels[0].value, "Orphan");
+    }
+
+    #[test]
+    fn test_nameless_display_from_email() {
+        let props = "EMAIL:jane.doe@example.com\r\nORG:Acme\r\n";
+        assert_eq!(display_fn(props, NamelessDisplay::Email), "jane.doe");
+        // Without an email the ORG is next in line
+        assert_eq!(display_fn("ORG:Acme\r\n", NamelessDisplay::Email), "Acme");
+    }
+
+    #[test]
+    fn test_nameless_display_from_org() {
+        let props = "EMAIL:jane.doe@example.com\r\nORG:Acme;Sales\r\n";
+        assert_eq!(display_fn(props, NamelessDisplay::Org), "Acme");
+        assert_eq!(display_fn("EMAIL:jd@example.com\r\n", NamelessDisplay::Org), "jd");
+    }
+
+    #[test]
+    fn test_nameless_display_placeholder() {
+        let props = "EMAIL:jane.doe@example.com\r\n";
+        assert_eq!(display_fn(props, NamelessDisplay::Placeholder), NAMELESS_PLACEHOLDER);
+        assert_eq!(display_fn("", NamelessDisplay::Email), NAMELESS_PLACEHOLDER);
+    }
+
+    #[test]
+    fn test_display_from_n_when_fn_missing() {
+        let vcard = format!("BEGIN:VCARD\r\nVERSION:4.0\r\n{UID}N:Doe;Jane;;Dr.;\r\nEMAIL:jd@example.com\r\nEND:VCARD\r\n");
+        let card = parse_str(&vcard, PhoneOptions::default()).unwrap().cards.remove(0);
+        let state = FileState { sha1: Vec::new(), mtime: 0 };
+        let record = build_record(Path::new("/vdir/a.vcf"), &card, &state, None, NamelessDisplay::Email).unwrap();
+        assert_eq!(record.item.display_fn, "Jane Doe");
+        // The N components stay searchable
+        assert!(record.props.iter().any(|p| p.field == "N" && p.value.contains("Doe")));
+
+        // An empty FN falls back to N before the nameless display
+        assert_eq!(display_fn("N:;Jane;;;\r\nEMAIL:jd@example.com\r\n", NamelessDisplay::Email), "Jane");
+    }
+
+    #[test]
+    fn test_work_norm_joins_org_title_and_role() {
+        let work_norm_of = |props: &str| {
+            let vcard = format!("BEGIN:VCARD\r\nVERSION:4.0\r\n{UID}FN:Ann\r\n{props}END:VCARD\r\n");
+            let card = parse_str(&vcard, PhoneOptions::default()).unwrap().cards.remove(0);
+            let state = FileState { sha1: Vec::new(), mtime: 0 };
+            build_record(Path::new("/vdir/a.vcf"), &card, &state, None, NamelessDisplay::Email)
+                .unwrap()
+                .item
+                .work_norm
+        };
+        assert_eq!(
+            work_norm_of("ORG:Acme Corp;Finance\r\nTITLE:CFO\r\nROLE:Signatory\r\nNOTE:Golf\r\n"),
+            "acme corp finance cfo signatory"
+        );
+        assert_eq!(work_norm_of("EMAIL:ann@example.com\r\n"), "");
+    }
 }
@@ -3,8 +3,10 @@ mod crypto;
 mod db;
 mod import;
 mod indexer;
+mod output;
 mod remote;
 mod search;
+mod serve;
 mod sync;
 mod translit;
 mod ui;
@@ -13,25 +15,67 @@ mod vdir;
 
 use std::collections::HashSet;
 use std::fs;
+use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
 use age::secrecy::ExposeSecret;
 use anyhow::{bail, Context, Result};
-use clap::{Args, Parser, Subcommand, ValueEnum};
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
 
-use config::Config;
+use config::{Config, OnDecryptError, RemoteConfig};
 use db::Database;
+use output::info;
+
+const EXIT_CODES_HELP: &str = "\
+Exit codes:
+  0  success
+  1  other error
+  2  not found (no query matches, unknown remote)
+  3  configuration error
+  4  remote/network error
+  5  encryption error";
 
 #[derive(Parser, Debug)]
-#[command(name = "rldx")]
+#[command(name = "rldx", after_help = EXIT_CODES_HELP)]
 struct Cli {
     /// Path to configuration file (default: ~/.config/rldx/config.toml)
     #[arg(long, short = 'c', global = true)]
     config: Option<PathBuf>,
 
+    /// Use this index database instead of the configured db_path for this
+    /// run; it is created on first use and keyed like the configured one
+    #[arg(long, global = true, value_name = "PATH")]
+    db: Option<PathBuf>,
+
+    /// Only print functional output (query results, listings) and errors
+    #[arg(long, short = 'q', global = true)]
+    quiet: bool,
+
+    /// Log diagnostics to stderr: -v info, -vv debug, -vvv trace
+    /// (or set RLDX_LOG=<level>). Goes before the subcommand, since
+    /// `remote -v` has its own meaning.
+    #[arg(long, short = 'v', action = clap::ArgAction::Count)]
+    verbose: u8,
+
     #[arg(long, default_value_t = false)]
     reindex: bool,
 
+    /// When two files share a UID, give the older one a new UID instead of
+    /// only indexing the newer one
+    #[arg(long, default_value_t = false)]
+    fix_dupe_uids: bool,
+
+    /// Stop when a vdir file's name or contents do not match the configured
+    /// encryption, instead of warning and skipping it
+    #[arg(long, default_value_t = false)]
+    strict: bool,
+
+    /// Reindex, then remove address-book directories left without any
+    /// contacts and exit instead of starting the TUI
+    #[arg(long, default_value_t = false)]
+    prune_empty_books: bool,
+
     #[command(subcommand)]
     command: Option<Command>,
 }
@@ -47,6 +91,136 @@ enum Command {
     Remote(RemoteArgs),
     /// Sync contacts with a remote server
     Sync(SyncArgs),
+    /// Manage contact photos
+    Photos(PhotosArgs),
+    /// Serve contact queries as JSON over HTTP for editor integrations
+    Serve(ServeArgs),
+    /// Export contacts as a single vCard stream
+    Export(ExportArgs),
+    /// Normalize EMAIL values (see email_case) and merge duplicates
+    CleanEmails(CleanEmailsArgs),
+    /// List every contact from the index, one line each
+    List(ListArgs),
+    /// Check cards for problems, currently a REV in the future
+    Verify(VerifyArgs),
+    /// Make an email or phone the contact's default (first, PREF=1)
+    SetDefault(SetDefaultArgs),
+    /// Create a contact and print its UID
+    New(NewArgs),
+    /// Rewrite every TEL in E.164, e.g. after setting phone_region
+    NormalizePhones(NormalizePhonesArgs),
+    /// Check that the vdir, index and encryption are usable, without the TUI
+    Status,
+    /// List files holding cards older than vCard 4.0
+    UpgradeCheck(UpgradeCheckArgs),
+    /// Print a shell completion script to stdout
+    Completions(CompletionsArgs),
+}
+
+#[derive(Args, Debug)]
+struct CompletionsArgs {
+    /// Shell to generate completions for
+    shell: clap_complete::Shell,
+}
+
+#[derive(Args, Debug)]
+struct UpgradeCheckArgs {
+    /// Convert the listed files to vCard 4.0 where that is safe and report
+    /// the ones that still need a manual upgrade
+    #[arg(long)]
+    upgrade: bool,
+}
+
+#[derive(Args, Debug)]
+struct NormalizePhonesArgs {
+    /// Region for numbers without a country code (default: phone_region)
+    #[arg(long)]
+    region: Option<String>,
+
+    /// Keep each card's REV instead of bumping it
+    #[arg(long)]
+    preserve_rev: bool,
+
+    /// Report what would change without writing anything
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(Args, Debug)]
+#[command(group = clap::ArgGroup::new("value").required(true).args(["email", "phone"]))]
+struct SetDefaultArgs {
+    /// UID of the contact, as printed by `rldx list`
+    uuid: String,
+
+    /// Email address to make the default (case-insensitive)
+    #[arg(long)]
+    email: Option<String>,
+
+    /// Phone number to make the default (formatting is ignored)
+    #[arg(long)]
+    phone: Option<String>,
+}
+
+#[derive(Args, Debug)]
+struct NewArgs {
+    /// Display name (FN); for an organization also its ORG
+    name: String,
+
+    /// What the contact is, written as its KIND
+    #[arg(long, value_enum, default_value_t = KindArg::Individual)]
+    kind: KindArg,
+
+    /// Address book to create it in, a path relative to the vdir (default: default_book)
+    #[arg(long)]
+    book: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum KindArg {
+    Individual,
+    Org,
+    Group,
+}
+
+impl From<KindArg> for vcard4::property::Kind {
+    fn from(kind: KindArg) -> Self {
+        match kind {
+            KindArg::Individual => Self::Individual,
+            KindArg::Org => Self::Org,
+            KindArg::Group => Self::Group,
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+struct VerifyArgs {
+    /// Reset a future REV to the current time
+    #[arg(long)]
+    fix: bool,
+}
+
+#[derive(Args, Debug)]
+struct ListArgs {
+    /// flat: UUID, FN, primary email, primary phone and address book,
+    /// tab-separated (empty columns stay empty)
+    #[arg(long, value_enum, default_value = "flat")]
+    format: ListFormat,
+}
+
+#[derive(Clone, Debug, ValueEnum)]
+enum ListFormat {
+    Flat,
+}
+
+#[derive(Args, Debug)]
+struct CleanEmailsArgs {
+    /// Only clean contacts in this address book
+    #[arg(long)]
+    book: Option<String>,
+
+    /// Report what would change without writing anything
+    #[arg(long)]
+    dry_run: bool,
 }
 
 #[derive(Args, Debug)]
@@ -80,12 +254,76 @@ struct InitArgs {
 enum EncryptionArg {
     Gpg,
     Age,
+    /// No encryption: contacts and the index are stored in cleartext
+    None,
+}
+
+#[derive(Args, Debug)]
+struct ExportArgs {
+    /// vcard: the cards themselves; csv: one row per indexed contact (FN,
+    /// given, family, emails, phones, org, title, categories, book) for
+    /// spreadsheets, with multiple values joined by semicolons
+    #[arg(long, value_enum, default_value = "vcard")]
+    format: ExportFormat,
+
+    /// Target application: plain RFC 6350 vCard 4.0, or Apple/Google
+    /// custom-label grouping (google also adds address books as CATEGORIES);
+    /// vcard format only
+    #[arg(long, value_enum, default_value = "rfc")]
+    dialect: DialectArg,
+
+    /// Only export this address book
+    #[arg(long)]
+    book: Option<String>,
+
+    /// Add each contact's address book to its CATEGORIES; vcard format only
+    #[arg(long)]
+    categories_from_books: bool,
+
+    /// Write to this file instead of stdout
+    #[arg(long, short = 'o', value_name = "PATH")]
+    output: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, ValueEnum)]
+enum ExportFormat {
+    Vcard,
+    Csv,
+}
+
+#[derive(Clone, Debug, ValueEnum)]
+enum DialectArg {
+    Rfc,
+    Apple,
+    Google,
 }
 
 #[derive(Args, Debug)]
 struct QueryArgs {
     /// Search term (matches name, email, nickname, org)
     query: String,
+
+    /// Output format: abook TSV for mutt/aerc, or the matching contacts'
+    /// full vCards separated by blank lines
+    #[arg(long, value_enum, default_value = "abook")]
+    format: QueryFormat,
+}
+
+#[derive(Clone, Debug, ValueEnum)]
+enum QueryFormat {
+    Abook,
+    Vcf,
+}
+
+#[derive(Args, Debug)]
+struct ServeArgs {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8787")]
+    addr: SocketAddr,
+
+    /// Allow listening on a non-loopback address
+    #[arg(long)]
+    allow_remote: bool,
 }
 
 #[derive(Args, Debug)]
@@ -93,15 +331,38 @@ struct ImportArgs {
     #[arg(long, value_enum)]
     format: ImportFormat,
 
+    /// Address book to import into; nested paths such as work/clients/acme
+    /// are created under the vdir
     #[arg(long)]
     book: Option<String>,
 
+    /// File each contact into an address book named after its first
+    /// CATEGORIES value (google only)
+    #[arg(long, conflicts_with = "book")]
+    categories_as_books: bool,
+
+    /// Process every record again instead of skipping those seen by an
+    /// earlier import of the same file (google only)
+    #[arg(long)]
+    full: bool,
+
     /// Auto-merge threshold (0.0-1.0). Contacts with FN similarity
     /// above this threshold will be merged. Recommended: 0.85-0.95
     #[arg(long)]
     automerge: Option<f64>,
 
-    /// Number of threads for parallel processing (maildir only).
+    /// What to do with a contact whose email is already in the index:
+    /// skip it, or merge its extra emails, phones and fields into the
+    /// existing contact (google only)
+    #[arg(long, value_enum, default_value = "skip")]
+    on_duplicate: OnDuplicateArg,
+
+    /// Add this CATEGORIES value to every newly created contact
+    /// (repeatable)
+    #[arg(long = "category", value_name = "NAME")]
+    categories: Vec<String>,
+
+    /// Number of threads for parsing and converting records in parallel.
     /// Defaults to number of CPU cores.
     #[arg(long, short = 'j')]
     threads: Option<usize>,
@@ -116,6 +377,12 @@ enum ImportFormat {
     Maildir,
 }
 
+#[derive(Clone, Debug, ValueEnum)]
+enum OnDuplicateArg {
+    Skip,
+    Merge,
+}
+
 #[derive(Args, Debug)]
 struct RemoteArgs {
     #[command(subcommand)]
@@ -136,6 +403,8 @@ enum RemoteCommand {
     Remove(RemoteRemoveArgs),
     /// Test connection to a remote
     Test(RemoteTestArgs),
+    /// Show the last sync time and tracked cards for a remote
+    Status(RemoteStatusArgs),
 }
 
 #[derive(Args, Debug)]
@@ -181,18 +450,40 @@ struct RemoteRemoveArgs {
     /// Also delete sync metadata for this remote
     #[arg(long)]
     purge: bool,
+
+    /// Do not ask for confirmation before purging
+    #[arg(long, short = 'y')]
+    yes: bool,
 }
 
 #[derive(Args, Debug)]
 struct RemoteTestArgs {
-    /// Name of the remote to test
+    /// Name of the remote to test; on a terminal with several remotes,
+    /// leaving it out lists them to pick from
+    #[arg(conflicts_with = "all")]
+    name: Option<String>,
+
+    /// Test every configured remote
+    #[arg(long)]
+    all: bool,
+}
+
+#[derive(Args, Debug)]
+struct RemoteStatusArgs {
+    /// Name of the remote
     name: String,
 }
 
 #[derive(Args, Debug)]
 struct SyncArgs {
-    /// Name of the remote to sync with
-    name: String,
+    /// Name of the remote to sync with; on a terminal with several remotes,
+    /// leaving it out lists them to pick from
+    #[arg(conflicts_with = "all")]
+    name: Option<String>,
+
+    /// Sync with every configured remote in turn
+    #[arg(long)]
+    all: bool,
 
     /// Only download changes from remote (don't upload local changes)
     #[arg(long)]
@@ -203,20 +494,120 @@ struct SyncArgs {
     dry_run: bool,
 }
 
-fn main() -> Result<()> {
+#[derive(Args, Debug)]
+struct PhotosArgs {
+    #[command(subcommand)]
+    command: PhotosCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum PhotosCommand {
+    /// Set photos from a directory of images named by UID or primary email
+    Import(PhotosImportArgs),
+}
+
+#[derive(Args, Debug)]
+struct PhotosImportArgs {
+    /// Directory containing .jpg/.jpeg/.png files
+    #[arg(value_name = "DIR")]
+    dir: PathBuf,
+}
+
+/// Error categories reported through the process exit code.
+///
+/// Attach one with `.context(Failure::...)`; anything untagged exits with 1.
+/// A bare `Failure` with no message exits silently, for commands that have
+/// already reported the outcome themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Failure {
+    /// A contact, remote or other requested item does not exist (exit 2)
+    NotFound,
+    /// The configuration is missing or invalid (exit 3)
+    Config,
+    /// The remote server could not be reached or rejected a request (exit 4)
+    Remote,
+    /// Encryption keys could not be loaded or used (exit 5)
+    Crypto,
+}
+
+impl Failure {
+    fn exit_code(self) -> u8 {
+        match self {
+            Failure::NotFound => 2,
+            Failure::Config => 3,
+            Failure::Remote => 4,
+            Failure::Crypto => 5,
+        }
+    }
+}
+
+impl std::fmt::Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Failure::NotFound => "not found",
+            Failure::Config => "configuration error",
+            Failure::Remote => "remote error",
+            Failure::Crypto => "encryption error",
+        })
+    }
+}
+
+impl std::error::Error for Failure {}
+
+/// An error with `message` that exits with the not-found code
+fn not_found(message: String) -> anyhow::Error {
+    anyhow::Error::new(Failure::NotFound).context(message)
+}
+
+fn main() -> ExitCode {
     let cli = Cli::parse();
+    output::set_quiet(cli.quiet);
+    output::init_logging(cli.verbose);
+
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            if !(err.is::<Failure>() && err.chain().count() == 1) {
+                eprintln!("Error: {:?}", err);
+            }
+            let code = err.downcast_ref::<Failure>().map_or(1, |f| f.exit_code());
+            ExitCode::from(code)
+        }
+    }
+}
 
+fn run(cli: Cli) -> Result<()> {
     // Handle commands that don't need config first
-    if let Some(ref command) = cli.command {
-        if let Command::Init(ref args) = command {
-            return handle_init(args, cli.config.as_deref());
-        }
+    if let Some(Command::Init(ref args)) = cli.command {
+        return handle_init(args, cli.config.as_deref());
+    }
+    if let Some(Command::Completions(ref args)) = cli.command {
+        clap_complete::generate(args.shell, &mut Cli::command(), "rldx", &mut std::io::stdout());
+        return Ok(());
     }
 
-    let config = config::load_from(cli.config.as_deref())?;
+    let mut config = config::load_from(cli.config.as_deref()).context(Failure::Config)?;
+    if let Some(db) = cli.db.as_deref() {
+        config.db_path = config::expand_tilde(db);
+    }
+    // Reports a broken encryption setup or a missing vdir instead of failing on it
+    if let Some(Command::Status) = cli.command {
+        return handle_status(&config);
+    }
+    if !config.vdir.exists() {
+        return Err(anyhow::Error::new(Failure::Config)
+            .context(format!("configured vdir does not exist: {}", config.vdir.display())));
+    }
 
     // Create the encryption provider
-    let provider = crypto::create_provider(&config.encryption)?;
+    let mut provider = crypto::create_provider(&config.encryption).context(Failure::Crypto)?;
+    if config.edit_backup {
+        let backup = vdir::EditBackup {
+            root: config.vdir.clone(),
+            keep: config.edit_backup_keep,
+        };
+        provider = Box::new(crypto::BackupProvider::new(provider, backup));
+    }
 
     if let Some(command) = cli.command {
         match command {
@@ -225,7 +616,7 @@ fn main() -> Result<()> {
                 return Ok(());
             }
             Command::Query(args) => {
-                handle_query(args, &config)?;
+                handle_query(args, &config, provider.as_ref())?;
                 return Ok(());
             }
             Command::Init(_) => {
@@ -240,23 +631,88 @@ fn main() -> Result<()> {
                 handle_sync(args, &config, provider.as_ref())?;
                 return Ok(());
             }
+            Command::Photos(args) => {
+                handle_photos(args, &config, provider.as_ref())?;
+                return Ok(());
+            }
+            Command::Serve(args) => {
+                handle_serve(args, &config, provider.as_ref())?;
+                return Ok(());
+            }
+            Command::Export(args) => {
+                handle_export(args, &config, provider.as_ref())?;
+                return Ok(());
+            }
+            Command::CleanEmails(args) => {
+                handle_clean_emails(args, &config, provider.as_ref())?;
+                return Ok(());
+            }
+            Command::List(args) => {
+                handle_list(args, &config, provider.as_ref())?;
+                return Ok(());
+            }
+            Command::Verify(args) => {
+                handle_verify(args, &config, provider.as_ref())?;
+                return Ok(());
+            }
+            Command::SetDefault(args) => {
+                handle_set_default(args, &config, provider.as_ref())?;
+                return Ok(());
+            }
+            Command::New(args) => {
+                handle_new(args, &config, provider.as_ref())?;
+                return Ok(());
+            }
+            Command::NormalizePhones(args) => {
+                handle_normalize_phones(args, &config, provider.as_ref())?;
+                return Ok(());
+            }
+            Command::Status => {
+                // Already handled above
+                unreachable!();
+            }
+            Command::UpgradeCheck(args) => {
+                handle_upgrade_check(args, &config, provider.as_ref())?;
+                return Ok(());
+            }
+            Command::Completions(_) => {
+                // Already handled above
+                unreachable!();
+            }
         }
     }
 
-    println!("Loaded configuration from {}", config.config_path.display());
+    info!("Loaded configuration from {}", config.config_path.display());
+    let mismatched = check_vdir_formats(&config, cli.strict)?;
+    config.vdir_ignore.skip_files(mismatched);
+    preflight_decrypt(&config, provider.as_ref())?;
 
-    let normalize_report = vdir::normalize(&config.vdir, config.phone_region.as_deref(), provider.as_ref())?;
+    let normalize_report = vdir::normalize(&config.vdir, config.phone_options(), provider.as_ref(), &config.vdir_ignore)?;
     if !normalize_report.needs_upgrade.is_empty() {
         eprintln!(
-            "warning: {} cards require manual upgrade to vCard 4.0",
+            "warning: {} cards require manual upgrade to vCard 4.0 (see `rldx upgrade-check`)",
             normalize_report.needs_upgrade.len()
         );
     }
 
     // Derive DB key from encryption provider
-    let db_key = provider.derive_db_key()?;
-    let mut db = Database::open_with_key(&config.db_path, Some(&db_key))?;
-    reindex(&mut db, &config, cli.reindex, provider.as_ref())?;
+    let db_key = provider.derive_db_key().context(Failure::Crypto)?;
+    let mut db = Database::open_with_key(&config.db_path, db_key.as_deref())?;
+    let uid_collision = if cli.fix_dupe_uids {
+        indexer::UidCollision::NewUid
+    } else {
+        indexer::UidCollision::KeepNewest
+    };
+    reindex_with(&mut db, &config, cli.reindex, uid_collision, provider.as_ref())?;
+
+    if cli.prune_empty_books {
+        let pruned = vdir::prune_empty_books(&config.vdir)?;
+        for dir in &pruned {
+            info!("Pruned {}", dir.display());
+        }
+        info!("Pruned {} empty address book(s)", pruned.len());
+        return Ok(());
+    }
 
     let mut app = ui::app::App::new(&mut db, &config, provider.as_ref())?;
     app.run()?;
@@ -264,12 +720,15 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-fn handle_query(args: QueryArgs, config: &Config) -> Result<()> {
-    // Create provider and derive DB key for encrypted database
-    let provider = crypto::create_provider(&config.encryption)?;
-    let db_key = provider.derive_db_key()?;
-    let db = Database::open_with_key(&config.db_path, Some(&db_key))?;
+fn handle_query(args: QueryArgs, config: &Config, provider: &dyn crypto::CryptoProvider) -> Result<()> {
+    // Derive DB key for encrypted database
+    let db_key = provider.derive_db_key().context(Failure::Crypto)?;
+    let db = Database::open_with_key(&config.db_path, db_key.as_deref())?;
+    if let QueryFormat::Vcf = args.format {
+        return print_query_vcards(&db, &args.query, provider);
+    }
     let results = db.query_emails(&args.query)?;
+    let found = !results.is_empty();
 
     // Header line (abook-compatible, ignored by mutt/aerc)
     if results.is_empty() {
@@ -292,9 +751,418 @@ fn handle_query(args: QueryArgs, config: &Config) -> Result<()> {
         );
     }
 
+    if !found {
+        // The header already reported it; only the exit code is left to set
+        return Err(Failure::NotFound.into());
+    }
+    Ok(())
+}
+
+fn handle_verify(args: VerifyArgs, config: &Config, provider: &dyn crypto::CryptoProvider) -> Result<()> {
+    let mut files = vdir::list_vcf_files(&config.vdir, &config.vdir_ignore, config.encryption.encryption_type)?;
+    files.sort();
+    let now = time::OffsetDateTime::now_utc();
+
+    let mut found = 0usize;
+    for path in files {
+        let parsed = vcard_io::parse_file(&path, config.phone_options(), provider)?;
+        let mut cards = parsed.cards;
+        let mut changed = false;
+        for card in &mut cards {
+            let Some(rev) = vcard_io::future_rev(card, now) else {
+                continue;
+            };
+            found += 1;
+            let rev = rev
+                .format(&time::format_description::well_known::Rfc3339)
+                .unwrap_or_default();
+            let uid = vcard_io::card_uid(card).unwrap_or_else(|| "(no UID)".to_string());
+            println!("future REV\t{}\t{}", uid, rev);
+            if args.fix {
+                vcard_io::touch_rev(card);
+                changed = true;
+            }
+        }
+        if changed {
+            vcard_io::write_cards(&path, &cards, provider)?;
+        }
+    }
+
+    if found == 0 {
+        info!("No problems found");
+    } else if args.fix {
+        info!("Reset REV on {} card(s)", found);
+        let db_key = provider.derive_db_key().context(Failure::Crypto)?;
+        let mut db = Database::open_with_key(&config.db_path, db_key.as_deref())?;
+        reindex(&mut db, config, false, provider)?;
+    } else {
+        bail!("{} card(s) have a REV in the future; run `rldx verify --fix` to reset them", found);
+    }
+    Ok(())
+}
+
+fn handle_upgrade_check(
+    args: UpgradeCheckArgs,
+    config: &Config,
+    provider: &dyn crypto::CryptoProvider,
+) -> Result<()> {
+    let mut files = vdir::list_vcf_files(&config.vdir, &config.vdir_ignore, config.encryption.encryption_type)?;
+    files.sort();
+
+    let is_v4 = |block: &[String]| block.iter().any(|line| line.trim().eq_ignore_ascii_case("VERSION:4.0"));
+    let mut flagged = 0usize;
+    let mut upgraded = 0usize;
+    for path in files {
+        let encrypted = fs::read(&path).with_context(|| format!("failed to read {}", path.display()))?;
+        let decrypted = provider
+            .decrypt(&encrypted)
+            .with_context(|| format!("failed to decrypt {}", path.display()))?;
+        let content = String::from_utf8(decrypted)
+            .with_context(|| format!("vCard file {} contains invalid UTF-8", path.display()))?;
+        let blocks = import::google::split_cards(&content);
+        if blocks.iter().all(|block| is_v4(block)) {
+            continue;
+        }
+        flagged += 1;
+
+        let name = blocks
+            .iter()
+            .flatten()
+            .filter_map(|line| line.split_once(':'))
+            .find(|(lhs, _)| lhs.split(';').next().is_some_and(|n| n.eq_ignore_ascii_case("FN")))
+            .map(|(_, value)| value.trim().to_string())
+            .unwrap_or_default();
+
+        if !args.upgrade {
+            println!("{}\t{}", path.display(), name);
+            continue;
+        }
+
+        let converted: Result<Vec<_>> = blocks
+            .iter()
+            .map(|block| {
+                if is_v4(block) {
+                    let parsed = vcard_io::parse_str(&block.join("\r\n"), config.phone_options())?;
+                    return parsed.cards.into_iter().next().context("card failed to parse");
+                }
+                let mut card = import::google::upgrade_v3_card(block, config.phone_options())?;
+                vcard_io::ensure_uuid_uid(&mut card)?;
+                vcard_io::touch_rev(&mut card);
+                Ok(card)
+            })
+            .collect();
+        match converted {
+            Ok(cards) => {
+                vcard_io::write_cards(&path, &cards, provider)?;
+                upgraded += 1;
+                println!("upgraded\t{}\t{}", path.display(), name);
+            }
+            Err(err) => println!("manual\t{}\t{}\t{:#}", path.display(), name, err),
+        }
+    }
+
+    if flagged == 0 {
+        info!("All cards are vCard 4.0");
+        return Ok(());
+    }
+    if !args.upgrade {
+        bail!(
+            "{} file(s) hold cards older than vCard 4.0; run `rldx upgrade-check --upgrade` to convert them",
+            flagged
+        );
+    }
+    if upgraded > 0 {
+        info!("Upgraded {} file(s) to vCard 4.0", upgraded);
+    }
+    // Files left behind usually fail to parse, which would stop the reindex
+    if upgraded < flagged {
+        bail!("{} file(s) need a manual upgrade to vCard 4.0", flagged - upgraded);
+    }
+    let db_key = provider.derive_db_key().context(Failure::Crypto)?;
+    let mut db = Database::open_with_key(&config.db_path, db_key.as_deref())?;
+    reindex(&mut db, config, false, provider)?;
+    Ok(())
+}
+
+/// Print one `rldx status` line; returns whether the check passed
+fn status_line(name: &str, subject: &str, outcome: Result<String>) -> bool {
+    match outcome {
+        Ok(detail) => {
+            println!("{:<12}{} (ok{})", name, subject, detail);
+            true
+        }
+        Err(err) => {
+            println!("{:<12}{} (FAILED: {:#})", name, subject, err);
+            false
+        }
+    }
+}
+
+fn handle_status(config: &Config) -> Result<()> {
+    let mut failed = 0usize;
+    let mut check = |name: &str, subject: &str, outcome: Result<String>| {
+        if !status_line(name, subject, outcome) {
+            failed += 1;
+        }
+    };
+
+    check("config", &config.config_path.display().to_string(), Ok(String::new()));
+
+    let vdir_outcome = if config.vdir.is_dir() {
+        vdir::list_vcf_files(&config.vdir, &config.vdir_ignore, config.encryption.encryption_type)
+            .map(|files| format!(", {} cards", files.len()))
+    } else {
+        Err(anyhow::anyhow!("not a directory"))
+    };
+    check("vdir", &config.vdir.display().to_string(), vdir_outcome);
+
+    let encryption = config.encryption.encryption_type.as_str();
+    let provider = crypto::create_provider(&config.encryption);
+    let crypto_outcome = provider.as_ref().map_err(|err| anyhow::anyhow!("{err:#}")).and_then(|provider| {
+        let probe = b"rldx status probe";
+        let decrypted = provider.decrypt(&provider.encrypt(probe)?)?;
+        if decrypted != probe {
+            bail!("decrypted test data does not match");
+        }
+        Ok(String::new())
+    });
+    check("encryption", encryption, crypto_outcome);
+
+    let db_outcome = match &provider {
+        Err(_) => Err(anyhow::anyhow!("no encryption provider to derive the key")),
+        Ok(_) if !config.db_path.exists() => Err(anyhow::anyhow!("not created yet; start rldx once to build it")),
+        Ok(provider) => provider.derive_db_key().and_then(|key| {
+            let db = Database::open_with_key(&config.db_path, key.as_deref())?;
+            Ok(format!(", {} contacts indexed", db.count_contacts()?))
+        }),
+    };
+    check("database", &config.db_path.display().to_string(), db_outcome);
+
+    let remotes: Vec<&str> = config.remotes.iter().map(|r| r.name.as_str()).collect();
+    println!(
+        "{:<12}{}",
+        "remotes",
+        if remotes.is_empty() { "(none)".to_string() } else { remotes.join(", ") }
+    );
+
+    if failed > 0 {
+        bail!("{} check(s) failed", failed);
+    }
+    Ok(())
+}
+
+/// Warns about vdir files the configured encryption cannot read and returns
+/// them so the run can skip them; with `strict` they are an error instead
+fn check_vdir_formats(config: &Config, strict: bool) -> Result<Vec<PathBuf>> {
+    if !config.vdir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mismatches = vdir::find_format_mismatches(
+        &config.vdir,
+        &config.vdir_ignore,
+        config.encryption.encryption_type,
+    )?;
+    for mismatch in &mismatches {
+        eprintln!("warning: {} {}", mismatch.path.display(), mismatch.reason);
+    }
+    if strict && !mismatches.is_empty() {
+        return Err(anyhow::Error::new(Failure::Config).context(format!(
+            "{} file(s) in the vdir do not match the configured encryption",
+            mismatches.len()
+        )));
+    }
+    if !mismatches.is_empty() {
+        eprintln!("warning: skipping {} unreadable file(s); --strict makes this an error", mismatches.len());
+    }
+    Ok(mismatches.into_iter().map(|mismatch| mismatch.path).collect())
+}
+
+/// How many cards `preflight_decrypt` tries when `on_decrypt_error = "abort"`
+const PREFLIGHT_PROBES: usize = 5;
+
+/// Decrypts a card before the vdir is touched, so a wrong key fails here
+/// with a hint instead of deep inside normalization or reindex
+fn preflight_decrypt(config: &Config, provider: &dyn crypto::CryptoProvider) -> Result<()> {
+    if !config.vdir.is_dir() {
+        return Ok(());
+    }
+    let files = vdir::list_vcf_files(&config.vdir, &config.vdir_ignore, config.encryption.encryption_type)?;
+    // Any card that decrypts proves the key. A few are tried even when one
+    // bad file aborts the run, so a single damaged card is reported as a bad
+    // file by the reindex rather than as the wrong key here.
+    let probes = match config.on_decrypt_error {
+        OnDecryptError::Abort => &files[..files.len().min(PREFLIGHT_PROBES)],
+        OnDecryptError::Skip => &files[..],
+    };
+    let mut first_failure = None;
+    for probe in probes {
+        let data = fs::read(probe).with_context(|| format!("failed to read {}", probe.display()))?;
+        match provider.decrypt(&data) {
+            Ok(_) => {
+                info!("{} cards in {}; the configured key decrypts them", files.len(), config.vdir.display());
+                return Ok(());
+            }
+            Err(err) => {
+                first_failure.get_or_insert((probe, err));
+            }
+        }
+    }
+    match first_failure {
+        Some((probe, err)) if probes.len() > 1 => Err(err.context(Failure::Crypto).context(format!(
+            "cannot decrypt {} or any of the {} other cards tried with {}",
+            probe.display(),
+            probes.len() - 1,
+            key_hint(&config.encryption)
+        ))),
+        Some((probe, err)) => Err(err.context(Failure::Crypto).context(format!(
+            "cannot decrypt {} with {}",
+            probe.display(),
+            key_hint(&config.encryption)
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Names the configured key and the likely cause when it fails to decrypt
+fn key_hint(encryption: &config::EncryptionConfig) -> String {
+    match encryption.encryption_type {
+        config::EncryptionType::Age => {
+            let identity = match (&encryption.age_identity, &encryption.age_identity_cmd) {
+                (_, Some(cmd)) => format!("`{}`", cmd),
+                (Some(path), None) => path.display().to_string(),
+                (None, None) => "(none)".to_string(),
+            };
+            format!("the configured age identity {} (recipient mismatch?)", identity)
+        }
+        config::EncryptionType::Gpg => format!(
+            "the configured GPG key {} (was the vdir encrypted for another key?)",
+            encryption.gpg_key_id.as_deref().unwrap_or("(none)")
+        ),
+        config::EncryptionType::None => "encryption disabled".to_string(),
+    }
+}
+
+fn handle_set_default(
+    args: SetDefaultArgs,
+    config: &Config,
+    provider: &dyn crypto::CryptoProvider,
+) -> Result<()> {
+    let db_key = provider.derive_db_key().context(Failure::Crypto)?;
+    let mut db = Database::open_with_key(&config.db_path, db_key.as_deref())?;
+    reindex(&mut db, config, false, provider)?;
+    let contact = db
+        .get_contact(&args.uuid)?
+        .ok_or_else(|| not_found(format!("no contact with UID {}", args.uuid)))?;
+
+    let parsed = vcard_io::parse_file(&contact.path, config.phone_options(), provider)?;
+    let mut cards = parsed.cards;
+    let Some(card) = cards.first_mut() else {
+        bail!("{} holds no vCard", contact.path.display());
+    };
+    let (kind, value) = match (&args.email, &args.phone) {
+        (Some(email), _) => {
+            let index = vcard_io::find_email_entry(card, email)
+                .ok_or_else(|| not_found(format!("{} has no email {}", contact.display_fn, email)))?;
+            vcard_io::promote_email_entry(card, index);
+            vcard_io::prefer_first_email(card);
+            ("email", email)
+        }
+        (None, Some(phone)) => {
+            let index = vcard_io::find_tel_entry(card, phone)
+                .ok_or_else(|| not_found(format!("{} has no phone {}", contact.display_fn, phone)))?;
+            vcard_io::promote_tel_entry(card, index);
+            vcard_io::prefer_first_tel(card);
+            ("phone", phone)
+        }
+        (None, None) => bail!("--email or --phone is required"),
+    };
+    vcard_io::touch_rev(card);
+    vcard_io::write_cards(&contact.path, &cards, provider)?;
+
+    let state = vdir::compute_file_state(&contact.path)?;
+    let record = indexer::build_record(&contact.path, &cards[0], &state, None, config.nameless_display)?;
+    db.upsert(&record.item, &record.props)?;
+    info!("Default {} of {} is now {}", kind, contact.display_fn, value);
+    Ok(())
+}
+
+fn handle_new(args: NewArgs, config: &Config, provider: &dyn crypto::CryptoProvider) -> Result<()> {
+    let name = args.name.trim();
+    if name.is_empty() {
+        bail!("the contact name is empty");
+    }
+    let book = args.book.as_deref().map(valid_book).transpose()?;
+    let book_dir = config.book_dir(book.as_deref());
+    fs::create_dir_all(&book_dir)
+        .with_context(|| format!("failed to create address book {}", book_dir.display()))?;
+
+    let (card, uuid) = vcard_io::new_card(name, args.kind.into())?;
+    let mut used = vdir::existing_stems(&book_dir)?;
+    let stem = vdir::select_filename(&uuid, &mut used, None);
+    let target = vdir::vcf_target_path(&book_dir, &stem, provider.encryption_type());
+    vcard_io::write_cards(&target, std::slice::from_ref(&card), provider)?;
+
+    let db_key = provider.derive_db_key().context(Failure::Crypto)?;
+    let mut db = Database::open_with_key(&config.db_path, db_key.as_deref())?;
+    let state = vdir::compute_file_state(&target)?;
+    let record = indexer::build_record(&target, &card, &state, None, config.nameless_display)?;
+    db.upsert(&record.item, &record.props)?;
+    info!("Created {} in {}", name, target.display());
+    println!("{}", uuid);
     Ok(())
 }
 
+fn handle_list(args: ListArgs, config: &Config, provider: &dyn crypto::CryptoProvider) -> Result<()> {
+    let db_key = provider.derive_db_key().context(Failure::Crypto)?;
+    let db = Database::open_with_key(&config.db_path, db_key.as_deref())?;
+    // Tabs and line breaks inside values would split the row
+    let cell = |value: &str| value.replace(['\t', '\r', '\n'], " ").trim().to_string();
+    match args.format {
+        ListFormat::Flat => {
+            for contact in db.list_contacts_full()? {
+                let book = vdir::book_of(&config.vdir, &contact.path).unwrap_or_default();
+                println!(
+                    "{}\t{}\t{}\t{}\t{}",
+                    contact.uuid,
+                    cell(&contact.display_fn),
+                    cell(contact.email.as_deref().unwrap_or("")),
+                    cell(contact.phone.as_deref().unwrap_or("")),
+                    cell(&book),
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Print the decrypted vCard files of every contact matching `query`
+fn print_query_vcards(db: &Database, query: &str, provider: &dyn crypto::CryptoProvider) -> Result<()> {
+    let paths = db.query_paths(query)?;
+    if paths.is_empty() {
+        return Err(not_found(format!("no matches for \"{}\"", query)));
+    }
+    let mut cards = Vec::with_capacity(paths.len());
+    for path in &paths {
+        let text = vdir::read_vcf_file(path, provider)?;
+        cards.push(text.trim_end().to_string());
+    }
+    println!("{}", cards.join("\n\n"));
+    Ok(())
+}
+
+fn handle_serve(args: ServeArgs, config: &Config, provider: &dyn crypto::CryptoProvider) -> Result<()> {
+    if !args.addr.ip().is_loopback() && !args.allow_remote {
+        anyhow::bail!(
+            "refusing to listen on non-loopback address {} (pass --allow-remote to override)",
+            args.addr
+        );
+    }
+
+    let db_key = provider.derive_db_key().context(Failure::Crypto)?;
+    let db = Database::open_with_key(&config.db_path, db_key.as_deref())?;
+    serve::run(args.addr, db)
+}
+
 fn handle_import(args: ImportArgs, config: &Config, provider: &dyn crypto::CryptoProvider) -> Result<()> {
     // Validate automerge threshold
     if let Some(threshold) = args.automerge {
@@ -302,36 +1170,67 @@ fn handle_import(args: ImportArgs, config: &Config, provider: &dyn crypto::Crypt
             anyhow::bail!("--automerge threshold must be between 0.0 and 1.0");
         }
     }
+    if args.categories_as_books && !matches!(args.format, ImportFormat::Google) {
+        anyhow::bail!("--categories-as-books is only supported with --format google");
+    }
+    if args.full && !matches!(args.format, ImportFormat::Google) {
+        anyhow::bail!("--full is only supported with --format google");
+    }
+    let on_duplicate = match args.on_duplicate {
+        OnDuplicateArg::Skip => import::OnDuplicate::Skip,
+        OnDuplicateArg::Merge => import::OnDuplicate::Merge,
+    };
+    if on_duplicate == import::OnDuplicate::Merge && !matches!(args.format, ImportFormat::Google) {
+        anyhow::bail!("--on-duplicate merge is only supported with --format google");
+    }
+    let categories: Vec<String> = args.categories.iter().map(|c| c.trim().to_string()).collect();
+    if categories.iter().any(|c| c.is_empty()) {
+        anyhow::bail!("--category must not be empty");
+    }
+    let book = args.book.as_deref().map(valid_book).transpose()?;
+    let options = import::ImportOptions {
+        book: book.as_deref(),
+        automerge: args.automerge,
+        on_duplicate,
+        categories: &categories,
+    };
 
-    let normalize_report = vdir::normalize(&config.vdir, config.phone_region.as_deref(), provider)?;
+    let normalize_report = vdir::normalize(&config.vdir, config.phone_options(), provider, &config.vdir_ignore)?;
     if !normalize_report.needs_upgrade.is_empty() {
         eprintln!(
-            "warning: {} cards require manual upgrade to vCard 4.0",
+            "warning: {} cards require manual upgrade to vCard 4.0 (see `rldx upgrade-check`)",
             normalize_report.needs_upgrade.len()
         );
     }
 
     // Open encrypted database
-    let db_key = provider.derive_db_key()?;
-    let mut db = Database::open_with_key(&config.db_path, Some(&db_key))?;
+    let db_key = provider.derive_db_key().context(Failure::Crypto)?;
+    let mut db = Database::open_with_key(&config.db_path, db_key.as_deref())?;
+
+    import::configure_threads(args.threads);
 
     match args.format {
         ImportFormat::Google => {
+            if args.full {
+                let source = import::google::manifest_source(Path::new(&args.input))?;
+                db.clear_import_manifest(import::google::MANIFEST_FORMAT, &source)?;
+            }
+
             let result = import::google::import_google_contacts(
                 Path::new(&args.input),
                 config,
-                args.book.as_deref(),
-                args.automerge,
+                &options,
+                args.categories_as_books,
                 &mut db,
                 provider,
             )?;
 
-            println!("Imported {} contacts.", result.imported);
+            info!("Imported {} contacts.", result.imported);
 
             if !result.merged.is_empty() {
-                println!("Auto-merged {} contacts:", result.merged.len());
+                info!("Auto-merged {} contacts:", result.merged.len());
                 for merge in &result.merged {
-                    println!(
+                    info!(
                         "  {} <{}> -> {} ({:.2})",
                         merge.name, merge.email, merge.merged_into, merge.score
                     );
@@ -339,29 +1238,34 @@ fn handle_import(args: ImportArgs, config: &Config, provider: &dyn crypto::Crypt
             }
 
             if result.skipped > 0 {
-                println!(
+                info!(
                     "Skipped {} contacts (duplicate email or conversion error).",
                     result.skipped
                 );
             }
+
+            if result.unchanged > 0 {
+                info!(
+                    "{} contacts unchanged since the last import (use --full to reprocess).",
+                    result.unchanged
+                );
+            }
         }
         ImportFormat::Maildir => {
             let result = import::maildir::import_maildir(
                 Path::new(&args.input),
                 config,
-                args.book.as_deref(),
-                args.automerge,
-                args.threads,
+                &options,
                 &mut db,
                 provider,
             )?;
 
-            println!("Imported {} contacts.", result.imported);
+            info!("Imported {} contacts.", result.imported);
 
             if !result.merged.is_empty() {
-                println!("Auto-merged {} contacts:", result.merged.len());
+                info!("Auto-merged {} contacts:", result.merged.len());
                 for merge in &result.merged {
-                    println!(
+                    info!(
                         "  {} <{}> -> {} ({:.2})",
                         merge.name, merge.email, merge.merged_into, merge.score
                     );
@@ -369,7 +1273,7 @@ fn handle_import(args: ImportArgs, config: &Config, provider: &dyn crypto::Crypt
             }
 
             if result.skipped > 0 {
-                println!(
+                info!(
                     "Skipped {} addresses (no name, too short, or duplicate email).",
                     result.skipped
                 );
@@ -381,6 +1285,291 @@ fn handle_import(args: ImportArgs, config: &Config, provider: &dyn crypto::Crypt
     Ok(())
 }
 
+fn handle_export(args: ExportArgs, config: &Config, provider: &dyn crypto::CryptoProvider) -> Result<()> {
+    let dialect = match args.dialect {
+        DialectArg::Rfc => vcard_io::ExportDialect::Rfc,
+        DialectArg::Apple => vcard_io::ExportDialect::Apple,
+        DialectArg::Google => vcard_io::ExportDialect::Google,
+    };
+    if matches!(args.format, ExportFormat::Csv) {
+        if dialect != vcard_io::ExportDialect::Rfc {
+            anyhow::bail!("--dialect is only supported with --format vcard");
+        }
+        if args.categories_from_books {
+            anyhow::bail!("--categories-from-books is only supported with --format vcard");
+        }
+    }
+    let root = book_root(config, args.book.as_deref())?;
+
+    let (output, exported) = match args.format {
+        ExportFormat::Vcard => export_vcards(&root, dialect, args.categories_from_books, config, provider)?,
+        ExportFormat::Csv => export_csv(&root, config, provider)?,
+    };
+
+    match args.output {
+        Some(path) => {
+            fs::write(&path, output)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+            info!("Exported {} contacts to {}", exported, path.display());
+        }
+        None => print!("{}", output),
+    }
+    Ok(())
+}
+
+/// The cards under `root` in `dialect`, and how many there were
+fn export_vcards(
+    root: &Path,
+    dialect: vcard_io::ExportDialect,
+    categories_from_books: bool,
+    config: &Config,
+    provider: &dyn crypto::CryptoProvider,
+) -> Result<(String, usize)> {
+    let mut files = vdir::list_vcf_files(root, &config.vdir_ignore, config.encryption.encryption_type)?;
+    files.sort();
+
+    let mut output = String::new();
+    let mut exported = 0usize;
+    for path in files {
+        let mut parsed = vcard_io::parse_file(&path, config.phone_options(), provider)?;
+        let book = vdir::book_of(&config.vdir, &path);
+        for card in &mut parsed.cards {
+            vcard_io::inline_managed_photos(card, &config.vdir, provider);
+            output.push_str(&vcard_io::card_to_dialect_string(
+                card,
+                dialect,
+                book.as_deref(),
+                categories_from_books,
+            ));
+            exported += 1;
+        }
+    }
+    Ok((output, exported))
+}
+
+/// A CSV sheet of the indexed contacts under `root`, and its row count
+fn export_csv(root: &Path, config: &Config, provider: &dyn crypto::CryptoProvider) -> Result<(String, usize)> {
+    let db_key = provider.derive_db_key().context(Failure::Crypto)?;
+    let db = Database::open_with_key(&config.db_path, db_key.as_deref())?;
+
+    let mut output = csv_record([
+        "FN", "Given", "Family", "Emails", "Phones", "Org", "Title", "Categories", "Book",
+    ]);
+    let mut exported = 0usize;
+    for contact in db.list_contacts_with_props(&["N", "EMAIL", "TEL", "ORG", "TITLE", "CATEGORIES"])? {
+        if !contact.path.starts_with(root) {
+            continue;
+        }
+        let values = |field: &'static str| contact.props.iter().filter(move |p| p.field == field);
+        let first = |field: &'static str| values(field).next().map(|p| p.value.as_str()).unwrap_or("");
+        // N is indexed as family;given;additional;prefix;suffix
+        let mut name = first("N").split(';');
+        let family = name.next().unwrap_or("");
+        let given = name.next().unwrap_or("");
+        let joined = |field: &'static str| values(field).map(|p| p.value.trim()).collect::<Vec<_>>().join("; ");
+        let categories = values("CATEGORIES")
+            .flat_map(|p| p.value.split(','))
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .collect::<Vec<_>>()
+            .join("; ");
+        let book = vdir::book_path_of(&config.vdir, &contact.path).unwrap_or_default();
+
+        output.push_str(&csv_record([
+            contact.display_fn.as_str(),
+            given,
+            family,
+            &joined("EMAIL"),
+            &joined("TEL"),
+            first("ORG").split(';').next().unwrap_or(""),
+            first("TITLE"),
+            &categories,
+            &book,
+        ]));
+        exported += 1;
+    }
+    Ok((output, exported))
+}
+
+/// One RFC 4180 line; fields holding commas, quotes or line breaks are quoted
+fn csv_record<'a>(fields: impl IntoIterator<Item = &'a str>) -> String {
+    let mut line = fields
+        .into_iter()
+        .map(|field| {
+            if field.contains([',', '"', '\r', '\n']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push_str("\r\n");
+    line
+}
+
+/// A `--book` value checked to name a (possibly nested) book inside the vdir
+fn valid_book(book: &str) -> Result<String> {
+    vdir::book_path(book).ok_or_else(|| {
+        anyhow::Error::new(Failure::Config)
+            .context(format!("invalid address book `{}`: expected a relative path like work/clients", book))
+    })
+}
+
+/// The vdir, or the named address book under it
+fn book_root(config: &Config, book: Option<&str>) -> Result<PathBuf> {
+    match book {
+        Some(book) => {
+            let dir = config.vdir.join(valid_book(book)?);
+            if !dir.is_dir() {
+                return Err(not_found(format!("address book not found: {}", book)));
+            }
+            Ok(dir)
+        }
+        None => Ok(config.vdir.clone()),
+    }
+}
+
+fn handle_clean_emails(
+    args: CleanEmailsArgs,
+    config: &Config,
+    provider: &dyn crypto::CryptoProvider,
+) -> Result<()> {
+    let root = book_root(config, args.book.as_deref())?;
+    let mut files = vdir::list_vcf_files(&root, &config.vdir_ignore, config.encryption.encryption_type)?;
+    files.sort();
+
+    let mut cleaned = 0usize;
+    for path in files {
+        let parsed = vcard_io::parse_file(&path, config.phone_options(), provider)?;
+        let mut cards = parsed.cards;
+        let mut removed = 0usize;
+        let mut changed = false;
+        for card in &mut cards {
+            let before = card.email.len();
+            if vcard_io::dedupe_emails(card, config.email_case) {
+                removed += before - card.email.len();
+                vcard_io::touch_rev(card);
+                changed = true;
+            }
+        }
+        if !changed {
+            continue;
+        }
+        cleaned += 1;
+        let relative = path.strip_prefix(&config.vdir).unwrap_or(&path);
+        info!("{}: {} duplicate(s) removed", relative.display(), removed);
+        if !args.dry_run {
+            vcard_io::write_cards(&path, &cards, provider)?;
+        }
+    }
+
+    if args.dry_run {
+        info!("Would clean emails in {} contact(s)", cleaned);
+        return Ok(());
+    }
+    info!("Cleaned emails in {} contact(s)", cleaned);
+    if cleaned > 0 {
+        let db_key = provider.derive_db_key().context(Failure::Crypto)?;
+        let mut db = Database::open_with_key(&config.db_path, db_key.as_deref())?;
+        reindex(&mut db, config, false, provider)?;
+    }
+    Ok(())
+}
+
+fn handle_normalize_phones(
+    args: NormalizePhonesArgs,
+    config: &Config,
+    provider: &dyn crypto::CryptoProvider,
+) -> Result<()> {
+    let region = args
+        .region
+        .as_deref()
+        .map(|region| region.trim().to_ascii_uppercase())
+        .or_else(|| config.phone_region.clone());
+    let mut files = vdir::list_vcf_files(&config.vdir, &config.vdir_ignore, config.encryption.encryption_type)?;
+    files.sort();
+
+    // Read without the lazy normalization so the changes can be counted
+    let as_stored = vcard_io::PhoneOptions::new(None, false);
+    let mut numbers = 0usize;
+    let mut contacts = 0usize;
+    for path in files {
+        let mut cards = vcard_io::parse_file(&path, as_stored, provider)?.cards;
+        let mut changed = 0usize;
+        for card in &mut cards {
+            let count = vcard_io::normalize_card_phone_numbers(card, region.as_deref());
+            if count > 0 && !args.preserve_rev {
+                vcard_io::touch_rev(card);
+            }
+            changed += count;
+        }
+        if changed == 0 {
+            continue;
+        }
+        numbers += changed;
+        contacts += 1;
+        let relative = path.strip_prefix(&config.vdir).unwrap_or(&path);
+        info!("{}: {} number(s) normalized", relative.display(), changed);
+        if !args.dry_run {
+            vcard_io::write_cards(&path, &cards, provider)?;
+        }
+    }
+
+    if args.dry_run {
+        info!("Would normalize {} phone number(s) in {} contact(s)", numbers, contacts);
+        return Ok(());
+    }
+    info!("Normalized {} phone number(s) in {} contact(s)", numbers, contacts);
+    if contacts > 0 {
+        let db_key = provider.derive_db_key().context(Failure::Crypto)?;
+        let mut db = Database::open_with_key(&config.db_path, db_key.as_deref())?;
+        reindex(&mut db, config, false, provider)?;
+    }
+    Ok(())
+}
+
+fn handle_photos(args: PhotosArgs, config: &Config, provider: &dyn crypto::CryptoProvider) -> Result<()> {
+    match args.command {
+        PhotosCommand::Import(import_args) => {
+            // Make sure the index knows about every contact before matching
+            let db_key = provider.derive_db_key().context(Failure::Crypto)?;
+            let mut db = Database::open_with_key(&config.db_path, db_key.as_deref())?;
+            reindex(&mut db, config, false, provider)?;
+
+            let result =
+                import::photos::import_photos(&import_args.dir, config, &mut db, provider)?;
+
+            info!("Set {} photo(s).", result.matched.len());
+            if result.external > 0 {
+                info!(
+                    "{} photo(s) exceeded max_embedded_photo_bytes and were stored in {}.",
+                    result.external,
+                    config.vdir.join(vdir::PHOTO_DIR).display()
+                );
+            }
+            for (file, contact) in &result.matched {
+                info!("  {} -> {}", file.display(), contact.display());
+            }
+
+            if !result.unmatched.is_empty() {
+                info!("No matching contact for {} file(s):", result.unmatched.len());
+                for file in &result.unmatched {
+                    info!("  {}", file.display());
+                }
+            }
+
+            if !result.failed.is_empty() {
+                info!("Failed to apply {} file(s):", result.failed.len());
+                for (file, reason) in &result.failed {
+                    info!("  {}: {}", file.display(), reason);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 fn handle_remote(args: RemoteArgs, config: &Config, provider: &dyn crypto::CryptoProvider) -> Result<()> {
     match args.command {
         Some(RemoteCommand::Add(add_args)) => {
@@ -395,6 +1584,9 @@ fn handle_remote(args: RemoteArgs, config: &Config, provider: &dyn crypto::Crypt
         Some(RemoteCommand::Test(test_args)) => {
             handle_remote_test(test_args, config)?;
         }
+        Some(RemoteCommand::Status(status_args)) => {
+            handle_remote_status(status_args, config, provider)?;
+        }
         None => {
             // With -v flag, show verbose list; otherwise short list
             handle_remote_list(config, args.verbose)?;
@@ -409,50 +1601,77 @@ fn handle_remote_add(args: RemoteAddArgs, config: &Config) -> Result<()> {
         bail!("remote '{}' already exists", args.name);
     }
 
-    // Build the TOML section to add to config
-    let mut toml_section = format!(
-        r#"
-[[remotes]]
-name = "{}"
-type = "carddav"
-url = "{}"
-username = "{}"
-address_book = "{}""#,
-        args.name, args.url, args.user, args.address_book
-    );
+    validate_remote_url(&args.url)?;
 
+    // Build the remote table; serialization takes care of escaping
+    let mut remote = toml::map::Map::new();
+    remote.insert("name".into(), toml::Value::String(args.name.clone()));
+    remote.insert("type".into(), toml::Value::String("carddav".into()));
+    remote.insert("url".into(), toml::Value::String(args.url.clone()));
+    remote.insert("username".into(), toml::Value::String(args.user.clone()));
+    remote.insert("address_book".into(), toml::Value::String(args.address_book.clone()));
     if let Some(ref cmd) = args.password_cmd {
-        toml_section.push_str(&format!("\npassword_cmd = \"{}\"", cmd));
+        remote.insert("password_cmd".into(), toml::Value::String(cmd.clone()));
     }
-
     if let Some(ref local_book) = args.local_book {
-        toml_section.push_str(&format!("\nlocal_book = \"{}\"", local_book));
+        remote.insert("local_book".into(), toml::Value::String(local_book.clone()));
     }
 
-    toml_section.push('\n');
-
-    // Append to config file
-    let mut content = fs::read_to_string(&config.config_path)
+    // Append the new table, leaving the user's comments and layout alone;
+    // serialization takes care of escaping
+    let content = fs::read_to_string(&config.config_path)
         .with_context(|| format!("failed to read config: {}", config.config_path.display()))?;
 
-    content.push_str(&toml_section);
+    let mut wrapper = toml::map::Map::new();
+    wrapper.insert(
+        "remotes".into(),
+        toml::Value::Array(vec![toml::Value::Table(remote)]),
+    );
+    let section = toml::to_string(&wrapper).context("failed to serialize remote")?;
 
-    fs::write(&config.config_path, content)
+    let mut new_content = content;
+    if !new_content.is_empty() && !new_content.ends_with('\n') {
+        new_content.push('\n');
+    }
+    new_content.push('\n');
+    new_content.push_str(&section);
+
+    // An existing `remotes = [...]` array cannot take another [[remotes]]
+    if let Err(err) = toml::from_str::<toml::Value>(&new_content) {
+        bail!("cannot append a [[remotes]] table to the config: {}", err);
+    }
+
+    fs::write(&config.config_path, new_content)
         .with_context(|| format!("failed to write config: {}", config.config_path.display()))?;
 
-    println!("Added remote '{}'", args.name);
-    println!();
-    println!("Run 'rldx remote test {}' to verify the connection.", args.name);
-    println!("Run 'rldx sync {}' to sync contacts.", args.name);
+    info!("Added remote '{}'", args.name);
+    info!();
+    info!("Run 'rldx remote test {}' to verify the connection.", args.name);
+    info!("Run 'rldx sync {}' to sync contacts.", args.name);
 
     Ok(())
 }
 
+/// Ensure a remote URL is an absolute http(s) URL with a host
+fn validate_remote_url(url: &str) -> Result<()> {
+    let uri: http::Uri = url
+        .parse()
+        .with_context(|| format!("invalid URL: {}", url))?;
+    match uri.scheme_str() {
+        Some("http") | Some("https") => {}
+        _ => bail!("invalid URL '{}': expected an http:// or https:// URL", url),
+    }
+    if uri.host().is_none_or(str::is_empty) {
+        bail!("invalid URL '{}': missing host", url);
+    }
+    Ok(())
+}
+
 fn handle_remote_list(config: &Config, verbose: bool) -> Result<()> {
     if config.remotes.is_empty() {
-        println!("No remotes configured.");
-        println!();
-        println!("Run 'rldx remote add <name> --type carddav --url <URL> --user <USER> --address-book <BOOK>' to add one.");
+        info!("No remotes configured.");
+        info!();
+        info!("Run 'rldx remote add <name> --type carddav --url <URL> --user <USER> --address-book <BOOK>' to add one.");
         return Ok(());
     }
 
@@ -485,7 +1704,19 @@ fn handle_remote_list(config: &Config, verbose: bool) -> Result<()> {
 fn handle_remote_remove(args: RemoteRemoveArgs, config: &Config, provider: &dyn crypto::CryptoProvider) -> Result<()> {
     // Find the remote
     if !config.remotes.iter().any(|r| r.name == args.name) {
-        bail!("remote '{}' not found", args.name);
+        return Err(not_found(format!("remote '{}' not found", args.name)));
+    }
+
+    // Purging forces the next sync with this remote to start from scratch
+    if args.purge && !args.yes {
+        let prompt = format!(
+            "Purge sync metadata for '{}'? Incremental sync state will be lost. [y/N] ",
+            args.name
+        );
+        if !confirm(&prompt)? {
+            info!("Aborted; remote '{}' was not removed.", args.name);
+            return Ok(());
+        }
     }
 
     // Read and parse config file, remove the remote section
@@ -515,63 +1746,207 @@ fn handle_remote_remove(args: RemoteRemoveArgs, config: &Config, provider: &dyn
     fs::write(&config.config_path, new_content)
         .with_context(|| format!("failed to write config: {}", config.config_path.display()))?;
 
-    println!("Removed remote '{}'", args.name);
+    info!("Removed remote '{}'", args.name);
 
     // Optionally purge sync metadata
     if args.purge {
-        let db_key = provider.derive_db_key()?;
-        let mut db = Database::open_with_key(&config.db_path, Some(&db_key))?;
-        db.delete_all_sync_metadata_for_remote(&args.name)?;
-        println!("Purged sync metadata for '{}'", args.name);
+        let db_key = provider.derive_db_key().context(Failure::Crypto)?;
+        let mut db = Database::open_with_key(&config.db_path, db_key.as_deref())?;
+        let purged = db.delete_all_sync_metadata_for_remote(&args.name)?;
+        info!("Purged {} sync record(s) for '{}'", purged, args.name);
     }
 
     Ok(())
 }
 
+/// Print `prompt` on stderr and read one trimmed line from stdin
+fn prompt_line(prompt: &str) -> Result<String> {
+    use std::io::{BufRead, Write};
+
+    eprint!("{}", prompt);
+    std::io::stderr().flush()?;
+    let mut line = String::new();
+    std::io::stdin().lock().read_line(&mut line)?;
+    Ok(line.trim().to_string())
+}
+
+/// Ask a yes/no question on stderr; anything but "y"/"yes" counts as no
+fn confirm(prompt: &str) -> Result<bool> {
+    let answer = prompt_line(prompt)?;
+    Ok(matches!(answer.to_ascii_lowercase().as_str(), "y" | "yes"))
+}
+
+/// The remotes a `sync` or `remote test` run covers: all of them with
+/// `--all`, the named one, the only one, or one picked from a list when
+/// several are configured and stdin and stderr are terminals
+fn select_remotes<'c>(config: &'c Config, name: Option<&str>, all: bool) -> Result<Vec<&'c RemoteConfig>> {
+    use std::io::IsTerminal;
+
+    if let Some(name) = name {
+        let remote = config.remotes.iter()
+            .find(|r| r.name == name)
+            .ok_or_else(|| not_found(format!("remote '{}' not found", name)))?;
+        return Ok(vec![remote]);
+    }
+    if config.remotes.is_empty() {
+        return Err(not_found("no remotes configured; add one with `rldx remote add`".to_string()));
+    }
+    if all || config.remotes.len() == 1 {
+        return Ok(config.remotes.iter().collect());
+    }
+    if !(std::io::stdin().is_terminal() && std::io::stderr().is_terminal()) {
+        return Err(anyhow::Error::new(Failure::Config)
+            .context("several remotes are configured; name one or pass --all"));
+    }
+    pick_remote(&config.remotes).map(|remote| vec![remote])
+}
+
+/// List the remotes on stderr and read a number or name from stdin
+fn pick_remote(remotes: &[RemoteConfig]) -> Result<&RemoteConfig> {
+    for (index, remote) in remotes.iter().enumerate() {
+        eprintln!("  {}) {}  {}", index + 1, remote.name, remote.url);
+    }
+    let prompt = format!("Remote [1-{}]: ", remotes.len());
+    let answer = prompt_line(&prompt)?;
+    let chosen = answer
+        .parse::<usize>()
+        .ok()
+        .and_then(|number| number.checked_sub(1))
+        .and_then(|index| remotes.get(index))
+        .or_else(|| remotes.iter().find(|r| r.name == answer));
+    match chosen {
+        Some(remote) => Ok(remote),
+        None if answer.is_empty() => bail!("no remote chosen"),
+        None => Err(not_found(format!("remote '{}' not found", answer))),
+    }
+}
+
 fn handle_remote_test(args: RemoteTestArgs, config: &Config) -> Result<()> {
-    use remote::Remote;
+    let remotes = select_remotes(config, args.name.as_deref(), args.all)?;
+    for_each_remote(&remotes, "remote test", test_remote)
+}
 
-    // Find the remote
-    let remote_config = config.remotes.iter()
-        .find(|r| r.name == args.name)
-        .ok_or_else(|| anyhow::anyhow!("remote '{}' not found", args.name))?
-        .clone();
+fn test_remote(remote_config: &RemoteConfig) -> Result<()> {
+    use remote::Remote;
 
-    println!("Testing connection to '{}'...", args.name);
+    info!("Testing connection to '{}'...", remote_config.name);
 
     // Use tokio runtime to test the connection
     let rt = tokio::runtime::Runtime::new()?;
     rt.block_on(async {
-        let client = remote::carddav::CardDavRemote::new(remote_config).await?;
+        let client = remote::carddav::CardDavRemote::new(remote_config.clone()).await?;
         client.test_connection().await?;
         Ok::<_, anyhow::Error>(())
-    })?;
+    })
+    .context(Failure::Remote)?;
+
+    info!("Connection successful!");
+    Ok(())
+}
+
+fn handle_remote_status(args: RemoteStatusArgs, config: &Config, provider: &dyn crypto::CryptoProvider) -> Result<()> {
+    if !config.remotes.iter().any(|r| r.name == args.name) {
+        return Err(not_found(format!("remote '{}' not found", args.name)));
+    }
 
-    println!("Connection successful!");
+    let db_key = provider.derive_db_key().context(Failure::Crypto)?;
+    let db = Database::open_with_key(&config.db_path, db_key.as_deref())?;
+    let summary = db.sync_summary(&args.name)?;
+
+    let last_sync = summary
+        .last_synced
+        .and_then(|secs| time::OffsetDateTime::from_unix_timestamp(secs).ok())
+        .and_then(|at| at.format(&time::format_description::well_known::Rfc3339).ok())
+        .unwrap_or_else(|| "never".to_string());
+
+    println!("{}", args.name);
+    println!("  Last Sync: {}", last_sync);
+    println!("  Tracked Cards: {}", summary.tracked);
+    println!("  Modified Locally: {}", summary.local_modified);
+    println!("  Excluded (no-sync): {}", summary.no_sync);
+    println!("  Pending Uploads: {}", summary.pending_uploads);
     Ok(())
 }
 
 fn handle_sync(args: SyncArgs, config: &Config, provider: &dyn crypto::CryptoProvider) -> Result<()> {
+    let remotes = select_remotes(config, args.name.as_deref(), args.all)?;
+    for_each_remote(&remotes, "sync", |remote_config| {
+        sync_remote(remote_config, &args, config, provider)
+    })
+}
+
+/// Run `action` on every remote. A failing remote does not stop the rest:
+/// its error is reported and the run fails at the end, exiting with the
+/// first failure's category.
+fn for_each_remote(
+    remotes: &[&RemoteConfig],
+    verb: &str,
+    mut action: impl FnMut(&RemoteConfig) -> Result<()>,
+) -> Result<()> {
+    if let [remote_config] = remotes {
+        return action(remote_config);
+    }
+
+    let mut failed = Vec::new();
+    let mut failure = None;
+    for remote_config in remotes {
+        if let Err(err) = action(remote_config) {
+            eprintln!("Error: {} '{}' failed: {:?}", verb, remote_config.name, err);
+            failure = failure.or_else(|| err.downcast_ref::<Failure>().copied());
+            failed.push(remote_config.name.as_str());
+        }
+    }
+    if failed.is_empty() {
+        return Ok(());
+    }
+
+    let message = format!(
+        "{} failed for {} of {} remotes: {}",
+        verb,
+        failed.len(),
+        remotes.len(),
+        failed.join(", ")
+    );
+    Err(match failure {
+        Some(failure) => anyhow::Error::new(failure).context(message),
+        None => anyhow::anyhow!(message),
+    })
+}
+
+fn sync_remote(
+    remote_config: &RemoteConfig,
+    args: &SyncArgs,
+    config: &Config,
+    provider: &dyn crypto::CryptoProvider,
+) -> Result<()> {
     use remote::Remote;
     use sync::SyncEngine;
 
-    // Find the remote
-    let remote_config = config.remotes.iter()
-        .find(|r| r.name == args.name)
-        .ok_or_else(|| anyhow::anyhow!("remote '{}' not found", args.name))?
-        .clone();
-
-    println!("Syncing with '{}'...", args.name);
+    info!("Syncing with '{}'...", remote_config.name);
     if args.dry_run {
-        println!("(dry run mode - no changes will be made)");
+        info!("(dry run mode - no changes will be made)");
     }
     if args.pull_only {
-        println!("(pull-only mode - local changes will not be uploaded)");
+        info!("(pull-only mode - local changes will not be uploaded)");
     }
 
-    // Open database
-    let db_key = provider.derive_db_key()?;
-    let mut db = Database::open_with_key(&config.db_path, Some(&db_key))?;
+    // Open database and bring the index up to date with local edits
+    let db_key = provider.derive_db_key().context(Failure::Crypto)?;
+    let mut db = Database::open_with_key(&config.db_path, db_key.as_deref())?;
+    reindex(&mut db, config, false, provider)?;
+
+    // Create sync engine
+    let mut engine = SyncEngine::new(
+        config,
+        remote_config,
+        &mut db,
+        provider,
+        args.dry_run,
+        args.pull_only,
+    );
+
+    // Record local changes before connecting, so they survive an offline failure
+    engine.queue_local_changes()?;
 
     // Use tokio runtime for async operations
     let rt = tokio::runtime::Runtime::new()?;
@@ -579,29 +1954,20 @@ fn handle_sync(args: SyncArgs, config: &Config, provider: &dyn crypto::CryptoPro
         // Create CardDAV client
         let client = remote::carddav::CardDavRemote::new(remote_config.clone()).await?;
 
-        // Test connection first
+        // Test connection first; on failure the upload queue is left intact
         client.test_connection().await?;
-        println!("Connected to CardDAV server.");
-
-        // Create sync engine
-        let mut engine = SyncEngine::new(
-            config,
-            &remote_config,
-            &mut db,
-            provider,
-            args.dry_run,
-            args.pull_only,
-        );
+        info!("Connected to CardDAV server.");
 
         // Run sync
         engine.sync(&client).await?;
 
         Ok::<_, anyhow::Error>(())
-    })?;
+    })
+    .context(Failure::Remote)?;
 
     // Reindex after sync to update the search database
     if !args.dry_run {
-        println!("Reindexing...");
+        info!("Reindexing...");
         reindex(&mut db, config, false, provider)?;
     }
 
@@ -614,13 +1980,25 @@ fn reindex(
     force: bool,
     provider: &dyn crypto::CryptoProvider,
 ) -> Result<()> {
-    let files = vdir::list_vcf_files(&config.vdir)?;
-    let paths_set: HashSet<_> = files.iter().cloned().collect();
+    reindex_with(db, config, force, indexer::UidCollision::KeepNewest, provider)
+}
+
+fn reindex_with(
+    db: &mut Database,
+    config: &Config,
+    force: bool,
+    uid_collision: indexer::UidCollision,
+    provider: &dyn crypto::CryptoProvider,
+) -> Result<()> {
+    let mut files = vdir::list_vcf_files(&config.vdir, &config.vdir_ignore, config.encryption.encryption_type)?;
+    files.sort();
+    let mut paths_set: HashSet<_> = files.iter().cloned().collect();
     if force {
         // Nuke DB schema and rebuild from scratch
         db.reset_schema()?;
     }
     let stored = if force { Default::default() } else { db.stored_items()? };
+    let mut skipped = Vec::new();
 
     for path in files {
         let state = vdir::compute_file_state(&path)?;
@@ -640,9 +2018,39 @@ fn reindex(
         }
 
         // Only parse files that need reindexing (decrypt with provider)
-        let parsed = vcard_io::parse_file(&path, config.phone_region.as_deref(), provider)?;
+        let mut parsed = match vcard_io::parse_file(&path, config.phone_options(), provider) {
+            Ok(parsed) => parsed,
+            Err(err)
+                if config.on_decrypt_error == OnDecryptError::Skip
+                    && vcard_io::decrypt_error_path(&err).is_some() =>
+            {
+                log::warn!("skipping {}: {:#}", path.display(), err);
+                skipped.push(path);
+                continue;
+            }
+            Err(err) => return Err(err),
+        };
+        if config.fill_missing_fn {
+            let filled = parsed
+                .cards
+                .iter_mut()
+                .fold(false, |filled, card| vcard_io::fill_fn_from_name(card) | filled);
+            if filled {
+                vcard_io::write_cards(&path, &parsed.cards, provider)?;
+                parsed.changed = true;
+            }
+        }
         let cards = parsed.cards;
 
+        let now = time::OffsetDateTime::now_utc();
+        if let Some(rev) = cards.iter().find_map(|card| vcard_io::future_rev(card, now)) {
+            log::warn!(
+                "{} has a REV in the future ({}); run `rldx verify --fix`",
+                path.display(),
+                rev
+            );
+        }
+
         if cards.is_empty() {
             eprintln!("warning: file {} contained no vCards", path.display());
             continue;
@@ -663,19 +2071,59 @@ fn reindex(
         };
 
         let card = cards.into_iter().next().unwrap();
-        let record = indexer::build_record(&path, &card, &final_state, None)?;
-        db.upsert(&record.item, &record.props)?;
+        let record = indexer::build_record(&path, &card, &final_state, None, config.nameless_display)?;
+        let clash = indexer::store_record(db, record, &paths_set, uid_collision, config, provider)?;
+        match clash {
+            Some(indexer::UidClash { uid, older, newer, renewed: None }) => eprintln!(
+                "warning: {} and {} share UID {}; indexing only the newer {} (use --fix-dupe-uids to keep both)",
+                older.display(),
+                newer.display(),
+                uid,
+                newer.display()
+            ),
+            Some(indexer::UidClash { uid, older, newer, renewed: Some(renewed) }) => eprintln!(
+                "warning: {} had the UID {} of {}; gave it the new UID {}",
+                older.display(),
+                uid,
+                newer.display(),
+                renewed
+            ),
+            None => {}
+        }
     }
 
+    // Skipped files drop out of the index like deleted ones
+    for path in &skipped {
+        paths_set.remove(path);
+    }
     db.remove_missing(&paths_set)?;
+    report_skipped(&skipped);
     Ok(())
 }
 
+/// List the files a reindex left out because they failed to decrypt
+fn report_skipped(skipped: &[PathBuf]) {
+    if skipped.is_empty() {
+        return;
+    }
+    eprintln!(
+        "warning: skipped {} file(s) that failed to decrypt (on_decrypt_error = \"skip\"):",
+        skipped.len()
+    );
+    for path in skipped {
+        eprintln!("  {}", path.display());
+    }
+}
+
 fn handle_init(args: &InitArgs, custom_config_path: Option<&Path>) -> Result<()> {
     let config_path = match custom_config_path {
         Some(p) => config::expand_tilde(p),
         None => config::config_path()?,
     };
+    // Relative paths in the config are read against its directory, so the
+    // paths written below must not depend on the current directory
+    let config_path = std::path::absolute(&config_path)
+        .with_context(|| format!("failed to resolve {}", config_path.display()))?;
     let config_dir = config_path
         .parent()
         .ok_or_else(|| anyhow::anyhow!("config path has no parent directory"))?
@@ -717,7 +2165,7 @@ type = "gpg"
 gpg_key_id = "{}""#,
                 key
             );
-            ("gpg", section)
+            (config::EncryptionType::Gpg, section)
         }
         EncryptionArg::Age => {
             // Handle identity and recipient
@@ -732,7 +2180,10 @@ gpg_key_id = "{}""#,
                     // Derive recipient from identity file
                     derive_age_recipient(identity)?
                 };
-                (identity.clone(), recipient)
+                let identity = identity
+                    .canonicalize()
+                    .with_context(|| format!("failed to resolve {}", identity.display()))?;
+                (identity, recipient)
             } else {
                 // Generate new identity
                 if args.recipient.is_some() {
@@ -757,7 +2208,7 @@ gpg_key_id = "{}""#,
                     fs::set_permissions(&identity_path, perms)?;
                 }
 
-                println!("Generated age identity at: {}", identity_path.display());
+                info!("Generated age identity at: {}", identity_path.display());
                 (identity_path, recipient)
             };
 
@@ -769,7 +2220,18 @@ age_recipient = "{}""#,
                 identity_path.display(),
                 recipient
             );
-            ("age", section)
+            (config::EncryptionType::Age, section)
+        }
+        EncryptionArg::None => {
+            if args.key.is_some() || args.identity.is_some() || args.recipient.is_some() {
+                bail!("--key, --identity and --recipient cannot be used with --encryption none");
+            }
+            log::warn!("--encryption none stores contacts and the index in cleartext");
+            let section = r#"[encryption]
+# WARNING: contacts and the index database are stored in cleartext
+type = "none""#
+                .to_string();
+            (config::EncryptionType::None, section)
         }
     };
 
@@ -811,13 +2273,16 @@ age_recipient = "{}""#,
     }
 
     // 6. Print success message
-    println!();
-    println!("Initialized rldx with {} encryption.", encryption_type);
-    println!();
-    println!("Configuration: {}", config_path.display());
-    println!("vCard storage: {}", vdir.display());
-    println!();
-    println!("Run 'rldx' to start the application.");
+    info!();
+    match encryption_type {
+        config::EncryptionType::None => info!("Initialized rldx with no encryption."),
+        other => info!("Initialized rldx with {} encryption.", other.as_str()),
+    }
+    info!();
+    info!("Configuration: {}", config_path.display());
+    info!("vCard storage: {}", vdir.display());
+    info!();
+    info!("Run 'rldx' to start the application.");
 
     Ok(())
 }
@@ -875,19 +2340,33 @@ fn generate_config_file(vdir: &Path, encryption_section: &str) -> Result<String>
         r##"# rldx configuration
 # Generated by 'rldx init'
 
-# Path to the directory containing your vCard files.
+# Path to the directory containing your vCard files. Relative paths here and
+# in db_path, age_identity and password_file are read against the directory
+# of this file.
 vdir = "{vdir}"
 
-# Path to the index database file.
+# Optional: glob patterns for files and directories under vdir that are never
+# indexed, synced or rewritten. Patterns with a "/" match the path relative to
+# vdir, others match any file or directory name.
+# vdir_ignore = ["templates", "*.tmpl.vcf.age", "Work/drafts/*"]
+
+# Path to the index database file. It may live anywhere, e.g. on another
+# volume; missing parent directories are created when it is first opened.
 # Default: ~/.local/share/rldx/index.db
 db_path = "{db_path}"
 
+# Key binding preset: "default", "vim" or "emacs".
+# Entries in the [keys.*] tables below override the preset.
+# keymap = "default"
+
 # =============================================================================
 # Encryption (required)
 # =============================================================================
 # All vCard files are encrypted. Both backends store files as .vcf.age.
-#   gpg - Uses GPG key to derive encryption key (requires gpg-agent)
-#   age - Uses Age X25519 keys directly (modern, simpler)
+#   gpg  - Uses GPG key to derive encryption key (requires gpg-agent)
+#   age  - Uses Age X25519 keys directly (modern, simpler)
+#   none - NO encryption: files (named .vcf) and the index database are
+#          stored in cleartext. Only for testing and non-secret books.
 
 {encryption}
 
@@ -905,11 +2384,92 @@ F7 = "share"
 F8 = "delete"
 
 # Optional: override which fields appear in the first pane.
-# fields_first_pane = ["fname", "mname", "lname", "alias", "phone", "email"]
+# fields_first_pane = ["fname", "mname", "lname", "alias", "phone", "email", "address"]
 
 # Optional: default region for phone number normalization (ISO 3166-1 alpha-2).
 # phone_region = "US"
 
+# Optional: set to false to keep phone numbers exactly as stored (no E.164 rewriting).
+# phone_normalize = true
+
+# Optional: on launch, look for likely duplicate contacts (similar names or a
+# shared email) and offer to review them in the marked-only view.
+# review_duplicates_on_startup = false
+# Name similarity (0.0-1.0, like import --automerge) needed to call two contacts
+# likely duplicates.
+# duplicate_similarity = 0.9
+
+# Optional: what the contact list shows for cards with neither FN nor N: "email"
+# (local part of the primary email, then ORG), "org" (ORG, then email) or
+# "placeholder". The card itself is not changed; run with --reindex after
+# changing this.
+# nameless_display = "email"
+
+# Optional: cards without FN but with N are listed as "Given Family". Set to
+# true to also write that FN into the card file on the next --reindex.
+# fill_missing_fn = false
+
+# Optional: tag values that imports and sync add with X-RLDX-SOURCE (the
+# import format or remote name), so merged contacts remember where each
+# value came from. Show them in the details pane with the toggle_sources key.
+# track_field_sources = false
+
+# Optional: address book (a vdir subdirectory, created on demand and possibly
+# nested like work/clients) for new contacts that do not name one, e.g. imports
+# without --book. Without it they are written to the vdir root.
+# default_book = "Inbox"
+
+# Optional: pressing confirm (Enter) on a search with no matches creates a
+# contact with the search text as its name, in default_book. Off by default
+# so a stray Enter never adds a contact.
+# create_on_no_match = false
+
+# Optional: photos whose encoded JPEG is larger than this many bytes (even after
+# the resize to 128x128) are written, encrypted like the cards, to .photos/ in
+# the vdir and referenced from the card instead of embedded. Unset embeds all.
+# max_embedded_photo_bytes = 16384
+
+# Optional: before a contact file is overwritten or deleted, copy its
+# (encrypted) bytes to .backups/ in the vdir under a timestamped name, keeping
+# the newest edit_backup_keep copies per contact.
+# edit_backup = false
+# edit_backup_keep = 5
+
+# Optional: how `clean-emails` (and the clean_emails key) normalize EMAIL
+# values before collapsing duplicates: "all" lowercases whole addresses
+# (default), "domain" only the part after the @.
+# email_case = "all"
+
+# Optional: what the confirm key (Enter) does on an email, phone or address
+# that has only one value: "copy" it, "edit" it, or "nothing" (default).
+# With several values it always opens the list.
+# single_value_enter = "nothing"
+
+# Optional: order of the entries in the email, phone, address and alias lists:
+# "seq" keeps card order (default), "type" groups entries with the same TYPE
+# (work together, home together), "default_first" lists the PREF-marked entry
+# first. Moving an entry up or down always changes its place in the card.
+# multivalue_sort = "seq"
+
+# Optional: what the TUI shows on start: "search" opens the search pane
+# (default), "last" reopens the card of the contact selected when rldx last
+# quit, "first" opens the first contact's card.
+# startup = "search"
+
+# Optional: what reindexing does with a contact file that fails to decrypt
+# (foreign or corrupt files in a mixed vdir): "abort" stops with the error
+# (default), "skip" leaves the file out of the index and lists the skipped
+# paths once the reindex is done.
+# on_decrypt_error = "abort"
+
+# Optional: mark contacts in the search list that lack any of these fields
+# ("email", "phone"), to spot incomplete records during cleanup.
+# flag_incomplete = ["email", "phone"]
+
+# Optional: fields the duplicate key leaves blank on the copy ("name",
+# "email", "phone"), for entering several people from the same company.
+# duplicate_clear = ["name", "email"]
+
 # =============================================================================
 # Key Bindings
 # =============================================================================
@@ -920,50 +2480,80 @@ F8 = "delete"
 #   - Arrow keys: "Up", "Down", "Left", "Right"
 #   - Navigation: "PageUp", "PageDown", "Home", "End"
 #   - Function keys: "F1" through "F12"
+#   - Modifiers: "Ctrl+" and/or "Alt+" before any of the above, e.g. "Ctrl+e"
+#
+# The bindings below are the defaults, commented out so the `keymap` preset
+# applies. Uncomment an entry to override that action.
 
 [keys.global]
-quit = ["q"]
-search = ["/"]
-help = ["F1", "?"]
+# quit = ["q"]
+# search = ["/"]
+# help = ["F1", "?"]
+# recent = ["R"]
 
 [keys.search_input]
-cancel = ["Escape"]
-confirm = ["Enter"]
+# cancel = ["Escape"]
+# confirm = ["Enter"]
 
 [keys.search_results]
-cancel = ["Escape"]
-confirm = ["Enter"]
-next = ["j", "Down", "Tab"]
-prev = ["k", "Up", "Backtab"]
-page_down = ["PageDown"]
-page_up = ["PageUp"]
-mark = ["Space"]
-merge = ["m"]
-toggle_marked = ["M"]
+# cancel = ["Escape"]
+# confirm = ["Enter"]
+# next = ["j", "Down", "Tab"]
+# prev = ["k", "Up", "Backtab"]
+# page_down = ["PageDown"]
+# page_up = ["PageUp"]
+# mark = ["Space"]
+# merge = ["m"]
+# toggle_marked = ["M"]
+# tag_marked = ["T"]
+# compare = ["="]
+# skip_group = [">"]
 
 [keys.navigation]
-next = ["Tab", "j", "Down"]
-prev = ["Backspace", "k", "Up"]
-edit = ["e"]
-copy = ["Space"]
-confirm = ["Enter"]
-add_field = ["a"]
-delete_field = ["d"]
-photo_fetch = ["i"]
-lang_cycle = ["L"]
+# next = ["Tab", "j", "Down"]
+# prev = ["Backspace", "k", "Up"]
+# edit = ["e"]
+# copy = ["Space"]
+# copy_uri = ["Y"]
+# edit_label = ["T"]
+# confirm = ["Enter"]
+# add_field = ["a"]
+# delete_field = ["d"]
+# split_name = ["S"]
+# clear_filter = ["c"]
+# toggle_filter = ["F"]
+# toggle_empty = ["z"]
+# toggle_nosync = ["N"]
+# open_path = ["O"]
+# copy_uid = ["U"]
+# copy_path = ["C"]
+# clean_emails = ["E"]
+# toggle_notes = ["v"]
+# expand = ["f"]
+# toggle_book = ["B"]
+# toggle_sources = ["I"]
+# move_contact_up = ["K"]
+# move_contact_down = ["J"]
+# duplicate = ["D"]
+# photo_fetch = ["i"]
+# lang_cycle = ["L"]
 
 [keys.modal]
-cancel = ["Escape", "q"]
-confirm = ["Enter"]
-next = ["j", "Down", "Tab"]
-prev = ["k", "Up", "Backtab"]
-edit = ["e"]
-copy = ["y", "Space"]
-set_default = ["d"]
+# cancel = ["Escape", "q"]
+# confirm = ["Enter"]
+# next = ["j", "Down", "Tab"]
+# prev = ["k", "Up", "Backtab"]
+# edit = ["e"]
+# copy = ["y", "Space"]
+# copy_uri = ["Y"]
+# edit_label = ["T"]
+# set_default = ["d"]
+# move_up = ["K"]
+# move_down = ["J"]
 
 [keys.editor]
-cancel = ["Escape"]
-confirm = ["Enter"]
+# cancel = ["Escape"]
+# confirm = ["Enter"]
 
 # =============================================================================
 # UI Configuration
@@ -985,6 +2575,12 @@ organization = "🏢 "
 [ui.pane.image]
 width = 40
 height = 12
+# Photos: "auto" (terminal image protocol, else half-blocks), "blocks" or "none"
+# mode = "auto"
+
+[ui.share]
+# QR error-correction level for the share modal: L, M, Q or H
+qr_error_correction = "M"
 
 # =============================================================================
 # Commands
@@ -998,6 +2594,30 @@ height = 12
 #   copy = ["pbcopy"]                            # macOS
 copy = ["wl-copy"]
 
+# Program run with the current contact's file path (the open_path key).
+# `{{path}}` and `{{dir}}` in the arguments become the file and its directory;
+# without either, the path is appended. Unset, the key shows the path in the
+# status line instead.
+#   open_path = ["xdg-open", "{{dir}}"]                # Linux
+#   open_path = ["open", "-R"]                     # macOS Finder, file selected
+
+# Optional: rewrite values before they are copied (the copy key and the copy
+# action in lists). Copying as URI is not affected.
+#   phone: "display" (default, as shown), "digits" (digits only, keeping a
+#          leading +) or "e164" (+15551234567 when the number parses)
+#   email: "display" (default) or "lowercase"
+# [copy_transform]
+# phone = "digits"
+# email = "lowercase"
+
+# Optional: show IMPP values as links in the details pane, by service. The
+# service is the value's scheme (xmpp:jane@example.org) or Apple's
+# X-SERVICE-TYPE; {{handle}} is the rest, without a leading @. The link is also
+# what gets copied. Services without a template show the raw value.
+# [impp_templates]
+# telegram = "https://t.me/{{handle}}"
+# skype = "skype:{{handle}}?chat"
+
 # =============================================================================
 # Maildir Import Filters
 # =============================================================================
@@ -26,6 +26,11 @@ pub struct CardDavRemote {
 impl CardDavRemote {
     /// Create a new CardDAV remote from configuration
     pub async fn new(config: RemoteConfig) -> Result<Self> {
+        log::info!(
+            "connecting to {} as {}",
+            crate::output::redact_url(&config.url),
+            config.username
+        );
         let password = config.get_password()?;
 
         let uri: Uri = config.url.parse()
@@ -54,6 +59,7 @@ impl CardDavRemote {
 
         // Resolve address book href
         let address_book_href = Self::resolve_address_book(&client, &config.address_book).await?;
+        log::debug!("using address book {}", address_book_href);
 
         Ok(Self {
             client,
@@ -106,8 +112,8 @@ impl CardDavRemote {
                 }
             }
             // Fall back to first address book if none matches
-            eprintln!(
-                "warning: address book '{}' not found, using '{}'",
+            log::warn!(
+                "address book '{}' not found, using '{}'",
                 address_book_name,
                 &addressbooks_response.addressbooks[0].href
             );
@@ -137,6 +143,7 @@ impl Remote for CardDavRemote {
 
     async fn list_contacts(&self) -> Result<Vec<RemoteContactSummary>> {
         // Use GetAddressBookResources to list all contacts with etags
+        log::debug!("REPORT {} (list)", self.address_book_href);
         let response = self.client
             .request(GetAddressBookResources::new(&self.address_book_href))
             .await
@@ -160,6 +167,7 @@ impl Remote for CardDavRemote {
         }
 
         // Use addressbook-multiget REPORT to fetch multiple contacts efficiently
+        log::debug!("REPORT {} (multiget, {} href(s))", self.address_book_href, hrefs.len());
         let response = self.client
             .request(
                 GetAddressBookResources::new(&self.address_book_href)
@@ -192,6 +200,7 @@ impl Remote for CardDavRemote {
             }
         };
 
+        log::debug!("PUT {} ({} bytes)", contact_href, vcard_data.len());
         // Use PutResource.create() to upload a new vCard
         // Note: For updates, we would need the etag and use .update() instead
         // For simplicity, we always try to create - the server will handle conflicts
@@ -209,6 +218,7 @@ impl Remote for CardDavRemote {
     async fn delete_contact(&self, href: &str) -> Result<()> {
         // Use Delete request with force() to remove the contact unconditionally
         // For conditional delete, we would use .with_etag(etag) instead
+        log::debug!("DELETE {}", href);
         self.client
             .request(Delete::new(href).force())
             .await
@@ -1,3 +1,8 @@
+use std::ops::Range;
+
+use deunicode::deunicode;
+use unicode_segmentation::UnicodeSegmentation;
+
 use crate::translit;
 
 /// Normalize a string for search indexing and querying.
@@ -19,3 +24,76 @@ pub fn like_pattern(normalized: &str) -> String {
     let escaped = normalized.replace('%', "\\%").replace('_', "\\_");
     format!("%{}%", escaped)
 }
+
+/// Find the byte ranges of `text` that match an already normalized query.
+///
+/// Each grapheme is normalized on its own, so matches are found case-insensitively
+/// and through transliteration ("ИВАН" matches "ivan") while still mapping back to
+/// whole graphemes of the original text.
+pub fn match_ranges(text: &str, normalized_query: &str) -> Vec<Range<usize>> {
+    if normalized_query.is_empty() {
+        return Vec::new();
+    }
+
+    // (source range, offset into `folded`) for every grapheme
+    let mut segments: Vec<(Range<usize>, usize)> = Vec::new();
+    let mut folded = String::new();
+    for (start, grapheme) in text.grapheme_indices(true) {
+        segments.push((start..start + grapheme.len(), folded.len()));
+        if grapheme.trim().is_empty() {
+            // Collapse whitespace runs like `transliterate` does
+            if !folded.is_empty() && !folded.ends_with(' ') {
+                folded.push(' ');
+            }
+        } else {
+            folded.push_str(&deunicode(grapheme).to_lowercase());
+        }
+    }
+
+    let segment_at = |offset: usize| {
+        segments.partition_point(|(_, folded_start)| *folded_start <= offset) - 1
+    };
+
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    let mut pos = 0;
+    while let Some(found) = folded[pos..].find(normalized_query) {
+        let start = pos + found;
+        let end = start + normalized_query.len();
+        let first = &segments[segment_at(start)].0;
+        let last = &segments[segment_at(end - 1)].0;
+        match ranges.last_mut() {
+            Some(prev) if prev.end >= first.start => prev.end = last.end,
+            _ => ranges.push(first.start..last.end),
+        }
+        pos = end;
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matched<'a>(text: &'a str, query: &str) -> Vec<&'a str> {
+        let query = normalize_query(query).unwrap();
+        match_ranges(text, &query)
+            .into_iter()
+            .map(|range| &text[range])
+            .collect()
+    }
+
+    #[test]
+    fn test_match_ranges_case_insensitive() {
+        assert_eq!(matched("JANE DOE", "doe"), vec!["DOE"]);
+        assert_eq!(matched("Anna Hannah", "ann"), vec!["Ann", "ann"]);
+        assert!(matched("Jane Doe", "smith").is_empty());
+    }
+
+    #[test]
+    fn test_match_ranges_transliterated() {
+        assert_eq!(matched("ИВАН ПЕТРОВ", "petrov"), vec!["ПЕТРОВ"]);
+        assert_eq!(matched("JOSÉ GARCÍA", "jose"), vec!["JOSÉ"]);
+        // Combining accents stay attached to their base letter
+        assert_eq!(matched("Zoe\u{301} Li", "zoe"), vec!["Zoe\u{301}"]);
+    }
+}
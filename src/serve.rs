@@ -0,0 +1,233 @@
+//! Minimal read-only HTTP/JSON server for editor integrations.
+//!
+//! Exposes `GET /query?q=...`, returning the same matches as `rldx query` as a
+//! JSON array. Each connection is handled on its own thread, so a slow
+//! client does not hold up the others; queries share one open database.
+//! Results are built per request and nothing is cached between requests.
+//!
+//! The server has no credentials of its own, so it only answers requests whose
+//! `Host` names it by IP address or `localhost`: a web page that rebinds its
+//! domain to the loopback address still sends that domain and is refused.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{IpAddr, SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde_json::json;
+
+use crate::db::Database;
+
+/// Longest request line or header line we are willing to read
+const MAX_LINE_LEN: u64 = 8192;
+
+/// How long a client may take to send its request or read the answer
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Serve queries on `addr` until the process is stopped
+pub fn run(addr: SocketAddr, db: Database) -> Result<()> {
+    let listener =
+        TcpListener::bind(addr).with_context(|| format!("failed to bind {}", addr))?;
+    let bound = listener.local_addr()?;
+    println!("Listening on http://{}", bound);
+    std::io::stdout().flush()?;
+
+    let db = Arc::new(Mutex::new(db));
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                eprintln!("warning: failed to accept connection: {}", err);
+                continue;
+            }
+        };
+        let db = Arc::clone(&db);
+        std::thread::spawn(move || {
+            if let Err(err) = handle_connection(stream, bound, &db) {
+                eprintln!("warning: request failed: {:#}", err);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+fn handle_connection(mut stream: TcpStream, bound: SocketAddr, db: &Mutex<Database>) -> Result<()> {
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut request_line = String::new();
+    reader.by_ref().take(MAX_LINE_LEN).read_line(&mut request_line)?;
+
+    // Read headers up to the blank line; requests we accept have no body
+    let mut host = None;
+    loop {
+        let mut line = String::new();
+        let read = reader.by_ref().take(MAX_LINE_LEN).read_line(&mut line)?;
+        if read == 0 || line.trim_end().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("host") {
+                host = Some(value.trim().to_string());
+            }
+        }
+    }
+
+    let (status, body) = if !host.as_deref().is_some_and(|host| host_allowed(host, bound)) {
+        error(403, "unexpected Host header")
+    } else {
+        match db.lock() {
+            Ok(db) => respond(&request_line, &db),
+            Err(_) => error(500, "database unavailable"),
+        }
+    };
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    )?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// Build the status code and JSON body for a request line
+fn respond(request_line: &str, db: &Database) -> (u16, String) {
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return error(400, "malformed request");
+    };
+    if method != "GET" {
+        return error(405, "only GET is supported");
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    if path != "/query" {
+        return error(404, "not found");
+    }
+
+    let Some(q) = query_param(query, "q").filter(|q| !q.trim().is_empty()) else {
+        return error(400, "missing query parameter `q`");
+    };
+
+    match db.query_emails(&q) {
+        Ok(results) => {
+            let items: Vec<_> = results
+                .into_iter()
+                .map(|r| {
+                    json!({
+                        "email": r.email,
+                        "name": r.display_fn,
+                        "notes": r.notes,
+                    })
+                })
+                .collect();
+            (200, serde_json::Value::Array(items).to_string())
+        }
+        Err(err) => error(500, &format!("{:#}", err)),
+    }
+}
+
+/// Whether a `Host` header names this server: `localhost` or an IP address
+/// it listens on, with its port. Any other name may be a rebound domain.
+fn host_allowed(host: &str, bound: SocketAddr) -> bool {
+    let Some((name, port)) = host.rsplit_once(':') else {
+        return false;
+    };
+    if port.parse::<u16>().ok() != Some(bound.port()) {
+        return false;
+    }
+    let name = name.trim_start_matches('[').trim_end_matches(']');
+    if name.eq_ignore_ascii_case("localhost") {
+        return bound.ip().is_loopback();
+    }
+    match name.parse::<IpAddr>() {
+        Ok(ip) => ip == bound.ip() || bound.ip().is_unspecified(),
+        Err(_) => false,
+    }
+}
+
+fn error(status: u16, message: &str) -> (u16, String) {
+    (status, json!({ "error": message }).to_string())
+}
+
+/// Find and decode a parameter in an `application/x-www-form-urlencoded` query
+fn query_param(query: &str, name: &str) -> Option<String> {
+    query.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        (percent_decode(key) == name).then(|| percent_decode(value))
+    })
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                    Some(byte) => {
+                        out.push(byte);
+                        i += 2;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_param_decodes_form_encoding() {
+        let query = "x=1&q=J%C3%B6rg+M%C3%BCller&y";
+        assert_eq!(query_param(query, "q").as_deref(), Some("Jörg Müller"));
+        assert_eq!(query_param(query, "y").as_deref(), Some(""));
+        assert_eq!(query_param(query, "z"), None);
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz%41"), "%zzA");
+    }
+
+    #[test]
+    fn test_host_must_name_the_server() {
+        let bound: SocketAddr = "127.0.0.1:8787".parse().unwrap();
+        assert!(host_allowed("127.0.0.1:8787", bound));
+        assert!(host_allowed("LOCALHOST:8787", bound));
+        assert!(!host_allowed("127.0.0.1:9000", bound));
+        assert!(!host_allowed("127.0.0.1", bound));
+        assert!(!host_allowed("evil.example:8787", bound));
+        assert!(!host_allowed("10.0.0.5:8787", bound));
+
+        let v6: SocketAddr = "[::1]:8787".parse().unwrap();
+        assert!(host_allowed("[::1]:8787", v6));
+        assert!(!host_allowed("127.0.0.1:8787", v6));
+
+        // --allow-remote on every interface takes any address, still no names
+        let any: SocketAddr = "0.0.0.0:8787".parse().unwrap();
+        assert!(host_allowed("192.168.1.20:8787", any));
+        assert!(!host_allowed("localhost:8787", any));
+        assert!(!host_allowed("contacts.example:8787", any));
+    }
+}
@@ -3,7 +3,7 @@
 //! This module orchestrates the synchronization between local vCard files
 //! and remote CardDAV servers.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -14,8 +14,9 @@ use indicatif::{ProgressBar, ProgressStyle};
 use crate::config::{ConflictPreference, Config, RemoteConfig};
 use crate::crypto::CryptoProvider;
 use crate::db::{Database, SyncMetadata};
-use crate::remote::Remote;
-use crate::vdir;
+use crate::output::{self, info};
+use crate::remote::{Remote, RemoteContactSummary};
+use crate::{vcard_io, vdir};
 
 /// Result of a sync operation
 #[derive(Debug, Default)]
@@ -47,6 +48,138 @@ impl std::fmt::Display for SyncError {
     }
 }
 
+/// Everything a sync will change, derived from the remote listing, the
+/// sync metadata and the upload queue before any I/O happens
+#[derive(Debug, Default)]
+pub struct SyncPlan {
+    /// Remote contacts not linked to a local file yet
+    pub pull_new: Vec<String>,
+    /// Linked remote contacts whose ETag changed
+    pub pull_update: Vec<String>,
+    /// Local contacts whose remote copy is gone
+    pub delete_local: Vec<SyncMetadata>,
+    /// Local-only contacts whose remote copy is gone; only the link is dropped
+    pub unlink: Vec<PathBuf>,
+    /// Queued contacts without a remote copy
+    pub push_new: Vec<PathBuf>,
+    /// Queued contacts with a remote copy, and its href
+    pub push_update: Vec<(PathBuf, String)>,
+    /// Remote copies of contacts deleted locally
+    pub delete_remote: Vec<SyncMetadata>,
+    /// Queue entries dropped without an upload (deleted or made local-only)
+    pub dequeue: Vec<PathBuf>,
+}
+
+impl SyncPlan {
+    /// Work out the plan. `queue` is None for a pull-only sync.
+    fn build(
+        remote_contacts: &[RemoteContactSummary],
+        metadata: &[SyncMetadata],
+        no_sync: &HashSet<PathBuf>,
+        queue: Option<&[PathBuf]>,
+        prefer: ConflictPreference,
+    ) -> Self {
+        let mut plan = SyncPlan::default();
+        let metadata_by_href: HashMap<&str, &SyncMetadata> = metadata
+            .iter()
+            .map(|m| (m.remote_href.as_str(), m))
+            .collect();
+        let remote_hrefs: HashSet<&str> = remote_contacts.iter().map(|c| c.href.as_str()).collect();
+
+        // Files the pull phase replaces or removes are not pushed afterwards
+        let mut pulled_paths: HashSet<&Path> = HashSet::new();
+
+        for contact in remote_contacts {
+            let Some(meta) = metadata_by_href.get(contact.href.as_str()) else {
+                plan.pull_new.push(contact.href.clone());
+                continue;
+            };
+            if no_sync.contains(&meta.contact_path) {
+                continue;
+            }
+
+            // Check if etag has changed
+            let etag_changed = match (&contact.etag, &meta.remote_etag) {
+                (Some(new_etag), Some(old_etag)) => new_etag != old_etag,
+                (Some(_), None) => true,
+                (None, Some(_)) => true,
+                (None, None) => false, // Can't detect changes without etags
+            };
+            if etag_changed {
+                plan.pull_update.push(contact.href.clone());
+                pulled_paths.insert(&meta.contact_path);
+            }
+        }
+
+        for meta in metadata {
+            if remote_hrefs.contains(meta.remote_href.as_str()) {
+                continue;
+            }
+            if no_sync.contains(&meta.contact_path) {
+                plan.unlink.push(meta.contact_path.clone());
+                continue;
+            }
+            // A local edit survives when the remote side is not preferred;
+            // it is uploaded again below
+            if meta.local_modified && prefer == ConflictPreference::Ours {
+                continue;
+            }
+            plan.delete_local.push(meta.clone());
+            pulled_paths.insert(&meta.contact_path);
+        }
+
+        let Some(queue) = queue else {
+            return plan;
+        };
+
+        let metadata_by_path: HashMap<&Path, &SyncMetadata> = metadata
+            .iter()
+            .map(|m| (m.contact_path.as_path(), m))
+            .collect();
+        for path in queue {
+            if no_sync.contains(path) || !path.exists() {
+                // Flagged after it was queued, or deleted locally (the remote
+                // deletion is planned below)
+                plan.dequeue.push(path.clone());
+                continue;
+            }
+            if pulled_paths.contains(path.as_path()) {
+                continue;
+            }
+            match metadata_by_path.get(path.as_path()) {
+                Some(meta) => plan.push_update.push((path.clone(), meta.remote_href.clone())),
+                None => plan.push_new.push(path.clone()),
+            }
+        }
+
+        // Deleting a local-only card never reaches the remote, and a contact
+        // gone on both sides only needs its link dropped
+        for meta in metadata {
+            if !meta.contact_path.exists()
+                && !no_sync.contains(&meta.contact_path)
+                && remote_hrefs.contains(meta.remote_href.as_str())
+            {
+                plan.delete_remote.push(meta.clone());
+            }
+        }
+
+        plan
+    }
+
+    /// One-line counts, e.g. "will pull 2 new, update 1, delete 0 locally; ..."
+    pub fn summary(&self) -> String {
+        format!(
+            "will pull {} new, update {}, delete {} locally; will push {} new, update {}, delete {} remotely",
+            self.pull_new.len(),
+            self.pull_update.len(),
+            self.delete_local.len(),
+            self.push_new.len(),
+            self.push_update.len(),
+            self.delete_remote.len()
+        )
+    }
+}
+
 /// Sync engine for CardDAV synchronization
 pub struct SyncEngine<'a> {
     config: &'a Config,
@@ -85,71 +218,99 @@ impl<'a> SyncEngine<'a> {
         }
     }
 
-    /// Run the sync operation
+    /// Record local changes since the last sync in this remote's upload queue.
+    /// Only touches the index, so it can run before (or without) a connection.
+    /// A dry run leaves the queue alone.
+    pub fn queue_local_changes(&mut self) -> Result<usize> {
+        if self.dry_run {
+            return Ok(0);
+        }
+        self.db
+            .queue_pending_uploads(&self.remote_config.name, &self.vdir, current_timestamp())
+    }
+
+    /// The upload queue as the next real sync would see it. A dry run adds
+    /// the changes that sync would queue instead of writing them.
+    fn upload_queue(&mut self) -> Result<Vec<PathBuf>> {
+        if !self.dry_run {
+            self.queue_local_changes()?;
+            return self.db.pending_uploads(&self.remote_config.name);
+        }
+        let mut queue = self.db.pending_uploads(&self.remote_config.name)?;
+        for path in self.db.changed_since_sync(&self.remote_config.name, &self.vdir)? {
+            if !queue.contains(&path) {
+                queue.push(path);
+            }
+        }
+        Ok(queue)
+    }
+
+    /// Run the sync operation: plan it, then print the plan (dry run) or
+    /// carry it out
     pub async fn sync<R: Remote>(&mut self, remote: &R) -> Result<SyncResult> {
         let mut result = SyncResult::default();
 
+        let plan = self.plan(remote).await?;
+        if self.dry_run {
+            self.print_plan(&plan);
+            return Ok(result);
+        }
+
         // Ensure local directory exists
-        if !self.dry_run && !self.vdir.exists() {
+        if !self.vdir.exists() {
             fs::create_dir_all(&self.vdir)
                 .with_context(|| format!("failed to create directory: {}", self.vdir.display()))?;
         }
 
         // Phase 1: Pull changes from remote
-        println!("Pulling changes from remote...");
-        self.pull_changes(remote, &mut result).await?;
+        info!("Pulling changes from remote...");
+        self.pull_changes(remote, &plan, &mut result).await?;
 
         // Phase 2: Push local changes (if not pull_only)
         if !self.pull_only {
-            println!("Pushing local changes to remote...");
-            self.push_changes(remote, &mut result).await?;
+            info!("Pushing local changes to remote...");
+            self.push_changes(remote, &plan, &mut result).await?;
         }
 
+        self.db.record_sync_run(&self.remote_config.name, current_timestamp())?;
+
         // Print summary
         self.print_summary(&result);
 
         Ok(result)
     }
 
-    /// Pull changes from remote to local
-    async fn pull_changes<R: Remote>(&mut self, remote: &R, result: &mut SyncResult) -> Result<()> {
+    /// Compare the remote listing with the sync metadata and upload queue.
+    /// Writes nothing but the upload queue, and not even that on a dry run.
+    pub async fn plan<R: Remote>(&mut self, remote: &R) -> Result<SyncPlan> {
         // Get list of all contacts on remote with their etags
         let remote_contacts = remote.list_contacts().await
             .context("failed to list remote contacts")?;
 
-        // Get existing sync metadata for this remote
-        let sync_metadata = self.db.get_sync_metadata_for_remote(&self.remote_config.name)?;
-        let metadata_by_href: HashMap<String, SyncMetadata> = sync_metadata
-            .into_iter()
-            .map(|m| (m.remote_href.clone(), m))
-            .collect();
-
-        // Find contacts that need to be downloaded (new or changed)
-        let mut to_download: Vec<String> = Vec::new();
-        let mut remote_hrefs: HashMap<String, Option<String>> = HashMap::new();
-
-        for contact in &remote_contacts {
-            remote_hrefs.insert(contact.href.clone(), contact.etag.clone());
-
-            if let Some(meta) = metadata_by_href.get(&contact.href) {
-                // Check if etag has changed
-                let etag_changed = match (&contact.etag, &meta.remote_etag) {
-                    (Some(new_etag), Some(old_etag)) => new_etag != old_etag,
-                    (Some(_), None) => true,
-                    (None, Some(_)) => true,
-                    (None, None) => false, // Can't detect changes without etags
-                };
+        // Pick up anything changed since the queue was last filled
+        let queue = if self.pull_only {
+            None
+        } else {
+            Some(self.upload_queue()?)
+        };
 
-                if etag_changed {
-                    to_download.push(contact.href.clone());
-                }
-            } else {
-                // New contact not in our metadata
-                to_download.push(contact.href.clone());
-            }
-        }
+        let metadata = self.db.get_sync_metadata_for_remote(&self.remote_config.name)?;
+        // Local-only (X-RLDX-NOSYNC) cards are never overwritten by the remote copy
+        let no_sync = self.db.no_sync_paths(&self.remote_config.name)?;
+
+        Ok(SyncPlan::build(
+            &remote_contacts,
+            &metadata,
+            &no_sync,
+            queue.as_deref(),
+            self.get_conflict_preference(),
+        ))
+    }
 
+    /// Pull changes from remote to local
+    async fn pull_changes<R: Remote>(&mut self, remote: &R, plan: &SyncPlan, result: &mut SyncResult) -> Result<()> {
         // Download changed/new contacts
+        let to_download: Vec<&String> = plan.pull_new.iter().chain(&plan.pull_update).collect();
         if !to_download.is_empty() {
             let pb = self.create_progress_bar(to_download.len() as u64, "Downloading");
 
@@ -163,20 +324,10 @@ impl<'a> SyncEngine<'a> {
                 for contact in contacts {
                     pb.inc(1);
 
-                    // Determine if this is a new contact or update
-                    let is_new = !metadata_by_href.contains_key(&contact.href);
-
-                    if self.dry_run {
-                        println!(
-                            "[dry-run] Would {} contact: {}",
-                            if is_new { "download" } else { "update" },
-                            &contact.href
-                        );
-                        continue;
-                    }
-
                     // Save the contact locally
-                    match self.save_contact_locally(&contact.href, &contact.vcard_data, &contact.etag).await {
+                    log::debug!("saving {} locally", contact.href);
+                    let vcard_data = self.with_source_tags(contact.vcard_data.clone());
+                    match self.save_contact_locally(&contact.href, &vcard_data, &contact.etag).await {
                         Ok(_local_path) => {
                             result.downloaded_count += 1;
                         }
@@ -193,79 +344,52 @@ impl<'a> SyncEngine<'a> {
             pb.finish_with_message("Download complete");
         }
 
-        // Find contacts that were deleted on remote
-        for (href, meta) in &metadata_by_href {
-            if !remote_hrefs.contains_key(href) {
-                // Contact was deleted on remote
-                if self.dry_run {
-                    println!("[dry-run] Would delete local contact: {}", meta.contact_path.display());
-                    continue;
-                }
-
-                // Handle conflict: local might have been modified
-                if meta.local_modified {
-                    let preference = self.get_conflict_preference();
-                    match preference {
-                        ConflictPreference::Ours => {
-                            // Keep local, will be re-uploaded in push phase
-                            continue;
-                        }
-                        ConflictPreference::Theirs => {
-                            // Delete local
-                        }
-                    }
-                }
+        // Keep local-only cards deleted on the remote, just forget the link
+        for path in &plan.unlink {
+            self.db.delete_sync_metadata(path, &self.remote_config.name)?;
+        }
 
-                // Delete local file
-                if meta.contact_path.exists() {
-                    if let Err(e) = fs::remove_file(&meta.contact_path) {
-                        result.errors.push(SyncError {
-                            path: meta.contact_path.display().to_string(),
-                            message: format!("failed to delete: {}", e),
-                        });
-                        continue;
-                    }
+        // Delete local copies of contacts deleted on the remote
+        for meta in &plan.delete_local {
+            log::debug!("{} was deleted remotely, deleting {}", meta.remote_href, meta.contact_path.display());
+            if meta.contact_path.exists() {
+                let removed = vdir::backup_before_change(&meta.contact_path, self.provider.edit_backup())
+                    .and_then(|()| Ok(fs::remove_file(&meta.contact_path)?));
+                if let Err(e) = removed {
+                    result.errors.push(SyncError {
+                        path: meta.contact_path.display().to_string(),
+                        message: format!("failed to delete: {:#}", e),
+                    });
+                    continue;
                 }
+            }
 
-                // Remove from database
-                self.db.delete_sync_metadata(&meta.contact_path, &self.remote_config.name)?;
-                self.db.delete_items_by_paths([meta.contact_path.clone()])?;
+            // Remove from database
+            self.db.delete_sync_metadata(&meta.contact_path, &self.remote_config.name)?;
+            self.db.remove_pending_upload(&meta.contact_path, &self.remote_config.name)?;
+            self.db.delete_items_by_paths([meta.contact_path.clone()])?;
 
-                result.deleted_local_count += 1;
-            }
+            result.deleted_local_count += 1;
         }
 
         Ok(())
     }
 
     /// Push local changes to remote
-    async fn push_changes<R: Remote>(&mut self, remote: &R, result: &mut SyncResult) -> Result<()> {
-        // Get all local vCard files
-        let local_files = vdir::list_vcf_files(&self.vdir)?;
+    async fn push_changes<R: Remote>(&mut self, remote: &R, plan: &SyncPlan, result: &mut SyncResult) -> Result<()> {
+        // Queue entries that no longer need an upload
+        for path in &plan.dequeue {
+            self.db.remove_pending_upload(path, &self.remote_config.name)?;
+        }
 
-        // Get existing sync metadata for this remote
-        let sync_metadata = self.db.get_sync_metadata_for_remote(&self.remote_config.name)?;
-        let metadata_by_path: HashMap<PathBuf, SyncMetadata> = sync_metadata
-            .into_iter()
-            .map(|m| (m.contact_path.clone(), m))
+        // Upload exactly the queued contacts: (path, href if update)
+        let to_upload: Vec<(&PathBuf, Option<&str>)> = plan
+            .push_new
+            .iter()
+            .map(|path| (path, None))
+            .chain(plan.push_update.iter().map(|(path, href)| (path, Some(href.as_str()))))
             .collect();
 
-        // Find local files that need to be uploaded
-        let mut to_upload: Vec<(PathBuf, Option<String>)> = Vec::new(); // (path, href if update)
-
-        for path in &local_files {
-            if let Some(meta) = metadata_by_path.get(path) {
-                // Check if file was modified since last sync
-                if meta.local_modified || self.file_modified_since(path, meta.last_synced)? {
-                    // File was modified locally, need to upload
-                    to_upload.push((path.clone(), Some(meta.remote_href.clone())));
-                }
-            } else {
-                // New local file not synced yet
-                to_upload.push((path.clone(), None));
-            }
-        }
-
         // Upload modified/new contacts
         if !to_upload.is_empty() {
             let pb = self.create_progress_bar(to_upload.len() as u64, "Uploading");
@@ -273,17 +397,9 @@ impl<'a> SyncEngine<'a> {
             for (path, href) in to_upload {
                 pb.inc(1);
 
-                if self.dry_run {
-                    println!(
-                        "[dry-run] Would {} contact: {}",
-                        if href.is_some() { "update" } else { "upload new" },
-                        path.display()
-                    );
-                    continue;
-                }
-
                 // Read the vCard file
-                let vcard_data = match vdir::read_vcf_file(&path, self.provider) {
+                let synced_sha1 = file_sha1(path);
+                let vcard_data = match vdir::read_vcf_file(path, self.provider) {
                     Ok(data) => data,
                     Err(e) => {
                         result.errors.push(SyncError {
@@ -293,9 +409,11 @@ impl<'a> SyncEngine<'a> {
                         continue;
                     }
                 };
+                let vcard_data = self.for_upload(path, vcard_data);
 
                 // Upload to remote
-                match remote.upload_contact(href.as_deref(), &vcard_data).await {
+                log::debug!("uploading {}", path.display());
+                match remote.upload_contact(href, &vcard_data).await {
                     Ok((new_href, new_etag)) => {
                         // Update sync metadata
                         let meta = SyncMetadata {
@@ -305,8 +423,10 @@ impl<'a> SyncEngine<'a> {
                             remote_etag: new_etag.clone(),
                             last_synced: Some(current_timestamp()),
                             local_modified: false,
+                            synced_sha1,
                         };
                         self.db.upsert_sync_metadata(&meta)?;
+                        self.db.remove_pending_upload(path, &self.remote_config.name)?;
 
                         result.uploaded_count += 1;
                     }
@@ -322,28 +442,21 @@ impl<'a> SyncEngine<'a> {
             pb.finish_with_message("Upload complete");
         }
 
-        // Find local files that were deleted (in metadata but file doesn't exist)
-        for (path, meta) in &metadata_by_path {
-            if !path.exists() {
-                if self.dry_run {
-                    println!("[dry-run] Would delete remote contact: {}", meta.remote_href);
-                    continue;
-                }
-
-                // Delete from remote
-                if let Err(e) = remote.delete_contact(&meta.remote_href).await {
-                    result.errors.push(SyncError {
-                        path: meta.remote_href.clone(),
-                        message: format!("failed to delete from remote: {}", e),
-                    });
-                    continue;
-                }
+        // Delete remote copies of contacts deleted locally
+        for meta in &plan.delete_remote {
+            log::debug!("{} was deleted locally, deleting {}", meta.contact_path.display(), meta.remote_href);
+            if let Err(e) = remote.delete_contact(&meta.remote_href).await {
+                result.errors.push(SyncError {
+                    path: meta.remote_href.clone(),
+                    message: format!("failed to delete from remote: {}", e),
+                });
+                continue;
+            }
 
-                // Remove sync metadata
-                self.db.delete_sync_metadata(path, &self.remote_config.name)?;
+            // Remove sync metadata
+            self.db.delete_sync_metadata(&meta.contact_path, &self.remote_config.name)?;
 
-                result.deleted_remote_count += 1;
-            }
+            result.deleted_remote_count += 1;
         }
 
         Ok(())
@@ -362,7 +475,6 @@ impl<'a> SyncEngine<'a> {
             .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
 
         let filename = format!("{}.vcf", sanitize_filename(&uid));
-        let local_path = self.vdir.join(&filename);
 
         // Check if we already have this contact at a different path (by href)
         if let Some(existing_meta) = self.db.get_sync_metadata_for_remote(&self.remote_config.name)?
@@ -383,12 +495,27 @@ impl<'a> SyncEngine<'a> {
                 remote_etag: etag.clone(),
                 last_synced: Some(current_timestamp()),
                 local_modified: false,
+                synced_sha1: file_sha1(&existing_path),
             };
             self.db.upsert_sync_metadata(&meta)?;
+            // The remote version replaced any queued local edit
+            self.db.remove_pending_upload(&existing_path, &self.remote_config.name)?;
 
             return Ok(existing_path);
         }
 
+        // New contacts may be filed into the book named by their first category
+        let target_dir = match self.category_book(vcard_data) {
+            Some(book) => {
+                let dir = self.vdir.join(book);
+                fs::create_dir_all(&dir)
+                    .with_context(|| format!("failed to create directory: {}", dir.display()))?;
+                dir
+            }
+            None => self.vdir.clone(),
+        };
+        let local_path = target_dir.join(&filename);
+
         // Write the vCard file (encrypted)
         vdir::write_vcf_file(&local_path, vcard_data.as_bytes(), self.provider)?;
 
@@ -400,28 +527,67 @@ impl<'a> SyncEngine<'a> {
             remote_etag: etag.clone(),
             last_synced: Some(current_timestamp()),
             local_modified: false,
+            synced_sha1: file_sha1(&local_path),
         };
         self.db.upsert_sync_metadata(&meta)?;
 
         Ok(local_path)
     }
 
-    /// Check if file was modified since a given timestamp
-    fn file_modified_since(&self, path: &Path, timestamp: Option<i64>) -> Result<bool> {
-        let Some(last_synced) = timestamp else {
-            return Ok(true); // No timestamp means never synced
-        };
-
-        let metadata = fs::metadata(path)
-            .with_context(|| format!("failed to stat file: {}", path.display()))?;
+    /// Address book for a downloaded card when `categories_as_books` is set
+    fn category_book(&self, vcard_data: &str) -> Option<String> {
+        if !self.remote_config.categories_as_books {
+            return None;
+        }
+        let parsed = vcard_io::parse_str(vcard_data, self.config.phone_options()).ok()?;
+        let card = parsed.cards.first()?;
+        let category = vcard_io::categories(card).next()?;
+        vdir::book_dir_name(category)
+    }
 
-        let mtime = metadata.modified()
-            .with_context(|| "failed to get mtime")?
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs() as i64;
+    /// Prepare a local card for upload: drop the `X-RLDX-SOURCE` provenance
+    /// tags and inline managed photo files, which only mean something to
+    /// rldx, and put the contact's address book first in CATEGORIES when
+    /// `categories_as_books` is set. The local file is left untouched.
+    fn for_upload(&self, path: &Path, vcard_data: String) -> String {
+        let Ok(parsed) = vcard_io::parse_str(&vcard_data, self.config.phone_options()) else {
+            return vcard_data;
+        };
+        let mut cards = parsed.cards;
+        let mut changed = false;
+        for card in &mut cards {
+            changed |= vcard_io::strip_source_tags(card);
+            changed |= vcard_io::inline_managed_photos(card, &self.config.vdir, self.provider);
+        }
+        if self.remote_config.categories_as_books {
+            if let (Some(book), Some(card)) = (vdir::book_of(&self.vdir, path), cards.first_mut()) {
+                changed |= vcard_io::ensure_leading_category(card, &book);
+            }
+        }
+        if !changed {
+            return vcard_data;
+        }
+        vcard_io::cards_to_string(&cards)
+    }
 
-        Ok(mtime > last_synced)
+    /// Tag downloaded values with this remote's name when
+    /// `track_field_sources` is set; values tagged earlier keep their source
+    fn with_source_tags(&self, vcard_data: String) -> String {
+        if !self.config.track_field_sources {
+            return vcard_data;
+        }
+        let Ok(parsed) = vcard_io::parse_str(&vcard_data, self.config.phone_options()) else {
+            return vcard_data;
+        };
+        let mut cards = parsed.cards;
+        let tagged: usize = cards
+            .iter_mut()
+            .map(|card| vcard_io::tag_source(card, &self.remote_config.name))
+            .sum();
+        if tagged == 0 {
+            return vcard_data;
+        }
+        vcard_io::cards_to_string(&cards)
     }
 
     /// Get the conflict preference for this remote
@@ -433,7 +599,7 @@ impl<'a> SyncEngine<'a> {
 
     /// Create a progress bar
     fn create_progress_bar(&self, total: u64, message: &str) -> ProgressBar {
-        let pb = ProgressBar::new(total);
+        let pb = output::progress_bar(total);
         pb.set_style(
             ProgressStyle::default_bar()
                 .template("{msg} [{bar:40.cyan/blue}] {pos}/{len} ({eta})")
@@ -444,17 +610,41 @@ impl<'a> SyncEngine<'a> {
         pb
     }
 
+    /// Print what a dry run would change, one line per contact
+    fn print_plan(&self, plan: &SyncPlan) {
+        for href in &plan.pull_new {
+            info!("[dry-run] Would download contact: {}", href);
+        }
+        for href in &plan.pull_update {
+            info!("[dry-run] Would update contact: {}", href);
+        }
+        for meta in &plan.delete_local {
+            info!("[dry-run] Would delete local contact: {}", meta.contact_path.display());
+        }
+        for path in &plan.push_new {
+            info!("[dry-run] Would upload new contact: {}", path.display());
+        }
+        for (path, _) in &plan.push_update {
+            info!("[dry-run] Would update remote contact: {}", path.display());
+        }
+        for meta in &plan.delete_remote {
+            info!("[dry-run] Would delete remote contact: {}", meta.remote_href);
+        }
+        info!();
+        info!("Sync plan: {}", plan.summary());
+    }
+
     /// Print sync summary
     fn print_summary(&self, result: &SyncResult) {
-        println!();
-        println!("Sync completed:");
-        println!("  Downloaded: {} contact(s)", result.downloaded_count);
-        println!("  Uploaded:   {} contact(s)", result.uploaded_count);
-        println!("  Deleted (local):  {} contact(s)", result.deleted_local_count);
-        println!("  Deleted (remote): {} contact(s)", result.deleted_remote_count);
+        info!();
+        info!("Sync completed:");
+        info!("  Downloaded: {} contact(s)", result.downloaded_count);
+        info!("  Uploaded:   {} contact(s)", result.uploaded_count);
+        info!("  Deleted (local):  {} contact(s)", result.deleted_local_count);
+        info!("  Deleted (remote): {} contact(s)", result.deleted_remote_count);
 
         if !result.errors.is_empty() {
-            println!("  Errors:     {} error(s)", result.errors.len());
+            info!("  Errors:     {} error(s)", result.errors.len());
             for err in &result.errors {
                 eprintln!("    - {}", err);
             }
@@ -470,6 +660,11 @@ fn current_timestamp() -> i64 {
         .as_secs() as i64
 }
 
+/// SHA1 of a local file, recorded as the synced state
+fn file_sha1(path: &Path) -> Option<Vec<u8>> {
+    vdir::compute_file_state(path).ok().map(|state| state.sha1)
+}
+
 /// Extract UID property from vCard data
 fn extract_uid_from_vcard(vcard_data: &str) -> Option<String> {
     for line in vcard_data.lines() {
@@ -533,4 +728,162 @@ END:VCARD"#;
         assert_eq!(sanitize_filename("with/slash"), "with_slash");
         assert_eq!(sanitize_filename("a:b*c?d"), "a_b_c_d");
     }
+
+    fn meta(path: &Path, href: &str, etag: &str) -> SyncMetadata {
+        SyncMetadata {
+            contact_path: path.to_path_buf(),
+            remote_name: "r".to_string(),
+            remote_href: href.to_string(),
+            remote_etag: Some(etag.to_string()),
+            last_synced: None,
+            local_modified: false,
+            synced_sha1: None,
+        }
+    }
+
+    fn summary(href: &str, etag: &str) -> RemoteContactSummary {
+        RemoteContactSummary {
+            href: href.to_string(),
+            etag: Some(etag.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_sync_plan_counts() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = |name: &str| dir.path().join(name);
+        for name in ["same", "changed", "edited", "added"] {
+            fs::write(path(name), "x").unwrap();
+        }
+
+        let metadata = vec![
+            meta(&path("same"), "/same", "1"),
+            meta(&path("changed"), "/changed", "1"),
+            meta(&path("edited"), "/edited", "1"),
+            meta(&path("gone-remote"), "/gone-remote", "1"),
+            meta(&path("gone-local"), "/gone-local", "1"),
+        ];
+        let remote = vec![
+            summary("/same", "1"),
+            summary("/changed", "2"),
+            summary("/edited", "1"),
+            summary("/gone-local", "1"),
+            summary("/fresh", "1"),
+        ];
+        // "changed" is queued too, but the pull replaces it
+        let queue = vec![path("changed"), path("edited"), path("added"), path("gone-local")];
+
+        let plan = SyncPlan::build(&remote, &metadata, &HashSet::new(), Some(&queue), ConflictPreference::Theirs);
+        assert_eq!(plan.pull_new, vec!["/fresh".to_string()]);
+        assert_eq!(plan.pull_update, vec!["/changed".to_string()]);
+        assert_eq!(plan.delete_local.len(), 1);
+        assert_eq!(plan.push_new, vec![path("added")]);
+        assert_eq!(plan.push_update, vec![(path("edited"), "/edited".to_string())]);
+        assert_eq!(plan.delete_remote.len(), 1);
+        assert_eq!(plan.dequeue, vec![path("gone-local")]);
+        assert_eq!(
+            plan.summary(),
+            "will pull 1 new, update 1, delete 1 locally; will push 1 new, update 1, delete 1 remotely"
+        );
+
+        let pull_only = SyncPlan::build(&remote, &metadata, &HashSet::new(), None, ConflictPreference::Theirs);
+        assert!(pull_only.push_new.is_empty() && pull_only.push_update.is_empty());
+        assert!(pull_only.delete_remote.is_empty());
+    }
+
+    /// A remote holding one card at `/ada.vcf`
+    struct OneCardRemote {
+        etag: String,
+        vcard_data: String,
+    }
+
+    impl Remote for OneCardRemote {
+        async fn test_connection(&self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn list_contacts(&self) -> Result<Vec<RemoteContactSummary>> {
+            Ok(vec![summary("/ada.vcf", &self.etag)])
+        }
+
+        async fn fetch_contacts(&self, hrefs: &[&str]) -> Result<Vec<crate::remote::RemoteContact>> {
+            Ok(hrefs
+                .iter()
+                .map(|href| crate::remote::RemoteContact {
+                    href: href.to_string(),
+                    etag: Some(self.etag.clone()),
+                    vcard_data: self.vcard_data.clone(),
+                })
+                .collect())
+        }
+
+        async fn upload_contact(&self, _href: Option<&str>, _vcard_data: &str) -> Result<(String, Option<String>)> {
+            anyhow::bail!("nothing should be uploaded")
+        }
+
+        async fn delete_contact(&self, _href: &str) -> Result<()> {
+            anyhow::bail!("nothing should be deleted")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_download_over_existing_card_is_backed_up() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        let vdir_path = root.join("vdir");
+        fs::create_dir_all(&vdir_path).unwrap();
+        let config_path = root.join("config.toml");
+        fs::write(
+            &config_path,
+            format!(
+                "vdir = {:?}\ndb_path = {:?}\n[encryption]\ntype = \"none\"\n",
+                vdir_path,
+                root.join("index.db")
+            ),
+        )
+        .unwrap();
+        let config = crate::config::load_from(Some(&config_path)).unwrap();
+
+        let old = "BEGIN:VCARD\r\nVERSION:4.0\r\nUID:ada\r\nFN:Ada\r\nEND:VCARD\r\n";
+        let new = "BEGIN:VCARD\r\nVERSION:4.0\r\nUID:ada\r\nFN:Ada Lovelace\r\nEND:VCARD\r\n";
+        let path = vdir_path.join("ada.vcf");
+        fs::write(&path, old).unwrap();
+        let mut db = Database::open_with_key(&config.db_path, None).unwrap();
+        let mut linked = meta(&path, "/ada.vcf", "1");
+        linked.remote_name = "work".to_string();
+        db.upsert_sync_metadata(&linked).unwrap();
+
+        let remote_config = RemoteConfig {
+            name: "work".to_string(),
+            remote_type: crate::config::RemoteType::CardDav,
+            url: "https://dav.example.com".to_string(),
+            username: "jane".to_string(),
+            address_book: "contacts".to_string(),
+            password_source: None,
+            conflict_prefer: None,
+            local_book: None,
+            categories_as_books: false,
+        };
+        let remote = OneCardRemote {
+            etag: "2".to_string(),
+            vcard_data: new.to_string(),
+        };
+        let backup = vdir::EditBackup {
+            root: config.vdir.clone(),
+            keep: 5,
+        };
+        let provider = crate::crypto::BackupProvider::new(Box::new(crate::crypto::PlainProvider), backup);
+        let mut engine = SyncEngine::new(&config, &remote_config, &mut db, &provider, false, true);
+        let result = engine.sync(&remote).await.unwrap();
+        assert!(result.errors.is_empty());
+        assert_eq!(result.downloaded_count, 1);
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), new);
+        let backups: Vec<PathBuf> = fs::read_dir(vdir_path.join(vdir::BACKUP_DIR))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(fs::read_to_string(&backups[0]).unwrap(), old);
+    }
 }
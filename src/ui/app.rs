@@ -1,8 +1,10 @@
 use std::collections::HashSet;
 use std::convert::TryFrom;
 use std::io::{stdout, Write};
-use std::path::{Component, Path};
+use std::path::{Component, Path, PathBuf};
 use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
 use std::time::Duration;
 
 use anyhow::{anyhow, bail, Context, Result};
@@ -22,25 +24,32 @@ use tui_widgets::popup::PopupState;
 use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
 use base64::Engine;
 
-use crate::config::{CommandExec, Config, DetailsSectionsConfig, TopBarAction, UiColors};
+use crate::config::{
+    split_binding_modifiers, CommandExec, Config, CopyTransform, DetailsSectionsConfig, ExpectedField, ImageMode,
+    ImppTemplates, MultivalueSort, OnDecryptError, QrErrorCorrection, SingleValueEnter, Startup, TopBarAction, UiColors,
+};
 use crate::crypto::CryptoProvider;
-use crate::db::{ContactItem, ContactListEntry, Database, PropRow};
+use crate::db::{primary_prop, ContactItem, ContactListEntry, Database, PropRow};
 use crate::indexer;
 use crate::search;
-use crate::vcard_io;
+use crate::vcard_io::{self, ClearedField, PhoneOptions, PhoneValidity};
 use crate::vdir;
 use vcard4::property::TextProperty;
+use vcard4::Vcard;
 
 use image::{self, DynamicImage};
 
 use super::draw;
 use super::edit::{FieldRef, InlineEditor};
+use super::text;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PaneField {
     pub label: String,
     pub value: String,
     pub copy_value: String,
+    /// What the editor starts from when it differs from `copy_value`
+    pub edit_value: Option<String>,
     pub source: Option<FieldRef>,
 }
 
@@ -80,6 +89,7 @@ impl PaneField {
             label: label.into(),
             value,
             copy_value,
+            edit_value: None,
             source,
         }
     }
@@ -88,6 +98,10 @@ impl PaneField {
         &self.copy_value
     }
 
+    pub fn edit_text(&self) -> &str {
+        self.edit_value.as_deref().unwrap_or(&self.copy_value)
+    }
+
     pub fn source(&self) -> Option<FieldRef> {
         self.source.clone()
     }
@@ -95,9 +109,14 @@ impl PaneField {
 
 const DEFAULT_FONT_SIZE: (u16, u16) = (8, 16);
 
-fn create_image_picker() -> Picker {
+/// Picker for `mode`: only `auto` probes the terminal for an image protocol;
+/// the others stay on half-blocks, which every terminal can show
+fn create_image_picker(mode: ImageMode) -> Picker {
     let mut picker = base_picker();
-    picker.guess_protocol();
+    if mode == ImageMode::Auto {
+        let protocol = picker.guess_protocol();
+        log::debug!("image protocol: {:?}", protocol);
+    }
     picker
 }
 
@@ -113,11 +132,33 @@ fn base_picker() -> Picker {
 
 const DEFAULT_ADDRESS_BOOK: &str = "default";
 
+/// Shown in place of a blank or missing value
+const EMPTY_FIELD: &str = "—";
+
+/// How many contacts the recently edited view lists
+const RECENT_LIMIT: usize = 20;
+
+/// Contacts loaded into the list per query; more are loaded as it scrolls
+const CONTACT_PAGE: usize = 500;
+
+/// Rows kept loaded below the selection, more than a screen shows
+const LOAD_AHEAD: usize = 100;
+
+/// `app_state` key holding the UUID selected at quit, for `startup = "last"`
+const LAST_CONTACT_STATE: &str = "last_contact";
+
 #[derive(Debug, Clone)]
 pub struct SearchRow {
     pub text: String,
     pub depth: u16,
     pub contact_index: Option<usize>,
+    /// Byte offset in `text` where the name starts (after the icon)
+    pub name_start: usize,
+    /// Contact lacks a field listed in `flag_incomplete`
+    pub incomplete: bool,
+    /// On a collapsed book header: how many contacts it hides, starting at
+    /// `contact_index`
+    pub folded: usize,
 }
 
 impl SearchRow {
@@ -132,12 +173,14 @@ pub enum PaneFocus {
     Card,
     Details,
     Image,
+    Notes,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MultiValueField {
     Email,
     Phone,
+    Address,
     Alias,
 }
 
@@ -146,6 +189,7 @@ impl MultiValueField {
         match name.to_ascii_uppercase().as_str() {
             "EMAIL" => Some(Self::Email),
             "TEL" => Some(Self::Phone),
+            "ADR" => Some(Self::Address),
             "NICKNAME" => Some(Self::Alias),
             _ => None,
         }
@@ -155,6 +199,7 @@ impl MultiValueField {
         match self {
             Self::Email => "EMAIL ADDRESSES",
             Self::Phone => "PHONE NUMBERS",
+            Self::Address => "ADDRESSES",
             Self::Alias => "ALIASES",
         }
     }
@@ -163,6 +208,7 @@ impl MultiValueField {
         match self {
             Self::Email => "EMAIL",
             Self::Phone => "TEL",
+            Self::Address => "ADR",
             Self::Alias => "NICKNAME",
         }
     }
@@ -170,7 +216,7 @@ impl MultiValueField {
     /// Whether this field type supports a "type" label (e.g., "work", "home")
     pub fn has_type_label(self) -> bool {
         match self {
-            Self::Email | Self::Phone => true,
+            Self::Email | Self::Phone | Self::Address => true,
             Self::Alias => false,
         }
     }
@@ -178,7 +224,7 @@ impl MultiValueField {
     /// Whether this field type supports "set default" operation
     pub fn has_default(self) -> bool {
         match self {
-            Self::Email | Self::Phone => true,
+            Self::Email | Self::Phone | Self::Address => true,
             Self::Alias => false,
         }
     }
@@ -196,6 +242,8 @@ pub struct MultiValueItem {
 pub struct ConfirmModal {
     pub title: String,
     pub message: String,
+    /// Extra lines shown under the message, such as a merge preview
+    pub details: Vec<String>,
     pub action: ConfirmAction,
 }
 
@@ -210,6 +258,8 @@ pub enum ConfirmAction {
     DeleteField { field: String, seq: i64 },
     /// Delete the contact photo
     DeletePhoto,
+    /// Review duplicate groups detected at startup
+    ReviewDuplicates,
 }
 
 #[derive(Debug, Clone)]
@@ -217,9 +267,9 @@ pub struct AliasModal {
     pub input: Input,
 }
 
-/// Help modal state with scroll support
+/// Scroll state for a text viewport (help modal, notes pane)
 #[derive(Debug, Clone)]
-pub struct HelpModal {
+pub struct ScrollView {
     /// Current scroll offset (line index at top of viewport)
     pub scroll: usize,
     /// Total number of content lines
@@ -228,17 +278,44 @@ pub struct HelpModal {
     pub viewport_height: usize,
 }
 
+/// Full view of one field value, for values cut off by their pane
+#[derive(Debug, Clone)]
+pub struct FieldPopup {
+    pub label: String,
+    pub value: String,
+    pub view: ScrollView,
+}
+
 /// Reindex modal (blocking during reindex operation)
 #[derive(Debug, Clone)]
 pub struct ReindexModal {
     pub message: String,
 }
 
+/// How far a full reindex got
+#[derive(Debug, Clone, Default)]
+struct ReindexProgress {
+    processed: usize,
+    total: usize,
+    cancelled: bool,
+    /// Files left out because they failed to decrypt (`on_decrypt_error = "skip"`)
+    skipped: Vec<PathBuf>,
+    /// Files left out because a newer file carries the same UID
+    uid_clashes: Vec<indexer::UidClash>,
+}
+
+/// How often the reindex modal is redrawn while files are being processed
+const REINDEX_REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
 /// Share modal with QR code
 #[derive(Debug, Clone)]
 pub struct ShareModal {
     /// QR code rendered as lines of Unicode characters
     pub qr_lines: Vec<String>,
+    /// Symbol version (1-40) chosen for the payload
+    pub version: i16,
+    /// Error-correction level the code was built with
+    pub error_correction: QrErrorCorrection,
 }
 
 /// Photo path input modal
@@ -247,7 +324,62 @@ pub struct PhotoPathModal {
     pub input: Input,
 }
 
-impl HelpModal {
+/// Category name input for the marked contacts; a leading `-` removes it
+#[derive(Debug, Clone)]
+pub struct CategoryModal {
+    pub input: Input,
+}
+
+/// How one row of the compare modal relates the two contacts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareState {
+    Same,
+    Differs,
+    LeftOnly,
+    RightOnly,
+}
+
+/// One value of a field, lined up against the other contact's value
+#[derive(Debug, Clone)]
+pub struct CompareRow {
+    pub field: String,
+    pub left: String,
+    pub right: String,
+    pub state: CompareState,
+}
+
+/// Side-by-side view of exactly two marked contacts
+#[derive(Debug, Clone)]
+pub struct CompareModal {
+    pub left_name: String,
+    pub right_name: String,
+    pub rows: Vec<CompareRow>,
+    pub view: ScrollView,
+}
+
+/// Structured component editor for N and ADR: the components are edited as
+/// one "; "-separated line and written back one component at a time
+#[derive(Debug, Clone)]
+pub struct ComponentEditModal {
+    pub title: &'static str,
+    /// Component names shown above the input
+    pub hint: &'static str,
+    /// Property being edited (field and seq; the component is per entry)
+    pub target: FieldRef,
+    pub max_components: usize,
+    /// Multivalue modal to return to once the edit is finished
+    pub return_to: Option<MultiValueField>,
+    pub input: Input,
+    /// ADR only: the free-form LABEL, lines separated by `|`
+    pub label: Option<Input>,
+    /// Whether typing goes to `label` rather than `input`
+    pub label_focused: bool,
+}
+
+const NAME_COMPONENTS_HINT: &str = "family; given; additional; prefix; suffix";
+const ADDRESS_COMPONENTS_HINT: &str = "po box; extended; street; city; region; postal code; country";
+
+impl ScrollView {
     pub fn new(total_lines: usize) -> Self {
         Self {
             scroll: 0,
@@ -280,6 +412,21 @@ impl HelpModal {
     pub fn can_scroll_down(&self) -> bool {
         self.scroll + self.viewport_height < self.total_lines
     }
+
+    /// Apply a j/k, paging or home/end key; returns false for any other key
+    fn scroll_by_key(&mut self, key: &KeyEvent) -> bool {
+        let page = self.viewport_height.saturating_sub(1).max(1);
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.scroll_down(1),
+            KeyCode::Char('k') | KeyCode::Up => self.scroll_up(1),
+            KeyCode::PageDown => self.scroll_down(page),
+            KeyCode::PageUp => self.scroll_up(page),
+            KeyCode::Char('g') | KeyCode::Home => self.scroll_to_top(),
+            KeyCode::Char('G') | KeyCode::End => self.scroll_to_bottom(),
+            _ => return false,
+        }
+        true
+    }
 }
 
 /// A section in the help modal (e.g., "Global", "Navigation")
@@ -356,10 +503,15 @@ pub struct DetailsField {
     pub label: String,
     pub value: String,
     pub copy_value: String,
+    /// The stored value, when `value` and `copy_value` show something derived
+    /// from it (an IMPP handle rendered as a link)
+    pub edit_value: Option<String>,
     /// Property parameters as name -> list of values (e.g., "TYPE" -> ["CELL", "WORK"])
     /// Excludes PREF which is handled separately in multivalue fields
     pub params: std::collections::HashMap<String, Vec<String>>,
     pub source: Option<FieldRef>,
+    /// Import format or remote the value came from (`X-RLDX-SOURCE`)
+    pub origin: Option<String>,
 }
 
 impl DetailsField {
@@ -369,6 +521,7 @@ impl DetailsField {
             label: self.label.clone(),
             value: self.value.clone(),
             copy_value: self.copy_value.clone(),
+            edit_value: self.edit_value.clone(),
             source: self.source.clone(),
         }
     }
@@ -386,8 +539,12 @@ pub struct App<'a> {
     config: &'a Config,
     provider: &'a dyn CryptoProvider,
     pub contacts: Vec<ContactListEntry>,
+    // Contacts matching the list beyond the pages loaded so far
+    more_contacts: bool,
     pub selected: usize,
     pub search_input: Input,
+    // Filter and selected contact set aside by the filter toggle
+    stashed_filter: Option<(String, Option<String>)>,
     pub show_search: bool,
     pub search_focus: SearchFocus,
     pub current_contact: Option<ContactItem>,
@@ -411,9 +568,22 @@ pub struct App<'a> {
     pub marked: HashSet<String>,
     // When true, the search pane shows only marked contacts
     pub show_marked_only: bool,
+    // When true, the search pane lists the most recently revised contacts
+    pub show_recent: bool,
+    // Address books (as paths relative to the vdir) whose contacts are hidden
+    pub collapsed_books: HashSet<PathBuf>,
+    // When true, the card and details panes also list blank and missing fields
+    pub show_empty_fields: bool,
+    // When true, details fields name the import or remote they came from
+    pub show_sources: bool,
+    // Notes pane (panel 4), shown beside the card and details panes
+    pub show_notes: bool,
+    pub notes_view: ScrollView,
     image_picker: Picker,
     image_state: Option<Box<dyn StatefulProtocol>>,
     pub photo_data: Option<PhotoData>,
+    // PHOTO seq of the photo shown in the image pane
+    photo_seq: Option<i64>,
     pub photo_error: Option<String>,
     multivalue_modal: Option<MultiValueModal>,
     // Popup state for modal dialog (tui-widgets popup)
@@ -423,7 +593,9 @@ pub struct App<'a> {
     // Add-alias modal
     pub alias_modal: Option<AliasModal>,
     // Help modal (F1)
-    pub help_modal: Option<HelpModal>,
+    pub help_modal: Option<ScrollView>,
+    // Focused value shown in full
+    pub field_popup: Option<FieldPopup>,
     // Reindex modal (blocking)
     pub reindex_modal: Option<ReindexModal>,
     // Share modal with QR code
@@ -432,8 +604,20 @@ pub struct App<'a> {
     pub add_field_modal: Option<AddFieldModal>,
     // Photo path input modal
     pub photo_path_modal: Option<PhotoPathModal>,
+    // Category input for the marked contacts
+    pub category_modal: Option<CategoryModal>,
+    pub compare_modal: Option<CompareModal>,
+    // Structured N/ADR component editor
+    pub component_modal: Option<ComponentEditModal>,
     // Flag to trigger reindex from event loop
     pub pending_reindex: bool,
+    // Search text changed but the list has not been reloaded yet; typing
+    // bursts (and pastes) cost one query instead of one per key
+    pending_filter: bool,
+    // Duplicate groups (UUIDs) still waiting to be reviewed
+    duplicate_groups: Vec<Vec<String>>,
+    // Whether the marked-only view currently shows a duplicate group
+    reviewing_duplicates: bool,
 }
 
 /// Add field modal - multi-step wizard for adding new fields
@@ -531,14 +715,17 @@ impl Default for AddFieldModal {
 
 impl<'a> App<'a> {
     pub fn new(db: &'a mut Database, config: &'a Config, provider: &'a dyn CryptoProvider) -> Result<Self> {
-        let contacts = db.list_contacts(None)?;
+        let contacts = db.list_contacts(None, 0, Some(CONTACT_PAGE))?;
+        let more_contacts = contacts.len() == CONTACT_PAGE;
         let mut app = Self {
             db,
             config,
             provider,
             contacts,
+            more_contacts,
             selected: 0,
             search_input: Input::default(),
+            stashed_filter: None,
             show_search: true,
             search_focus: SearchFocus::Input,
             current_contact: None,
@@ -557,23 +744,41 @@ impl<'a> App<'a> {
             selected_row: None,
             marked: HashSet::new(),
             show_marked_only: false,
-            image_picker: create_image_picker(),
+            show_recent: false,
+            collapsed_books: HashSet::new(),
+            show_empty_fields: false,
+            show_sources: false,
+            show_notes: false,
+            notes_view: ScrollView::new(0),
+            image_picker: create_image_picker(config.ui.pane.image.mode),
             image_state: None,
             photo_data: None,
+            photo_seq: None,
             photo_error: None,
             multivalue_modal: None,
             modal_popup: PopupState::default(),
             confirm_modal: None,
             alias_modal: None,
             help_modal: None,
+            field_popup: None,
             reindex_modal: None,
             share_modal: None,
             add_field_modal: None,
             photo_path_modal: None,
+            category_modal: None,
+            compare_modal: None,
+            component_modal: None,
             pending_reindex: false,
+            pending_filter: false,
+            duplicate_groups: Vec::new(),
+            reviewing_duplicates: false,
         };
         app.rebuild_search_rows();
         app.load_selection()?;
+        app.apply_startup()?;
+        if app.config.review_duplicates_on_startup {
+            app.offer_duplicate_review()?;
+        }
         Ok(app)
     }
 
@@ -591,7 +796,34 @@ impl<'a> App<'a> {
         terminal.backend_mut().execute(LeaveAlternateScreen)?;
         terminal.show_cursor()?;
 
-        result
+        result?;
+        if self.config.startup == Startup::Last {
+            if let Some(entry) = self.contacts.get(self.selected) {
+                self.db.set_state(LAST_CONTACT_STATE, &entry.uuid)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Open the card `startup` asks for instead of the search pane. A last
+    /// contact that no longer exists falls back to search.
+    fn apply_startup(&mut self) -> Result<()> {
+        let index = match self.config.startup {
+            Startup::Search => None,
+            Startup::Last => {
+                match self.db.get_state(LAST_CONTACT_STATE)? {
+                    Some(uuid) => self.find_contact(&uuid)?,
+                    None => None,
+                }
+            }
+            Startup::First => Some(0).filter(|_| !self.contacts.is_empty()),
+        };
+        if let Some(index) = index {
+            self.selected = index;
+            self.load_selection()?;
+            self.focus_pane(PaneFocus::Card);
+        }
+        Ok(())
     }
 
     fn event_loop<B>(&mut self, terminal: &mut Terminal<B>) -> Result<()>
@@ -599,6 +831,11 @@ impl<'a> App<'a> {
         B: ratatui::backend::Backend,
     {
         loop {
+            // Reload the filtered list once the typed keys are all handled
+            if self.pending_filter && !event::poll(Duration::ZERO)? {
+                self.refresh_contacts()?;
+            }
+
             draw::render(terminal, self)?;
 
             // Handle pending reindex (shows blocking modal)
@@ -608,19 +845,57 @@ impl<'a> App<'a> {
                     message: "REINDEXING...".to_string(),
                 });
                 draw::render(terminal, self)?; // Show the modal
-                self.perform_reindex()?;
+                let result = self.perform_reindex(terminal);
                 self.reindex_modal = None;
+                let progress = result?;
                 self.refresh_contacts()?;
-                self.set_status("Reindex complete");
+                if progress.cancelled {
+                    self.set_status(format!(
+                        "Reindex cancelled after {}/{} files; refresh again to finish",
+                        progress.processed, progress.total
+                    ));
+                } else if !progress.skipped.is_empty() {
+                    self.set_status(format!(
+                        "Reindex complete; skipped {} file(s) that failed to decrypt",
+                        progress.skipped.len()
+                    ));
+                } else if !progress.uid_clashes.is_empty() {
+                    self.set_status(format!(
+                        "Reindex complete; {} file(s) share a UID with a newer one (run `rldx --fix-dupe-uids`)",
+                        progress.uid_clashes.len()
+                    ));
+                } else {
+                    self.set_status("Reindex complete");
+                }
+                if !progress.skipped.is_empty() {
+                    let paths: Vec<String> =
+                        progress.skipped.iter().map(|path| path.display().to_string()).collect();
+                    self.field_popup = Some(FieldPopup {
+                        label: "Skipped (failed to decrypt)".to_string(),
+                        value: paths.join("\n"),
+                        view: ScrollView::new(0),
+                    });
+                } else if !progress.uid_clashes.is_empty() {
+                    let lines: Vec<String> = progress
+                        .uid_clashes
+                        .iter()
+                        .map(|clash| {
+                            format!("{} (UID of {})", clash.older.display(), clash.newer.display())
+                        })
+                        .collect();
+                    self.field_popup = Some(FieldPopup {
+                        label: "Not indexed (duplicate UID)".to_string(),
+                        value: lines.join("\n"),
+                        view: ScrollView::new(0),
+                    });
+                }
                 continue;
             }
 
             if event::poll(Duration::from_millis(250))? {
                 match event::read()? {
-                    Event::Key(key) => {
-                        if self.handle_key(key)? {
-                            break;
-                        }
+                    Event::Key(key) if self.handle_key(key)? => {
+                        break;
                     }
                     Event::Resize(_, _) => {}
                     _ => {}
@@ -638,12 +913,27 @@ impl<'a> App<'a> {
             return Ok(true);
         }
 
+        // Anything but more typing sees the list for the current search text
+        if self.pending_filter && !is_text_entry(&key) {
+            self.refresh_contacts()?;
+        }
+
         // If help modal is open, handle its keys first
         if self.help_modal.is_some() {
             self.handle_help_modal_key(key);
             return Ok(false);
         }
 
+        if self.field_popup.is_some() {
+            self.handle_field_popup_key(key);
+            return Ok(false);
+        }
+
+        if self.compare_modal.is_some() {
+            self.handle_compare_modal_key(key);
+            return Ok(false);
+        }
+
         // If share modal is open, handle its keys
         if self.share_modal.is_some() {
             self.handle_share_modal_key(key);
@@ -653,6 +943,8 @@ impl<'a> App<'a> {
         // Check top bar buttons (work in any context except modals/editor)
         if self.confirm_modal.is_none()
             && self.alias_modal.is_none()
+            && self.category_modal.is_none()
+            && self.component_modal.is_none()
             && self.multivalue_modal.is_none()
             && !self.editor.active
         {
@@ -686,6 +978,16 @@ impl<'a> App<'a> {
             return Ok(false);
         }
 
+        if self.category_modal.is_some() {
+            self.handle_category_modal_key(key)?;
+            return Ok(false);
+        }
+
+        if self.component_modal.is_some() {
+            self.handle_component_modal_key(key)?;
+            return Ok(false);
+        }
+
         if self.multivalue_modal.is_some() {
             self.handle_multivalue_modal_key(key)?;
             return Ok(false);
@@ -701,6 +1003,11 @@ impl<'a> App<'a> {
 
     /// Handle keys in navigation context (card/detail panes)
     fn handle_navigation_key(&mut self, key: KeyEvent) -> Result<bool> {
+        // Notes pane: scroll keys win over field navigation
+        if matches!(self.focused_pane, PaneFocus::Notes) && self.notes_view.scroll_by_key(&key) {
+            return Ok(false);
+        }
+
         let nav = &self.config.keys.navigation;
         let global = &self.config.keys.global;
 
@@ -723,13 +1030,38 @@ impl<'a> App<'a> {
             return Ok(false);
         }
 
-        // Navigation: confirm (open multivalue modal if applicable)
-        if self.key_matches_any(&key, &nav.confirm)
-            && self.open_multivalue_modal_for_current_field()
-        {
+        // Global: recently edited contacts
+        if self.key_matches_any(&key, &global.recent) {
+            self.toggle_recent_view()?;
             return Ok(false);
         }
 
+        // Navigation: confirm (open multivalue modal if applicable, otherwise
+        // apply single_value_enter to a lone email/phone/address). In the
+        // Image pane it makes the shown photo the primary one.
+        if self.key_matches_any(&key, &nav.confirm) {
+            if matches!(self.focused_pane, PaneFocus::Image) {
+                self.make_photo_primary()?;
+                return Ok(false);
+            }
+            if self.open_multivalue_modal_for_current_field() {
+                return Ok(false);
+            }
+            if self.focused_single_value() {
+                match self.config.single_value_enter {
+                    SingleValueEnter::Copy => {
+                        self.copy_focused_value()?;
+                        return Ok(false);
+                    }
+                    SingleValueEnter::Edit => {
+                        self.begin_edit();
+                        return Ok(false);
+                    }
+                    SingleValueEnter::Nothing => {}
+                }
+            }
+        }
+
         // Navigation: next/prev field
         if self.key_matches_any(&key, &nav.next) {
             self.advance_field(1);
@@ -746,12 +1078,27 @@ impl<'a> App<'a> {
             return Ok(false);
         }
 
+        // Navigation: edit the custom (X-ABLabel) label
+        if self.key_matches_any(&key, &nav.edit_label) {
+            match self.focused_field().and_then(|field| field.source()) {
+                Some(source) => self.begin_label_edit(&source.field, source.seq),
+                None => self.set_status("Field has no label"),
+            }
+            return Ok(false);
+        }
+
         // Navigation: copy
         if self.key_matches_any(&key, &nav.copy) {
             self.copy_focused_value()?;
             return Ok(false);
         }
 
+        // Navigation: copy as mailto:/tel: URI
+        if self.key_matches_any(&key, &nav.copy_uri) {
+            self.copy_focused_uri()?;
+            return Ok(false);
+        }
+
         // Navigation: add field
         if self.key_matches_any(&key, &nav.add_field) {
             // For ALIAS in Card pane, use existing add alias modal
@@ -784,6 +1131,7 @@ impl<'a> App<'a> {
                     self.confirm_modal = Some(ConfirmModal {
                         title: "DELETE FIELD".to_string(),
                         message: format!("Delete {} \"{}\"?", field.label, truncate_value(&field.value, 30)),
+                        details: Vec::new(),
                         action: ConfirmAction::DeleteField {
                             field: source.field,
                             seq: source.seq,
@@ -798,6 +1146,109 @@ impl<'a> App<'a> {
             return Ok(false);
         }
 
+        // Navigation: split FN into N components (only offered when N is empty)
+        if self.key_matches_any(&key, &nav.split_name) {
+            self.open_split_name_modal();
+            return Ok(false);
+        }
+
+        // Navigation: drop the search filter that still scopes the list
+        if self.key_matches_any(&key, &nav.clear_filter) {
+            self.clear_filter()?;
+            return Ok(false);
+        }
+
+        // Navigation: peek at the full list, then bring the filter back
+        if self.key_matches_any(&key, &nav.toggle_filter) {
+            self.toggle_filter()?;
+            return Ok(false);
+        }
+
+        // Navigation: show or hide blank and missing fields
+        if self.key_matches_any(&key, &nav.toggle_empty) {
+            self.show_empty_fields = !self.show_empty_fields;
+            self.rebuild_field_views();
+            self.set_status(if self.show_empty_fields {
+                "Showing empty fields"
+            } else {
+                "Hiding empty fields"
+            });
+            return Ok(false);
+        }
+
+        // Navigation: show or hide where details fields came from
+        if self.key_matches_any(&key, &nav.toggle_sources) {
+            self.show_sources = !self.show_sources;
+            self.set_status(if self.show_sources {
+                "Showing field sources"
+            } else {
+                "Hiding field sources"
+            });
+            return Ok(false);
+        }
+
+        // Navigation: keep the contact out of CardDAV sync
+        if self.key_matches_any(&key, &nav.toggle_nosync) {
+            self.toggle_no_sync()?;
+            return Ok(false);
+        }
+
+        // Navigation: show or hide the notes pane
+        if self.key_matches_any(&key, &nav.toggle_notes) {
+            self.toggle_notes();
+            return Ok(false);
+        }
+
+        // Navigation: show the focused value in full
+        if self.key_matches_any(&key, &nav.expand) {
+            self.expand_focused_field();
+            return Ok(false);
+        }
+
+        // Navigation: collapse or expand an address book
+        if self.key_matches_any(&key, &nav.toggle_book) {
+            self.toggle_book()?;
+            return Ok(false);
+        }
+
+        // Navigation: reorder the contact within its address book
+        if self.key_matches_any(&key, &nav.move_contact_up) {
+            self.move_contact(-1)?;
+            return Ok(false);
+        }
+        if self.key_matches_any(&key, &nav.move_contact_down) {
+            self.move_contact(1)?;
+            return Ok(false);
+        }
+
+        // Navigation: copy the contact as a new one
+        if self.key_matches_any(&key, &nav.duplicate) {
+            self.duplicate_current_contact()?;
+            return Ok(false);
+        }
+
+        // Navigation: hand the contact's file to commands.open_path
+        if self.key_matches_any(&key, &nav.open_path) {
+            self.open_contact_path();
+            return Ok(false);
+        }
+
+        // Navigation: copy the contact's UID or file path for scripts
+        if self.key_matches_any(&key, &nav.copy_uid) {
+            self.copy_contact_uid();
+            return Ok(false);
+        }
+        if self.key_matches_any(&key, &nav.copy_path) {
+            self.copy_contact_path();
+            return Ok(false);
+        }
+
+        // Navigation: normalize and dedupe the contact's emails
+        if self.key_matches_any(&key, &nav.clean_emails) {
+            self.clean_emails()?;
+            return Ok(false);
+        }
+
         // Navigation: photo fetch (only when Image pane is focused)
         if self.key_matches_any(&key, &nav.photo_fetch) {
             if matches!(self.focused_pane, PaneFocus::Image) {
@@ -821,6 +1272,7 @@ impl<'a> App<'a> {
                 self.confirm_modal = Some(ConfirmModal {
                     title: "DELETE PHOTO".to_string(),
                     message: "Delete the contact photo?".to_string(),
+                    details: Vec::new(),
                     action: ConfirmAction::DeletePhoto,
                 });
             } else if self.photo_data.is_none() {
@@ -837,7 +1289,7 @@ impl<'a> App<'a> {
             return Ok(false);
         }
 
-        // Digit shortcuts for pane focus (1-3) - only when search is closed
+        // Digit shortcuts for pane focus (1-4) - only when search is closed
         if !self.show_search {
             if let KeyCode::Char(c) = key.code {
                 if self.focus_by_digit(c) {
@@ -861,7 +1313,11 @@ impl<'a> App<'a> {
 
                 // Confirm: open selected contact, collapse search
                 if self.key_matches_any(&key, &input_keys.confirm) {
+                    if self.create_from_search()? {
+                        return Ok(true);
+                    }
                     self.show_search = false;
+                    self.show_recent = false;
                     self.focus_pane(PaneFocus::Card);
                     self.refresh_contacts()?;
                     return Ok(true);
@@ -880,7 +1336,8 @@ impl<'a> App<'a> {
                 // Pass other keys to the input widget
                 if let Some(change) = self.search_input.handle_event(&Event::Key(key)) {
                     if change.value {
-                        self.refresh_contacts()?;
+                        self.show_recent = false;
+                        self.pending_filter = true;
                     }
                     return Ok(true);
                 }
@@ -896,6 +1353,12 @@ impl<'a> App<'a> {
                     return Ok(true);
                 }
 
+                // Global: recently edited contacts
+                if self.key_matches_any(&key, &global_keys.recent) {
+                    self.toggle_recent_view()?;
+                    return Ok(true);
+                }
+
                 // Global: search key refocuses input
                 if self.key_matches_any(&key, &global_keys.search) {
                     self.search_focus = SearchFocus::Input;
@@ -905,13 +1368,18 @@ impl<'a> App<'a> {
                 // Cancel: close search
                 if self.key_matches_any(&key, &results_keys.cancel) {
                     self.show_search = false;
+                    self.show_recent = false;
                     self.refresh_contacts()?;
                     return Ok(true);
                 }
 
                 // Confirm: open selected contact and collapse search
                 if self.key_matches_any(&key, &results_keys.confirm) {
+                    if self.create_from_search()? {
+                        return Ok(true);
+                    }
                     self.show_search = false;
+                    self.show_recent = false;
                     self.focus_pane(PaneFocus::Card);
                     self.refresh_contacts()?;
                     return Ok(true);
@@ -955,6 +1423,14 @@ impl<'a> App<'a> {
                         self.set_status("Mark at least 2 contacts to merge");
                         return Ok(true);
                     }
+                    let details = match vcard_io::plan_merge(
+                        &self.marked_paths()?,
+                        self.provider,
+                        self.config.phone_options(),
+                    ) {
+                        Ok(card) => vcard_io::merge_preview(&card),
+                        Err(err) => vec![format!("Preview unavailable: {err:#}")],
+                    };
                     self.modal_popup = PopupState::default();
                     self.confirm_modal = Some(ConfirmModal {
                         title: "MERGE CONTACTS".to_string(),
@@ -962,28 +1438,90 @@ impl<'a> App<'a> {
                             "Merge {} marked contacts into a single card?",
                             count
                         ),
+                        details,
                         action: ConfirmAction::MergeContacts,
                     });
                     return Ok(true);
                 }
 
+                // Add or remove a category on every marked contact
+                if self.key_matches_any(&key, &results_keys.tag_marked) {
+                    if self.marked.is_empty() {
+                        self.set_status("Mark contacts to tag");
+                        return Ok(true);
+                    }
+                    self.modal_popup = PopupState::default();
+                    self.category_modal = Some(CategoryModal { input: Input::default() });
+                    return Ok(true);
+                }
+
+                // Compare the two marked contacts side by side
+                if self.key_matches_any(&key, &results_keys.compare) {
+                    self.open_compare_modal()?;
+                    return Ok(true);
+                }
+
+                // Leave the duplicate group under review unmerged and move on
+                if self.key_matches_any(&key, &results_keys.skip_group) {
+                    if self.reviewing_duplicates {
+                        self.review_next_duplicate_group()?;
+                    } else {
+                        self.set_status("No duplicate review in progress");
+                    }
+                    return Ok(true);
+                }
+
                 // Toggle marked-only view
                 if self.key_matches_any(&key, &results_keys.toggle_marked) {
-                    self.show_marked_only = !self.show_marked_only;
                     if self.show_marked_only {
-                        self.rebuild_marked_contacts()?;
-                    } else {
+                        self.leave_marked_view();
                         self.refresh_contacts()?;
+                    } else {
+                        self.show_marked_only = true;
+                        self.rebuild_marked_contacts()?;
                     }
                     return Ok(true);
                 }
 
+                // Quick jump: an unbound letter selects the next contact starting with it
+                if let KeyCode::Char(c) = key.code {
+                    if c.is_alphabetic()
+                        && !key
+                            .modifiers
+                            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+                    {
+                        self.jump_to_initial(c)?;
+                        return Ok(true);
+                    }
+                }
+
                 Ok(false)
             }
         }
     }
 
+    fn jump_to_initial(&mut self, initial: char) -> Result<()> {
+        let mut found = next_with_initial(&self.contacts, self.selected, initial);
+        // Wrapping around is only right once the rest of the list is loaded
+        if self.more_contacts && found.is_none_or(|index| index <= self.selected) {
+            self.load_more_contacts(None)?;
+            found = next_with_initial(&self.contacts, self.selected, initial);
+        }
+        match found {
+            Some(index) => {
+                self.selected = index;
+                self.load_selection()?;
+            }
+            None => self.set_status(format!("No contact starting with '{}'", initial)),
+        }
+        Ok(())
+    }
+
     fn toggle_mark_current(&mut self) {
+        if self.on_folded_book() {
+            self.set_status("Expand the book to mark its contacts");
+            return;
+        }
         if let Some(uuid) = self
             .contacts
             .get(self.selected)
@@ -1001,14 +1539,35 @@ impl<'a> App<'a> {
         }
     }
 
+    /// Switch the search pane between the normal list and the most recently
+    /// revised contacts, selecting the newest one
+    fn toggle_recent_view(&mut self) -> Result<()> {
+        self.show_recent = !self.show_recent;
+        if !self.show_recent {
+            return self.refresh_contacts();
+        }
+
+        self.leave_marked_view();
+        self.show_search = true;
+        self.focus_pane(PaneFocus::Search);
+        self.search_focus = SearchFocus::Results;
+        self.refresh_contacts()?;
+        if self.selected != 0 {
+            self.move_selection(-(self.selected as isize))?;
+        }
+        self.set_status(format!("{} recently edited contacts", self.contacts.len()));
+        Ok(())
+    }
+
     fn rebuild_marked_contacts(&mut self) -> Result<()> {
+        self.show_recent = false;
         // load all contacts and filter to marked
-        let all = self.db.list_contacts(None)?;
+        let all = self.db.list_contacts(None, 0, None)?;
         self.contacts = all
             .into_iter()
             .filter(|c| self.marked.contains(&c.uuid))
             .collect();
-        self.sort_contacts();
+        self.more_contacts = false;
 
         if self.selected >= self.contacts.len() {
             self.selected = self.contacts.len().saturating_sub(1);
@@ -1027,62 +1586,298 @@ impl<'a> App<'a> {
         Ok(())
     }
 
-    fn merge_marked_contacts(&mut self) -> Result<()> {
-        use std::path::PathBuf;
+    /// Handle keys for the marked-contacts category modal
+    fn handle_category_modal_key(&mut self, key: KeyEvent) -> Result<()> {
+        let modal_keys = &self.config.keys.modal;
 
-        if self.marked.len() < 2 {
-            self.set_status("Mark at least 2 contacts to merge");
+        if self.key_matches_any(&key, &modal_keys.cancel) {
+            self.category_modal = None;
             return Ok(());
         }
 
-        // Collect paths for marked contacts, preserving current sort order
-        let mut paths: Vec<PathBuf> = Vec::new();
-        for entry in &self.contacts {
-            if self.marked.contains(&entry.uuid) {
-                paths.push(entry.path.clone());
+        if self.key_matches_any(&key, &modal_keys.confirm) {
+            let value = self
+                .category_modal
+                .take()
+                .map(|m| m.input.value().trim().to_string())
+                .unwrap_or_default();
+            let (name, add) = match value.strip_prefix('-') {
+                Some(rest) => (rest.trim(), false),
+                None => (value.as_str(), true),
+            };
+            if name.is_empty() {
+                self.set_status("No category entered");
+                return Ok(());
             }
+            return self.tag_marked_contacts(name, add);
         }
-        if paths.len() < 2 {
-            self.set_status("Mark at least 2 contacts to merge");
+
+        if let Some(modal) = self.category_modal.as_mut() {
+            let _ = modal.input.handle_event(&Event::Key(key));
+        }
+        Ok(())
+    }
+
+    /// Line up the properties of the two marked contacts for the compare modal
+    fn open_compare_modal(&mut self) -> Result<()> {
+        if self.marked.len() != 2 {
+            self.set_status("Mark exactly 2 contacts to compare");
             return Ok(());
         }
+        let entries: Vec<ContactListEntry> = self
+            .db
+            .list_contacts(None, 0, None)?
+            .into_iter()
+            .filter(|entry| self.marked.contains(&entry.uuid))
+            .collect();
+        let [left, right] = entries.as_slice() else {
+            self.set_status("Marked contacts are no longer indexed");
+            return Ok(());
+        };
+        let rows = compare_props(&self.db.get_props(&left.uuid)?, &self.db.get_props(&right.uuid)?);
+        self.compare_modal = Some(CompareModal {
+            left_name: left.display_fn.clone(),
+            right_name: right.display_fn.clone(),
+            rows,
+            view: ScrollView::new(0),
+        });
+        Ok(())
+    }
 
-        // Determine target directory (same as first contact)
-        let target_dir = paths
-            .first()
-            .and_then(|p| p.parent().map(|p| p.to_path_buf()))
-            .unwrap_or_else(|| self.config.vdir.clone());
+    fn handle_compare_modal_key(&mut self, key: KeyEvent) {
+        let close = matches!(key.code, KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter)
+            || self.key_matches_any(&key, &self.config.keys.modal.cancel);
+        if close {
+            self.compare_modal = None;
+            return;
+        }
+        if let Some(modal) = self.compare_modal.as_mut() {
+            modal.view.scroll_by_key(&key);
+        }
+    }
 
-        // Merge using the standalone function (handles encryption properly)
-        let result = vcard_io::merge_vcard_files(
-            &paths,
-            &target_dir,
-            self.provider,
-            self.config.phone_region.as_deref(),
-        )?;
+    /// Add `name` to (or remove it from) the CATEGORIES of every marked
+    /// contact, reindexing the ones that changed
+    fn tag_marked_contacts(&mut self, name: &str, add: bool) -> Result<()> {
+        let paths: Vec<PathBuf> = self
+            .db
+            .list_contacts(None, 0, None)?
+            .into_iter()
+            .filter(|entry| self.marked.contains(&entry.uuid))
+            .map(|entry| entry.path)
+            .collect();
 
-        // Remove old files
+        let mut changed = 0;
+        let mut failed = 0;
         for path in &paths {
-            let _ = std::fs::remove_file(path);
+            match self.tag_contact_file(path, name, add) {
+                Ok(true) => changed += 1,
+                Ok(false) => {}
+                Err(err) => {
+                    log::warn!("could not update categories of {}: {:#}", path.display(), err);
+                    failed += 1;
+                }
+            }
         }
 
-        // Update DB: delete old, insert new
-        self.db
-            .delete_items_by_paths(paths.clone().into_iter())?;
-        let state = vdir::compute_file_state(&result.path)?;
-        let record = indexer::build_record(&result.path, &result.card, &state, None)?;
-        self.db.upsert(&record.item, &record.props)?;
-
-        // Refresh UI
-        self.marked.clear();
-        self.show_marked_only = false;
-        self.refresh_contacts()?;
-        self.set_status("Merged contacts");
+        if self.show_marked_only {
+            self.rebuild_marked_contacts()?;
+        } else {
+            self.refresh_contacts()?;
+        }
+        let verb = if add { "Added" } else { "Removed" };
+        let direction = if add { "to" } else { "from" };
+        let mut status = format!("{} '{}' {} {} of {} contacts", verb, name, direction, changed, paths.len());
+        if failed > 0 {
+            status.push_str(&format!(", {} failed", failed));
+        }
+        self.set_status(status);
         Ok(())
     }
 
-    /// Delete the current contact file
-    fn delete_current_contact(&mut self) -> Result<()> {
+    /// Add or remove one category on a contact file. Returns false when the
+    /// card already matched and was left alone.
+    fn tag_contact_file(&mut self, path: &Path, name: &str, add: bool) -> Result<bool> {
+        let parsed = vcard_io::parse_file(path, self.config.phone_options(), self.provider)?;
+        let mut cards = parsed.cards;
+        let Some(card) = cards.first_mut() else {
+            return Ok(false);
+        };
+        let changed = if add {
+            vcard_io::add_category(card, name)
+        } else {
+            vcard_io::remove_category(card, name)
+        };
+        if !changed {
+            return Ok(false);
+        }
+
+        self.write_edited_cards(path, &mut cards)?;
+        let state = vdir::compute_file_state(path)?;
+        let record = indexer::build_record(path, &cards[0], &state, None, self.config.nameless_display)?;
+        self.db.upsert(&record.item, &record.props)?;
+        Ok(true)
+    }
+
+    /// Paths of the marked contacts in the list, in its current sort order
+    fn marked_paths(&self) -> Result<Vec<PathBuf>> {
+        // Marked contacts may sit in pages of the list not loaded yet
+        let unloaded;
+        let entries = if self.more_contacts {
+            let filter = search::normalize_query(self.search_input.value());
+            unloaded = self.db.list_contacts(filter.as_deref(), 0, None)?;
+            &unloaded
+        } else {
+            &self.contacts
+        };
+        Ok(entries
+            .iter()
+            .filter(|entry| self.marked.contains(&entry.uuid))
+            .map(|entry| entry.path.clone())
+            .collect())
+    }
+
+    fn merge_marked_contacts(&mut self) -> Result<()> {
+        if self.marked.len() < 2 {
+            self.set_status("Mark at least 2 contacts to merge");
+            return Ok(());
+        }
+
+        let paths = self.marked_paths()?;
+        if paths.len() < 2 {
+            self.set_status("Mark at least 2 contacts to merge");
+            return Ok(());
+        }
+
+        // Determine target directory (same as first contact)
+        let target_dir = paths
+            .first()
+            .and_then(|p| p.parent().map(|p| p.to_path_buf()))
+            .unwrap_or_else(|| self.config.vdir.clone());
+
+        // Merge using the standalone function (handles encryption properly)
+        let result = vcard_io::merge_vcard_files(
+            &paths,
+            &target_dir,
+            self.provider,
+            self.config.phone_options(),
+        )?;
+
+        // Swap the originals for the merged card in one DB transaction. If
+        // that fails, drop the new file so the vdir matches the index again.
+        let indexed = vdir::compute_file_state(&result.path)
+            .and_then(|state| {
+                indexer::build_record(&result.path, &result.card, &state, None, self.config.nameless_display)
+            })
+            .and_then(|record| self.db.replace_items(&paths, &record.item, &record.props));
+        if let Err(err) = indexed {
+            if let Err(remove_err) = std::fs::remove_file(&result.path) {
+                log::warn!("could not remove {}: {}", result.path.display(), remove_err);
+            }
+            return Err(err.context("failed to index the merged contact; originals kept"));
+        }
+
+        // Remove the originals last: one left behind is only a duplicate that
+        // the next reindex picks up again, the index stays consistent
+        let mut leftover = 0;
+        for path in &paths {
+            if let Err(err) = vdir::backup_before_change(path, self.provider.edit_backup()).and_then(|()| Ok(std::fs::remove_file(path)?)) {
+                log::warn!("could not remove merged contact {}: {:#}", path.display(), err);
+                leftover += 1;
+            }
+        }
+
+        // Refresh UI
+        self.marked.clear();
+        self.show_marked_only = false;
+        self.refresh_contacts()?;
+        if leftover > 0 {
+            self.set_status(format!(
+                "Merged contacts, but {} original file(s) could not be removed",
+                leftover
+            ));
+        } else {
+            self.set_status("Merged contacts");
+        }
+
+        if self.reviewing_duplicates {
+            self.review_next_duplicate_group()?;
+        }
+        Ok(())
+    }
+
+    /// Detect likely duplicates and ask whether to review them
+    fn offer_duplicate_review(&mut self) -> Result<()> {
+        self.duplicate_groups = self.db.find_duplicate_groups(
+            self.config.maildir_import.simhash_threshold,
+            self.config.duplicate_similarity,
+        )?;
+        let count = self.duplicate_groups.len();
+        if count == 0 {
+            return Ok(());
+        }
+
+        self.modal_popup = PopupState::default();
+        self.confirm_modal = Some(ConfirmModal {
+            title: "DUPLICATES".to_string(),
+            message: format!("{} likely duplicates found, review?", count),
+            details: Vec::new(),
+            action: ConfirmAction::ReviewDuplicates,
+        });
+        Ok(())
+    }
+
+    /// Mark the next pending duplicate group and show it in the marked-only view
+    fn review_next_duplicate_group(&mut self) -> Result<()> {
+        while !self.duplicate_groups.is_empty() {
+            let group = self.duplicate_groups.remove(0);
+            self.marked = group.into_iter().collect();
+            self.show_marked_only = true;
+            self.rebuild_marked_contacts()?;
+
+            // Skip groups that earlier merges have already collapsed
+            if self.contacts.len() < 2 {
+                continue;
+            }
+
+            self.focus_pane(PaneFocus::Search);
+            self.show_search = true;
+            self.search_focus = SearchFocus::Results;
+            self.reviewing_duplicates = true;
+            let remaining = self.duplicate_groups.len();
+            let skip = self
+                .config
+                .keys
+                .search_results
+                .skip_group
+                .first()
+                .cloned()
+                .unwrap_or_default();
+            self.set_status(format!(
+                "Reviewing {} possible duplicates ({} more groups, {} to skip)",
+                self.contacts.len(),
+                remaining,
+                skip
+            ));
+            return Ok(());
+        }
+
+        self.leave_marked_view();
+        self.marked.clear();
+        self.refresh_contacts()?;
+        self.set_status("No more duplicates to review");
+        Ok(())
+    }
+
+    /// Leave the marked-only view, ending the duplicate review shown in it
+    fn leave_marked_view(&mut self) {
+        self.show_marked_only = false;
+        self.reviewing_duplicates = false;
+        self.duplicate_groups.clear();
+    }
+
+    /// Delete the current contact file
+    fn delete_current_contact(&mut self) -> Result<()> {
         let Some(contact) = self.current_contact.take() else {
             self.set_status("No contact selected");
             return Ok(());
@@ -1091,10 +1886,15 @@ impl<'a> App<'a> {
         // Remove from database first
         self.db.delete_items_by_paths(std::iter::once(contact.path.clone()))?;
 
-        // Delete the file
+        // Delete the file, and the photo files only it refers to
         if contact.path.exists() {
+            let photos = vcard_io::parse_file(&contact.path, self.config.phone_options(), self.provider)
+                .map(|parsed| parsed.cards.iter().flat_map(vcard_io::managed_photo_names).collect::<Vec<_>>())
+                .unwrap_or_default();
+            vdir::backup_before_change(&contact.path, self.provider.edit_backup())?;
             std::fs::remove_file(&contact.path)
                 .with_context(|| format!("failed to delete {}", contact.path.display()))?;
+            vdir::remove_photo_files(&self.config.vdir, &photos, self.provider.encryption_type());
         }
 
         // Refresh contacts list
@@ -1119,7 +1919,7 @@ impl<'a> App<'a> {
         };
 
         // Parse the vCard file
-        let parsed = vcard_io::parse_file(&contact.path, self.config.phone_region.as_deref(), self.provider)?;
+        let parsed = vcard_io::parse_file(&contact.path, self.config.phone_options(), self.provider)?;
         let mut cards = parsed.cards;
         if cards.is_empty() {
             self.set_status("Contact has no cards");
@@ -1138,12 +1938,12 @@ impl<'a> App<'a> {
         }
 
         // Write back
-        vcard_io::write_cards(&contact.path, &cards, self.provider)?;
+        self.write_edited_cards(&contact.path, &mut cards)?;
 
         // Update database
         let card_clone = cards[0].clone();
         let state = vdir::compute_file_state(&contact.path)?;
-        let record = indexer::build_record(&contact.path, &card_clone, &state, None)?;
+        let record = indexer::build_record(&contact.path, &card_clone, &state, None, self.config.nameless_display)?;
         self.db.upsert(&record.item, &record.props)?;
 
         // Refresh UI
@@ -1152,6 +1952,164 @@ impl<'a> App<'a> {
         Ok(())
     }
 
+    /// Flip the contact's `X-RLDX-NOSYNC` flag
+    fn toggle_no_sync(&mut self) -> Result<()> {
+        let Some(contact) = &self.current_contact else {
+            self.set_status("No contact selected");
+            return Ok(());
+        };
+
+        let parsed =
+            vcard_io::parse_file(&contact.path, self.config.phone_options(), self.provider)?;
+        let mut cards = parsed.cards;
+        if cards.is_empty() {
+            self.set_status("Contact has no cards");
+            return Ok(());
+        }
+
+        let no_sync = !vcard_io::is_no_sync(&cards[0]);
+        vcard_io::set_no_sync(&mut cards[0], no_sync);
+
+        // Write back
+        self.write_edited_cards(&contact.path, &mut cards)?;
+
+        // Update database
+        let card_clone = cards[0].clone();
+        let state = vdir::compute_file_state(&contact.path)?;
+        let record = indexer::build_record(&contact.path, &card_clone, &state, None, self.config.nameless_display)?;
+        self.db.upsert(&record.item, &record.props)?;
+
+        // Refresh UI
+        self.refresh_contacts()?;
+        self.set_status(if no_sync {
+            "Contact will not be synced"
+        } else {
+            "Contact will be synced"
+        });
+        Ok(())
+    }
+
+    /// Normalize the current contact's emails per `email_case` and merge duplicates
+    fn clean_emails(&mut self) -> Result<()> {
+        let Some(contact) = &self.current_contact else {
+            self.set_status("No contact selected");
+            return Ok(());
+        };
+
+        let parsed = vcard_io::parse_file(&contact.path, self.config.phone_options(), self.provider)?;
+        let mut cards = parsed.cards;
+        if cards.is_empty() {
+            self.set_status("Contact has no cards");
+            return Ok(());
+        }
+
+        let before = cards[0].email.len();
+        if !vcard_io::dedupe_emails(&mut cards[0], self.config.email_case) {
+            self.set_status("Emails already clean");
+            return Ok(());
+        }
+        let removed = before - cards[0].email.len();
+
+        self.write_edited_cards(&contact.path, &mut cards)?;
+
+        let card_clone = cards[0].clone();
+        let state = vdir::compute_file_state(&contact.path)?;
+        let record = indexer::build_record(&contact.path, &card_clone, &state, None, self.config.nameless_display)?;
+        self.db.upsert(&record.item, &record.props)?;
+
+        self.refresh_contacts()?;
+        self.set_status(format!("Emails cleaned ({} duplicate(s) removed)", removed));
+        Ok(())
+    }
+
+    /// With `create_on_no_match`, turn a search that matched nothing into a
+    /// new contact named after it. Returns false when there was nothing to do.
+    fn create_from_search(&mut self) -> Result<bool> {
+        if !self.config.create_on_no_match || self.show_recent || !self.contacts.is_empty() {
+            return Ok(false);
+        }
+        let Some(name) = self.active_filter().map(str::to_string) else {
+            return Ok(false);
+        };
+
+        let (card, uuid) = vcard_io::new_card(&name, vcard4::property::Kind::Individual)?;
+        let book_dir = self.config.book_dir(None);
+        std::fs::create_dir_all(&book_dir)
+            .with_context(|| format!("failed to create address book {}", book_dir.display()))?;
+        let mut used = vdir::existing_stems(&book_dir)?;
+        let stem = vdir::select_filename(&uuid, &mut used, None);
+        let target = vdir::vcf_target_path(&book_dir, &stem, self.provider.encryption_type());
+        vcard_io::write_cards(&target, std::slice::from_ref(&card), self.provider)?;
+
+        let state = vdir::compute_file_state(&target)?;
+        let record = indexer::build_record(&target, &card, &state, None, self.config.nameless_display)?;
+        self.db.upsert(&record.item, &record.props)?;
+
+        self.show_search = false;
+        self.refresh_contacts()?;
+        if let Some(index) = self.find_contact(&uuid.to_string())? {
+            self.selected = index;
+            self.load_selection()?;
+        }
+        self.focus_pane(PaneFocus::Card);
+        self.set_status(format!("Created {}", name));
+        Ok(true)
+    }
+
+    /// Copy the current contact into a new file in the same address book and
+    /// select the copy, ready to edit its name when `duplicate_clear` blanked it
+    fn duplicate_current_contact(&mut self) -> Result<()> {
+        let Some(contact) = &self.current_contact else {
+            self.set_status("No contact selected");
+            return Ok(());
+        };
+
+        let parsed = vcard_io::parse_file(&contact.path, self.config.phone_options(), self.provider)?;
+        let Some(card) = parsed.cards.first() else {
+            self.set_status("Contact has no cards");
+            return Ok(());
+        };
+        let (mut copy, uuid) = vcard_io::duplicate_card(card, &self.config.duplicate_clear)?;
+        vcard_io::copy_managed_photos(&mut copy, &self.config.vdir, self.provider)?;
+
+        let book_dir = contact
+            .path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| self.config.vdir.clone());
+        let mut used = vdir::existing_stems(&book_dir)?;
+        let stem = vdir::select_filename(&uuid, &mut used, None);
+        let target = vdir::vcf_target_path(&book_dir, &stem, self.provider.encryption_type());
+        vcard_io::write_cards(&target, std::slice::from_ref(&copy), self.provider)?;
+
+        let state = vdir::compute_file_state(&target)?;
+        let record = indexer::build_record(&target, &copy, &state, None, self.config.nameless_display)?;
+        self.db.upsert(&record.item, &record.props)?;
+
+        // Select the copy, dropping a filter that would hide it
+        let uuid = uuid.to_string();
+        self.refresh_contacts()?;
+        let mut index = self.find_contact(&uuid)?;
+        if index.is_none() {
+            self.search_input.reset();
+            self.refresh_contacts()?;
+            index = self.find_contact(&uuid)?;
+        }
+        if let Some(index) = index {
+            self.selected = index;
+            self.load_selection()?;
+        }
+
+        self.focus_pane(PaneFocus::Card);
+        if self.config.duplicate_clear.contains(&ClearedField::Name) {
+            self.editor.start("", FieldRef::new("FN", 0));
+            self.set_status("Contact duplicated, enter the new name");
+        } else {
+            self.set_status("Contact duplicated");
+        }
+        Ok(())
+    }
+
     /// Delete the photo from the current contact
     fn delete_contact_photo(&mut self) -> Result<()> {
         let Some(contact) = &self.current_contact else {
@@ -1160,7 +2118,7 @@ impl<'a> App<'a> {
         };
 
         // Parse the vCard file
-        let parsed = vcard_io::parse_file(&contact.path, self.config.phone_region.as_deref(), self.provider)?;
+        let parsed = vcard_io::parse_file(&contact.path, self.config.phone_options(), self.provider)?;
         let mut cards = parsed.cards;
         if cards.is_empty() {
             self.set_status("Contact has no cards");
@@ -1168,18 +2126,20 @@ impl<'a> App<'a> {
         }
 
         // Delete the photo
+        let managed = vcard_io::managed_photo_names(&cards[0]);
         {
             let card = cards.get_mut(0).unwrap();
             vcard_io::delete_photo(card);
         }
 
-        // Write back
-        vcard_io::write_cards(&contact.path, &cards, self.provider)?;
+        // Write back, then drop the files the card no longer refers to
+        self.write_edited_cards(&contact.path, &mut cards)?;
+        vdir::remove_photo_files(&self.config.vdir, &managed, self.provider.encryption_type());
 
         // Update database
         let card_clone = cards[0].clone();
         let state = vdir::compute_file_state(&contact.path)?;
-        let record = indexer::build_record(&contact.path, &card_clone, &state, None)?;
+        let record = indexer::build_record(&contact.path, &card_clone, &state, None, self.config.nameless_display)?;
         self.db.upsert(&record.item, &record.props)?;
 
         // Refresh UI
@@ -1188,6 +2148,75 @@ impl<'a> App<'a> {
         Ok(())
     }
 
+    /// Show the next (or previous) embedded photo of the current contact,
+    /// skipping PHOTO values that are links or fail to decode
+    fn cycle_photo(&mut self, delta: isize) {
+        let photos: Vec<&PropRow> = self
+            .current_props
+            .iter()
+            .filter(|prop| prop.field == "PHOTO")
+            .collect();
+        if photos.len() < 2 {
+            self.set_status(if photos.is_empty() { "No photos" } else { "Contact has one photo" });
+            return;
+        }
+
+        let len = photos.len() as isize;
+        let current = photos
+            .iter()
+            .position(|prop| Some(prop.seq) == self.photo_seq)
+            .unwrap_or(0) as isize;
+        for step in 1..len {
+            let index = (current + delta * step).rem_euclid(len) as usize;
+            if let Ok(Some(photo)) = decode_photo_prop(photos[index], &self.config.vdir, self.provider) {
+                let seq = photos[index].seq;
+                self.photo_error = None;
+                self.photo_seq = Some(seq);
+                self.set_photo(Some(photo));
+                self.set_status(format!("Photo {} of {}", index + 1, len));
+                return;
+            }
+        }
+        self.set_status("No other embedded photo");
+    }
+
+    /// Move the photo shown in the image pane to the front of the PHOTO list
+    /// and give it `PREF=1`
+    fn make_photo_primary(&mut self) -> Result<()> {
+        let Some(contact) = &self.current_contact else {
+            self.set_status("No contact selected");
+            return Ok(());
+        };
+        let Some(seq) = self.photo_seq.and_then(|seq| usize::try_from(seq).ok()) else {
+            self.set_status("No photo shown");
+            return Ok(());
+        };
+
+        let parsed =
+            vcard_io::parse_file(&contact.path, self.config.phone_options(), self.provider)?;
+        let mut cards = parsed.cards;
+        if cards.is_empty() {
+            self.set_status("Contact has no cards");
+            return Ok(());
+        }
+        if !vcard_io::promote_photo_entry(&mut cards[0], seq) {
+            self.set_status("Photo no longer present");
+            return Ok(());
+        }
+        vcard_io::prefer_first_photo(&mut cards[0]);
+
+        self.write_edited_cards(&contact.path, &mut cards)?;
+
+        let card_clone = cards[0].clone();
+        let state = vdir::compute_file_state(&contact.path)?;
+        let record = indexer::build_record(&contact.path, &card_clone, &state, None, self.config.nameless_display)?;
+        self.db.upsert(&record.item, &record.props)?;
+
+        self.refresh_contacts()?;
+        self.set_status("Photo set as primary");
+        Ok(())
+    }
+
     /// Handle keys for photo path modal
     fn handle_photo_path_modal_key(&mut self, key: KeyEvent) -> Result<()> {
         let modal_keys = &self.config.keys.modal;
@@ -1212,9 +2241,9 @@ impl<'a> App<'a> {
             }
 
             // Expand ~ to home directory
-            let expanded_path = if path.starts_with("~/") {
+            let expanded_path = if let Some(rest) = path.strip_prefix("~/") {
                 if let Some(home) = home::home_dir() {
-                    home.join(&path[2..])
+                    home.join(rest)
                 } else {
                     std::path::PathBuf::from(&path)
                 }
@@ -1235,10 +2264,6 @@ impl<'a> App<'a> {
 
     /// Load image from path, resize to max 128x128, and set as contact photo
     fn set_contact_photo_from_path(&mut self, path: &Path) -> Result<()> {
-        use base64::Engine;
-        use image::GenericImageView;
-        use image::imageops::FilterType;
-
         let Some(contact) = &self.current_contact else {
             self.set_status("No contact selected");
             return Ok(());
@@ -1250,66 +2275,50 @@ impl<'a> App<'a> {
             return Ok(());
         }
 
-        // Load the image
-        let img = match image::open(path) {
-            Ok(img) => img,
+        // Load, resize and encode the image
+        let jpeg = match vcard_io::photo_jpeg_from_path(path) {
+            Ok(jpeg) => jpeg,
             Err(e) => {
-                self.set_status(format!("Failed to load image: {}", e));
+                self.set_status(format!("Failed to load image: {:#}", e));
                 return Ok(());
             }
         };
 
-        // Resize to max 128x128 preserving aspect ratio
-        let (width, height) = img.dimensions();
-        let max_dim = 128u32;
-        let (new_width, new_height) = if width > max_dim || height > max_dim {
-            let ratio = f64::min(max_dim as f64 / width as f64, max_dim as f64 / height as f64);
-            let new_w = (width as f64 * ratio).round() as u32;
-            let new_h = (height as f64 * ratio).round() as u32;
-            (new_w.max(1), new_h.max(1))
-        } else {
-            (width, height)
-        };
-
-        let resized = img.resize_exact(new_width, new_height, FilterType::Lanczos3);
-
-        // Encode as JPEG
-        let mut jpeg_data = Vec::new();
-        {
-            let mut cursor = std::io::Cursor::new(&mut jpeg_data);
-            resized.write_to(&mut cursor, image::ImageFormat::Jpeg)
-                .with_context(|| "failed to encode image as JPEG")?;
-        }
-
-        // Create data URI
-        let base64_data = BASE64_STANDARD.encode(&jpeg_data);
-        let data_uri = format!("data:image/jpeg;base64,{}", base64_data);
-
         // Parse and update the vCard
-        let parsed = vcard_io::parse_file(&contact.path, self.config.phone_region.as_deref(), self.provider)?;
+        let parsed =
+            vcard_io::parse_file(&contact.path, self.config.phone_options(), self.provider)?;
         let mut cards = parsed.cards;
         if cards.is_empty() {
             self.set_status("Contact has no cards");
             return Ok(());
         }
 
-        {
-            let card = cards.get_mut(0).unwrap();
-            vcard_io::set_photo(card, &data_uri);
-        }
+        let (placement, stale) = vcard_io::store_photo(
+            cards.get_mut(0).unwrap(),
+            &jpeg,
+            &self.config.vdir,
+            self.config.max_embedded_photo_bytes,
+            self.provider,
+        )?;
 
-        // Write back
-        vcard_io::write_cards(&contact.path, &cards, self.provider)?;
+        // Write back, then drop the files the card no longer refers to
+        self.write_edited_cards(&contact.path, &mut cards)?;
+        vdir::remove_photo_files(&self.config.vdir, &stale, self.provider.encryption_type());
 
         // Update database
         let card_clone = cards[0].clone();
         let state = vdir::compute_file_state(&contact.path)?;
-        let record = indexer::build_record(&contact.path, &card_clone, &state, None)?;
+        let record = indexer::build_record(&contact.path, &card_clone, &state, None, self.config.nameless_display)?;
         self.db.upsert(&record.item, &record.props)?;
 
         // Refresh UI
         self.refresh_contacts()?;
-        self.set_status("Photo updated");
+        match placement {
+            vcard_io::PhotoPlacement::Embedded => self.set_status("Photo updated"),
+            vcard_io::PhotoPlacement::External(path) => {
+                self.set_status(format!("Photo updated (stored externally in {})", path.display()))
+            }
+        }
         Ok(())
     }
 
@@ -1332,8 +2341,9 @@ impl<'a> App<'a> {
             if let Some(target) = self.editor.target().cloned() {
                 let value = self.editor.value().to_string();
                 self.editor.cancel();
+                let status = self.field_updated_status(&target, &value);
                 self.commit_field_edit(target, value)?;
-                self.set_status("Field updated");
+                self.set_status(status);
             } else {
                 self.editor.cancel();
                 self.set_status("Field not editable");
@@ -1366,12 +2376,13 @@ impl<'a> App<'a> {
                 if let Some(target) = self.editor.target().cloned() {
                     let value = self.editor.value().to_string();
                     self.editor.cancel();
+                    let status = self.field_updated_status(&target, &value);
                     self.commit_field_edit(target.clone(), value)?;
                     // Keep the modal open and rebuild it for the same field, keep selection
                     if let Some(field) = MultiValueField::from_field_name(&target.field) {
                         self.rebuild_multivalue_modal(field, Some(target.seq));
                     }
-                    self.set_status("Field updated");
+                    self.set_status(status);
                     return Ok(());
                 }
             }
@@ -1391,6 +2402,9 @@ impl<'a> App<'a> {
         // Modal: edit selected item
         if self.key_matches_any(&key, &modal_keys.edit) {
             if let Some((field, item)) = self.current_modal_selection() {
+                if field == MultiValueField::Address {
+                    return self.open_address_editor(item.seq, Some(field));
+                }
                 let target = FieldRef::new(field.field_name(), item.seq);
                 self.editor.start(&item.value, target);
                 self.set_status(format!("Editing {}", field.field_name()));
@@ -1398,6 +2412,16 @@ impl<'a> App<'a> {
             return Ok(());
         }
 
+        // Modal: edit the selected item's custom label
+        if self.key_matches_any(&key, &modal_keys.edit_label) {
+            if let Some((field, item)) = self.current_modal_selection() {
+                if field.has_type_label() {
+                    self.begin_label_edit(field.field_name(), item.seq);
+                }
+            }
+            return Ok(());
+        }
+
         // Modal: next/prev selection
         if self.key_matches_any(&key, &modal_keys.next) {
             if let Some(modal) = self.multivalue_modal.as_mut() {
@@ -1415,24 +2439,34 @@ impl<'a> App<'a> {
         // Modal: set default (only for fields that support it)
         if self.key_matches_any(&key, &modal_keys.set_default) {
             if let Some((field, item)) = self.current_modal_selection() {
-                if field.has_default() {
-                    if self.set_multivalue_default(field, item.seq)? {
-                        self.rebuild_multivalue_modal(field, None);
-                        self.set_status("Default updated");
-                    }
+                if field.has_default() && self.set_multivalue_default(field, item.seq)? {
+                    self.rebuild_multivalue_modal(field, None);
+                    self.set_status("Default updated");
                 }
             }
             return Ok(());
         }
 
+        // Modal: move the selected item within the field's order
+        for (bindings, delta) in [(&modal_keys.move_up, -1), (&modal_keys.move_down, 1)] {
+            if self.key_matches_any(&key, bindings) {
+                if let Some((field, item)) = self.current_modal_selection() {
+                    if field.has_default() {
+                        if let Some(seq) = self.move_multivalue_entry(field, item.seq, delta)? {
+                            self.rebuild_multivalue_modal(field, Some(seq));
+                        }
+                    }
+                }
+                return Ok(());
+            }
+        }
+
         // Modal: delete (only for Alias currently)
         if self.key_matches_any(&key, &modal_keys.delete) {
             if let Some((field, item)) = self.current_modal_selection() {
-                if field == MultiValueField::Alias {
-                    if self.delete_alias_entry(item.seq)? {
-                        self.rebuild_multivalue_modal(field, None);
-                        self.set_status("Alias deleted");
-                    }
+                if field == MultiValueField::Alias && self.delete_alias_entry(item.seq)? {
+                    self.rebuild_multivalue_modal(field, None);
+                    self.set_status("Alias deleted");
                 }
             }
             return Ok(());
@@ -1462,14 +2496,29 @@ impl<'a> App<'a> {
 
         // Modal: copy and close
         if self.key_matches_any(&key, &modal_keys.copy) {
-            if let Some((_, item)) = self.current_modal_selection() {
-                self.copy_value_to_clipboard(&item.copy_value)?;
+            if let Some((field, item)) = self.current_modal_selection() {
+                let value = self.copy_transformed(field.field_name(), &item.copy_value);
+                self.copy_value_to_clipboard(&value)?;
                 self.close_multivalue_modal();
             }
             return Ok(());
         }
 
-        // Modal: confirm (sets default for EMAIL/PHONE, closes for ALIAS)
+        // Modal: copy as URI and close
+        if self.key_matches_any(&key, &modal_keys.copy_uri) {
+            if let Some((field, item)) = self.current_modal_selection() {
+                match self.copy_uri_for(field.field_name(), &item.copy_value) {
+                    Some(uri) => {
+                        self.copy_value_to_clipboard(&uri)?;
+                        self.close_multivalue_modal();
+                    }
+                    None => self.set_status("No URI form for this field"),
+                }
+            }
+            return Ok(());
+        }
+
+        // Modal: confirm (sets default for EMAIL/PHONE/ADR, closes for ALIAS)
         if self.key_matches_any(&key, &modal_keys.confirm) {
             if let Some((field, item)) = self.current_modal_selection() {
                 if field.has_default() {
@@ -1488,6 +2537,166 @@ impl<'a> App<'a> {
         Ok(())
     }
 
+    fn open_split_name_modal(&mut self) {
+        if self.current_contact.is_none() {
+            self.set_status("No contact selected");
+            return;
+        }
+        if props_is_org(&self.current_props) {
+            self.set_status("Organizations have no name components");
+            return;
+        }
+        let has_components = self
+            .current_props
+            .iter()
+            .filter(|p| p.field == "N")
+            .any(|p| name_components(&p.value).iter().any(|c| !c.is_empty()));
+        if has_components {
+            self.set_status("Name components already set");
+            return;
+        }
+        let formatted = self
+            .current_props
+            .iter()
+            .find(|p| p.field == "FN")
+            .map(|p| p.value.trim().to_string())
+            .unwrap_or_default();
+        if formatted.is_empty() {
+            self.set_status("Contact has no FN to split");
+            return;
+        }
+
+        let proposed = vcard_io::split_formatted_name(&formatted).join("; ");
+        self.modal_popup = PopupState::default();
+        self.component_modal = Some(ComponentEditModal {
+            title: "SPLIT NAME",
+            hint: NAME_COMPONENTS_HINT,
+            target: FieldRef::new("N", 0),
+            max_components: 5,
+            return_to: None,
+            input: Input::new(proposed),
+            label: None,
+            label_focused: false,
+        });
+        self.set_status("Review name components");
+    }
+
+    /// Open the structured editor for the ADR entry at `seq`
+    fn open_address_editor(&mut self, seq: i64, return_to: Option<MultiValueField>) -> Result<()> {
+        let Some(contact) = &self.current_contact else {
+            self.set_status("No contact selected");
+            return Ok(());
+        };
+
+        let parsed = vcard_io::parse_file(&contact.path, self.config.phone_options(), self.provider)?;
+        let Some(card) = parsed.cards.first() else {
+            self.set_status("Address not found");
+            return Ok(());
+        };
+        let Some(components) = vcard_io::address_components(card, seq) else {
+            self.set_status("Address not found");
+            return Ok(());
+        };
+        let label = vcard_io::adr_label(card, seq)
+            .map(|label| label.lines().collect::<Vec<_>>().join(" | "))
+            .unwrap_or_default();
+
+        self.multivalue_modal = None;
+        self.modal_popup = PopupState::default();
+        self.component_modal = Some(ComponentEditModal {
+            title: "EDIT ADDRESS",
+            hint: ADDRESS_COMPONENTS_HINT,
+            target: FieldRef::new("ADR", seq),
+            max_components: vcard_io::ADR_COMPONENTS,
+            return_to,
+            input: Input::new(components.join("; ")),
+            label: Some(Input::new(label)),
+            label_focused: false,
+        });
+        self.set_status("Editing ADR");
+        Ok(())
+    }
+
+    fn handle_component_modal_key(&mut self, key: KeyEvent) -> Result<()> {
+        let modal_keys = &self.config.keys.modal;
+
+        // Cancel: close modal without writing anything
+        if self.key_matches_any(&key, &modal_keys.cancel) {
+            if let Some(field) = self.component_modal.take().and_then(|m| m.return_to) {
+                self.rebuild_multivalue_modal(field, None);
+            }
+            return Ok(());
+        }
+
+        // Confirm: write each component through the component update path
+        if self.key_matches_any(&key, &modal_keys.confirm) {
+            let Some(modal) = self.component_modal.take() else {
+                return Ok(());
+            };
+            let components: Vec<String> = modal
+                .input
+                .value()
+                .split(';')
+                .map(|c| c.trim().to_string())
+                .collect();
+            if components.len() > modal.max_components {
+                self.set_status(format!(
+                    "Too many components (expected {})",
+                    modal.max_components
+                ));
+            } else if components.iter().all(|c| c.is_empty()) {
+                self.set_status("Nothing to save");
+            } else {
+                let target = &modal.target;
+                let mut edits: Vec<_> = components
+                    .into_iter()
+                    .enumerate()
+                    .map(|(idx, component)| {
+                        (FieldRef::with_component(target.field.clone(), target.seq, idx), component)
+                    })
+                    .collect();
+                // After the components, which drop any LABEL they no longer match
+                if let Some(label) = &modal.label {
+                    let lines = label.value().split('|').collect::<Vec<_>>().join("\n");
+                    edits.push((
+                        FieldRef::with_component(
+                            target.field.clone(),
+                            target.seq,
+                            vcard_io::ADR_LABEL_COMPONENT,
+                        ),
+                        lines,
+                    ));
+                }
+                self.commit_field_edits(edits)?;
+                self.set_status(format!("{} updated", target.field));
+            }
+            if let Some(field) = modal.return_to {
+                self.rebuild_multivalue_modal(field, Some(modal.target.seq));
+            }
+            return Ok(());
+        }
+
+        // Switch between the components and the LABEL line (ADR only)
+        let switch = self.key_matches_any(&key, &self.config.keys.search_input.next)
+            || self.key_matches_any(&key, &self.config.keys.search_input.prev);
+        if let Some(modal) = self.component_modal.as_mut() {
+            if switch && modal.label.is_some() {
+                modal.label_focused = !modal.label_focused;
+                return Ok(());
+            }
+        }
+
+        // Route other keys to inline input
+        if let Some(modal) = self.component_modal.as_mut() {
+            let input = match modal.label.as_mut() {
+                Some(label) if modal.label_focused => label,
+                _ => &mut modal.input,
+            };
+            let _ = input.handle_event(&Event::Key(key));
+        }
+        Ok(())
+    }
+
     fn handle_alias_modal_key(&mut self, key: KeyEvent) -> Result<()> {
         let modal_keys = &self.config.keys.modal;
 
@@ -1692,7 +2901,8 @@ impl<'a> App<'a> {
         let type_param = modal.current_type().map(|s| s.to_string());
 
         // Parse and update the vCard
-        let parsed = vcard_io::parse_file(&contact.path, self.config.phone_region.as_deref(), self.provider)?;
+        let parsed =
+            vcard_io::parse_file(&contact.path, self.config.phone_options(), self.provider)?;
         let mut cards = parsed.cards;
         if cards.is_empty() {
             self.set_status("Contact has no cards");
@@ -1709,17 +2919,27 @@ impl<'a> App<'a> {
         }
 
         // Write back
-        vcard_io::write_cards(&contact.path, &cards, self.provider)?;
+        self.write_edited_cards(&contact.path, &mut cards)?;
 
         // Update database
         let card_clone = cards[0].clone();
         let state = vdir::compute_file_state(&contact.path)?;
-        let record = indexer::build_record(&contact.path, &card_clone, &state, None)?;
+        let record = indexer::build_record(&contact.path, &card_clone, &state, None, self.config.nameless_display)?;
         self.db.upsert(&record.item, &record.props)?;
 
         // Refresh UI
         self.refresh_contacts()?;
-        self.set_status(format!("{} added", field_name));
+        if field_name.eq_ignore_ascii_case("EMAIL")
+            && !vcard_io::email_looks_valid(&vcard_io::clean_email(&value))
+        {
+            self.set_status(format!("{} added (email looks invalid)", field_name));
+        } else if field_name.eq_ignore_ascii_case("URL")
+            && !vcard_io::url_looks_valid(&vcard_io::clean_url(&value))
+        {
+            self.set_status(format!("{} added (url looks invalid)", field_name));
+        } else {
+            self.set_status(format!("{} added", field_name));
+        }
 
         Ok(())
     }
@@ -1756,6 +2976,9 @@ impl<'a> App<'a> {
                 ConfirmAction::DeletePhoto => {
                     self.delete_contact_photo()?;
                 }
+                ConfirmAction::ReviewDuplicates => {
+                    self.review_next_duplicate_group()?;
+                }
             }
             return Ok(());
         }
@@ -1811,6 +3034,15 @@ impl<'a> App<'a> {
         true
     }
 
+    /// Whether the focused field is an email, phone or address, the kinds
+    /// confirm opens a list for once there are several values.
+    fn focused_single_value(&self) -> bool {
+        self.focused_field()
+            .and_then(|field| field.source())
+            .and_then(|source| MultiValueField::from_field_name(&source.field))
+            .is_some_and(|kind| kind != MultiValueField::Alias)
+    }
+
     fn rebuild_multivalue_modal(&mut self, field: MultiValueField, selected_seq: Option<i64>) {
         let items = self.build_multivalue_items(field);
         if items.is_empty() {
@@ -1826,12 +3058,18 @@ impl<'a> App<'a> {
     }
 
     fn build_multivalue_items(&self, field: MultiValueField) -> Vec<MultiValueItem> {
-        let default_region = self.config.phone_region.as_deref();
+        let phone = self.config.phone_options();
         let field_name = field.field_name();
-        let mut items: Vec<MultiValueItem> = self
+        let mut props: Vec<&PropRow> = self
             .current_props
             .iter()
             .filter(|prop| prop.field.eq_ignore_ascii_case(field_name))
+            .collect();
+        props.sort_by_key(|prop| prop.seq);
+        sort_multivalue_props(&mut props, self.config.multivalue_sort);
+
+        props
+            .into_iter()
             .map(|prop| {
                 let type_label = if field.has_type_label() {
                     extract_type_labels(&prop.params).unwrap_or_else(|| "—".to_string())
@@ -1844,9 +3082,13 @@ impl<'a> App<'a> {
                         (trimmed.clone(), trimmed)
                     }
                     MultiValueField::Phone => {
-                        let display = vcard_io::phone_display_value(&prop.value, default_region);
+                        let display = vcard_io::phone_display_value(&prop.value, phone);
                         (display.clone(), display)
                     }
+                    MultiValueField::Address => {
+                        let formatted = format_address_value(prop);
+                        (formatted.clone(), formatted)
+                    }
                     MultiValueField::Alias => {
                         let trimmed = prop.value.trim().to_string();
                         (trimmed.clone(), trimmed)
@@ -1859,26 +3101,79 @@ impl<'a> App<'a> {
                     type_label,
                 }
             })
-            .collect();
+            .collect()
+    }
+
+    /// The search text still narrowing the contact list, if any
+    pub fn active_filter(&self) -> Option<&str> {
+        Some(self.search_input.value().trim()).filter(|value| !value.is_empty())
+    }
+
+    fn clear_filter(&mut self) -> Result<()> {
+        if self.active_filter().is_none() {
+            self.set_status("No active filter");
+            return Ok(());
+        }
+        self.search_input.reset();
+        self.refresh_contacts()?;
+        self.set_status("Filter cleared");
+        Ok(())
+    }
+
+    /// Set the active filter aside to show every contact, or restore the
+    /// filter and the contact that was selected under it
+    fn toggle_filter(&mut self) -> Result<()> {
+        if let Some(filter) = self.active_filter() {
+            let filter = filter.to_string();
+            let uuid = self.contacts.get(self.selected).map(|entry| entry.uuid.clone());
+            self.stashed_filter = Some((filter, uuid));
+            self.search_input.reset();
+            self.refresh_contacts()?;
+            self.set_status("Showing all contacts");
+            return Ok(());
+        }
 
-        items.sort_by_key(|item| item.seq);
-        items
+        let Some((filter, uuid)) = self.stashed_filter.take() else {
+            self.set_status("No active filter");
+            return Ok(());
+        };
+        self.search_input = Input::new(filter.clone());
+        self.refresh_contacts()?;
+        let index = match uuid {
+            Some(uuid) => self.find_contact(&uuid)?,
+            None => None,
+        };
+        if let Some(index) = index {
+            if index != self.selected {
+                self.selected = index;
+                self.load_selection()?;
+            }
+        }
+        self.set_status(format!("Filter restored: {}", filter));
+        Ok(())
     }
 
     fn refresh_contacts(&mut self) -> Result<()> {
+        self.pending_filter = false;
         let previous_uuid = self
             .contacts
             .get(self.selected)
             .map(|entry| entry.uuid.clone());
 
-        let normalized = search::normalize_query(self.search_input.value());
-        self.contacts = if let Some(filter) = normalized.as_ref() {
-            self.db.list_contacts(Some(filter))?
+        if self.show_recent {
+            self.contacts = self.db.list_recent_contacts(RECENT_LIMIT)?;
+            self.more_contacts = false;
         } else {
-            self.db.list_contacts(None)?
-        };
-
-        self.sort_contacts();
+            // Load as many pages as it takes to keep the selected contact
+            let filter = search::normalize_query(self.search_input.value());
+            let position = match previous_uuid.as_deref() {
+                Some(uuid) => self.db.contact_position(filter.as_deref(), uuid)?,
+                None => None,
+            };
+            let rows = position.map_or(CONTACT_PAGE, |index| (index + 1).next_multiple_of(CONTACT_PAGE));
+            self.contacts = self.db.list_contacts(filter.as_deref(), 0, Some(rows))?;
+            self.more_contacts = self.contacts.len() == rows;
+        }
 
         if let Some(uuid) = previous_uuid {
             if let Some(index) = self.contacts.iter().position(|entry| entry.uuid == uuid) {
@@ -1915,9 +3210,24 @@ impl<'a> App<'a> {
         }
 
         let mut last_chain: Vec<String> = Vec::new();
+        // Collapsed book being folded and the row of its header
+        let mut folding: Option<(Vec<String>, usize)> = None;
 
         for (index, contact) in self.contacts.iter().enumerate() {
-            let chain = self.address_book_chain(&contact.path);
+            // The recent view is ordered by REV, so book headings would repeat
+            let chain = if self.show_recent {
+                Vec::new()
+            } else {
+                self.address_book_chain(&contact.path)
+            };
+
+            if let Some((prefix, row)) = &folding {
+                if chain.starts_with(prefix) {
+                    self.search_rows[*row].folded += 1;
+                    continue;
+                }
+                folding = None;
+            }
 
             let mut shared_prefix = 0;
             while shared_prefix < chain.len()
@@ -1927,39 +3237,106 @@ impl<'a> App<'a> {
                 shared_prefix += 1;
             }
 
-            for level in shared_prefix..chain.len() {
-                let name = &chain[level];
-                let text = format!("{}{}", self.config.ui.icons.address_book, name);
+            let collapsed_level = (0..chain.len())
+                .find(|&level| self.collapsed_books.contains(&book_key(&chain[..=level])));
+            let header_end = collapsed_level.map_or(chain.len(), |level| level + 1);
+
+            for (level, name) in chain.iter().enumerate().take(header_end).skip(shared_prefix) {
+                let icon = &self.config.ui.icons.address_book;
+                let text = format!("{}{}", icon, name);
                 self.search_rows.push(SearchRow {
                     text,
                     depth: level as u16,
                     contact_index: None,
+                    name_start: icon.len(),
+                    incomplete: false,
+                    folded: 0,
                 });
             }
 
+            if let Some(level) = collapsed_level {
+                // The header stands in for the whole book
+                let row = self.search_rows.len() - 1;
+                self.search_rows[row].contact_index = Some(index);
+                self.search_rows[row].folded = 1;
+                last_chain = chain[..=level].to_vec();
+                folding = Some((last_chain.clone(), row));
+                continue;
+            }
+
             let depth = chain.len() as u16;
             last_chain = chain.clone();
 
-            let text = if self.marked.contains(&contact.uuid) {
-                format!("★ {}", contact.display_fn.to_uppercase())
+            let icon = if contact_is_org(contact) {
+                &self.config.ui.icons.organization
             } else {
-                let icon = if contact_is_org(contact) {
-                    &self.config.ui.icons.organization
-                } else {
-                    &self.config.ui.icons.contact
-                };
-                format!("{}{}", icon, contact.display_fn.to_uppercase())
+                &self.config.ui.icons.contact
+            };
+            // Keep marked rows aligned with unmarked ones, whatever the icon width
+            let prefix = if self.marked.contains(&contact.uuid) {
+                text::pad_to_width("★", text::display_width(icon).max(2))
+            } else {
+                icon.clone()
             };
+            let text = format!("{}{}", prefix, contact.display_fn.to_uppercase());
             self.search_rows.push(SearchRow {
                 text,
                 depth,
                 contact_index: Some(index),
+                name_start: prefix.len(),
+                incomplete: self.is_incomplete(contact),
+                folded: 0,
             });
         }
 
+        for row in self.search_rows.iter_mut().filter(|row| row.folded > 0) {
+            row.text.push_str(&format!(" ({})", row.folded));
+        }
+
         self.update_selected_row();
     }
 
+    /// Collapse the address book of the selected contact, or expand the
+    /// collapsed book whose header is selected
+    fn toggle_book(&mut self) -> Result<()> {
+        if self.show_recent {
+            return Ok(());
+        }
+        let Some(row) = self.selected_row.and_then(|idx| self.search_rows.get(idx)) else {
+            return Ok(());
+        };
+        let Some(contact) = row.contact_index.and_then(|idx| self.contacts.get(idx)) else {
+            return Ok(());
+        };
+        let chain = self.address_book_chain(&contact.path);
+        if row.folded > 0 {
+            if let Some(key) = (1..=chain.len())
+                .map(|len| book_key(&chain[..len]))
+                .find(|key| self.collapsed_books.contains(key))
+            {
+                self.collapsed_books.remove(&key);
+            }
+        } else {
+            self.collapsed_books.insert(book_key(&chain));
+        }
+        self.rebuild_search_rows();
+        self.load_selection()
+    }
+
+    /// The selected row is a collapsed book header rather than a contact
+    fn on_folded_book(&self) -> bool {
+        self.selected_row
+            .and_then(|idx| self.search_rows.get(idx))
+            .is_some_and(|row| row.folded > 0)
+    }
+
+    fn is_incomplete(&self, contact: &ContactListEntry) -> bool {
+        self.config.flag_incomplete.iter().any(|field| match field {
+            ExpectedField::Email => !contact.has_email,
+            ExpectedField::Phone => !contact.has_phone,
+        })
+    }
+
     fn address_book_chain(&self, path: &Path) -> Vec<String> {
         address_book_chain_from(&self.config.vdir, path)
     }
@@ -1974,12 +3351,16 @@ impl<'a> App<'a> {
             self.selected = self.contacts.len() - 1;
         }
 
-        if let Some((idx, _)) = self
+        // A contact in a collapsed book is represented by the book header
+        if let Some((idx, contact_index)) = self
             .search_rows
             .iter()
             .enumerate()
-            .find(|(_, row)| row.contact_index == Some(self.selected))
+            .filter_map(|(idx, row)| row.contact_index.map(|ci| (idx, ci, row.folded.max(1))))
+            .find(|&(_, ci, count)| (ci..ci + count).contains(&self.selected))
+            .map(|(idx, ci, _)| (idx, ci))
         {
+            self.selected = contact_index;
             self.selected_row = Some(idx);
             return;
         }
@@ -1998,34 +3379,151 @@ impl<'a> App<'a> {
         }
     }
 
-    fn sort_contacts(&mut self) {
-        self.contacts.sort_by_cached_key(|entry| {
-            let book = address_book_chain_from(&self.config.vdir, &entry.path)
-                .join("/")
-                .to_ascii_lowercase();
-            let name = entry.display_fn.to_ascii_lowercase();
-            (book, name)
-        });
+    /// Load the next `count` contacts of the list, or all that are left
+    fn load_more_contacts(&mut self, count: Option<usize>) -> Result<()> {
+        if !self.more_contacts {
+            return Ok(());
+        }
+        let filter = search::normalize_query(self.search_input.value());
+        let page = self
+            .db
+            .list_contacts(filter.as_deref(), self.contacts.len(), count)?;
+        self.more_contacts = count.is_some_and(|count| page.len() == count);
+        self.contacts.extend(page);
+        self.rebuild_search_rows();
+        Ok(())
+    }
+
+    /// Index of contact `uuid` in the list, loading the pages up to it
+    fn find_contact(&mut self, uuid: &str) -> Result<Option<usize>> {
+        if let Some(index) = self.contacts.iter().position(|entry| entry.uuid == uuid) {
+            return Ok(Some(index));
+        }
+        if !self.more_contacts {
+            return Ok(None);
+        }
+        let filter = search::normalize_query(self.search_input.value());
+        let Some(index) = self.db.contact_position(filter.as_deref(), uuid)? else {
+            return Ok(None);
+        };
+        let missing = (index + 1).saturating_sub(self.contacts.len());
+        if missing > 0 {
+            self.load_more_contacts(Some(missing.next_multiple_of(CONTACT_PAGE)))?;
+        }
+        Ok(self
+            .contacts
+            .get(index)
+            .filter(|entry| entry.uuid == uuid)
+            .map(|_| index))
+    }
+
+    /// Move the selected contact `delta` places within its address book and
+    /// store the resulting order in `X-RLDX-SORT`
+    fn move_contact(&mut self, delta: isize) -> Result<()> {
+        if self.show_recent {
+            self.set_status("Contacts are reordered in the address book list");
+            return Ok(());
+        }
+        if self.on_folded_book() {
+            self.set_status("Expand the book to reorder its contacts");
+            return Ok(());
+        }
+        let Some(selected) = self.contacts.get(self.selected) else {
+            return Ok(());
+        };
+        let uuid = selected.uuid.clone();
+        let chain = self.address_book_chain(&selected.path);
+
+        // The whole book, so contacts hidden by a search keep their places
+        let vdir = &self.config.vdir;
+        let mut book: Vec<ContactListEntry> = self
+            .db
+            .list_contacts(None, 0, None)?
+            .into_iter()
+            .filter(|entry| address_book_chain_from(vdir, &entry.path) == chain)
+            .collect();
+        let Some(from) = book.iter().position(|entry| entry.uuid == uuid) else {
+            return Ok(());
+        };
+        let to = (from as isize + delta).clamp(0, book.len() as isize - 1) as usize;
+        if to == from {
+            self.set_status(if delta < 0 {
+                "Already first in the book"
+            } else {
+                "Already last in the book"
+            });
+            return Ok(());
+        }
+        let entry = book.remove(from);
+        book.insert(to, entry);
+
+        // Pin everything up to the moved contact and any pinned ones after it;
+        // the rest keep sorting by name behind them
+        let pinned = book
+            .iter()
+            .rposition(|entry| entry.sort_position.is_some())
+            .map_or(0, |index| index + 1)
+            .max(to + 1);
+        for (index, entry) in book.iter().take(pinned).enumerate() {
+            let position = index as i64 + 1;
+            if entry.sort_position == Some(position) {
+                continue;
+            }
+            let parsed = vcard_io::parse_file(&entry.path, self.config.phone_options(), self.provider)?;
+            let mut cards = parsed.cards;
+            if cards.is_empty() {
+                continue;
+            }
+            vcard_io::set_sort_position(&mut cards[0], Some(position));
+            self.write_edited_cards(&entry.path, &mut cards)?;
+
+            let state = vdir::compute_file_state(&entry.path)?;
+            let record = indexer::build_record(&entry.path, &cards[0], &state, None, self.config.nameless_display)?;
+            self.db.upsert(&record.item, &record.props)?;
+        }
+
+        self.refresh_contacts()?;
+        self.set_status(format!("Moved to position {} in the book", to + 1));
+        Ok(())
     }
 
     fn move_selection(&mut self, delta: isize) -> Result<()> {
         if self.contacts.is_empty() {
             return Ok(());
         }
-        let len = self.contacts.len() as isize;
-        let mut index = self.selected as isize + delta;
-        if index < 0 {
-            index = 0;
-        } else if index >= len {
-            index = len - 1;
-        }
-        self.selected = index as usize;
+        let (selectable, index) = loop {
+            // Step over rows, so a collapsed book counts once
+            let selectable: Vec<usize> = self
+                .search_rows
+                .iter()
+                .filter_map(|row| row.contact_index)
+                .collect();
+            if selectable.is_empty() {
+                return Ok(());
+            }
+            let current = selectable
+                .iter()
+                .position(|&index| index == self.selected)
+                .unwrap_or(0) as isize;
+            if self.more_contacts && current + delta + LOAD_AHEAD as isize >= selectable.len() as isize {
+                self.load_more_contacts(Some(CONTACT_PAGE))?;
+                continue;
+            }
+            let index = (current + delta).clamp(0, selectable.len() as isize - 1);
+            break (selectable, index);
+        };
+        self.selected = selectable[index as usize];
         self.load_selection()?;
         Ok(())
     }
 
     fn load_selection(&mut self) -> Result<()> {
-        if self.contacts.is_empty() {
+        if !self.contacts.is_empty() {
+            self.update_selected_row();
+        }
+        // A collapsed book header stands for several contacts, so it loads
+        // none of them and edits, deletes and marks have nothing to act on
+        if self.contacts.is_empty() || self.on_folded_book() {
             self.current_contact = None;
             self.current_props.clear();
             self.aliases.clear();
@@ -2035,19 +3533,24 @@ impl<'a> App<'a> {
             self.rebuild_field_views();
             return Ok(());
         }
-        self.update_selected_row();
         let contact = &self.contacts[self.selected];
+        let previous_path = self.current_contact.take().map(|c| c.path);
         self.current_contact = self.db.get_contact(&contact.uuid)?;
+        if self.current_contact.as_ref().map(|c| &c.path) != previous_path.as_ref() {
+            self.notes_view.scroll_to_top();
+        }
         self.current_props = self.db.get_props(&contact.uuid)?;
         self.aliases = collect_aliases(&self.current_props, &contact.display_fn);
         self.languages = collect_languages(&self.current_props);
-        match decode_embedded_photo(&self.current_props) {
+        match decode_embedded_photo(&self.current_props, &self.config.vdir, self.provider) {
             Ok(photo) => {
                 self.photo_error = None;
-                self.set_photo(photo);
+                self.photo_seq = photo.as_ref().map(|(seq, _)| *seq);
+                self.set_photo(photo.map(|(_, photo)| photo));
             }
             Err(err) => {
                 self.photo_error = Some(err.to_string());
+                self.photo_seq = None;
                 self.set_photo(None);
             }
         }
@@ -2058,8 +3561,10 @@ impl<'a> App<'a> {
     fn set_photo(&mut self, photo: Option<PhotoData>) {
         match photo {
             Some(photo) => {
-                let protocol = self.image_picker.new_resize_protocol(photo.image().clone());
-                self.image_state = Some(protocol);
+                // Text-only mode keeps the photo (for delete and the placeholder)
+                // without building anything to draw
+                self.image_state = (self.config.ui.pane.image.mode != ImageMode::None)
+                    .then(|| self.image_picker.new_resize_protocol(photo.image().clone()));
                 self.photo_data = Some(photo);
             }
             None => {
@@ -2070,22 +3575,21 @@ impl<'a> App<'a> {
     }
 
     fn rebuild_field_views(&mut self) {
-        let default_region = self.config.phone_region.as_deref();
+        let phone = self.config.phone_options();
 
         if self.current_contact.is_some() {
             self.card_fields = build_card_fields(
                 &self.current_props,
                 &self.aliases,
                 &self.config.fields_first_pane,
-                default_region,
+                phone,
+                self.show_empty_fields,
             );
         } else {
             self.card_fields.clear();
         }
 
-        if self.card_fields.is_empty() {
-            self.card_field_index = 0;
-        } else if self.card_field_index >= self.card_fields.len() {
+        if self.card_field_index >= self.card_fields.len() {
             self.card_field_index = 0;
         }
 
@@ -2094,16 +3598,16 @@ impl<'a> App<'a> {
             self.details_sections = build_details_sections(
                 &self.current_props,
                 &self.config.details_sections,
-                default_region,
+                &self.config.impp_templates,
+                phone,
+                self.show_empty_fields,
             );
         } else {
             self.details_sections.clear();
         }
 
         let total_details = self.details_total_fields();
-        if total_details == 0 {
-            self.details_field_index = 0;
-        } else if self.details_field_index >= total_details {
+        if self.details_field_index >= total_details {
             self.details_field_index = 0;
         }
     }
@@ -2119,21 +3623,17 @@ impl<'a> App<'a> {
     fn ensure_focus_field(&mut self, pane: PaneFocus) {
         match pane {
             PaneFocus::Card => {
-                if self.card_fields.is_empty() {
-                    self.card_field_index = 0;
-                } else if self.card_field_index >= self.card_fields.len() {
+                if self.card_field_index >= self.card_fields.len() {
                     self.card_field_index = 0;
                 }
             }
             PaneFocus::Details => {
                 let total = self.details_total_fields();
-                if total == 0 {
-                    self.details_field_index = 0;
-                } else if self.details_field_index >= total {
+                if self.details_field_index >= total {
                     self.details_field_index = 0;
                 }
             }
-            PaneFocus::Image | PaneFocus::Search => {}
+            PaneFocus::Image | PaneFocus::Notes | PaneFocus::Search => {}
         }
     }
 
@@ -2175,7 +3675,8 @@ impl<'a> App<'a> {
                 let next = (current + delta).rem_euclid(len);
                 self.details_field_index = next as usize;
             }
-            PaneFocus::Image | PaneFocus::Search => {}
+            PaneFocus::Image => self.cycle_photo(delta),
+            PaneFocus::Notes | PaneFocus::Search => {}
         }
     }
 
@@ -2186,17 +3687,130 @@ impl<'a> App<'a> {
                 self.details_field_at(self.details_field_index)
                     .map(|df| df.to_pane_field())
             }
+            PaneFocus::Notes => self.note_field(),
             PaneFocus::Image | PaneFocus::Search => None,
         }
     }
 
+    /// The first NOTE as a field, so the notes pane can edit, copy and delete it
+    fn note_field(&self) -> Option<PaneField> {
+        self.current_props
+            .iter()
+            .find(|prop| prop.field.eq_ignore_ascii_case("NOTE"))
+            .map(|prop| PaneField::from_prop("Note", &prop.value, &prop.value, "NOTE", prop.seq, None))
+    }
+
+    /// All NOTE values of the current contact, separated by a blank line
+    pub fn notes_text(&self) -> String {
+        self.current_props
+            .iter()
+            .filter(|prop| prop.field.eq_ignore_ascii_case("NOTE"))
+            .map(|prop| prop.value.as_str())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    fn expand_focused_field(&mut self) {
+        match self.focused_field() {
+            Some(field) if !field.value.is_empty() => {
+                self.field_popup = Some(FieldPopup {
+                    label: field.label,
+                    value: field.value,
+                    view: ScrollView::new(0),
+                });
+            }
+            _ => self.set_status("No value to show"),
+        }
+    }
+
+    fn handle_field_popup_key(&mut self, key: KeyEvent) {
+        let close = matches!(key.code, KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter)
+            || self.key_matches_any(&key, &self.config.keys.navigation.expand);
+        if close {
+            self.field_popup = None;
+            return;
+        }
+        if let Some(popup) = self.field_popup.as_mut() {
+            popup.view.scroll_by_key(&key);
+        }
+    }
+
+    fn toggle_notes(&mut self) {
+        self.show_notes = !self.show_notes;
+        if self.show_notes {
+            self.notes_view.scroll_to_top();
+            self.focus_pane(PaneFocus::Notes);
+            self.set_status("Showing notes");
+        } else {
+            if matches!(self.focused_pane, PaneFocus::Notes) {
+                self.focus_pane(PaneFocus::Card);
+            }
+            self.set_status("Hiding notes");
+        }
+    }
+
     fn copy_focused_value(&mut self) -> Result<()> {
         let Some(field) = self.focused_field() else {
             self.set_status("Nothing to copy");
             return Ok(());
         };
 
-        self.copy_value_to_clipboard(field.copy_text())
+        let property = field
+            .source()
+            .map(|source| source.field)
+            .unwrap_or_else(|| field.label.clone());
+        let value = self.copy_transformed(&property, field.copy_text());
+        self.copy_value_to_clipboard(&value)
+    }
+
+    /// Apply the `[copy_transform]` setting for `property` to a copied value
+    fn copy_transformed(&self, property: &str, value: &str) -> String {
+        let transform = match property.to_ascii_uppercase().as_str() {
+            "TEL" => self.config.copy_transform.phone,
+            "EMAIL" => self.config.copy_transform.email,
+            _ => CopyTransform::Display,
+        };
+        match transform {
+            CopyTransform::Display => value.to_string(),
+            CopyTransform::Digits => vcard_io::phone_digits(value),
+            CopyTransform::E164 => vcard_io::phone_e164(value, self.config.phone_region.as_deref()),
+            CopyTransform::Lowercase => value.trim().to_lowercase(),
+        }
+    }
+
+    fn copy_focused_uri(&mut self) -> Result<()> {
+        let Some(field) = self.focused_field() else {
+            self.set_status("Nothing to copy");
+            return Ok(());
+        };
+
+        let property = field
+            .source()
+            .map(|source| source.field)
+            .unwrap_or_else(|| field.label.clone());
+        match self.copy_uri_for(&property, field.copy_text()) {
+            Some(uri) => self.copy_value_to_clipboard(&uri),
+            None => {
+                self.set_status("No URI form for this field");
+                Ok(())
+            }
+        }
+    }
+
+    /// Wrap EMAIL values as `mailto:` and TEL values as E.164 `tel:` URIs
+    fn copy_uri_for(&self, property: &str, value: &str) -> Option<String> {
+        let trimmed = value.trim();
+        if trimmed.is_empty() {
+            return None;
+        }
+        match property.to_ascii_uppercase().as_str() {
+            "EMAIL" => Some(format!("mailto:{}", trimmed)),
+            "TEL" => Some(vcard_io::phone_tel_uri(
+                trimmed,
+                self.config.phone_region.as_deref(),
+            )),
+            _ => None,
+        }
     }
 
     fn copy_value_to_clipboard(&mut self, value: &str) -> Result<()> {
@@ -2206,16 +3820,40 @@ impl<'a> App<'a> {
             return Ok(());
         }
 
+        self.copy_with_status(trimmed, "Field copied!".to_string());
+        Ok(())
+    }
+
+    fn copy_with_status(&mut self, value: &str, copied: String) {
         if let Some(command) = self.config.commands.copy.clone() {
-            match self.run_copy_command(&command, trimmed) {
-                Ok(_) => self.set_status("Field copied!"),
+            match self.run_copy_command(&command, value) {
+                Ok(_) => self.set_status(copied),
                 Err(err) => self.set_status(format!("Copy failed: {}", err)),
             }
         } else {
             self.set_status("Copy command not configured");
         }
+    }
 
-        Ok(())
+    fn copy_contact_uid(&mut self) {
+        let Some(entry) = self.current_contact.as_ref().and(self.contacts.get(self.selected)) else {
+            self.set_status("No contact selected");
+            return;
+        };
+        let uid = entry.uuid.clone();
+        self.copy_with_status(&uid, format!("Copied UID {}", uid));
+    }
+
+    /// Copy the contact's file path relative to the vdir (absolute when the
+    /// file lies outside it)
+    fn copy_contact_path(&mut self) {
+        let Some(contact) = &self.current_contact else {
+            self.set_status("No contact selected");
+            return;
+        };
+        let path = contact.path.strip_prefix(&self.config.vdir).unwrap_or(&contact.path);
+        let path = path.display().to_string();
+        self.copy_with_status(&path, format!("Copied path {}", path));
     }
 
     fn run_copy_command(&self, command: &CommandExec, value: &str) -> Result<()> {
@@ -2237,6 +3875,43 @@ impl<'a> App<'a> {
         Ok(())
     }
 
+    /// Run `commands.open_path` on the current contact's file, or show the
+    /// path in the status line when no command is configured
+    fn open_contact_path(&mut self) {
+        let Some(contact) = &self.current_contact else {
+            self.set_status("No contact selected");
+            return;
+        };
+        let path = contact.path.clone();
+        let Some(command) = self.config.commands.open_path.clone() else {
+            self.set_status(path.display().to_string());
+            return;
+        };
+
+        let spawned = Command::new(&command.program)
+            .args(command.with_path(&path))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        match spawned {
+            Ok(mut child) => {
+                // File managers may stay open; reap them without blocking the UI
+                std::thread::spawn(move || child.wait());
+                self.set_status(format!("Opened {}", path.display()));
+            }
+            Err(err) => self.set_status(format!("Open failed: `{}`: {}", command.program, err)),
+        }
+    }
+
+    /// Stamp REV on the edited card and write the contact file back
+    fn write_edited_cards(&self, path: &Path, cards: &mut [Vcard]) -> Result<()> {
+        if let Some(card) = cards.first_mut() {
+            vcard_io::touch_rev(card);
+        }
+        vcard_io::write_cards(path, cards, self.provider)
+    }
+
     fn set_status<S: Into<String>>(&mut self, message: S) {
         self.status = Some(message.into());
     }
@@ -2291,7 +3966,13 @@ impl<'a> App<'a> {
     fn begin_edit(&mut self) {
         if let Some(field) = self.focused_field() {
             if let Some(source) = field.source() {
-                self.editor.start(field.copy_text(), source);
+                if source.field.eq_ignore_ascii_case("ADR") {
+                    if let Err(err) = self.open_address_editor(source.seq, None) {
+                        self.set_status(format!("Unable to edit address: {err:#}"));
+                    }
+                    return;
+                }
+                self.editor.start(field.edit_text(), source);
                 self.set_status(format!("Editing {}", field.label));
             } else {
                 self.set_status("Field not editable");
@@ -2301,12 +3982,29 @@ impl<'a> App<'a> {
         }
     }
 
+    /// Edit the `X-ABLabel` of a TEL/EMAIL/ADR/URL property
+    fn begin_label_edit(&mut self, field: &str, seq: i64) {
+        if !matches!(field.to_ascii_uppercase().as_str(), "TEL" | "EMAIL" | "ADR" | "URL") {
+            self.set_status("Field has no label");
+            return;
+        }
+        let current = self
+            .current_props
+            .iter()
+            .find(|prop| prop.field.eq_ignore_ascii_case(field) && prop.seq == seq)
+            .and_then(|prop| ab_label(&prop.params))
+            .unwrap_or_default()
+            .to_string();
+        self.editor.start(&current, FieldRef::label_of(field, seq));
+        self.set_status(format!("Editing {} label", field.to_ascii_uppercase()));
+    }
+
     fn add_alias_to_current_contact(&mut self, alias: &str) -> Result<()> {
         let Some(contact) = &self.current_contact else { return Ok(()); };
         let trimmed = alias.trim();
         if trimmed.is_empty() { return Ok(()); }
 
-        let parsed = vcard_io::parse_file(&contact.path, self.config.phone_region.as_deref(), self.provider)?;
+        let parsed = vcard_io::parse_file(&contact.path, self.config.phone_options(), self.provider)?;
         let mut cards = parsed.cards;
         if cards.is_empty() { return Ok(()); }
 
@@ -2325,11 +4023,11 @@ impl<'a> App<'a> {
             }
         }
 
-        vcard_io::write_cards(&contact.path, &cards, self.provider)?;
+        self.write_edited_cards(&contact.path, &mut cards)?;
 
         let card_clone = cards[0].clone();
         let state = vdir::compute_file_state(&contact.path)?;
-        let record = indexer::build_record(&contact.path, &card_clone, &state, None)?;
+        let record = indexer::build_record(&contact.path, &card_clone, &state, None, self.config.nameless_display)?;
         self.db.upsert(&record.item, &record.props)?;
 
         // Refresh and keep focus stable
@@ -2362,7 +4060,8 @@ impl<'a> App<'a> {
             return Ok(false);
         };
 
-        let parsed = vcard_io::parse_file(&contact.path, self.config.phone_region.as_deref(), self.provider)?;
+        let parsed =
+            vcard_io::parse_file(&contact.path, self.config.phone_options(), self.provider)?;
         let mut cards = parsed.cards;
         if cards.is_empty() {
             self.set_status("Contact has no cards");
@@ -2379,11 +4078,11 @@ impl<'a> App<'a> {
             return Ok(false);
         }
 
-        vcard_io::write_cards(&contact.path, &cards, self.provider)?;
+        self.write_edited_cards(&contact.path, &mut cards)?;
 
         let card_clone = cards[0].clone();
         let state = vdir::compute_file_state(&contact.path)?;
-        let record = indexer::build_record(&contact.path, &card_clone, &state, None)?;
+        let record = indexer::build_record(&contact.path, &card_clone, &state, None, self.config.nameless_display)?;
         self.db.upsert(&record.item, &record.props)?;
 
         // Refresh and keep focus stable
@@ -2397,41 +4096,87 @@ impl<'a> App<'a> {
         Ok(true)
     }
 
+    /// Status after an inline edit; TEL values also report whether the
+    /// number is recognized for `phone_region` (the save goes ahead either way)
+    fn field_updated_status(&self, target: &FieldRef, value: &str) -> &'static str {
+        if target.label {
+            return "Label updated";
+        }
+        if target.field.eq_ignore_ascii_case("EMAIL") && !value.trim().is_empty() {
+            return if vcard_io::email_looks_valid(&vcard_io::clean_email(value)) {
+                "Field updated"
+            } else {
+                "Field updated (email looks invalid)"
+            };
+        }
+        if target.field.eq_ignore_ascii_case("URL") && !value.trim().is_empty() {
+            let cleaned = vcard_io::clean_url(value);
+            return if vcard_io::url_looks_valid(&cleaned) {
+                "Field updated"
+            } else if vcard_io::url_parses(&cleaned) {
+                "Field updated (url looks invalid)"
+            } else {
+                "URL not saved (not a valid URI)"
+            };
+        }
+        if !target.field.eq_ignore_ascii_case("TEL") || value.trim().is_empty() {
+            return "Field updated";
+        }
+        match vcard_io::phone_validity(value, self.config.phone_region.as_deref()) {
+            PhoneValidity::Valid => "Field updated (valid)",
+            PhoneValidity::Possible => "Field updated (possible, not a known number range)",
+            PhoneValidity::Unrecognized => "Field updated (unrecognized format)",
+        }
+    }
+
     fn commit_field_edit(&mut self, target: FieldRef, new_value: String) -> Result<()> {
+        self.commit_field_edits(vec![(target, new_value)])
+    }
+
+    /// Apply several field edits to the current contact in a single write
+    fn commit_field_edits(&mut self, edits: Vec<(FieldRef, String)>) -> Result<()> {
         let Some(contact) = &self.current_contact else {
             self.set_status("No contact selected");
             return Ok(());
         };
 
-        let parsed = vcard_io::parse_file(&contact.path, self.config.phone_region.as_deref(), self.provider)?;
+        let parsed =
+            vcard_io::parse_file(&contact.path, self.config.phone_options(), self.provider)?;
         let mut cards = parsed.cards;
         if cards.is_empty() {
             self.set_status("Contact has no cards");
             return Ok(());
         }
 
-        let updated = {
+        let mut updated = false;
+        {
             let card = cards.get_mut(0).unwrap();
-            vcard_io::update_card_field(
-                card,
-                &target.field,
-                target.seq,
-                target.component,
-                &new_value,
-                self.config.phone_region.as_deref(),
-            )?
-        };
+            for (target, new_value) in &edits {
+                updated |= if target.label {
+                    vcard_io::set_ab_label(card, &target.field, target.seq, new_value)
+                } else {
+                    vcard_io::update_card_field(
+                        card,
+                        &target.field,
+                        target.seq,
+                        target.component,
+                        new_value,
+                        self.config.phone_options(),
+                    )?
+                };
+            }
+        }
 
         if !updated {
             self.set_status("Field not editable");
             return Ok(());
         }
 
-        vcard_io::write_cards(&contact.path, &cards, self.provider)?;
+        self.write_edited_cards(&contact.path, &mut cards)?;
 
         let card_clone = cards[0].clone();
         let state = vdir::compute_file_state(&contact.path)?;
-        let record = indexer::build_record(&contact.path, &card_clone, &state, None)?;
+        let record = indexer::build_record(&contact.path, &card_clone, &state, None, self.config.nameless_display)?;
         self.db.upsert(&record.item, &record.props)?;
 
         let previous_index = self.card_field_index;
@@ -2463,7 +4208,8 @@ impl<'a> App<'a> {
             return Ok(false);
         };
 
-        let parsed = vcard_io::parse_file(&contact.path, self.config.phone_region.as_deref(), self.provider)?;
+        let parsed =
+            vcard_io::parse_file(&contact.path, self.config.phone_options(), self.provider)?;
         let mut cards = parsed.cards;
         if cards.is_empty() {
             self.set_status("Contact has no cards");
@@ -2475,6 +4221,7 @@ impl<'a> App<'a> {
             match field {
                 MultiValueField::Email => vcard_io::promote_email_entry(card, idx),
                 MultiValueField::Phone => vcard_io::promote_tel_entry(card, idx),
+                MultiValueField::Address => vcard_io::promote_address_entry(card, idx),
                 MultiValueField::Alias => {
                     // Aliases don't have a "default" concept
                     return Ok(false);
@@ -2487,11 +4234,11 @@ impl<'a> App<'a> {
             return Ok(false);
         }
 
-        vcard_io::write_cards(&contact.path, &cards, self.provider)?;
+        self.write_edited_cards(&contact.path, &mut cards)?;
 
         let card_clone = cards[0].clone();
         let state = vdir::compute_file_state(&contact.path)?;
-        let record = indexer::build_record(&contact.path, &card_clone, &state, None)?;
+        let record = indexer::build_record(&contact.path, &card_clone, &state, None, self.config.nameless_display)?;
         self.db.upsert(&record.item, &record.props)?;
 
         let previous_index = self.card_field_index;
@@ -2504,6 +4251,48 @@ impl<'a> App<'a> {
         Ok(true)
     }
 
+    /// Move the entry at `seq` by `delta` places and write the card back.
+    /// Returns the entry's new seq, or None when it could not move.
+    fn move_multivalue_entry(&mut self, field: MultiValueField, seq: i64, delta: i64) -> Result<Option<i64>> {
+        let Some(contact) = &self.current_contact else {
+            self.set_status("No contact selected");
+            return Ok(None);
+        };
+        let (Ok(from), Ok(to)) = (usize::try_from(seq), usize::try_from(seq + delta)) else {
+            self.set_status("Already first");
+            return Ok(None);
+        };
+
+        let parsed =
+            vcard_io::parse_file(&contact.path, self.config.phone_options(), self.provider)?;
+        let mut cards = parsed.cards;
+        if cards.is_empty() {
+            self.set_status("Contact has no cards");
+            return Ok(None);
+        }
+        if !vcard_io::reorder_entry(&mut cards[0], field.field_name(), from, to) {
+            self.set_status(if delta < 0 { "Already first" } else { "Already last" });
+            return Ok(None);
+        }
+
+        self.write_edited_cards(&contact.path, &mut cards)?;
+
+        let card_clone = cards[0].clone();
+        let state = vdir::compute_file_state(&contact.path)?;
+        let record = indexer::build_record(&contact.path, &card_clone, &state, None, self.config.nameless_display)?;
+        self.db.upsert(&record.item, &record.props)?;
+
+        let previous_index = self.card_field_index;
+        self.refresh_contacts()?;
+        if !self.card_fields.is_empty() {
+            let max_index = self.card_fields.len().saturating_sub(1);
+            self.card_field_index = previous_index.min(max_index);
+        }
+
+        self.set_status(if to == 0 { "Moved to top (default)" } else { "Moved" });
+        Ok(Some(to as i64))
+    }
+
     /// Check if the key event matches any of the bindings in the list
     fn key_matches_any(&self, event: &KeyEvent, bindings: &[String]) -> bool {
         bindings.iter().any(|b| self.key_matches_single(event, b))
@@ -2511,14 +4300,16 @@ impl<'a> App<'a> {
 
     /// Check if the key event matches a single binding string
     fn key_matches_single(&self, event: &KeyEvent, binding: &str) -> bool {
-        let trimmed = binding.trim();
+        let (modifiers, trimmed) = split_binding_modifiers(binding);
         if trimmed.is_empty() {
             return false;
         }
 
-        // Disallow Ctrl/Alt/Super modifiers (we don't support them)
-        let disallowed = KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SUPER;
-        if event.modifiers.intersects(disallowed) {
+        // Ctrl and Alt must be held exactly as the binding says; Super never binds
+        if event.modifiers.contains(KeyModifiers::SUPER)
+            || event.modifiers.contains(KeyModifiers::CONTROL) != modifiers.ctrl
+            || event.modifiers.contains(KeyModifiers::ALT) != modifiers.alt
+        {
             return false;
         }
 
@@ -2579,6 +4370,10 @@ impl<'a> App<'a> {
                 self.focus_pane(PaneFocus::Image);
                 true
             }
+            '4' if self.show_notes => {
+                self.focus_pane(PaneFocus::Notes);
+                true
+            }
             _ => false,
         }
     }
@@ -2607,6 +4402,10 @@ impl<'a> App<'a> {
                         action: "Help",
                         keys: keys.global.help.join(", "),
                     },
+                    HelpEntry {
+                        action: "Recently edited",
+                        keys: keys.global.recent.join(", "),
+                    },
                 ],
             },
             HelpSection {
@@ -2658,16 +4457,32 @@ impl<'a> App<'a> {
                         keys: keys.search_results.page_up.join(", "),
                     },
                     HelpEntry {
-                        action: "Mark",
-                        keys: keys.search_results.mark.join(", "),
+                        action: "Mark",
+                        keys: keys.search_results.mark.join(", "),
+                    },
+                    HelpEntry {
+                        action: "Merge",
+                        keys: keys.search_results.merge.join(", "),
+                    },
+                    HelpEntry {
+                        action: "Toggle Marked",
+                        keys: keys.search_results.toggle_marked.join(", "),
+                    },
+                    HelpEntry {
+                        action: "Tag Marked",
+                        keys: keys.search_results.tag_marked.join(", "),
+                    },
+                    HelpEntry {
+                        action: "Compare Marked",
+                        keys: keys.search_results.compare.join(", "),
                     },
                     HelpEntry {
-                        action: "Merge",
-                        keys: keys.search_results.merge.join(", "),
+                        action: "Skip Duplicate Group",
+                        keys: keys.search_results.skip_group.join(", "),
                     },
                     HelpEntry {
-                        action: "Toggle Marked",
-                        keys: keys.search_results.toggle_marked.join(", "),
+                        action: "Jump to Letter",
+                        keys: "unbound letters".to_string(),
                     },
                 ],
             },
@@ -2690,6 +4505,14 @@ impl<'a> App<'a> {
                         action: "Copy",
                         keys: keys.navigation.copy.join(", "),
                     },
+                    HelpEntry {
+                        action: "Copy as URI",
+                        keys: keys.navigation.copy_uri.join(", "),
+                    },
+                    HelpEntry {
+                        action: "Edit Label",
+                        keys: keys.navigation.edit_label.join(", "),
+                    },
                     HelpEntry {
                         action: "Confirm",
                         keys: keys.navigation.confirm.join(", "),
@@ -2702,6 +4525,82 @@ impl<'a> App<'a> {
                         action: "Delete Field",
                         keys: keys.navigation.delete_field.join(", "),
                     },
+                    HelpEntry {
+                        action: "Split Name",
+                        keys: keys.navigation.split_name.join(", "),
+                    },
+                    HelpEntry {
+                        action: "Clear Filter",
+                        keys: keys.navigation.clear_filter.join(", "),
+                    },
+                    HelpEntry {
+                        action: "Toggle Filter",
+                        keys: keys.navigation.toggle_filter.join(", "),
+                    },
+                    HelpEntry {
+                        action: "Toggle Empty Fields",
+                        keys: keys.navigation.toggle_empty.join(", "),
+                    },
+                    HelpEntry {
+                        action: "Toggle Do Not Sync",
+                        keys: keys.navigation.toggle_nosync.join(", "),
+                    },
+                    HelpEntry {
+                        action: "Open File Path",
+                        keys: keys.navigation.open_path.join(", "),
+                    },
+                    HelpEntry {
+                        action: "Copy UID",
+                        keys: keys.navigation.copy_uid.join(", "),
+                    },
+                    HelpEntry {
+                        action: "Copy File Path",
+                        keys: keys.navigation.copy_path.join(", "),
+                    },
+                    HelpEntry {
+                        action: "Clean Up Emails",
+                        keys: keys.navigation.clean_emails.join(", "),
+                    },
+                    HelpEntry {
+                        action: "Toggle Notes Pane",
+                        keys: keys.navigation.toggle_notes.join(", "),
+                    },
+                    HelpEntry {
+                        action: "Toggle Field Sources",
+                        keys: keys.navigation.toggle_sources.join(", "),
+                    },
+                    HelpEntry {
+                        action: "Show Full Value",
+                        keys: keys.navigation.expand.join(", "),
+                    },
+                    HelpEntry {
+                        action: "Collapse/Expand Book",
+                        keys: keys.navigation.toggle_book.join(", "),
+                    },
+                    HelpEntry {
+                        action: "Move Contact Up",
+                        keys: keys.navigation.move_contact_up.join(", "),
+                    },
+                    HelpEntry {
+                        action: "Move Contact Down",
+                        keys: keys.navigation.move_contact_down.join(", "),
+                    },
+                    HelpEntry {
+                        action: "Duplicate Contact",
+                        keys: keys.navigation.duplicate.join(", "),
+                    },
+                    HelpEntry {
+                        action: "Next/Prev Photo",
+                        keys: format!(
+                            "{} / {}",
+                            keys.navigation.next.join(", "),
+                            keys.navigation.prev.join(", ")
+                        ),
+                    },
+                    HelpEntry {
+                        action: "Primary Photo",
+                        keys: keys.navigation.confirm.join(", "),
+                    },
                     HelpEntry {
                         action: "Fetch Photo",
                         keys: keys.navigation.photo_fetch.join(", "),
@@ -2739,10 +4638,26 @@ impl<'a> App<'a> {
                         action: "Copy",
                         keys: keys.modal.copy.join(", "),
                     },
+                    HelpEntry {
+                        action: "Copy as URI",
+                        keys: keys.modal.copy_uri.join(", "),
+                    },
+                    HelpEntry {
+                        action: "Edit Label",
+                        keys: keys.modal.edit_label.join(", "),
+                    },
                     HelpEntry {
                         action: "Set Default",
                         keys: keys.modal.set_default.join(", "),
                     },
+                    HelpEntry {
+                        action: "Move Up",
+                        keys: keys.modal.move_up.join(", "),
+                    },
+                    HelpEntry {
+                        action: "Move Down",
+                        keys: keys.modal.move_down.join(", "),
+                    },
                     HelpEntry {
                         action: "Delete",
                         keys: keys.modal.delete.join(", "),
@@ -2785,7 +4700,7 @@ impl<'a> App<'a> {
     pub fn show_help(&mut self) {
         let total_lines = self.help_total_lines();
         self.modal_popup = PopupState::default();
-        self.help_modal = Some(HelpModal::new(total_lines));
+        self.help_modal = Some(ScrollView::new(total_lines));
     }
 
     /// Handle keys when help modal is open
@@ -2796,38 +4711,8 @@ impl<'a> App<'a> {
             return;
         }
 
-        let Some(modal) = self.help_modal.as_mut() else {
-            return;
-        };
-
-        match key.code {
-            // Scroll down
-            KeyCode::Char('j') | KeyCode::Down => {
-                modal.scroll_down(1);
-            }
-            // Scroll up
-            KeyCode::Char('k') | KeyCode::Up => {
-                modal.scroll_up(1);
-            }
-            // Page down
-            KeyCode::PageDown => {
-                let page = modal.viewport_height.saturating_sub(1).max(1);
-                modal.scroll_down(page);
-            }
-            // Page up
-            KeyCode::PageUp => {
-                let page = modal.viewport_height.saturating_sub(1).max(1);
-                modal.scroll_up(page);
-            }
-            // Scroll to top
-            KeyCode::Char('g') | KeyCode::Home => {
-                modal.scroll_to_top();
-            }
-            // Scroll to bottom
-            KeyCode::Char('G') | KeyCode::End => {
-                modal.scroll_to_bottom();
-            }
-            _ => {}
+        if let Some(modal) = self.help_modal.as_mut() {
+            modal.scroll_by_key(&key);
         }
     }
 
@@ -2898,6 +4783,7 @@ impl<'a> App<'a> {
                     self.confirm_modal = Some(ConfirmModal {
                         title: "DELETE CONTACT".to_string(),
                         message: format!("Delete {}?", contact.display_fn),
+                        details: Vec::new(),
                         action: ConfirmAction::DeleteContact,
                     });
                 } else {
@@ -2908,35 +4794,129 @@ impl<'a> App<'a> {
         }
     }
 
-    /// Perform the actual reindex operation
-    fn perform_reindex(&mut self) -> Result<()> {
-        let files = vdir::list_vcf_files(&self.config.vdir)?;
+    /// Rebuild the index from scratch. Files are decrypted and parsed on a
+    /// worker thread while this thread stores the records, keeps the modal's
+    /// "N/M" count current and watches for Esc, which stops the worker after
+    /// the file it is on.
+    fn perform_reindex<B>(&mut self, terminal: &mut Terminal<B>) -> Result<ReindexProgress>
+    where
+        B: ratatui::backend::Backend,
+    {
+        let files = vdir::list_vcf_files(&self.config.vdir, &self.config.vdir_ignore, self.provider.encryption_type())?;
         let paths_set: HashSet<_> = files.iter().cloned().collect();
+        let mut progress = ReindexProgress { total: files.len(), ..ReindexProgress::default() };
 
         // Force full reindex
         self.db.reset_schema()?;
 
-        for path in files {
-            let state = vdir::compute_file_state(&path)?;
-            let parsed = vcard_io::parse_file(&path, self.config.phone_region.as_deref(), self.provider)?;
-            let cards = parsed.cards;
+        let config = self.config;
+        let provider = self.provider;
+        let cancel = AtomicBool::new(false);
+        std::thread::scope(|scope| -> Result<()> {
+            let (tx, rx) = mpsc::channel();
+            scope.spawn(|| {
+                for path in &files {
+                    if cancel.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let record = index_file(path, config, provider);
+                    if tx.send(record).is_err() {
+                        break;
+                    }
+                }
+                drop(tx);
+            });
+
+            let result = self.receive_reindexed(terminal, &rx, &cancel, &paths_set, &mut progress);
+            // On an error the worker must not keep going in the background
+            cancel.store(true, Ordering::Relaxed);
+            result
+        })?;
 
-            if cards.is_empty() {
-                continue;
+        // An Esc that arrives after the last file changes nothing
+        progress.cancelled &= progress.processed < progress.total;
+        if !progress.cancelled {
+            self.db.remove_missing(&paths_set)?;
+        }
+        Ok(progress)
+    }
+
+    fn receive_reindexed<B>(
+        &mut self,
+        terminal: &mut Terminal<B>,
+        rx: &mpsc::Receiver<Result<Option<indexer::IndexedRecord>>>,
+        cancel: &AtomicBool,
+        on_disk: &HashSet<PathBuf>,
+        progress: &mut ReindexProgress,
+    ) -> Result<()>
+    where
+        B: ratatui::backend::Backend,
+    {
+        loop {
+            match rx.recv_timeout(REINDEX_REDRAW_INTERVAL) {
+                Ok(record) => {
+                    self.store_reindexed(record, on_disk, progress)?;
+                    // Drain whatever else is ready before the next redraw
+                    while let Ok(record) = rx.try_recv() {
+                        self.store_reindexed(record, on_disk, progress)?;
+                    }
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => return Ok(()),
             }
 
-            let final_state = if parsed.changed {
-                vdir::compute_file_state(&path)?
-            } else {
-                state
-            };
+            while event::poll(Duration::ZERO)? {
+                if let Event::Key(key) = event::read()? {
+                    let ctrl_c = key.modifiers.contains(KeyModifiers::CONTROL)
+                        && matches!(key.code, KeyCode::Char('c') | KeyCode::Char('C'));
+                    if matches!(key.code, KeyCode::Esc) || ctrl_c {
+                        cancel.store(true, Ordering::Relaxed);
+                        progress.cancelled = true;
+                    }
+                }
+            }
 
-            let card = cards.into_iter().next().unwrap();
-            let record = indexer::build_record(&path, &card, &final_state, None)?;
-            self.db.upsert(&record.item, &record.props)?;
+            let label = if progress.cancelled { "CANCELLING" } else { "REINDEXING" };
+            self.reindex_modal = Some(ReindexModal {
+                message: format!("{} {}/{}", label, progress.processed, progress.total),
+            });
+            draw::render(terminal, self)?;
         }
+    }
 
-        self.db.remove_missing(&paths_set)?;
+    /// Store one record from the reindex worker; a file that failed to
+    /// decrypt is only noted when `on_decrypt_error` is "skip", and of two
+    /// files sharing a UID only the newer is indexed
+    fn store_reindexed(
+        &mut self,
+        record: Result<Option<indexer::IndexedRecord>>,
+        on_disk: &HashSet<PathBuf>,
+        progress: &mut ReindexProgress,
+    ) -> Result<()> {
+        progress.processed += 1;
+        let record = match record {
+            Ok(record) => record,
+            Err(err) if self.config.on_decrypt_error == OnDecryptError::Skip => {
+                let Some(path) = vcard_io::decrypt_error_path(&err) else {
+                    return Err(err);
+                };
+                log::warn!("skipping {}: {:#}", path.display(), err);
+                progress.skipped.push(path.to_path_buf());
+                return Ok(());
+            }
+            Err(err) => return Err(err),
+        };
+        if let Some(record) = record {
+            let clash = indexer::store_record(
+                self.db,
+                record,
+                on_disk,
+                indexer::UidCollision::KeepNewest,
+                self.config,
+                self.provider,
+            )?;
+            progress.uid_clashes.extend(clash);
+        }
         Ok(())
     }
 
@@ -2945,7 +4925,7 @@ impl<'a> App<'a> {
     // =========================================================================
 
     fn show_share_modal(&mut self) -> Result<()> {
-        use qrcode::{QrCode, render::unicode};
+        use qrcode::{EcLevel, QrCode, Version, render::unicode};
 
         let Some(contact) = &self.current_contact else {
             self.set_status("No contact selected");
@@ -2953,7 +4933,8 @@ impl<'a> App<'a> {
         };
 
         // Parse the vCard file to get full card data
-        let parsed = vcard_io::parse_file(&contact.path, self.config.phone_region.as_deref(), self.provider)?;
+        let parsed =
+            vcard_io::parse_file(&contact.path, self.config.phone_options(), self.provider)?;
         let Some(mut card) = parsed.cards.into_iter().next() else {
             self.set_status("Unable to load contact");
             return Ok(());
@@ -2966,14 +4947,27 @@ impl<'a> App<'a> {
         // Serialize to vCard string
         let vcard_string = card.to_string();
 
-        // Check if data is too large for QR code (max ~2953 bytes for alphanumeric)
-        if vcard_string.len() > 2500 {
-            self.set_status("Contact data too large for QR code");
+        // Check the payload fits a version 40 symbol at the configured level
+        let error_correction = self.config.ui.share.qr_error_correction;
+        if vcard_string.len() > error_correction.max_bytes() {
+            self.set_status(format!(
+                "Contact data too large for QR code ({} bytes, max {} at level {})",
+                vcard_string.len(),
+                error_correction.max_bytes(),
+                error_correction.as_str()
+            ));
             return Ok(());
         }
 
+        let ec_level = match error_correction {
+            QrErrorCorrection::L => EcLevel::L,
+            QrErrorCorrection::M => EcLevel::M,
+            QrErrorCorrection::Q => EcLevel::Q,
+            QrErrorCorrection::H => EcLevel::H,
+        };
+
         // Generate QR code
-        let code = match QrCode::new(vcard_string.as_bytes()) {
+        let code = match QrCode::with_error_correction_level(vcard_string.as_bytes(), ec_level) {
             Ok(c) => c,
             Err(e) => {
                 self.set_status(format!("QR generation failed: {}", e));
@@ -2992,7 +4986,10 @@ impl<'a> App<'a> {
         let qr_lines: Vec<String> = qr_string.lines().map(|s| s.to_string()).collect();
 
         self.modal_popup = PopupState::default();
-        self.share_modal = Some(ShareModal { qr_lines });
+        let version = match code.version() {
+            Version::Normal(v) | Version::Micro(v) => v,
+        };
+        self.share_modal = Some(ShareModal { qr_lines, version, error_correction });
 
         Ok(())
     }
@@ -3005,13 +5002,44 @@ impl<'a> App<'a> {
     }
 }
 
+/// KIND decides when the card has one; otherwise an ORG value marks an organization
 fn contact_is_org(entry: &ContactListEntry) -> bool {
-    if let Some(kind) = entry.kind.as_deref() {
-        if kind.eq_ignore_ascii_case("org") || kind.eq_ignore_ascii_case("organization") {
-            return true;
-        }
+    match entry.kind.as_deref() {
+        Some(kind) => kind_is_org(kind),
+        None => entry.primary_org.is_some(),
     }
-    entry.primary_org.is_some()
+}
+
+fn kind_is_org(kind: &str) -> bool {
+    let kind = kind.trim();
+    kind.eq_ignore_ascii_case("org") || kind.eq_ignore_ascii_case("organization")
+}
+
+/// Index of the first contact after `current` (wrapping) whose name starts
+/// with `initial`, compared case-insensitively.
+fn next_with_initial(contacts: &[ContactListEntry], current: usize, initial: char) -> Option<usize> {
+    let len = contacts.len();
+    (1..=len)
+        .map(|offset| (current + offset) % len)
+        .find(|&index| {
+            contacts[index]
+                .display_fn
+                .trim_start()
+                .chars()
+                .next()
+                .is_some_and(|first| first.to_lowercase().eq(initial.to_lowercase()))
+        })
+}
+
+/// A key that only edits text: a character or Backspace/Delete without Ctrl or Alt
+fn is_text_entry(key: &KeyEvent) -> bool {
+    matches!(key.code, KeyCode::Char(_) | KeyCode::Backspace | KeyCode::Delete)
+        && !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+}
+
+/// Key of an address book in `collapsed_books`: its chain joined as a path
+fn book_key(chain: &[String]) -> PathBuf {
+    chain.iter().collect()
 }
 
 fn address_book_chain_from(vdir: &Path, path: &Path) -> Vec<String> {
@@ -3075,10 +5103,35 @@ impl PhotoData {
     }
 }
 
-fn decode_embedded_photo(props: &[PropRow]) -> Result<Option<PhotoData>> {
+/// Decrypt, parse and index one file for a full reindex; `None` for a file
+/// without cards
+fn index_file(
+    path: &Path,
+    config: &Config,
+    provider: &dyn CryptoProvider,
+) -> Result<Option<indexer::IndexedRecord>> {
+    let state = vdir::compute_file_state(path)?;
+    let parsed = vcard_io::parse_file(path, config.phone_options(), provider)?;
+    let Some(card) = parsed.cards.into_iter().next() else {
+        return Ok(None);
+    };
+    let state = if parsed.changed {
+        vdir::compute_file_state(path)?
+    } else {
+        state
+    };
+    indexer::build_record(path, &card, &state, None, config.nameless_display).map(Some)
+}
+
+/// The first PHOTO that decodes to an image, with its seq
+fn decode_embedded_photo(
+    props: &[PropRow],
+    root: &Path,
+    provider: &dyn CryptoProvider,
+) -> Result<Option<(i64, PhotoData)>> {
     for prop in props.iter().filter(|p| p.field == "PHOTO") {
-        match decode_photo_prop(prop) {
-            Ok(Some(photo)) => return Ok(Some(photo)),
+        match decode_photo_prop(prop, root, provider) {
+            Ok(Some(photo)) => return Ok(Some((prop.seq, photo))),
             Ok(None) => continue,
             Err(err) => return Err(err),
         }
@@ -3086,7 +5139,16 @@ fn decode_embedded_photo(props: &[PropRow]) -> Result<Option<PhotoData>> {
     Ok(None)
 }
 
-fn decode_photo_prop(prop: &PropRow) -> Result<Option<PhotoData>> {
+fn decode_photo_prop(prop: &PropRow, root: &Path, provider: &dyn CryptoProvider) -> Result<Option<PhotoData>> {
+    // Photos spilled out of the card by max_embedded_photo_bytes
+    if let Some(name) = vcard_io::photo_ref_name(&prop.value) {
+        let path = vdir::photo_file_path(root, name, provider.encryption_type());
+        let data = vdir::read_photo_file(&path, provider)?;
+        let image = image::load_from_memory(&data)
+            .with_context(|| format!("unable to parse photo {}", path.display()))?;
+        return Ok(Some(PhotoData { image }));
+    }
+
     if prop
         .params
         .get("value")
@@ -3172,26 +5234,29 @@ fn decode_base64_blob(value: &str) -> Result<Vec<u8>> {
     Ok(decoded)
 }
 
-const DEFAULT_CARD_FIELDS: &[&str] = &["fname", "mname", "lname", "alias", "phone", "email"];
+const DEFAULT_CARD_FIELDS: &[&str] = &["fname", "mname", "lname", "alias", "phone", "email", "address"];
 
 fn build_card_fields(
     props: &[PropRow],
     aliases: &[String],
     order: &[String],
-    default_region: Option<&str>,
+    phone: PhoneOptions<'_>,
+    show_empty: bool,
 ) -> Vec<PaneField> {
     let fields = build_card_fields_inner(
         props,
         aliases,
         order.iter().map(|s| s.as_str()),
-        default_region,
+        phone,
+        show_empty,
     );
     if fields.is_empty() {
         build_card_fields_inner(
             props,
             aliases,
             DEFAULT_CARD_FIELDS.iter().copied(),
-            default_region,
+            phone,
+            show_empty,
         )
     } else {
         fields
@@ -3202,7 +5267,8 @@ fn build_card_fields_inner<I, S>(
     props: &[PropRow],
     aliases: &[String],
     order: I,
-    default_region: Option<&str>,
+    phone: PhoneOptions<'_>,
+    show_empty: bool,
 ) -> Vec<PaneField>
 where
     I: IntoIterator<Item = S>,
@@ -3210,9 +5276,10 @@ where
 {
     let mut fields = Vec::new();
     let is_org = props_is_org(props);
+    let is_org_kind = props_kind_is_org(props);
 
     let alias_value = if aliases.is_empty() {
-        "—".to_string()
+        EMPTY_FIELD.to_string()
     } else {
         aliases.join("/")
     };
@@ -3220,10 +5287,12 @@ where
     let first_nickname = props.iter().find(|p| p.field == "NICKNAME");
     let total_nickname_count = props.iter().filter(|p| p.field == "NICKNAME").count();
 
-    let first_phone = props.iter().find(|p| p.field == "TEL");
+    let first_phone = primary_prop(props, "TEL");
     let total_phone_count = props.iter().filter(|p| p.field == "TEL").count();
-    let first_email = props.iter().find(|p| p.field == "EMAIL");
+    let first_email = primary_prop(props, "EMAIL");
     let total_email_count = props.iter().filter(|p| p.field == "EMAIL").count();
+    let first_address = primary_prop(props, "ADR");
+    let total_address_count = props.iter().filter(|p| p.field == "ADR").count();
 
     let mut handled_name = false;
 
@@ -3231,82 +5300,36 @@ where
         let key = item.as_ref().trim().to_ascii_lowercase();
         match key.as_str() {
             "fname" => {
-                if let Some(prop) = props.iter().find(|p| p.field == "FN") {
-                    let value = prop.value.trim().to_string();
-                    fields.push(PaneField::from_prop(
-                        "FNAME",
-                        value.clone(),
-                        value,
-                        "FN",
-                        prop.seq,
-                        None,
-                    ));
-                }
-                if !handled_name && !is_org {
-                    handled_name = true;
-                    if let Some(prop) = props.iter().find(|p| p.field == "N") {
-                        for (idx, component) in name_components(&prop.value).into_iter().enumerate()
-                        {
-                            let display_component = if component.is_empty() {
-                                "—".to_string()
-                            } else {
-                                component.clone()
-                            };
-                            let copy_component = component.clone();
-                            let label = match idx {
-                                0 => "NAME_FAMILY",
-                                1 => "NAME_GIVEN",
-                                2 => "NAME_ADDITIONAL",
-                                3 => "NAME_PREFIX",
-                                4 => "NAME_SUFFIX",
-                                _ => "NAME",
-                            };
-                            fields.push(PaneField::from_prop(
-                                label,
-                                display_component,
-                                copy_component,
-                                "N",
-                                prop.seq,
-                                Some(idx),
-                            ));
-                        }
+                match props.iter().find(|p| p.field == "FN") {
+                    Some(prop) => {
+                        let value = prop.value.trim().to_string();
+                        let display = if value.is_empty() {
+                            EMPTY_FIELD.to_string()
+                        } else {
+                            value.clone()
+                        };
+                        fields.push(PaneField::from_prop("FNAME", display, value, "FN", prop.seq, None));
                     }
+                    None if show_empty => fields.push(PaneField::new("FNAME", EMPTY_FIELD)),
+                    None => {}
                 }
-            }
-            "mname" | "lname" => {
-                if !handled_name && !is_org {
+                if !handled_name && is_org_kind {
                     handled_name = true;
-                    if let Some(prop) = props.iter().find(|p| p.field == "N") {
-                        for (idx, component) in name_components(&prop.value).into_iter().enumerate()
-                        {
-                            let display_component = if component.is_empty() {
-                                "—".to_string()
-                            } else {
-                                component.clone()
-                            };
-                            let copy_component = component.clone();
-                            let label = match idx {
-                                0 => "NAME_FAMILY",
-                                1 => "NAME_GIVEN",
-                                2 => "NAME_ADDITIONAL",
-                                3 => "NAME_PREFIX",
-                                4 => "NAME_SUFFIX",
-                                _ => "NAME",
-                            };
-                            fields.push(PaneField::from_prop(
-                                label,
-                                display_component,
-                                copy_component,
-                                "N",
-                                prop.seq,
-                                Some(idx),
-                            ));
-                        }
-                    }
+                    push_org_fields(&mut fields, props, show_empty);
+                } else if !handled_name && !is_org {
+                    handled_name = true;
+                    push_name_fields(&mut fields, props, show_empty);
                 }
             }
+            "mname" | "lname" if !handled_name && is_org_kind => {
+                handled_name = true;
+                push_org_fields(&mut fields, props, show_empty);
+            }
+            "mname" | "lname" if !handled_name && !is_org => {
+                handled_name = true;
+                push_name_fields(&mut fields, props, show_empty);
+            }
             "alias" => {
-                // Show alias field even if empty (allows adding new aliases)
                 if let Some(prop) = first_nickname {
                     let display_value = if total_nickname_count > 1 {
                         format!("{} [{}]", alias_value, total_nickname_count)
@@ -3326,18 +5349,41 @@ where
                     fields.push(PaneField::new("ALIAS", alias_value.clone()));
                 }
             }
+            "address" => {
+                let base_value = first_address.map(format_address_value).unwrap_or_default();
+                match first_address {
+                    Some(prop) if !base_value.is_empty() => {
+                        let display_value = if total_address_count > 1 {
+                            format!("{} [{}]", base_value, total_address_count)
+                        } else {
+                            base_value.clone()
+                        };
+                        fields.push(PaneField::from_prop(
+                            "ADDRESS",
+                            display_value,
+                            base_value,
+                            "ADR",
+                            prop.seq,
+                            None,
+                        ));
+                    }
+                    _ if show_empty => fields.push(PaneField::new("ADDRESS", EMPTY_FIELD)),
+                    _ => {}
+                }
+            }
             "phone" => {
-                if let Some(prop) = first_phone {
-                    let label = "PHONE".to_string();
-                    let base_value = vcard_io::phone_display_value(&prop.value, default_region);
-                    if !base_value.is_empty() {
+                let base_value = first_phone
+                    .map(|prop| vcard_io::phone_display_value(&prop.value, phone))
+                    .unwrap_or_default();
+                match first_phone {
+                    Some(prop) if !base_value.is_empty() => {
                         let display_value = if total_phone_count > 1 {
                             format!("{} [{}]", base_value, total_phone_count)
                         } else {
                             base_value.clone()
                         };
                         fields.push(PaneField::from_prop(
-                            label,
+                            "PHONE",
                             display_value,
                             base_value,
                             "TEL",
@@ -3345,25 +5391,32 @@ where
                             None,
                         ));
                     }
+                    _ if show_empty => fields.push(PaneField::new("PHONE", EMPTY_FIELD)),
+                    _ => {}
                 }
             }
             "email" => {
-                if let Some(prop) = first_email {
-                    let label = "EMAIL".to_string();
-                    let copy_text = prop.value.trim().to_string();
-                    let display_value = if total_email_count > 1 && !copy_text.is_empty() {
-                        format!("{} [{}]", copy_text, total_email_count)
-                    } else {
-                        copy_text.clone()
-                    };
-                    fields.push(PaneField::from_prop(
-                        label,
-                        display_value,
-                        copy_text,
-                        "EMAIL",
-                        prop.seq,
-                        None,
-                    ));
+                let copy_text = first_email
+                    .map(|prop| prop.value.trim().to_string())
+                    .unwrap_or_default();
+                match first_email {
+                    Some(prop) if !copy_text.is_empty() => {
+                        let display_value = if total_email_count > 1 {
+                            format!("{} [{}]", copy_text, total_email_count)
+                        } else {
+                            copy_text.clone()
+                        };
+                        fields.push(PaneField::from_prop(
+                            "EMAIL",
+                            display_value,
+                            copy_text,
+                            "EMAIL",
+                            prop.seq,
+                            None,
+                        ));
+                    }
+                    _ if show_empty => fields.push(PaneField::new("EMAIL", EMPTY_FIELD)),
+                    _ => {}
                 }
             }
             _ => {}
@@ -3373,20 +5426,105 @@ where
     fields
 }
 
+/// N components as editable rows; blank components only when `show_empty`
+fn push_name_fields(fields: &mut Vec<PaneField>, props: &[PropRow], show_empty: bool) {
+    const LABELS: [&str; 5] = [
+        "NAME_FAMILY",
+        "NAME_GIVEN",
+        "NAME_ADDITIONAL",
+        "NAME_PREFIX",
+        "NAME_SUFFIX",
+    ];
+    let Some(prop) = props.iter().find(|p| p.field == "N") else {
+        if show_empty {
+            fields.extend(LABELS.iter().map(|label| PaneField::new(*label, EMPTY_FIELD)));
+        }
+        return;
+    };
+    for (idx, component) in name_components(&prop.value).into_iter().enumerate() {
+        let display_component = if component.is_empty() {
+            EMPTY_FIELD.to_string()
+        } else {
+            component.clone()
+        };
+        fields.push(PaneField::from_prop(
+            LABELS.get(idx).copied().unwrap_or("NAME"),
+            display_component,
+            component,
+            "N",
+            prop.seq,
+            Some(idx),
+        ));
+    }
+}
+
+/// Primary rows of a KIND:org card, standing in for the name components:
+/// ORG, TITLE and ROLE, then the contact person from RELATED (TYPE=contact
+/// preferred over the first entry)
+fn push_org_fields(fields: &mut Vec<PaneField>, props: &[PropRow], show_empty: bool) {
+    for (label, field) in [("ORG", "ORG"), ("TITLE", "TITLE"), ("ROLE", "ROLE")] {
+        let prop = props.iter().find(|p| p.field == field);
+        let value = prop
+            .map(|p| {
+                p.value
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|part| !part.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" / ")
+            })
+            .unwrap_or_default();
+        match prop {
+            Some(prop) if !value.is_empty() => {
+                fields.push(PaneField::from_prop(label, value, prop.value.trim(), field, prop.seq, None));
+            }
+            _ if show_empty => fields.push(PaneField::new(label, EMPTY_FIELD)),
+            _ => {}
+        }
+    }
+
+    let related: Vec<&PropRow> = props
+        .iter()
+        .filter(|p| p.field == "RELATED" && !p.value.trim().is_empty())
+        .collect();
+    let contact = related
+        .iter()
+        .find(|p| {
+            extract_field_params(&p.params)
+                .get("TYPE")
+                .is_some_and(|types| types.iter().any(|t| t.eq_ignore_ascii_case("contact")))
+        })
+        .or_else(|| related.first());
+    match contact {
+        Some(prop) => {
+            let value = prop.value.trim().to_string();
+            fields.push(PaneField::from_prop("CONTACT", value.clone(), value, "RELATED", prop.seq, None));
+        }
+        None if show_empty => fields.push(PaneField::new("CONTACT", EMPTY_FIELD)),
+        None => {}
+    }
+}
+
+/// KIND:org only; unlike `props_is_org`, an ORG value alone does not count
+fn props_kind_is_org(props: &[PropRow]) -> bool {
+    props.iter().any(|p| p.field.eq_ignore_ascii_case("KIND") && kind_is_org(&p.value))
+}
+
+/// KIND decides when the card has one; otherwise an ORG field marks an organization
 fn props_is_org(props: &[PropRow]) -> bool {
-    // KIND:org or presence of ORG field implies organization
-    if props.iter().any(|p| p.field.eq_ignore_ascii_case("ORG")) {
-        return true;
+    match props.iter().find(|p| p.field.eq_ignore_ascii_case("KIND")) {
+        Some(kind) => kind_is_org(&kind.value),
+        None => props.iter().any(|p| p.field.eq_ignore_ascii_case("ORG")),
     }
-    props.iter().any(|p| p.field.eq_ignore_ascii_case("KIND")
-        && (p.value.eq_ignore_ascii_case("org") || p.value.eq_ignore_ascii_case("organization")))
 }
 
 /// Build details sections from config, matching props to configured sections
 fn build_details_sections(
     props: &[PropRow],
     config: &DetailsSectionsConfig,
-    default_region: Option<&str>,
+    impp: &ImppTemplates,
+    phone: PhoneOptions<'_>,
+    show_empty: bool,
 ) -> Vec<DetailsSection> {
     use std::collections::HashSet;
     
@@ -3402,26 +5540,33 @@ fn build_details_sections(
     // Build each configured section
     for section_config in &config.sections {
         let mut fields = Vec::new();
+        let take_untyped = config.untyped_section.as_deref() == Some(section_config.name.as_str());
         
         for prop in props {
-            let field_upper = prop.field.to_uppercase();
-            
-            // Check if this field matches any of the section's configured fields
-            let matches = section_config.fields.iter().any(|f| {
-                let f_upper = f.to_uppercase();
-                if f_upper.ends_with('*') {
-                    // Wildcard match (e.g., "X-*" matches "X-TELEGRAM")
-                    field_upper.starts_with(&f_upper[..f_upper.len()-1])
-                } else {
-                    field_upper == f_upper
-                }
-            });
+            let types = extract_field_params(&prop.params)
+                .remove("TYPE")
+                .unwrap_or_default();
             
-            if matches {
+            if section_config.matches(&prop.field, &types, take_untyped) {
                 let prop_key = (prop.field.clone(), prop.seq);
                 if !used_props.contains(&prop_key) {
                     used_props.insert(prop_key);
-                    fields.push(build_details_field(prop, default_region));
+                    let field = build_details_field(prop, impp, phone);
+                    if show_empty || !field.value.trim().is_empty() {
+                        fields.push(field);
+                    }
+                }
+            }
+        }
+
+        // Name the plain fields this section lists but the card lacks
+        if show_empty {
+            for pattern in &section_config.fields {
+                if pattern.contains('*') || pattern.contains(';') {
+                    continue;
+                }
+                if !props.iter().any(|prop| prop.field.eq_ignore_ascii_case(pattern)) {
+                    fields.push(empty_details_field(pattern));
                 }
             }
         }
@@ -3446,7 +5591,10 @@ fn build_details_sections(
             continue;
         }
         
-        extras_fields.push(build_details_field(prop, default_region));
+        let field = build_details_field(prop, impp, phone);
+        if show_empty || !field.value.trim().is_empty() {
+            extras_fields.push(field);
+        }
     }
     
     if !extras_fields.is_empty() {
@@ -3459,17 +5607,36 @@ fn build_details_sections(
     sections
 }
 
+/// Placeholder row for a section field the card does not have
+fn empty_details_field(field: &str) -> DetailsField {
+    let field_upper = field.to_uppercase();
+    let label = match field_upper.as_str() {
+        "ADR" => "ADDRESS".to_string(),
+        other => other.strip_prefix("X-").unwrap_or(other).to_string(),
+    };
+    DetailsField {
+        label,
+        value: EMPTY_FIELD.to_string(),
+        copy_value: String::new(),
+        edit_value: None,
+        params: std::collections::HashMap::new(),
+        source: None,
+        origin: None,
+    }
+}
+
 /// Build a single field for the details pane
-fn build_details_field(prop: &PropRow, default_region: Option<&str>) -> DetailsField {
+fn build_details_field(prop: &PropRow, impp: &ImppTemplates, phone: PhoneOptions<'_>) -> DetailsField {
     let field_upper = prop.field.to_uppercase();
     
     // Extract all parameters (except PREF which is handled in multivalue fields)
     let params = extract_field_params(&prop.params);
     
     // Format value based on field type
+    let mut edit_value = None;
     let (label, value, copy_value) = match field_upper.as_str() {
         "TEL" => {
-            let base = vcard_io::phone_display_value(&prop.value, default_region);
+            let base = vcard_io::phone_display_value(&prop.value, phone);
             ("TEL".to_string(), base.clone(), base)
         }
         "EMAIL" => {
@@ -3484,13 +5651,19 @@ fn build_details_field(prop: &PropRow, default_region: Option<&str>) -> DetailsF
             let value = prop.value.clone();
             ("".to_string(), value.clone(), value)
         }
+        "IMPP" => match impp.link(&prop.value, extension_param(&prop.params, "X-SERVICE-TYPE")) {
+            Some((service, url)) => {
+                edit_value = Some(prop.value.clone());
+                (service.to_uppercase(), url.clone(), url)
+            }
+            None => ("IMPP".to_string(), prop.value.clone(), prop.value.clone()),
+        },
         _ => {
             // For X-* fields, use a cleaner label
-            let label = if field_upper.starts_with("X-") {
-                field_upper[2..].to_string()
-            } else {
-                field_upper.clone()
-            };
+            let label = field_upper
+                .strip_prefix("X-")
+                .unwrap_or(&field_upper)
+                .to_string();
             let value = prop.value.clone();
             (label, value.clone(), value)
         }
@@ -3507,8 +5680,10 @@ fn build_details_field(prop: &PropRow, default_region: Option<&str>) -> DetailsF
         label: display_label,
         value,
         copy_value,
+        edit_value,
         params,
         source: Some(FieldRef::new(prop.field.clone(), prop.seq)),
+        origin: extension_param(&prop.params, vcard_io::SOURCE_PARAM).map(str::to_string),
     }
 }
 
@@ -3526,8 +5701,9 @@ fn extract_field_params(params: &Value) -> std::collections::HashMap<String, Vec
     for (key, value) in obj {
         let key_upper = key.to_uppercase();
         
-        // Skip PREF (handled in multivalue) and VALUE (internal vCard param)
-        if key_upper == "PREF" || key_upper == "VALUE" {
+        // Skip PREF (handled in multivalue) and VALUE (internal vCard param);
+        // X-ABLABEL is shown with the TYPE values below
+        if key_upper == "PREF" || key_upper == "VALUE" || key_upper == "X-ABLABEL" {
             continue;
         }
         
@@ -3547,19 +5723,42 @@ fn extract_field_params(params: &Value) -> std::collections::HashMap<String, Vec
             result.insert(key_upper, values);
         }
     }
+
+    if let Some(label) = ab_label(params) {
+        result.entry("TYPE".to_string()).or_default().push(label.to_string());
+    }
     
     result
 }
 
+/// First value of an `X-` parameter, e.g. Apple's `X-SERVICE-TYPE` naming
+/// the service of an IMPP value
+fn extension_param<'a>(params: &'a Value, name: &str) -> Option<&'a str> {
+    params
+        .get("extensions")?
+        .as_array()?
+        .iter()
+        .find(|ext| {
+            ext.get("name")
+                .and_then(Value::as_str)
+                .is_some_and(|ext_name| ext_name.eq_ignore_ascii_case(name))
+        })?
+        .get("values")?
+        .as_array()?
+        .first()?
+        .as_str()
+}
+
+/// Apple `X-ABLabel` the indexer attached to a grouped property
+fn ab_label(params: &Value) -> Option<&str> {
+    params.get("x-ablabel").and_then(Value::as_str)
+}
+
 /// Extract TYPE parameter as a list of strings
 fn extract_type_list(params: &Value) -> Vec<String> {
-    let Some(entry) = params.get("type") else {
-        return Vec::new();
-    };
-    
-    match entry {
-        Value::String(s) if !s.is_empty() => vec![s.to_uppercase()],
-        Value::Array(items) => {
+    let mut types = match params.get("type") {
+        Some(Value::String(s)) if !s.is_empty() => vec![s.to_uppercase()],
+        Some(Value::Array(items)) => {
             items.iter()
                 .filter_map(|item| item.as_str())
                 .filter(|s| !s.is_empty())
@@ -3567,6 +5766,37 @@ fn extract_type_list(params: &Value) -> Vec<String> {
                 .collect()
         }
         _ => Vec::new(),
+    };
+    // Custom labels keep their case so they read apart from TYPE values
+    types.extend(ab_label(params).map(str::to_string));
+    types
+}
+
+/// Reorder props already in seq order for the multi-value list: grouped by
+/// TYPE in order of first appearance, or with the default value first. Both
+/// reorderings are stable, so card order breaks ties.
+fn sort_multivalue_props(props: &mut [&PropRow], sort: MultivalueSort) {
+    match sort {
+        MultivalueSort::Seq => {}
+        MultivalueSort::Type => {
+            let key = |prop: &PropRow| extract_type_labels(&prop.params).unwrap_or_default().to_lowercase();
+            let mut groups: Vec<String> = Vec::new();
+            for prop in props.iter() {
+                let group = key(prop);
+                if !groups.contains(&group) {
+                    groups.push(group);
+                }
+            }
+            props.sort_by_key(|prop| groups.iter().position(|group| *group == key(prop)));
+        }
+        MultivalueSort::DefaultFirst => {
+            let default = props
+                .iter()
+                .filter_map(|prop| prop.pref().map(|pref| (pref, prop.seq)))
+                .min()
+                .map(|(_, seq)| seq);
+            props.sort_by_key(|prop| Some(prop.seq) != default);
+        }
     }
 }
 
@@ -3580,9 +5810,97 @@ fn extract_type_labels(params: &Value) -> Option<String> {
     }
 }
 
+/// Fields that differ on every card and say nothing about the contact
+const COMPARE_SKIP_FIELDS: &[&str] = &["UID", "REV", "PRODID", "VERSION"];
+
+/// Pair up the values of two contacts field by field. Values that match
+/// (phones by digits, everything else case- and script-insensitively) come
+/// first, leftovers on both sides are paired as differing, and the rest
+/// exist on one side only. FN and N lead; other fields are alphabetical.
+fn compare_props(left: &[PropRow], right: &[PropRow]) -> Vec<CompareRow> {
+    let mut fields: Vec<String> = left
+        .iter()
+        .chain(right)
+        .map(|prop| prop.field.to_ascii_uppercase())
+        .filter(|field| !COMPARE_SKIP_FIELDS.contains(&field.as_str()))
+        .collect();
+    let rank = |field: &str| match field {
+        "FN" => 0,
+        "N" => 1,
+        _ => 2,
+    };
+    fields.sort_by(|a, b| rank(a).cmp(&rank(b)).then_with(|| a.cmp(b)));
+    fields.dedup();
+
+    let mut rows = Vec::new();
+    for field in fields {
+        let values = |props: &[PropRow]| -> Vec<(String, String)> {
+            props
+                .iter()
+                .filter(|prop| prop.field.eq_ignore_ascii_case(&field))
+                .map(|prop| (compare_key(&field, &prop.value), compare_display(&field, prop)))
+                .collect()
+        };
+        let mut only_left = values(left);
+        let mut only_right = values(right);
+
+        let mut same = Vec::new();
+        only_left.retain(|(key, value)| {
+            match only_right.iter().position(|(other, _)| other == key) {
+                Some(index) => {
+                    let (_, other) = only_right.remove(index);
+                    same.push((value.clone(), other));
+                    false
+                }
+                None => true,
+            }
+        });
+
+        let row = |left: String, right: String, state| CompareRow {
+            field: field.clone(),
+            left,
+            right,
+            state,
+        };
+        rows.extend(same.into_iter().map(|(l, r)| row(l, r, CompareState::Same)));
+        let mut rest_right = only_right.into_iter().map(|(_, value)| value);
+        for (_, l) in only_left {
+            rows.push(match rest_right.next() {
+                Some(r) => row(l, r, CompareState::Differs),
+                None => row(l, String::new(), CompareState::LeftOnly),
+            });
+        }
+        rows.extend(rest_right.map(|r| row(String::new(), r, CompareState::RightOnly)));
+    }
+    rows
+}
+
+/// What two values of `field` must share to count as the same. Phones compare
+/// by digits, unless there are none ("ask reception"), which would make every
+/// such value equal.
+fn compare_key(field: &str, value: &str) -> String {
+    match field {
+        "TEL" => match vcard_io::phone_digits(value) {
+            digits if digits.is_empty() => search::normalize(value.trim()),
+            digits => digits,
+        },
+        "PHOTO" | "LOGO" | "SOUND" | "KEY" => value.to_string(),
+        _ => search::normalize(value.trim()),
+    }
+}
+
+fn compare_display(field: &str, prop: &PropRow) -> String {
+    match field {
+        "ADR" => format_address_value(prop),
+        _ if prop.value.starts_with("data:") => "(embedded data)".to_string(),
+        _ => prop.value.clone(),
+    }
+}
+
 fn format_address_value(prop: &PropRow) -> String {
     if let Some(label) = prop.params.get("label").and_then(Value::as_str) {
-        label.to_string()
+        // Multiline labels read as one line in the panes
+        label.lines().map(str::trim).filter(|l| !l.is_empty()).collect::<Vec<_>>().join(", ")
     } else {
         let parts: Vec<_> = prop
             .value
@@ -3611,14 +5929,9 @@ fn name_components(value: &str) -> Vec<String> {
     components
 }
 
-/// Truncate a string to max_len characters, adding "..." if truncated
-fn truncate_value(value: &str, max_len: usize) -> String {
-    let trimmed = value.trim();
-    if trimmed.len() <= max_len {
-        trimmed.to_string()
-    } else {
-        format!("{}...", &trimmed[..max_len.saturating_sub(3)])
-    }
+/// Truncate a string to max_width terminal cells, adding "…" if truncated
+fn truncate_value(value: &str, max_width: usize) -> String {
+    text::truncate_to_width(value.trim(), max_width)
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -13,8 +13,10 @@ use ratatui_image::{Resize, StatefulImage};
 use tui_widgets::popup::Popup;
 
 use crate::config::{RgbColor, TopBarButton};
+use crate::search;
 
-use super::app::{AddFieldState, App, DetailsField, DetailsSection, MultiValueField, PaneField, PaneFocus, SearchFocus, SearchRow, STANDARD_PROPERTIES, TYPE_VALUES};
+use super::text::{display_width, pad_to_width, truncate_to_width, wrap_note};
+use super::app::{AddFieldState, App, CompareState, DetailsField, DetailsSection, MultiValueField, PaneField, PaneFocus, SearchFocus, SearchRow, STANDARD_PROPERTIES, TYPE_VALUES};
 
 const MULTIVALUE_HELP: &str =
     "j/k: nav  Space: copy  Enter: default  e: edit  q/Esc: close";
@@ -25,7 +27,18 @@ const SEARCH_HELP_INPUT: &str =
 const ADD_ALIAS_HELP: &str = "Type alias  Enter: add  Esc: cancel";
 const ADD_FIELD_HELP: &str = "j/k: nav  Enter: select  Esc: back/close";
 const PHOTO_PATH_HELP: &str = "Enter path to image  Enter: set  Esc: cancel";
+const CATEGORY_HELP: &str = "name: add  -name: remove  Enter: apply  Esc: cancel";
+const COMPONENT_EDIT_HELP: &str = "Edit components  Enter: save  Esc: cancel";
+const ADDRESS_EDIT_HELP: &str = "Tab: components/label (| between label lines)  Enter: save  Esc: cancel";
+const ADR_LABEL_PREFIX: &str = "LABEL: ";
+/// Trails contacts flagged by `flag_incomplete` in the search list
+const INCOMPLETE_MARKER: &str = " ○";
 const HELP_MODAL_FOOTER: &str = "j/k: scroll  Esc/q: close";
+const REINDEX_MODAL_HELP: &str = "Esc: cancel";
+const FIELD_POPUP_FOOTER: &str = "j/k: scroll  Esc/q: close";
+const COMPARE_FOOTER: &str = "j/k: scroll  Esc/q: close";
+/// Share of the content width given to the notes pane
+const NOTES_PANE_PERCENT: u16 = 35;
 
 pub fn render<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
     terminal.draw(|frame| draw_frame(frame, app))?;
@@ -49,9 +62,13 @@ fn draw_frame(frame: &mut Frame<'_>, app: &mut App) {
     draw_alias_modal(frame, size, app);
     draw_add_field_modal(frame, size, app);
     draw_photo_path_modal(frame, size, app);
+    draw_category_modal(frame, size, app);
+    draw_component_modal(frame, size, app);
     draw_multivalue_modal(frame, size, app);
     draw_confirm_modal(frame, size, app);
     draw_help_modal(frame, size, app);
+    draw_field_popup(frame, size, app);
+    draw_compare_modal(frame, size, app);
     draw_reindex_modal(frame, size, app);
     draw_share_modal(frame, size, app);
 }
@@ -114,6 +131,14 @@ fn draw_header_left(frame: &mut Frame<'_>, area: Rect, app: &App) {
         spans.push(Span::styled("No contacts indexed", header_style));
     }
 
+    // The search pane is closed, but its text still scopes the list
+    if !app.show_search {
+        if let Some(filter) = app.active_filter() {
+            spans.push(Span::raw("   "));
+            spans.push(Span::styled(format!("FILTER: {}", filter), selection_style(app)));
+        }
+    }
+
     frame.render_widget(Paragraph::new(Line::from(spans)), area);
 }
 
@@ -221,13 +246,23 @@ fn draw_content(frame: &mut Frame<'_>, area: Rect, app: &mut App) {
     if area.height <= top_gap {
         return;
     }
-    let content_area = Rect {
+    let mut content_area = Rect {
         x: area.x,
         y: area.y + top_gap,
         width: area.width,
         height: area.height - top_gap,
     };
 
+    // Notes take a full-height column on the right when toggled on
+    if app.show_notes {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Percentage(NOTES_PANE_PERCENT)])
+            .split(content_area);
+        content_area = columns[0];
+        draw_notes_pane(frame, columns[1], app);
+    }
+
     let image_height = app.image_pane_height().min(content_area.height);
     let upper_height = image_height.min(content_area.height);
 
@@ -316,7 +351,7 @@ fn draw_search_header(frame: &mut Frame<'_>, area: Rect, app: &App, active: bool
         return;
     }
 
-    let label = "SEARCH: ";
+    let label = if app.show_recent { "RECENT: " } else { "SEARCH: " };
     let label_style = header_text_style(app);
     let value_style = if active {
         selection_style(app)
@@ -383,7 +418,7 @@ fn draw_search_list(frame: &mut Frame<'_>, area: Rect, app: &App) {
     } else {
         app.search_rows
             .iter()
-            .map(|row| build_search_item(row, app))
+            .map(|row| build_search_item(row, app, area.width as usize))
             .collect()
     };
 
@@ -433,7 +468,7 @@ fn draw_main_card(frame: &mut Frame<'_>, area: Rect, app: &App) {
         let label_width = app
             .card_fields
             .iter()
-            .map(|f| f.label.len() + 1) // +1 for colon
+            .map(|f| display_width(&f.label) + 1) // +1 for colon
             .max()
             .unwrap_or(0);
 
@@ -600,11 +635,13 @@ fn draw_confirm_modal(frame: &mut Frame<'_>, area: Rect, app: &mut App) {
     if width > area.width { width = area.width; }
 
     let content_width = width.saturating_sub(2) as usize;
-    let lines = vec![
-        Line::from(modal.message.clone()),
-        Line::from("".to_string()),
-        Line::from(CONFIRM_HELP.to_string()),
-    ];
+    let mut lines = vec![Line::from(modal.message.clone())];
+    if !modal.details.is_empty() {
+        lines.push(Line::from("".to_string()));
+        lines.extend(modal.details.iter().map(|detail| Line::from(detail.clone())));
+    }
+    lines.push(Line::from("".to_string()));
+    lines.push(Line::from(CONFIRM_HELP.to_string()));
     let body_text = ratatui::text::Text::from(
         lines
             .into_iter()
@@ -747,13 +784,178 @@ fn draw_help_modal(frame: &mut Frame<'_>, area: Rect, app: &mut App) {
     frame.render_widget(paragraph, inner);
 }
 
+fn draw_field_popup(frame: &mut Frame<'_>, area: Rect, app: &mut App) {
+    let header_style = header_text_style(app);
+    let border_s = border_style(app, true);
+    let Some(popup) = app.field_popup.as_mut() else {
+        return;
+    };
+
+    // Same width as the help modal; only as tall as the wrapped value needs
+    let width = area.width.saturating_mul(2).saturating_div(3).max(40).min(area.width);
+    let lines = wrap_note(&popup.value, width.saturating_sub(4) as usize);
+    let max_height = area.height.saturating_mul(4).saturating_div(5).max(5).min(area.height);
+    let height = (lines.len() as u16).saturating_add(2).clamp(3, max_height);
+
+    let x = area.x + (area.width.saturating_sub(width)) / 2;
+    let y = area.y + (area.height.saturating_sub(height)) / 2;
+    let modal_area = Rect::new(x, y, width, height);
+    frame.render_widget(Clear, modal_area);
+
+    let view = &mut popup.view;
+    view.total_lines = lines.len();
+    view.viewport_height = height.saturating_sub(2) as usize;
+    let max_scroll = view.total_lines.saturating_sub(view.viewport_height);
+    view.scroll = view.scroll.min(max_scroll);
+    let indicator = match (view.can_scroll_up(), view.can_scroll_down()) {
+        (true, true) => "▲▼",
+        (true, false) => "▲ ",
+        (false, true) => " ▼",
+        (false, false) => "",
+    };
+
+    let title = Line::from(vec![
+        Span::styled(format!(" {} ", popup.label.to_uppercase()), header_style),
+        Span::styled(indicator, header_style),
+    ]);
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(border_s)
+        .title(title)
+        .title_alignment(Alignment::Center);
+    if view.total_lines > view.viewport_height {
+        block = block.title_bottom(Line::from(Span::styled(
+            format!(" {} ", FIELD_POPUP_FOOTER),
+            header_style,
+        )));
+    }
+
+    let inner = block.inner(modal_area);
+    frame.render_widget(block, modal_area);
+
+    let inner = Rect {
+        x: inner.x.saturating_add(1),
+        width: inner.width.saturating_sub(2),
+        ..inner
+    };
+    let visible: Vec<Line> = lines
+        .into_iter()
+        .skip(view.scroll)
+        .take(view.viewport_height)
+        .map(Line::from)
+        .collect();
+    frame.render_widget(Paragraph::new(visible), inner);
+}
+
+fn draw_compare_modal(frame: &mut Frame<'_>, area: Rect, app: &mut App) {
+    let header_style = header_text_style(app);
+    let same_style = separator_style(app);
+    let differs_style = match_highlight_style(app);
+    let border_s = border_style(app, true);
+    let Some(modal) = app.compare_modal.as_mut() else {
+        return;
+    };
+
+    let width = area.width.saturating_mul(4).saturating_div(5).max(60).min(area.width);
+    let max_height = area.height.saturating_mul(4).saturating_div(5).max(6).min(area.height);
+    let height = (modal.rows.len() as u16).saturating_add(3).clamp(4, max_height);
+    let x = area.x + (area.width.saturating_sub(width)) / 2;
+    let y = area.y + (area.height.saturating_sub(height)) / 2;
+    let modal_area = Rect::new(x, y, width, height);
+    frame.render_widget(Clear, modal_area);
+
+    let view = &mut modal.view;
+    view.total_lines = modal.rows.len();
+    view.viewport_height = height.saturating_sub(3) as usize;
+    let max_scroll = view.total_lines.saturating_sub(view.viewport_height);
+    view.scroll = view.scroll.min(max_scroll);
+    let indicator = match (view.can_scroll_up(), view.can_scroll_down()) {
+        (true, true) => "▲▼",
+        (true, false) => "▲ ",
+        (false, true) => " ▼",
+        (false, false) => "",
+    };
+
+    let count = |state| modal.rows.iter().filter(|row| row.state == state).count();
+    let one_side = count(CompareState::LeftOnly) + count(CompareState::RightOnly);
+    let summary = format!(
+        " {} same  {} differ  {} one side  {} ",
+        count(CompareState::Same),
+        count(CompareState::Differs),
+        one_side,
+        COMPARE_FOOTER
+    );
+    let title = Line::from(vec![
+        Span::styled(" COMPARE ", header_style),
+        Span::styled(indicator, header_style),
+    ]);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(border_s)
+        .title(title)
+        .title_alignment(Alignment::Center)
+        .title_bottom(Line::from(Span::styled(summary, header_style)));
+    let inner = block.inner(modal_area);
+    frame.render_widget(block, modal_area);
+
+    // marker, field label, then the two value columns split by " │ "
+    let inner_width = inner.width.saturating_sub(2) as usize;
+    let label_width = modal
+        .rows
+        .iter()
+        .map(|row| display_width(&row.field))
+        .max()
+        .unwrap_or(0)
+        .clamp(2, 10);
+    let value_width = inner_width.saturating_sub(label_width + 2 + 3) / 2;
+    let columns = |label: &str, left: &str, right: &str| {
+        format!(
+            "{} {} │ {}",
+            pad_to_width(&truncate_to_width(label, label_width), label_width),
+            pad_to_width(&truncate_to_width(left, value_width), value_width),
+            truncate_to_width(right, value_width)
+        )
+    };
+
+    let mut lines = vec![Line::from(Span::styled(
+        format!("  {}", columns("", &modal.left_name, &modal.right_name)),
+        header_style,
+    ))];
+    lines.extend(
+        modal
+            .rows
+            .iter()
+            .skip(view.scroll)
+            .take(view.viewport_height)
+            .map(|row| {
+                let (marker, style) = match row.state {
+                    CompareState::Same => ("=", same_style),
+                    CompareState::Differs => ("≠", differs_style),
+                    CompareState::LeftOnly => ("<", Style::default()),
+                    CompareState::RightOnly => (">", Style::default()),
+                };
+                Line::from(Span::styled(
+                    format!("{marker} {}", columns(&row.field, &row.left, &row.right)),
+                    style,
+                ))
+            }),
+    );
+
+    let inner = Rect {
+        x: inner.x.saturating_add(1),
+        width: inner.width.saturating_sub(2),
+        ..inner
+    };
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
 fn draw_reindex_modal(frame: &mut Frame<'_>, area: Rect, app: &App) {
     let Some(modal) = &app.reindex_modal else {
         return;
     };
 
     let width = 30u16.min(area.width);
-    let height = 3u16.min(area.height);
+    let height = 4u16.min(area.height);
 
     let x = area.x + (area.width.saturating_sub(width)) / 2;
     let y = area.y + (area.height.saturating_sub(height)) / 2;
@@ -768,9 +970,12 @@ fn draw_reindex_modal(frame: &mut Frame<'_>, area: Rect, app: &App) {
     let inner = block.inner(modal_area);
     frame.render_widget(block, modal_area);
 
-    let text = Paragraph::new(modal.message.clone())
-        .alignment(Alignment::Center)
-        .style(header_text_style(app));
+    let text = Paragraph::new(vec![
+        Line::from(modal.message.clone()),
+        Line::from(REINDEX_MODAL_HELP),
+    ])
+    .alignment(Alignment::Center)
+    .style(header_text_style(app));
     frame.render_widget(text, inner);
 }
 
@@ -798,7 +1003,14 @@ fn draw_share_modal(frame: &mut Frame<'_>, area: Rect, app: &App) {
     frame.render_widget(Clear, modal_area);
 
     let title = Line::from(Span::styled(" SHARE ", header_text_style(app)));
-    let footer = Line::from(Span::styled(" Esc: close ", header_text_style(app)));
+    let footer = Line::from(Span::styled(
+        format!(
+            " v{} EC:{}  Esc: close ",
+            modal.version,
+            modal.error_correction.as_str()
+        ),
+        header_text_style(app),
+    ));
 
     let block = Block::default()
         .borders(Borders::ALL)
@@ -861,6 +1073,12 @@ fn draw_image(frame: &mut Frame<'_>, area: Rect, app: &mut App) {
         return;
     }
 
+    // ui.pane.image.mode = "none"
+    if app.photo_data.is_some() {
+        render_centered_words(frame, content_area, "[PHOTO PRESENT]");
+        return;
+    }
+
     if let Some(contact) = &app.current_contact {
         let message = if contact.has_photo {
             "PHOTO NOT EMBEDDED"
@@ -871,6 +1089,56 @@ fn draw_image(frame: &mut Frame<'_>, area: Rect, app: &mut App) {
     }
 }
 
+fn draw_notes_pane(frame: &mut Frame<'_>, area: Rect, app: &mut App) {
+    let focused = matches!(app.focused_pane, PaneFocus::Notes);
+
+    if area.width < 2 || area.height < 2 {
+        return;
+    }
+
+    let header_area = Rect { x: area.x, y: area.y, width: area.width, height: 1 };
+    let content_area = render_panel_borders(frame, area, app, focused);
+    frame.render_widget(Clear, content_area);
+
+    let text = app.notes_text();
+    let lines = wrap_note(&text, content_area.width as usize);
+
+    let view = &mut app.notes_view;
+    view.total_lines = lines.len();
+    view.viewport_height = content_area.height as usize;
+    let max_scroll = view.total_lines.saturating_sub(view.viewport_height);
+    view.scroll = view.scroll.min(max_scroll);
+    let indicator = match (view.can_scroll_up(), view.can_scroll_down()) {
+        (true, true) => " ▲▼",
+        (true, false) => " ▲",
+        (false, true) => " ▼",
+        (false, false) => "",
+    };
+    let scroll = view.scroll;
+    let viewport_height = view.viewport_height;
+
+    render_panel_header(frame, header_area, &format!("NOTES{indicator}"), '4', app);
+
+    if content_area.width == 0 || content_area.height == 0 {
+        return;
+    }
+
+    if lines.is_empty() {
+        if app.current_contact.is_some() {
+            render_centered_words(frame, content_area, "NO NOTES");
+        }
+        return;
+    }
+
+    let visible: Vec<Line> = lines
+        .into_iter()
+        .skip(scroll)
+        .take(viewport_height)
+        .map(Line::from)
+        .collect();
+    frame.render_widget(Paragraph::new(visible), content_area);
+}
+
 fn image_render_area(app: &App, area: Rect) -> Rect {
     if area.width == 0 || area.height == 0 {
         return area;
@@ -927,7 +1195,7 @@ fn div_ceil_u32(value: u32, divisor: u32) -> u32 {
     if divisor == 0 {
         return 0;
     }
-    value / divisor + u32::from(value % divisor != 0)
+    value / divisor + u32::from(!value.is_multiple_of(divisor))
 }
 
 fn draw_details_pane(frame: &mut Frame<'_>, area: Rect, app: &App) {
@@ -974,14 +1242,14 @@ fn draw_details_pane(frame: &mut Frame<'_>, area: Rect, app: &App) {
         
         // Calculate column widths for this section
         let label_width = section.fields.iter()
-            .map(|f| f.label.len())
+            .map(|f| display_width(&f.label))
             .max()
             .unwrap_or(0)
             .max(5); // Minimum label width
         
         // Calculate max value width for alignment
         let value_width = section.fields.iter()
-            .map(|f| f.value.len())
+            .map(|f| display_width(&f.value))
             .max()
             .unwrap_or(0)
             .max(10); // Minimum value width
@@ -1057,7 +1325,7 @@ fn render_section_header(name: &str, width: usize) -> String {
     let prefix = "─ ";
     let suffix = " ";
     let name_part = format!("{}{}{}", prefix, name, suffix);
-    let remaining = width.saturating_sub(name_part.len());
+    let remaining = width.saturating_sub(display_width(&name_part));
     let dashes = "─".repeat(remaining);
     format!("{}{}", name_part, dashes)
 }
@@ -1085,7 +1353,7 @@ fn collect_section_property_columns(section: &DetailsSection) -> Vec<PropColumn>
             entry.0 = entry.0.max(values.len());
             // Update max width
             for v in values {
-                entry.1 = entry.1.max(v.len());
+                entry.1 = entry.1.max(display_width(v));
             }
         }
     }
@@ -1123,7 +1391,7 @@ fn build_section_column_header(
     for prop in prop_columns {
         // One header per column (repeated if count > 1)
         for _ in 0..prop.count {
-            spans.push(Span::styled(format!("{:<width$}", prop.name, width = prop.width), header_style));
+            spans.push(Span::styled(pad_to_width(&prop.name, prop.width), header_style));
         }
     }
     
@@ -1144,11 +1412,11 @@ fn build_details_field_line(
     let mut spans: Vec<Span> = Vec::new();
     
     // Label column (right-padded)
-    let label_text = format!("{:width$}: ", field.label, width = label_width);
+    let label_text = format!("{}: ", pad_to_width(&field.label, label_width));
     spans.push(Span::styled(label_text, label_style));
     
     // Value column (right-padded to value_width)
-    let value_text = format!("{:<width$}", field.value, width = value_width);
+    let value_text = pad_to_width(&field.value, value_width);
     spans.push(Span::styled(value_text, value_style));
     
     // Gap before property columns
@@ -1164,7 +1432,13 @@ fn build_details_field_line(
                 .and_then(|v| v.get(i))
                 .map(|s| s.as_str())
                 .unwrap_or("");
-            spans.push(Span::styled(format!("{:<width$}", cell, width = prop.width), value_style));
+            spans.push(Span::styled(pad_to_width(cell, prop.width), value_style));
+        }
+    }
+
+    if app.show_sources {
+        if let Some(origin) = &field.origin {
+            spans.push(Span::styled(format!(" [{}]", origin), header_text_style(app)));
         }
     }
     
@@ -1176,6 +1450,10 @@ fn draw_footer(frame: &mut Frame<'_>, area: Rect, app: &App) {
         ADD_ALIAS_HELP.to_string()
     } else if app.photo_path_modal.is_some() {
         PHOTO_PATH_HELP.to_string()
+    } else if app.category_modal.is_some() {
+        CATEGORY_HELP.to_string()
+    } else if let Some(modal) = &app.component_modal {
+        if modal.label.is_some() { ADDRESS_EDIT_HELP } else { COMPONENT_EDIT_HELP }.to_string()
     } else if let Some(modal) = app.multivalue_modal() {
         if modal.field() == MultiValueField::Alias {
             ALIAS_MODAL_HELP.to_string()
@@ -1188,8 +1466,10 @@ fn draw_footer(frame: &mut Frame<'_>, area: Rect, app: &App) {
         match app.search_focus {
             SearchFocus::Input => SEARCH_HELP_INPUT.to_string(),
             SearchFocus::Results => {
-                if app.show_marked_only {
-                    "Space: unmark  M: show search results  /: focus search  Enter: open & close search  Esc: close".to_string()
+                if app.show_recent {
+                    "R: back to all contacts  Space: mark  /: focus search  Enter: open & close search  Esc: close".to_string()
+                } else if app.show_marked_only {
+                    "Space: unmark  T: tag  =: compare  M: show search results  /: focus search  Enter: open & close search  Esc: close".to_string()
                 } else {
                     "Space: mark  M: show marked only  /: focus search  Enter: open & close search  Esc: close".to_string()
                 }
@@ -1399,6 +1679,52 @@ fn draw_add_field_modal(frame: &mut Frame<'_>, area: Rect, app: &mut App) {
     }
 }
 
+fn draw_component_modal(frame: &mut Frame<'_>, area: Rect, app: &mut App) {
+    let Some(modal) = &app.component_modal else { return; };
+
+    let label = format!("{}: ", modal.target.field);
+    let mut lines = vec![
+        Line::from(Span::styled(modal.hint, header_text_style(app))),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(label.clone(), header_text_style(app)),
+            Span::raw(modal.input.value().to_string()),
+        ]),
+    ];
+    if let Some(label_input) = &modal.label {
+        lines.push(Line::from(vec![
+            Span::styled(ADR_LABEL_PREFIX, header_text_style(app)),
+            Span::raw(label_input.value().to_string()),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(if modal.label.is_some() {
+        ADDRESS_EDIT_HELP
+    } else {
+        COMPONENT_EDIT_HELP
+    }));
+    let (cursor, prefix_len, row) = match &modal.label {
+        Some(label_input) if modal.label_focused => {
+            (label_input.visual_cursor(), ADR_LABEL_PREFIX.len(), 3)
+        }
+        _ => (modal.input.visual_cursor(), label.len(), 2),
+    };
+
+    let title_line = Line::from(Span::styled(modal.title, header_text_style(app)));
+    let popup = Popup::new(ratatui::text::Text::from(lines))
+        .title(title_line)
+        .border_style(border_style(app, true));
+
+    frame.render_stateful_widget_ref(popup, area, &mut app.modal_popup);
+
+    if let Some(popup_area) = app.modal_popup.area() {
+        let inner = Block::default().borders(Borders::ALL).inner(*popup_area);
+        let x = inner.x.saturating_add(prefix_len as u16 + cursor as u16);
+        let y = inner.y.saturating_add(row);
+        frame.set_cursor_position((x, y));
+    }
+}
+
 fn draw_photo_path_modal(frame: &mut Frame<'_>, area: Rect, app: &mut App) {
     if app.photo_path_modal.is_none() { return; }
 
@@ -1456,6 +1782,41 @@ fn draw_photo_path_modal(frame: &mut Frame<'_>, area: Rect, app: &mut App) {
     }
 }
 
+fn draw_category_modal(frame: &mut Frame<'_>, area: Rect, app: &mut App) {
+    let Some(modal) = app.category_modal.as_ref() else { return; };
+
+    let label = "CATEGORY: ";
+    let value = modal.input.value().to_string();
+    let cursor = modal.input.visual_cursor();
+    let lines = vec![
+        Line::from(Span::styled(
+            format!("Tag {} marked contacts", app.marked.len()),
+            header_text_style(app),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(label, header_text_style(app)),
+            Span::raw(value),
+        ]),
+        Line::from(""),
+        Line::from(CATEGORY_HELP.to_string()),
+    ];
+
+    let title_line = Line::from(Span::styled("TAG MARKED", header_text_style(app)));
+    let popup = Popup::new(ratatui::text::Text::from(lines))
+        .title(title_line)
+        .border_style(border_style(app, true));
+
+    frame.render_stateful_widget_ref(popup, area, &mut app.modal_popup);
+
+    if let Some(popup_area) = app.modal_popup.area() {
+        let inner = Block::default().borders(Borders::ALL).inner(*popup_area);
+        let x = inner.x.saturating_add(label.len() as u16 + cursor as u16);
+        let y = inner.y.saturating_add(2);
+        frame.set_cursor_position((x, y));
+    }
+}
+
 fn field_line(
     app: &App,
     field: &PaneField,
@@ -1470,7 +1831,7 @@ fn field_line(
             .unwrap_or(false);
     let (label_style, value_style) = line_styles(app, highlight || editing);
     // Pad the label (including colon) to consistent width, then add space before value
-    let label = format!("{:width$} ", format!("{}:", field.label), width = label_width);
+    let label = format!("{} ", pad_to_width(&format!("{}:", field.label), label_width));
     let mut spans = vec![Span::styled(label.clone(), label_style)];
     let mut cursor = None;
 
@@ -1496,17 +1857,72 @@ fn line_styles(app: &App, highlight: bool) -> (Style, Style) {
     }
 }
 
-fn build_search_item(row: &SearchRow, app: &App) -> ListItem<'static> {
+fn build_search_item(row: &SearchRow, app: &App, width: usize) -> ListItem<'static> {
     let indent = "  ".repeat(row.depth as usize);
     let mut text = String::with_capacity(indent.len() + row.text.len());
     text.push_str(&indent);
     text.push_str(&row.text);
+    let marker = if row.incomplete { INCOMPLETE_MARKER } else { "" };
+    // Leave room for the highlight symbol and the marker
+    let truncated = truncate_to_width(&text, width.saturating_sub(1 + display_width(marker)));
+
+    if !row.selectable() || row.folded > 0 {
+        return ListItem::new(Line::from(truncated)).style(header_text_style(app));
+    }
+
+    let name_offset = indent.len() + row.name_start;
+    let mut spans = highlight_search_name(app, &text, name_offset, truncated);
+    if !marker.is_empty() {
+        spans.push(Span::styled(marker, header_text_style(app)));
+    }
+    ListItem::new(Line::from(spans))
+}
+
+/// Split a search row into spans, highlighting the query matches in the name
+/// that starts at `name_offset` in `text`
+fn highlight_search_name(
+    app: &App,
+    text: &str,
+    name_offset: usize,
+    truncated: String,
+) -> Vec<Span<'static>> {
+    let Some(query) = search::normalize_query(app.search_input.value()) else {
+        return vec![Span::raw(truncated)];
+    };
 
-    let mut item = ListItem::new(Line::from(text));
-    if !row.selectable() {
-        item = item.style(header_text_style(app));
+    // Only the untruncated prefix can carry highlights; the ellipsis stays plain
+    let kept = if truncated.len() == text.len() {
+        text.len()
+    } else {
+        truncated.len().saturating_sub("…".len())
+    };
+    let style = match_highlight_style(app);
+
+    let mut spans = Vec::new();
+    let mut cursor = 0;
+    for range in search::match_ranges(&text[name_offset..], &query) {
+        let start = name_offset + range.start;
+        let end = (name_offset + range.end).min(kept);
+        if start >= end {
+            break;
+        }
+        if start > cursor {
+            spans.push(Span::raw(truncated[cursor..start].to_string()));
+        }
+        spans.push(Span::styled(truncated[start..end].to_string(), style));
+        cursor = end;
+    }
+    if cursor < truncated.len() {
+        spans.push(Span::raw(truncated[cursor..].to_string()));
     }
-    item
+    spans
+}
+
+fn match_highlight_style(app: &App) -> Style {
+    let colors = app.ui_colors();
+    Style::default()
+        .fg(color(colors.selection_bg))
+        .add_modifier(Modifier::BOLD)
 }
 
 fn selection_style(app: &App) -> Style {
@@ -1555,10 +1971,11 @@ fn render_panel_header(
 
     // Build the line: "▐TITLE ... | #▌"
     let suffix = format!("| {}", panel_number);
-    let title_len = title.len();
-    let suffix_len = suffix.len();
+    let suffix_len = display_width(&suffix);
     // Available space for content (excluding the two half-block chars)
     let inner_width = (area.width as usize).saturating_sub(2);
+    let title = truncate_to_width(title, inner_width.saturating_sub(suffix_len + 1));
+    let title_len = display_width(&title);
 
     // Calculate padding between title and suffix
     let padding_needed = inner_width.saturating_sub(title_len + suffix_len);
@@ -1566,7 +1983,7 @@ fn render_panel_header(
 
     let line = Line::from(vec![
         Span::styled(LEFT_HALF_BLOCK, header_style),
-        Span::styled(title.to_string(), header_style),
+        Span::styled(title, header_style),
         Span::styled(padding, header_style),
         Span::styled(suffix, header_style),
         Span::styled(RIGHT_HALF_BLOCK, header_style),
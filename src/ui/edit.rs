@@ -7,6 +7,8 @@ pub struct FieldRef {
     pub field: String,
     pub seq: i64,
     pub component: Option<usize>,
+    /// Targets the property's `X-ABLabel` rather than its value
+    pub label: bool,
 }
 
 impl FieldRef {
@@ -15,6 +17,15 @@ impl FieldRef {
             field: field.into(),
             seq,
             component: None,
+            label: false,
+        }
+    }
+
+    /// The `X-ABLabel` of the property at `seq`
+    pub fn label_of(field: impl Into<String>, seq: i64) -> Self {
+        Self {
+            label: true,
+            ..Self::new(field, seq)
         }
     }
 
@@ -23,6 +34,7 @@ impl FieldRef {
             field: field.into(),
             seq,
             component: Some(component),
+            label: false,
         }
     }
 }
@@ -2,3 +2,4 @@ pub mod app;
 pub mod draw;
 pub mod edit;
 pub mod panes;
+pub mod text;
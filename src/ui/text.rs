@@ -0,0 +1,160 @@
+//! Display-width helpers for laying out text in terminal cells.
+//!
+//! Wide characters (CJK, most emoji) occupy two cells, combining marks none,
+//! so byte or char counts cannot be used for padding and truncation.
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+const ELLIPSIS: &str = "…";
+const BULLET: &str = "• ";
+const BULLET_MARKERS: [&str; 3] = ["- ", "* ", "• "];
+
+/// Number of terminal cells needed to render `text`.
+pub fn display_width(text: &str) -> usize {
+    UnicodeWidthStr::width(text)
+}
+
+/// Left-align `text` and pad it with spaces to `width` cells.
+pub fn pad_to_width(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(display_width(text));
+    let mut padded = String::with_capacity(text.len() + padding);
+    padded.push_str(text);
+    padded.extend(std::iter::repeat_n(' ', padding));
+    padded
+}
+
+/// Truncate `text` to at most `max_width` cells, ending with an ellipsis when
+/// shortened. Grapheme clusters are never split.
+pub fn truncate_to_width(text: &str, max_width: usize) -> String {
+    if display_width(text) <= max_width {
+        return text.to_string();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+
+    let budget = max_width - display_width(ELLIPSIS);
+    let mut used = 0;
+    let mut truncated = String::new();
+    for grapheme in text.graphemes(true) {
+        let width = display_width(grapheme);
+        if used + width > budget {
+            break;
+        }
+        used += width;
+        truncated.push_str(grapheme);
+    }
+    truncated.push_str(ELLIPSIS);
+    truncated
+}
+
+/// Soft-wrap free-form note text to `width` cells. Lines starting with `-`,
+/// `*` or `•` become bullets whose wrapped lines hang under the bullet text;
+/// blank lines are kept as paragraph breaks.
+pub fn wrap_note(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for raw in text.lines() {
+        let trimmed = raw.trim_start();
+        let indent = &raw[..raw.len() - trimmed.len()];
+        let (first_prefix, rest_prefix, body) = match BULLET_MARKERS
+            .iter()
+            .find_map(|marker| trimmed.strip_prefix(marker))
+        {
+            Some(body) => (
+                format!("{indent}{BULLET}"),
+                " ".repeat(display_width(indent) + display_width(BULLET)),
+                body,
+            ),
+            None => (indent.to_string(), indent.to_string(), trimmed),
+        };
+
+        let mut current = first_prefix;
+        let mut prefix_width = display_width(&current);
+        let mut has_words = false;
+        for word in body.split_whitespace() {
+            let word_width = display_width(word);
+            let used = display_width(&current);
+            if has_words && used + 1 + word_width > width {
+                lines.push(std::mem::replace(&mut current, rest_prefix.clone()));
+                prefix_width = display_width(&rest_prefix);
+                has_words = false;
+            }
+            if has_words {
+                current.push(' ');
+            }
+            // Break words that cannot fit on a line of their own
+            for grapheme in word.graphemes(true) {
+                let used = display_width(&current);
+                if used > prefix_width && used + display_width(grapheme) > width {
+                    lines.push(std::mem::replace(&mut current, rest_prefix.clone()));
+                    prefix_width = display_width(&rest_prefix);
+                }
+                current.push_str(grapheme);
+            }
+            has_words = true;
+        }
+        lines.push(if has_words { current } else { String::new() });
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_width_mixed_names() {
+        assert_eq!(display_width("Ana"), 3);
+        assert_eq!(display_width("山田太郎"), 8);
+        assert_eq!(display_width("Ana 山田"), 8);
+        assert_eq!(display_width("Zoe\u{301}"), 3);
+        assert_eq!(display_width("Bob 😀"), 6);
+    }
+
+    #[test]
+    fn test_pad_to_width_aligns_wide_and_narrow() {
+        let names = ["Ana", "山田太郎", "Bob 😀"];
+        for name in names {
+            assert_eq!(display_width(&pad_to_width(name, 10)), 10, "{name}");
+        }
+        // Never shortens text that is already wider
+        assert_eq!(pad_to_width("山田太郎", 4), "山田太郎");
+    }
+
+    #[test]
+    fn test_truncate_to_width_respects_cells() {
+        assert_eq!(truncate_to_width("山田太郎", 8), "山田太郎");
+        assert_eq!(truncate_to_width("山田太郎", 7), "山田太…");
+        assert_eq!(truncate_to_width("山田太郎", 6), "山田…");
+        assert!(display_width(&truncate_to_width("山田太郎", 6)) <= 6);
+        assert_eq!(truncate_to_width("abc", 0), "");
+    }
+
+    #[test]
+    fn test_truncate_to_width_keeps_graphemes_whole() {
+        // "e" + combining acute accent must stay together
+        assert_eq!(truncate_to_width("Zoe\u{301}lle", 4), "Zoe\u{301}…");
+    }
+
+    #[test]
+    fn test_wrap_note_bullets_and_paragraphs() {
+        let note = "Met at the conference in Lisbon\n\n- prefers email over phone calls\n* vegetarian";
+        assert_eq!(
+            wrap_note(note, 16),
+            vec![
+                "Met at the",
+                "conference in",
+                "Lisbon",
+                "",
+                "• prefers email",
+                "  over phone",
+                "  calls",
+                "• vegetarian",
+            ]
+        );
+        // Over-long words are split instead of overflowing
+        assert_eq!(wrap_note("abcdefgh", 3), vec!["abc", "def", "gh"]);
+        assert!(wrap_note("", 10).is_empty());
+    }
+}
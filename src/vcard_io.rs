@@ -1,11 +1,15 @@
+use std::borrow::Cow;
+use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::{anyhow, Context, Result};
 use rlibphonenumber::{region_code::RegionCode, PhoneNumber, PhoneNumberFormat, PHONE_NUMBER_UTIL};
 use uuid::Uuid;
 use vcard4::parameter::Parameters;
-use vcard4::property::{DateTimeProperty, TextListProperty, TextOrUriProperty, TextProperty};
+use vcard4::property::{
+    DateTimeProperty, Kind, KindProperty, TextListProperty, TextOrUriProperty, TextProperty,
+};
 use vcard4::{parse, DateTime, Uri, Vcard};
 
 use crate::crypto::CryptoProvider;
@@ -25,11 +29,53 @@ pub struct ParsedCards {
     pub changed: bool,
 }
 
+/// Phone number handling applied while parsing, editing and displaying cards.
+#[derive(Debug, Clone, Copy)]
+pub struct PhoneOptions<'a> {
+    /// Region used to interpret numbers without a country code.
+    pub default_region: Option<&'a str>,
+    /// When false, TEL values are kept exactly as stored.
+    pub normalize: bool,
+}
+
+impl<'a> PhoneOptions<'a> {
+    pub fn new(default_region: Option<&'a str>, normalize: bool) -> Self {
+        Self {
+            default_region,
+            normalize,
+        }
+    }
+}
+
+impl Default for PhoneOptions<'_> {
+    fn default() -> Self {
+        Self::new(None, true)
+    }
+}
+
+/// Context on the error `parse_file` returns when the file itself could not
+/// be decrypted, as opposed to read or parsed
+#[derive(Debug)]
+pub struct DecryptError {
+    pub path: PathBuf,
+}
+
+impl fmt::Display for DecryptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to decrypt vCard file at {}", self.path.display())
+    }
+}
+
+/// The file behind `err` if it is a decrypt failure from `parse_file`
+pub fn decrypt_error_path(err: &anyhow::Error) -> Option<&Path> {
+    err.downcast_ref::<DecryptError>().map(|err| err.path.as_path())
+}
+
 /// Parse a UTF-8 encoded vCard file into `Vcard` values.
 /// Decrypts the file using the provided crypto provider.
 pub fn parse_file(
     path: &Path,
-    default_region: Option<&str>,
+    phone: PhoneOptions<'_>,
     provider: &dyn CryptoProvider,
 ) -> Result<ParsedCards> {
     // Read and decrypt file
@@ -37,11 +83,11 @@ pub fn parse_file(
         .with_context(|| format!("failed to read vCard file at {}", path.display()))?;
     let decrypted = provider
         .decrypt(&encrypted)
-        .with_context(|| format!("failed to decrypt vCard file at {}", path.display()))?;
+        .map_err(|err| err.context(DecryptError { path: path.to_path_buf() }))?;
     let input = String::from_utf8(decrypted)
         .with_context(|| format!("vCard file {} contains invalid UTF-8", path.display()))?;
 
-    let parsed = parse_str(&input, default_region)?;
+    let parsed = parse_str(&input, phone)?;
     if parsed.changed {
         write_cards(path, &parsed.cards, provider)?;
     }
@@ -49,20 +95,19 @@ pub fn parse_file(
 }
 
 /// Parse a UTF-8 string into `Vcard` values.
-pub fn parse_str(input: &str, default_region: Option<&str>) -> Result<ParsedCards> {
-    let mut cards = parse(input)
+pub fn parse_str(input: &str, phone: PhoneOptions<'_>) -> Result<ParsedCards> {
+    let input = add_missing_fn(input);
+    let input = lowercase_registered_types(&input);
+    let mut cards = parse(input.as_ref())
         .map_err(|err| anyhow!(err))
         .context("parsing vCard data")?;
-    let changed = normalize_cards(&mut cards, default_region);
+    let changed = normalize_cards(&mut cards, phone);
     Ok(ParsedCards { cards, changed })
 }
 
 /// Parse a UTF-8 string and also capture the raw block for each vCard.
-pub fn parse_str_with_source(
-    input: &str,
-    default_region: Option<&str>,
-) -> Result<Vec<CardWithSource>> {
-    let ParsedCards { cards, .. } = parse_str(input, default_region)?;
+pub fn parse_str_with_source(input: &str, phone: PhoneOptions<'_>) -> Result<Vec<CardWithSource>> {
+    let ParsedCards { cards, .. } = parse_str(input, phone)?;
     let blocks = extract_card_blocks(input);
 
     if cards.len() != blocks.len() {
@@ -75,7 +120,7 @@ pub fn parse_str_with_source(
 
     Ok(cards
         .into_iter()
-        .zip(blocks.into_iter())
+        .zip(blocks)
         .map(|(card, raw_block)| {
             let is_v4 = raw_block
                 .lines()
@@ -85,6 +130,102 @@ pub fn parse_str_with_source(
         .collect())
 }
 
+/// vcard4 rejects cards without FN, but plenty of exported cards only carry
+/// N. Give those an empty FN so they load and the indexer can fall back to N.
+fn add_missing_fn(input: &str) -> Cow<'_, str> {
+    let mut output = String::new();
+    let mut added = false;
+    let mut has_fn = false;
+
+    for line in input.split_inclusive('\n') {
+        let trimmed = line.trim_end_matches(['\r', '\n']);
+        if trimmed.eq_ignore_ascii_case("BEGIN:VCARD") {
+            has_fn = false;
+        } else if trimmed.eq_ignore_ascii_case("END:VCARD") {
+            if !has_fn {
+                output.push_str("FN:\r\n");
+                added = true;
+            }
+        } else if !line.starts_with([' ', '\t']) {
+            let name = trimmed.split([':', ';']).next().unwrap_or("");
+            let name = name.rsplit('.').next().unwrap_or(name);
+            has_fn |= name.eq_ignore_ascii_case("FN");
+        }
+        output.push_str(line);
+    }
+
+    if added {
+        Cow::Owned(output)
+    } else {
+        Cow::Borrowed(input)
+    }
+}
+
+/// TYPE values defined by RFC 6350, which vcard4 only recognises in lower case
+const REGISTERED_TYPES: &[&str] = &[
+    "home", "work", "text", "voice", "fax", "cell", "video", "pager", "textphone",
+    "contact", "acquaintance", "friend", "met", "co-worker", "colleague", "co-resident",
+    "neighbor", "child", "parent", "sibling", "spouse", "kin", "muse", "crush", "date",
+    "sweetheart", "me", "agent", "emergency",
+];
+
+/// TYPE values are case-insensitive, but vcard4 reads `TYPE=HOME` (as Apple
+/// writes it) as an extension and writes it back as `TYPE=X-HOME`. Lowercase
+/// the registered values so they survive a round trip.
+fn lowercase_registered_types(input: &str) -> Cow<'_, str> {
+    let mut output = String::with_capacity(input.len());
+    let mut changed = false;
+
+    for line in input.split_inclusive('\n') {
+        if line.starts_with([' ', '\t']) {
+            output.push_str(line);
+            continue;
+        }
+        // Parameters end at the first colon outside double quotes
+        let mut quoted = false;
+        let head_end = line
+            .char_indices()
+            .find(|&(_, c)| {
+                quoted ^= c == '"';
+                c == ':' && !quoted
+            })
+            .map_or(line.len(), |(index, _)| index);
+        let (head, rest) = line.split_at(head_end);
+
+        let mut segments = head.split(';');
+        output.push_str(segments.next().unwrap_or(""));
+        for segment in segments {
+            output.push(';');
+            match segment.split_once('=') {
+                Some((name, values)) if name.eq_ignore_ascii_case("TYPE") => {
+                    output.push_str(name);
+                    output.push('=');
+                    for (index, value) in values.split(',').enumerate() {
+                        if index > 0 {
+                            output.push(',');
+                        }
+                        match REGISTERED_TYPES.iter().find(|ty| ty.eq_ignore_ascii_case(value)) {
+                            Some(ty) => {
+                                changed |= *ty != value;
+                                output.push_str(ty);
+                            }
+                            None => output.push_str(value),
+                        }
+                    }
+                }
+                _ => output.push_str(segment),
+            }
+        }
+        output.push_str(rest);
+    }
+
+    if changed {
+        Cow::Owned(output)
+    } else {
+        Cow::Borrowed(input)
+    }
+}
+
 fn extract_card_blocks(input: &str) -> Vec<String> {
     let mut blocks: Vec<String> = Vec::new();
     let mut collecting = false;
@@ -117,29 +258,34 @@ fn extract_card_blocks(input: &str) -> Vec<String> {
     blocks
 }
 
-fn normalize_cards(cards: &mut [Vcard], default_region: Option<&str>) -> bool {
+fn normalize_cards(cards: &mut [Vcard], phone: PhoneOptions<'_>) -> bool {
+    if !phone.normalize {
+        return false;
+    }
     let mut changed = false;
     for card in cards {
-        if normalize_card_phone_numbers(card, default_region) {
+        if normalize_card_phone_numbers(card, phone.default_region) > 0 {
             changed = true;
         }
     }
     changed
 }
 
-fn normalize_card_phone_numbers(card: &mut Vcard, default_region: Option<&str>) -> bool {
+/// Rewrite every TEL of `card` in E.164, reading numbers without a country
+/// code as `default_region` ones. Returns how many values changed.
+pub fn normalize_card_phone_numbers(card: &mut Vcard, default_region: Option<&str>) -> usize {
     normalize_tel_properties(&mut card.tel, default_region)
 }
 
-fn normalize_tel_properties(props: &mut [TextOrUriProperty], default_region: Option<&str>) -> bool {
-    let mut changed = false;
+fn normalize_tel_properties(props: &mut [TextOrUriProperty], default_region: Option<&str>) -> usize {
+    let mut changed = 0;
     for prop in props {
         match prop {
             TextOrUriProperty::Text(text) => {
                 if let Some(normalized) = normalize_phone_value(&text.value, default_region) {
                     if text.value != normalized.value {
                         text.value = normalized.value;
-                        changed = true;
+                        changed += 1;
                     }
                 }
             }
@@ -151,7 +297,7 @@ fn normalize_tel_properties(props: &mut [TextOrUriProperty], default_region: Opt
                         if new_uri != original {
                             if let Ok(parsed) = new_uri.parse::<Uri>() {
                                 uri_prop.value = parsed;
-                                changed = true;
+                                changed += 1;
                             }
                         }
                     }
@@ -197,6 +343,11 @@ fn normalize_phone_value(raw: &str, default_region: Option<&str>) -> Option<Norm
 }
 
 fn parse_with_regions(input: &str, default_region: Option<&str>) -> Option<String> {
+    parse_number(input, default_region).map(|parsed| format_parsed_number(&parsed))
+}
+
+/// Parse against the default region first, then as an international number
+fn parse_number(input: &str, default_region: Option<&str>) -> Option<PhoneNumber> {
     let util = &*PHONE_NUMBER_UTIL;
     let mut candidates: Vec<&str> = Vec::new();
 
@@ -216,13 +367,134 @@ fn parse_with_regions(input: &str, default_region: Option<&str>) -> Option<Strin
 
     for region in candidates {
         if let Ok(parsed) = util.parse(input, region) {
-            return Some(format_parsed_number(&parsed));
+            return Some(parsed);
         }
     }
 
     None
 }
 
+/// How rlibphonenumber judges a TEL value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhoneValidity {
+    /// A valid number for its region
+    Valid,
+    /// Plausible length for its region, but not a known number range
+    Possible,
+    /// Not parseable as a phone number, or not plausible anywhere
+    Unrecognized,
+}
+
+/// Check a TEL value (with or without a `tel:` scheme) against `default_region`
+pub fn phone_validity(raw: &str, default_region: Option<&str>) -> PhoneValidity {
+    let (_, remainder) = strip_tel_scheme(raw.trim());
+    let util = &*PHONE_NUMBER_UTIL;
+    match parse_number(remainder, default_region) {
+        Some(number) if util.is_valid_number(&number) => PhoneValidity::Valid,
+        Some(number) if util.is_possible_number(&number) => PhoneValidity::Possible,
+        _ => PhoneValidity::Unrecognized,
+    }
+}
+
+/// Tidy a pasted email address: surrounding whitespace, quotes, a
+/// `Name <addr>` wrapper, a `mailto:` scheme (with any `?query`) and
+/// trailing list punctuation are dropped
+pub fn clean_email(raw: &str) -> String {
+    let mut value = raw.trim();
+
+    if let (Some(start), true) = (value.rfind('<'), value.ends_with('>')) {
+        value = &value[start + 1..value.len() - 1];
+    }
+
+    value = value.trim().trim_end_matches([',', ';']).trim();
+    value = value.trim_matches(|c| c == '"' || c == '\'').trim();
+
+    if value.get(..7).is_some_and(|scheme| scheme.eq_ignore_ascii_case("mailto:")) {
+        value = &value[7..];
+        if let Some(query) = value.find('?') {
+            value = &value[..query];
+        }
+    }
+
+    value.trim().to_string()
+}
+
+/// Light sanity check for an email address: one `@`, a non-empty local
+/// part and domain, and no whitespace
+pub fn email_looks_valid(value: &str) -> bool {
+    if value.chars().any(char::is_whitespace) {
+        return false;
+    }
+    match value.split_once('@') {
+        Some((local, domain)) => !local.is_empty() && !domain.is_empty() && !domain.contains('@'),
+        None => false,
+    }
+}
+
+/// Tidy a URL typed or pasted into a URL field: surrounding whitespace,
+/// quotes and angle brackets are dropped, inner whitespace is
+/// percent-encoded, and `https://` is added when there is no scheme
+/// (`mailto:`, `ftp://` and other schemes pass through)
+pub fn clean_url(raw: &str) -> String {
+    let value = raw
+        .trim()
+        .trim_matches(|c| c == '"' || c == '\'' || c == '<' || c == '>')
+        .trim();
+    let value: String = value
+        .chars()
+        .flat_map(|c| match c {
+            ' ' => "%20".chars().collect::<Vec<_>>(),
+            '\t' | '\r' | '\n' => Vec::new(),
+            c => vec![c],
+        })
+        .collect();
+    if value.is_empty() || has_url_scheme(&value) {
+        value
+    } else {
+        format!("https://{}", value)
+    }
+}
+
+/// A scheme is followed by `//`, or by something other than a port and has
+/// no dot, so `localhost:8080` and `example.com:8080` are a host and port
+/// rather than the schemes `localhost` and `example.com`
+fn has_url_scheme(value: &str) -> bool {
+    let Some((scheme, rest)) = value.split_once(':') else {
+        return false;
+    };
+    let valid = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    let port = rest.split(['/', '?', '#']).next().unwrap_or("");
+    let is_port = !port.is_empty() && port.chars().all(|c| c.is_ascii_digit());
+    valid && (rest.starts_with("//") || (!scheme.contains('.') && !is_port))
+}
+
+/// Whether a cleaned URL can be stored at all
+pub fn url_parses(value: &str) -> bool {
+    value.parse::<Uri>().is_ok()
+}
+
+/// Light sanity check for a cleaned URL: it parses as a URI, and web and
+/// FTP URLs name a host with a dot in it (or localhost)
+pub fn url_looks_valid(value: &str) -> bool {
+    if !url_parses(value) {
+        return false;
+    }
+    let Some((scheme, rest)) = value.split_once("://") else {
+        return has_url_scheme(value);
+    };
+    if !matches!(scheme.to_ascii_lowercase().as_str(), "http" | "https" | "ftp" | "ftps") {
+        return true;
+    }
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or("");
+    let host_port = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+    let host = host_port.split(':').next().unwrap_or("");
+    let labels_ok = host.split('.').all(|label| {
+        !label.is_empty() && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+    });
+    labels_ok && (host.contains('.') || host.eq_ignore_ascii_case("localhost"))
+}
+
 fn format_parsed_number(number: &PhoneNumber) -> String {
     let mut normalized = PHONE_NUMBER_UTIL
         .format(number, PhoneNumberFormat::E164)
@@ -259,31 +531,65 @@ fn strip_tel_scheme(value: &str) -> (bool, &str) {
     }
 }
 
-pub fn phone_display_value(raw: &str, default_region: Option<&str>) -> String {
+pub fn phone_display_value(raw: &str, phone: PhoneOptions<'_>) -> String {
     let trimmed = raw.trim();
     let (_, remainder) = strip_tel_scheme(trimmed);
-    normalize_phone_value(raw, default_region)
+    if !phone.normalize {
+        return remainder.to_string();
+    }
+    normalize_phone_value(raw, phone.default_region)
         .map(|n| n.value)
         .unwrap_or_else(|| remainder.to_string())
 }
 
-/// Write cards to an encrypted file using the given provider
-pub fn write_cards(path: &Path, cards: &[Vcard], provider: &dyn CryptoProvider) -> Result<()> {
+/// Build a `tel:` URI for a phone value, using E.164 when the number parses.
+/// Unparseable values fall back to the raw number with whitespace removed.
+pub fn phone_tel_uri(raw: &str, default_region: Option<&str>) -> String {
+    format!("tel:{}", phone_e164(raw, default_region))
+}
+
+/// A phone value in E.164 (`+15551234567`), or the raw number with
+/// whitespace removed when it does not parse
+pub fn phone_e164(raw: &str, default_region: Option<&str>) -> String {
+    let (_, remainder) = strip_tel_scheme(raw.trim());
+    parse_with_regions(remainder, default_region)
+        .unwrap_or_else(|| remainder.split_whitespace().collect())
+}
+
+/// The digits of a phone value, keeping a leading `+`
+pub fn phone_digits(raw: &str) -> String {
+    let (_, remainder) = strip_tel_scheme(raw.trim());
+    let mut digits = String::with_capacity(remainder.len());
+    if remainder.starts_with('+') {
+        digits.push('+');
+    }
+    digits.extend(remainder.chars().filter(char::is_ascii_digit));
+    digits
+}
+
+/// Render cards in the one layout rldx writes: every content line ends in
+/// CRLF (RFC 6350 section 3.2) and cards follow each other without blank
+/// lines. Lines are never trimmed: a folded line may end in a space that
+/// belongs to the value. Writing the same cards twice always yields the same
+/// bytes, so unchanged contacts never look modified.
+pub fn cards_to_string(cards: &[Vcard]) -> String {
     let mut output = String::new();
-    for (idx, card) in cards.iter().enumerate() {
-        if idx > 0 {
+    for card in cards {
+        for line in card.to_string().lines() {
+            output.push_str(line);
             output.push_str("\r\n");
         }
-        let mut card_text = card.to_string();
-        if !card_text.ends_with("\r\n") {
-            card_text.push_str("\r\n");
-        }
-        output.push_str(&card_text);
     }
+    output
+}
+
+/// Write cards to an encrypted file using the given provider
+pub fn write_cards(path: &Path, cards: &[Vcard], provider: &dyn CryptoProvider) -> Result<()> {
+    let output = cards_to_string(cards);
     let encrypted = provider
         .encrypt(output.as_bytes())
         .with_context(|| format!("failed to encrypt vCard for {}", path.display()))?;
-    crate::vdir::write_atomic(path, &encrypted)
+    crate::vdir::write_atomic(path, &encrypted, provider.edit_backup())
 }
 
 /// Ensure the provided card has a UUID-based UID property.
@@ -310,6 +616,35 @@ pub fn card_uid(card: &Vcard) -> Option<String> {
     }
 }
 
+/// "Given Family" from the N property, for cards whose FN is empty.
+pub fn name_from_n(card: &Vcard) -> Option<String> {
+    let name = card.name.as_ref()?;
+    let part = |idx: usize| {
+        name.value
+            .get(idx)
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+    };
+    let joined = [part(1), part(0)].into_iter().flatten().collect::<Vec<_>>().join(" ");
+    (!joined.is_empty()).then_some(joined)
+}
+
+/// Fill an empty or missing FN from N. Returns true when the card changed.
+pub fn fill_fn_from_name(card: &mut Vcard) -> bool {
+    if card.formatted_name.iter().any(|prop| !prop.value.trim().is_empty()) {
+        return false;
+    }
+    let Some(name) = name_from_n(card) else {
+        return false;
+    };
+    card.formatted_name = vec![TextProperty {
+        group: None,
+        value: name,
+        parameters: None,
+    }];
+    true
+}
+
 /// Set the card UID to the provided UUID value.
 pub fn set_card_uid(card: &mut Vcard, uuid: Uuid) {
     let value = uuid.to_string();
@@ -330,31 +665,245 @@ pub fn touch_rev(card: &mut Vcard) {
     });
 }
 
-/// Render the card to its canonical textual form.
+/// Fields `duplicate_card` can leave blank on the copy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClearedField {
+    /// FN, N and NICKNAME
+    Name,
+    Email,
+    Phone,
+}
+
+impl ClearedField {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "name" => Some(ClearedField::Name),
+            "email" => Some(ClearedField::Email),
+            "phone" | "tel" => Some(ClearedField::Phone),
+            _ => None,
+        }
+    }
+}
+
+/// A new contact holding just `formatted_name`, an explicit KIND, a fresh
+/// UID and REV. An organization also gets the name as its ORG.
+pub fn new_card(formatted_name: &str, kind: Kind) -> Result<(Vcard, Uuid)> {
+    let mut card = Vcard::new(formatted_name.to_string());
+    if kind == Kind::Org {
+        card.org.push(TextListProperty {
+            group: None,
+            value: vec![formatted_name.to_string()],
+            parameters: None,
+            delimiter: vcard4::property::TextListDelimiter::SemiColon,
+        });
+    }
+    card.kind = Some(KindProperty {
+        group: None,
+        value: kind,
+        parameters: None,
+    });
+    let uuid = ensure_uuid_uid(&mut card)?;
+    touch_rev(&mut card);
+    Ok((card, uuid))
+}
+
+/// Copy a card as a new contact: a fresh UID and REV, and the `clear` fields
+/// emptied so they can be filled in for the new person.
+pub fn duplicate_card(card: &Vcard, clear: &[ClearedField]) -> Result<(Vcard, Uuid)> {
+    let mut copy = card.clone();
+    copy.uid = None;
+    let uuid = ensure_uuid_uid(&mut copy)?;
+    touch_rev(&mut copy);
+    for field in clear {
+        match field {
+            ClearedField::Name => {
+                copy.formatted_name = vec![TextProperty {
+                    group: None,
+                    value: String::new(),
+                    parameters: None,
+                }];
+                copy.name = None;
+                copy.nickname.clear();
+            }
+            ClearedField::Email => copy.email.clear(),
+            ClearedField::Phone => copy.tel.clear(),
+        }
+    }
+    Ok((copy, uuid))
+}
+
+/// How far past now a REV may be before it counts as being in the future,
+/// so ordinary clock skew between devices is not reported
+pub const REV_FUTURE_SLACK: time::Duration = time::Duration::minutes(5);
+
+/// The card's REV as a UTC timestamp
+pub fn rev_timestamp(card: &Vcard) -> Option<time::OffsetDateTime> {
+    let rev = card.rev.as_ref()?;
+    Some(rev.value.as_ref().to_offset(time::UtcOffset::UTC))
+}
+
+/// REV rendered in UTC as `YYYYMMDDTHHMMSSZ`, which sorts chronologically as text
+pub fn rev_sort_key(card: &Vcard) -> Option<String> {
+    format_rev_sort_key(rev_timestamp(card)?)
+}
+
+/// Sort key of a REV value as written in a card, whatever its offset. Older
+/// indexes stored REV this way.
+pub fn rev_text_sort_key(value: &str) -> Option<String> {
+    let rev = vcard4::helper::parse_date_time(value).ok()?;
+    format_rev_sort_key(rev.as_ref().to_offset(time::UtcOffset::UTC))
+}
+
+fn format_rev_sort_key(rev: time::OffsetDateTime) -> Option<String> {
+    let format = time::macros::format_description!("[year][month][day]T[hour][minute][second]Z");
+    rev.format(&format).ok()
+}
+
+/// The card's REV when it lies more than `REV_FUTURE_SLACK` after `now`
+pub fn future_rev(card: &Vcard, now: time::OffsetDateTime) -> Option<time::OffsetDateTime> {
+    rev_timestamp(card).filter(|rev| *rev > now + REV_FUTURE_SLACK)
+}
+
+/// Render the card to its canonical textual form (see `cards_to_string`).
 pub fn card_to_bytes(card: &Vcard) -> Vec<u8> {
-    card.to_string().into_bytes()
+    cards_to_string(std::slice::from_ref(card)).into_bytes()
 }
 
+/// Replace the value of one property in place. Its group and parameters
+/// (TYPE, PREF, LABEL, X- parameters, ...) are kept as they are; only ADR
+/// drops a LABEL that no longer matches the edited components.
 pub fn update_card_field(
     card: &mut Vcard,
     field: &str,
     seq: i64,
     component: Option<usize>,
     new_value: &str,
-    default_region: Option<&str>,
+    phone: PhoneOptions<'_>,
 ) -> Result<bool> {
     match field.to_ascii_uppercase().as_str() {
-        "TEL" => update_tel_value(card, seq, new_value, default_region),
+        "TEL" => update_tel_value(card, seq, new_value, phone),
         "EMAIL" => Ok(update_email_value(card, seq, new_value)),
         "FN" => Ok(update_fn_value(card, seq, new_value)),
         "N" => Ok(update_n_value(card, component, new_value)),
+        "ADR" => Ok(update_adr_value(card, seq, component, new_value)),
         "NICKNAME" => Ok(update_nickname_value(card, seq, new_value)),
+        "URL" => Ok(update_url_value(card, seq, new_value)),
         _ => Ok(false),
     }
 }
 
 pub fn promote_tel_entry(card: &mut Vcard, index: usize) -> bool {
-    if index >= card.tel.len() {
+    reorder_entry(card, "TEL", index, 0)
+}
+
+pub fn promote_email_entry(card: &mut Vcard, index: usize) -> bool {
+    reorder_entry(card, "EMAIL", index, 0)
+}
+
+/// Move the EMAIL, TEL or ADR entry at `from` to position `to`. When the
+/// first entry changes and the field marks one `PREF=1`, the mark follows the
+/// new first entry, so the top of the list stays the preferred one. Returns
+/// false for another field or an index out of range.
+pub fn reorder_entry(card: &mut Vcard, field: &str, from: usize, to: usize) -> bool {
+    fn shift<T>(entries: &mut Vec<T>, from: usize, to: usize) -> bool {
+        if from >= entries.len() || to >= entries.len() {
+            return false;
+        }
+        let entry = entries.remove(from);
+        entries.insert(to, entry);
+        true
+    }
+    fn has_preferred<'a>(mut parameters: impl Iterator<Item = &'a Option<Parameters>>) -> bool {
+        parameters.any(|params| params.as_ref().is_some_and(|params| params.pref == Some(1)))
+    }
+
+    let top_changed = from != to && (from == 0 || to == 0);
+    match field.to_ascii_uppercase().as_str() {
+        "EMAIL" => {
+            if !shift(&mut card.email, from, to) {
+                return false;
+            }
+            if top_changed && has_preferred(card.email.iter().map(|prop| &prop.parameters)) {
+                prefer_first_email(card);
+            }
+        }
+        "TEL" => {
+            if !shift(&mut card.tel, from, to) {
+                return false;
+            }
+            let preferred = has_preferred(card.tel.iter().map(|prop| match prop {
+                TextOrUriProperty::Text(text) => &text.parameters,
+                TextOrUriProperty::Uri(uri) => &uri.parameters,
+            }));
+            if top_changed && preferred {
+                prefer_first_tel(card);
+            }
+        }
+        "ADR" => {
+            if !shift(&mut card.address, from, to) {
+                return false;
+            }
+            if top_changed && has_preferred(card.address.iter().map(|prop| &prop.parameters)) {
+                prefer_first(card.address.iter_mut().map(|prop| &mut prop.parameters));
+            }
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Index of the EMAIL matching `value`, ignoring case and surrounding space
+pub fn find_email_entry(card: &Vcard, value: &str) -> Option<usize> {
+    let needle = value.trim();
+    card.email
+        .iter()
+        .position(|prop| prop.value.trim().eq_ignore_ascii_case(needle))
+}
+
+/// Index of the TEL with the same digits as `value`, ignoring formatting
+/// and a `tel:` scheme
+pub fn find_tel_entry(card: &Vcard, value: &str) -> Option<usize> {
+    let needle = phone_digits(value);
+    if needle.trim_start_matches('+').is_empty() {
+        return None;
+    }
+    card.tel.iter().position(|prop| {
+        let raw = match prop {
+            TextOrUriProperty::Text(text) => text.value.clone(),
+            TextOrUriProperty::Uri(uri) => uri.value.to_string(),
+        };
+        phone_digits(&raw) == needle
+    })
+}
+
+/// Give the first EMAIL `PREF=1` and drop `PREF=1` from the others, so
+/// clients that rank by PREF agree with the promoted order
+pub fn prefer_first_email(card: &mut Vcard) {
+    prefer_first(card.email.iter_mut().map(|prop| &mut prop.parameters));
+}
+
+/// Give the first TEL `PREF=1` and drop `PREF=1` from the others
+pub fn prefer_first_tel(card: &mut Vcard) {
+    prefer_first(card.tel.iter_mut().map(|prop| match prop {
+        TextOrUriProperty::Text(text) => &mut text.parameters,
+        TextOrUriProperty::Uri(uri) => &mut uri.parameters,
+    }));
+}
+
+fn prefer_first<'a>(mut parameters: impl Iterator<Item = &'a mut Option<Parameters>>) {
+    let Some(first) = parameters.next() else {
+        return;
+    };
+    first.get_or_insert_with(Parameters::default).pref = Some(1);
+    for params in parameters.flatten() {
+        if params.pref == Some(1) {
+            params.pref = None;
+        }
+    }
+}
+
+pub fn promote_photo_entry(card: &mut Vcard, index: usize) -> bool {
+    if index >= card.photo.len() {
         return false;
     }
 
@@ -362,22 +911,104 @@ pub fn promote_tel_entry(card: &mut Vcard, index: usize) -> bool {
         return true;
     }
 
-    let entry = card.tel.remove(index);
-    card.tel.insert(0, entry);
+    let entry = card.photo.remove(index);
+    card.photo.insert(0, entry);
     true
 }
 
-pub fn promote_email_entry(card: &mut Vcard, index: usize) -> bool {
-    if index >= card.email.len() {
+/// Give the first PHOTO `PREF=1` and drop `PREF=1` from the others
+pub fn prefer_first_photo(card: &mut Vcard) {
+    prefer_first(card.photo.iter_mut().map(|prop| match prop {
+        TextOrUriProperty::Text(text) => &mut text.parameters,
+        TextOrUriProperty::Uri(uri) => &mut uri.parameters,
+    }));
+}
+
+pub fn promote_address_entry(card: &mut Vcard, index: usize) -> bool {
+    reorder_entry(card, "ADR", index, 0)
+}
+
+/// Number of structured components in an ADR value
+pub const ADR_COMPONENTS: usize = 7;
+
+/// Pseudo-component of an ADR edit that sets its LABEL parameter
+pub const ADR_LABEL_COMPONENT: usize = ADR_COMPONENTS;
+
+/// ADR components (PO box, extended, street, locality, region, postal code,
+/// country) of the address at `seq`
+pub fn address_components(card: &Vcard, seq: i64) -> Option<Vec<String>> {
+    let index = usize::try_from(seq).ok()?;
+    let adr = &card.address.get(index)?.value;
+    Some(
+        [
+            &adr.po_box,
+            &adr.extended_address,
+            &adr.street_address,
+            &adr.locality,
+            &adr.region,
+            &adr.postal_code,
+            &adr.country_name,
+        ]
+        .into_iter()
+        .map(|part| part.clone().unwrap_or_default())
+        .collect(),
+    )
+}
+
+/// LABEL parameter (the formatted delivery label) of the address at `seq`
+pub fn adr_label(card: &Vcard, seq: i64) -> Option<String> {
+    let index = usize::try_from(seq).ok()?;
+    card.address.get(index)?.parameters.as_ref()?.label.clone()
+}
+
+/// Set the free-form LABEL of the address at `seq`, one line per line of
+/// `label` (blank lines dropped). An empty label removes the parameter. The
+/// structured components are left as they are. Returns false when there is
+/// no such address.
+pub fn set_adr_label(card: &mut Vcard, seq: i64, label: &str) -> bool {
+    let Some(prop) = usize::try_from(seq).ok().and_then(|i| card.address.get_mut(i)) else {
         return false;
+    };
+    let lines: Vec<&str> = label
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    if lines.is_empty() {
+        if let Some(params) = prop.parameters.as_mut() {
+            params.label = None;
+        }
+    } else {
+        prop.parameters.get_or_insert_with(Parameters::default).label = Some(lines.join("\n"));
     }
+    true
+}
 
-    if index == 0 {
-        return true;
+fn update_adr_value(card: &mut Vcard, seq: i64, component: Option<usize>, new_value: &str) -> bool {
+    let Some(index) = component else {
+        return false;
+    };
+    if index == ADR_LABEL_COMPONENT {
+        return set_adr_label(card, seq, new_value);
     }
+    let Some(prop) = usize::try_from(seq).ok().and_then(|i| card.address.get_mut(i)) else {
+        return false;
+    };
 
-    let entry = card.email.remove(index);
-    card.email.insert(0, entry);
+    let trimmed = new_value.trim();
+    let value = (!trimmed.is_empty()).then(|| trimmed.to_string());
+    let adr = &mut prop.value;
+    let slot = match index {
+        0 => &mut adr.po_box,
+        1 => &mut adr.extended_address,
+        2 => &mut adr.street_address,
+        3 => &mut adr.locality,
+        4 => &mut adr.region,
+        5 => &mut adr.postal_code,
+        6 => &mut adr.country_name,
+        _ => return false,
+    };
+    *slot = value;
     true
 }
 
@@ -385,15 +1016,18 @@ fn update_tel_value(
     card: &mut Vcard,
     seq: i64,
     new_value: &str,
-    default_region: Option<&str>,
+    phone: PhoneOptions<'_>,
 ) -> Result<bool> {
-    let normalized = normalize_phone_value(new_value, default_region)
-        .map(|n| n.value)
-        .unwrap_or_else(|| new_value.trim().to_string());
+    let normalized = if phone.normalize {
+        normalize_phone_value(new_value, phone.default_region)
+            .map(|n| n.value)
+            .unwrap_or_else(|| new_value.trim().to_string())
+    } else {
+        new_value.trim().to_string()
+    };
 
-    let mut index = 0;
-    for prop in &mut card.tel {
-        if index == seq {
+    for (index, prop) in card.tel.iter_mut().enumerate() {
+        if index as i64 == seq {
             match prop {
                 TextOrUriProperty::Text(text) => {
                     text.value = normalized.clone();
@@ -408,25 +1042,35 @@ fn update_tel_value(
             }
             return Ok(true);
         }
-        index += 1;
     }
 
     Ok(false)
 }
 
 fn update_email_value(card: &mut Vcard, seq: i64, new_value: &str) -> bool {
-    let trimmed = new_value.trim();
-    let mut index = 0;
-    for prop in &mut card.email {
-        if index == seq {
-            prop.value = trimmed.to_string();
+    let cleaned = clean_email(new_value);
+    for (index, prop) in card.email.iter_mut().enumerate() {
+        if index as i64 == seq {
+            prop.value = cleaned;
             return true;
         }
-        index += 1;
     }
     false
 }
 
+fn update_url_value(card: &mut Vcard, seq: i64, new_value: &str) -> bool {
+    let Some(prop) = usize::try_from(seq).ok().and_then(|index| card.url.get_mut(index)) else {
+        return false;
+    };
+    match clean_url(new_value).parse::<Uri>() {
+        Ok(uri) => {
+            prop.value = uri;
+            true
+        }
+        Err(_) => false,
+    }
+}
+
 fn update_fn_value(card: &mut Vcard, seq: i64, new_value: &str) -> bool {
     if seq < 0 {
         return false;
@@ -472,15 +1116,61 @@ fn update_n_value(card: &mut Vcard, component: Option<usize>, new_value: &str) -
     true
 }
 
+const NAME_PREFIXES: &[&str] = &["dr", "mr", "mrs", "ms", "miss", "mx", "prof", "sir", "rev"];
+const NAME_SUFFIXES: &[&str] = &["jr", "sr", "ii", "iii", "iv", "phd", "md", "esq"];
+
+/// Guess N components (family, given, additional, prefix, suffix) from a
+/// formatted name. Handles "Last, First Middle" and mononyms (given only).
+pub fn split_formatted_name(formatted: &str) -> [String; 5] {
+    let is_affix = |word: &str, list: &[&str]| {
+        let bare = word.trim_end_matches('.').replace('.', "");
+        list.contains(&bare.to_ascii_lowercase().as_str())
+    };
+
+    let (family_part, rest) = match formatted.split_once(',') {
+        Some((last, first)) if !first.trim().is_empty() && !is_affix(first.trim(), NAME_SUFFIXES) => {
+            (Some(last.trim()), first)
+        }
+        _ => (None, formatted),
+    };
+
+    let mut words: Vec<&str> = rest
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let mut prefixes = Vec::new();
+    while words.len() > 1 && is_affix(words[0], NAME_PREFIXES) {
+        prefixes.push(words.remove(0));
+    }
+    let mut suffixes = Vec::new();
+    while words.len() > 1 && is_affix(words[words.len() - 1], NAME_SUFFIXES) {
+        suffixes.insert(0, words.pop().unwrap());
+    }
+
+    let family = match family_part {
+        Some(last) => last.to_string(),
+        None if words.len() > 1 => words.pop().unwrap().to_string(),
+        None => String::new(),
+    };
+    let given = if words.is_empty() { String::new() } else { words.remove(0).to_string() };
+
+    [
+        family,
+        given,
+        words.join(" "),
+        prefixes.join(" "),
+        suffixes.join(" "),
+    ]
+}
+
 fn update_nickname_value(card: &mut Vcard, seq: i64, new_value: &str) -> bool {
     let trimmed = new_value.trim().to_string();
-    let mut index = 0i64;
-    for prop in &mut card.nickname {
-        if index == seq {
+    for (index, prop) in card.nickname.iter_mut().enumerate() {
+        if index as i64 == seq {
             prop.value = trimmed;
             return true;
         }
-        index += 1;
     }
     false
 }
@@ -496,7 +1186,11 @@ pub fn delete_nickname_entry(card: &mut Vcard, index: usize) -> bool {
 
 /// Delete a field entry by field name and sequence number
 pub fn delete_card_field(card: &mut Vcard, field: &str, seq: usize) -> bool {
-    match field.to_ascii_uppercase().as_str() {
+    let group = i64::try_from(seq)
+        .ok()
+        .and_then(|seq| labelled_group_mut(card, field, seq))
+        .and_then(|group| group.clone());
+    let deleted = match field.to_ascii_uppercase().as_str() {
         "TEL" => {
             if seq < card.tel.len() {
                 card.tel.remove(seq);
@@ -599,7 +1293,13 @@ pub fn delete_card_field(card: &mut Vcard, field: &str, seq: usize) -> bool {
                 false
             }
         }
+    };
+    if deleted {
+        if let Some(group) = group {
+            drop_group_companions(card, &group);
+        }
     }
+    deleted
 }
 
 /// Add a new field to the card
@@ -646,9 +1346,13 @@ pub fn add_card_field(
             true
         }
         "EMAIL" => {
+            let cleaned = clean_email(&trimmed);
+            if cleaned.is_empty() {
+                return false;
+            }
             card.email.push(TextProperty {
                 group: None,
-                value: trimmed,
+                value: cleaned,
                 parameters,
             });
             true
@@ -670,7 +1374,7 @@ pub fn add_card_field(
             true
         }
         "URL" => {
-            if let Ok(uri) = trimmed.parse::<Uri>() {
+            if let Ok(uri) = clean_url(&trimmed).parse::<Uri>() {
                 card.url.push(UriProperty {
                     group: None,
                     value: uri,
@@ -711,25 +1415,723 @@ pub fn add_card_field(
             });
             true
         }
-        _ => false,
+        _ => false,
+    }
+}
+
+/// CATEGORIES values in card order, skipping empty entries
+pub fn categories(card: &Vcard) -> impl Iterator<Item = &str> {
+    card.categories
+        .iter()
+        .flat_map(|prop| prop.value.iter())
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+}
+
+/// Make `name` the first CATEGORIES value, removing any later duplicate.
+/// Returns false when it already was first.
+pub fn ensure_leading_category(card: &mut Vcard, name: &str) -> bool {
+    if categories(card).next() == Some(name) {
+        return false;
+    }
+    for prop in &mut card.categories {
+        prop.value.retain(|value| value.trim() != name);
+    }
+    card.categories.retain(|prop| !prop.value.is_empty());
+    match card.categories.first_mut() {
+        Some(prop) => prop.value.insert(0, name.to_string()),
+        None => card
+            .categories
+            .push(TextListProperty::new_comma(vec![name.to_string()])),
+    }
+    true
+}
+
+/// Append `name` to the card's CATEGORIES unless it is already there.
+/// Returns false when nothing was added.
+pub fn add_category(card: &mut Vcard, name: &str) -> bool {
+    if categories(card).any(|existing| existing.eq_ignore_ascii_case(name)) {
+        return false;
+    }
+    match card.categories.last_mut() {
+        Some(prop) => prop.value.push(name.to_string()),
+        None => card
+            .categories
+            .push(TextListProperty::new_comma(vec![name.to_string()])),
+    }
+    true
+}
+
+/// Remove `name` (compared case-insensitively) from the card's CATEGORIES,
+/// dropping a CATEGORIES property left empty. Returns false when it was absent.
+pub fn remove_category(card: &mut Vcard, name: &str) -> bool {
+    if !categories(card).any(|existing| existing.eq_ignore_ascii_case(name)) {
+        return false;
+    }
+    for prop in &mut card.categories {
+        prop.value.retain(|value| !value.trim().eq_ignore_ascii_case(name));
+    }
+    card.categories.retain(|prop| !prop.value.is_empty());
+    true
+}
+
+/// Extension property that keeps a contact out of CardDAV sync
+pub const NOSYNC_PROPERTY: &str = "X-RLDX-NOSYNC";
+
+/// True when the card carries `X-RLDX-NOSYNC` with a value other than 0
+pub fn is_no_sync(card: &Vcard) -> bool {
+    card.extensions.iter().any(|ext| {
+        ext.name.eq_ignore_ascii_case(NOSYNC_PROPERTY) && {
+            let value = ext.value.to_string();
+            let value = value.trim();
+            !value.is_empty() && value != "0"
+        }
+    })
+}
+
+/// Set or clear the `X-RLDX-NOSYNC` flag. Returns false when nothing changed.
+pub fn set_no_sync(card: &mut Vcard, no_sync: bool) -> bool {
+    if is_no_sync(card) == no_sync {
+        return false;
+    }
+    card.extensions
+        .retain(|ext| !ext.name.eq_ignore_ascii_case(NOSYNC_PROPERTY));
+    if no_sync {
+        card.extensions.push(vcard4::property::ExtensionProperty {
+            group: None,
+            name: NOSYNC_PROPERTY.to_string(),
+            value: vcard4::property::AnyProperty::Text("1".to_string()),
+            parameters: None,
+        });
+    }
+    true
+}
+
+/// Extension property holding a contact's manual position within its book
+pub const SORT_PROPERTY: &str = "X-RLDX-SORT";
+
+/// The card's `X-RLDX-SORT` position, if it holds an integer
+pub fn sort_position(card: &Vcard) -> Option<i64> {
+    card.extensions
+        .iter()
+        .find(|ext| ext.name.eq_ignore_ascii_case(SORT_PROPERTY))
+        .and_then(|ext| ext.value.to_string().trim().parse().ok())
+}
+
+/// Set or clear the card's `X-RLDX-SORT` position. Returns false when nothing
+/// changed.
+pub fn set_sort_position(card: &mut Vcard, position: Option<i64>) -> bool {
+    if sort_position(card) == position {
+        return false;
+    }
+    card.extensions
+        .retain(|ext| !ext.name.eq_ignore_ascii_case(SORT_PROPERTY));
+    if let Some(position) = position {
+        card.extensions.push(vcard4::property::ExtensionProperty {
+            group: None,
+            name: SORT_PROPERTY.to_string(),
+            value: vcard4::property::AnyProperty::Text(position.to_string()),
+            parameters: None,
+        });
+    }
+    true
+}
+
+/// Parameter naming where a property came from (an import format or a remote)
+pub const SOURCE_PARAM: &str = "X-RLDX-SOURCE";
+
+/// Parameters holding just `X-RLDX-SOURCE=source`, for a property about to be
+/// added to a card
+pub fn source_parameters(source: &str) -> Parameters {
+    let mut params = Parameters::default();
+    params.extensions = Some(vec![(SOURCE_PARAM.to_string(), vec![source.to_string()])]);
+    params
+}
+
+/// Parameters of the contact properties `tag_source` tags
+fn source_tagged_params(card: &mut Vcard) -> Vec<&mut Option<Parameters>> {
+    let mut params: Vec<&mut Option<Parameters>> = Vec::new();
+    params.extend(card.email.iter_mut().map(|prop| &mut prop.parameters));
+    params.extend(card.tel.iter_mut().map(|prop| match prop {
+        TextOrUriProperty::Text(text) => &mut text.parameters,
+        TextOrUriProperty::Uri(uri) => &mut uri.parameters,
+    }));
+    params.extend(card.address.iter_mut().map(|prop| &mut prop.parameters));
+    params.extend(card.url.iter_mut().map(|prop| &mut prop.parameters));
+    params.extend(card.impp.iter_mut().map(|prop| &mut prop.parameters));
+    params.extend(card.org.iter_mut().map(|prop| &mut prop.parameters));
+    params.extend(card.title.iter_mut().map(|prop| &mut prop.parameters));
+    params.extend(card.role.iter_mut().map(|prop| &mut prop.parameters));
+    params.extend(card.nickname.iter_mut().map(|prop| &mut prop.parameters));
+    params.extend(card.note.iter_mut().map(|prop| &mut prop.parameters));
+    params
+}
+
+/// Tag the contact properties of `card` that carry no source yet with
+/// `source`, so merged cards remember where each value came from. Returns
+/// how many properties were tagged.
+pub fn tag_source(card: &mut Vcard, source: &str) -> usize {
+    let mut tagged = 0;
+    for params in source_tagged_params(card) {
+        let extensions = params
+            .get_or_insert_with(Parameters::default)
+            .extensions
+            .get_or_insert_with(Vec::new);
+        if extensions.iter().any(|(name, _)| name.eq_ignore_ascii_case(SOURCE_PARAM)) {
+            continue;
+        }
+        extensions.push((SOURCE_PARAM.to_string(), vec![source.to_string()]));
+        tagged += 1;
+    }
+    tagged
+}
+
+/// Remove every `X-RLDX-SOURCE` tag, e.g. before a card leaves rldx.
+/// Returns whether any was removed.
+pub fn strip_source_tags(card: &mut Vcard) -> bool {
+    let mut stripped = false;
+    for params in source_tagged_params(card) {
+        let Some(extensions) = params.as_mut().and_then(|p| p.extensions.as_mut()) else {
+            continue;
+        };
+        let before = extensions.len();
+        extensions.retain(|(name, _)| !name.eq_ignore_ascii_case(SOURCE_PARAM));
+        if extensions.len() == before {
+            continue;
+        }
+        stripped = true;
+        if extensions.is_empty() {
+            params.as_mut().unwrap().extensions = None;
+        }
+        if params.as_ref().is_some_and(|p| *p == Parameters::default()) {
+            *params = None;
+        }
+    }
+    stripped
+}
+
+/// How `dedupe_emails` normalizes letter case
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmailCase {
+    /// Lowercase the whole address
+    #[default]
+    All,
+    /// Lowercase only the domain; local parts keep their case
+    Domain,
+}
+
+impl EmailCase {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "all" => Some(EmailCase::All),
+            "domain" => Some(EmailCase::Domain),
+            _ => None,
+        }
+    }
+}
+
+/// Trim an email address and lowercase it according to `case`
+pub fn normalize_email(value: &str, case: EmailCase) -> String {
+    let trimmed = value.trim();
+    match (case, trimmed.rsplit_once('@')) {
+        (EmailCase::Domain, Some((local, domain))) => {
+            format!("{}@{}", local, domain.to_lowercase())
+        }
+        (EmailCase::Domain, None) => trimmed.to_string(),
+        (EmailCase::All, _) => trimmed.to_lowercase(),
+    }
+}
+
+/// Normalize every EMAIL with `normalize_email` and collapse the ones that
+/// become identical into the first. The survivor keeps its position (so the
+/// default email stays first), takes the strongest PREF and the union of TYPE
+/// values. Returns false when the card was already clean.
+pub fn dedupe_emails(card: &mut Vcard, case: EmailCase) -> bool {
+    let mut changed = false;
+    let mut kept: Vec<TextProperty> = Vec::with_capacity(card.email.len());
+    let mut dropped_groups = Vec::new();
+    for mut prop in std::mem::take(&mut card.email) {
+        let normalized = normalize_email(&prop.value, case);
+        if normalized != prop.value {
+            prop.value = normalized;
+            changed = true;
+        }
+        let Some(first) = kept.iter_mut().find(|kept| kept.value == prop.value) else {
+            kept.push(prop);
+            continue;
+        };
+        changed = true;
+        dropped_groups.extend(prop.group.take());
+        let Some(params) = prop.parameters.as_ref() else {
+            continue;
+        };
+        let first_params = first.parameters.get_or_insert_with(Parameters::default);
+        first_params.pref = match (first_params.pref, params.pref) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        for ty in params.types.iter().flatten() {
+            let types = first_params.types.get_or_insert_with(Vec::new);
+            if !types.contains(ty) {
+                types.push(ty.clone());
+            }
+        }
+    }
+    card.email = kept;
+    for group in dropped_groups {
+        drop_group_companions(card, &group);
+    }
+    changed
+}
+
+/// Target application for exported vCards
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportDialect {
+    /// Plain RFC 6350 vCard 4.0, as stored
+    Rfc,
+    /// Custom TYPE labels become `itemN.X-ABLabel` groups
+    Apple,
+    /// Apple-style labels, with the address book as a CATEGORIES label
+    Google,
+}
+
+/// Render a card for export in `dialect`. `book` is the address book holding
+/// the card; it becomes the first CATEGORIES value when `book_category` is
+/// set or the dialect is Google. The stored card is not modified.
+pub fn card_to_dialect_string(
+    card: &Vcard,
+    dialect: ExportDialect,
+    book: Option<&str>,
+    book_category: bool,
+) -> String {
+    let mut card = card.clone();
+    if let Some(book) = book.filter(|_| book_category || dialect == ExportDialect::Google) {
+        ensure_leading_category(&mut card, book);
+    }
+    if dialect != ExportDialect::Rfc {
+        group_custom_labels(&mut card);
+    }
+    card.to_string()
+}
+
+/// Move non-standard TYPE values of TEL/EMAIL/ADR/URL into an `X-ABLabel`
+/// property sharing a new `itemN` group, the way Apple and Google expect
+/// custom labels. Properties that already carry a group are left alone.
+fn group_custom_labels(card: &mut Vcard) {
+    use vcard4::property::{AnyProperty, ExtensionProperty};
+
+    let mut used = used_groups(card);
+    let mut labels: Vec<(String, String)> = Vec::new();
+    let mut take = |group: &mut Option<String>, params: &mut Option<Parameters>| {
+        if group.is_some() {
+            return;
+        }
+        let Some(label) = take_custom_type(params) else {
+            return;
+        };
+        let name = allocate_group(&mut used);
+        *group = Some(name.clone());
+        labels.push((name, label));
+    };
+
+    for prop in &mut card.tel {
+        match prop {
+            TextOrUriProperty::Text(prop) => take(&mut prop.group, &mut prop.parameters),
+            TextOrUriProperty::Uri(prop) => take(&mut prop.group, &mut prop.parameters),
+        }
+    }
+    for prop in &mut card.email {
+        take(&mut prop.group, &mut prop.parameters);
+    }
+    for prop in &mut card.address {
+        take(&mut prop.group, &mut prop.parameters);
+    }
+    for prop in &mut card.url {
+        take(&mut prop.group, &mut prop.parameters);
+    }
+
+    for (group, label) in labels {
+        card.extensions.push(ExtensionProperty {
+            name: "X-ABLabel".to_string(),
+            group: Some(group),
+            value: AnyProperty::Text(label),
+            parameters: None,
+        });
+    }
+}
+
+/// Lowercased groups of every property on `card`, read from its serialized
+/// form so no property type is missed
+fn used_groups(card: &Vcard) -> Vec<String> {
+    card.to_string()
+        .lines()
+        .filter(|line| !line.starts_with([' ', '\t']))
+        .filter_map(|line| {
+            let name = &line[..line.find([':', ';'])?];
+            let (group, _) = name.split_once('.')?;
+            Some(group.to_ascii_lowercase())
+        })
+        .collect()
+}
+
+/// First `itemN` group not in `used` (lowercased names), which it joins
+fn allocate_group(used: &mut Vec<String>) -> String {
+    let name = (1..)
+        .map(|n| format!("item{}", n))
+        .find(|name| !used.contains(name))
+        .unwrap();
+    used.push(name.clone());
+    name
+}
+
+/// Remove custom (extension) TYPE values, returning the first as a label
+fn take_custom_type(params: &mut Option<Parameters>) -> Option<String> {
+    use vcard4::parameter::TypeParameter;
+
+    let types = params.as_mut()?.types.as_mut()?;
+    let mut label = None;
+    types.retain(|ty| match ty {
+        TypeParameter::Extension(value) if !value.trim().is_empty() => {
+            label.get_or_insert_with(|| value.trim().to_string());
+            false
+        }
+        _ => true,
+    });
+    if types.is_empty() {
+        params.as_mut()?.types = None;
+    }
+    label
+}
+
+/// Extension property Apple uses to label the properties sharing its group
+pub const AB_LABEL_PROPERTY: &str = "X-ABLabel";
+
+/// Human-readable form of an `X-ABLabel` value: Apple's built-in labels come
+/// wrapped as `_$!<Home>!$_`, custom ones are stored as typed.
+pub fn ab_label_display(raw: &str) -> String {
+    let raw = raw.trim();
+    raw.strip_prefix("_$!<")
+        .and_then(|inner| inner.strip_suffix(">!$_"))
+        .unwrap_or(raw)
+        .trim()
+        .to_string()
+}
+
+/// `X-ABLabel` values by (lowercased) group name, in display form
+pub fn ab_labels(card: &Vcard) -> std::collections::HashMap<String, String> {
+    card.extensions
+        .iter()
+        .filter(|ext| ext.name.eq_ignore_ascii_case(AB_LABEL_PROPERTY))
+        .filter_map(|ext| {
+            let group = ext.group.as_ref()?.to_ascii_lowercase();
+            let label = ab_label_display(&ext.value.to_string());
+            (!label.is_empty()).then_some((group, label))
+        })
+        .collect()
+}
+
+/// Remove the extension properties (such as `X-ABLabel`) grouped under
+/// `group` once no standard property is left in that group
+fn drop_group_companions(card: &mut Vcard, group: &str) {
+    let text = card.to_string();
+    let mut lines = text.lines().filter(|line| !line.starts_with([' ', '\t']));
+    let still_used = lines.any(|line| {
+        let Some(end) = line.find([':', ';']) else {
+            return false;
+        };
+        line[..end].split_once('.').is_some_and(|(line_group, name)| {
+            line_group.eq_ignore_ascii_case(group)
+                && !name.to_ascii_uppercase().starts_with("X-")
+        })
+    });
+    if !still_used {
+        card.extensions
+            .retain(|ext| !ext.group.as_deref().is_some_and(|g| g.eq_ignore_ascii_case(group)));
+    }
+}
+
+/// Group of the TEL/EMAIL/ADR/URL property at `seq`, if that property exists
+fn labelled_group_mut<'a>(
+    card: &'a mut Vcard,
+    field: &str,
+    seq: i64,
+) -> Option<&'a mut Option<String>> {
+    let index = usize::try_from(seq).ok()?;
+    match field.to_ascii_uppercase().as_str() {
+        "TEL" => card.tel.get_mut(index).map(|prop| match prop {
+            TextOrUriProperty::Text(prop) => &mut prop.group,
+            TextOrUriProperty::Uri(prop) => &mut prop.group,
+        }),
+        "EMAIL" => card.email.get_mut(index).map(|prop| &mut prop.group),
+        "ADR" => card.address.get_mut(index).map(|prop| &mut prop.group),
+        "URL" => card.url.get_mut(index).map(|prop| &mut prop.group),
+        _ => None,
+    }
+}
+
+/// Set the `X-ABLabel` of the TEL/EMAIL/ADR/URL property at `seq`, giving
+/// the property a fresh `itemN` group when it has none. An empty label
+/// removes the `X-ABLabel`; an unchanged one keeps Apple's `_$!<...>!$_`
+/// wrapping. Returns false when the property does not exist.
+pub fn set_ab_label(card: &mut Vcard, field: &str, seq: i64, label: &str) -> bool {
+    use vcard4::property::{AnyProperty, ExtensionProperty};
+
+    let label = label.trim();
+    let mut used = used_groups(card);
+
+    let Some(slot) = labelled_group_mut(card, field, seq) else {
+        return false;
+    };
+    let group = match slot {
+        Some(group) => group.clone(),
+        None if label.is_empty() => return true,
+        None => {
+            let name = allocate_group(&mut used);
+            *slot = Some(name.clone());
+            name
+        }
+    };
+
+    let is_label = |ext: &ExtensionProperty| {
+        ext.name.eq_ignore_ascii_case(AB_LABEL_PROPERTY)
+            && ext.group.as_deref().is_some_and(|g| g.eq_ignore_ascii_case(&group))
+    };
+    if label.is_empty() {
+        card.extensions.retain(|ext| !is_label(ext));
+        return true;
+    }
+    match card.extensions.iter_mut().find(|ext| is_label(ext)) {
+        Some(ext) if ab_label_display(&ext.value.to_string()) == label => {}
+        Some(ext) => ext.value = AnyProperty::Text(label.to_string()),
+        None => card.extensions.push(ExtensionProperty {
+            name: AB_LABEL_PROPERTY.to_string(),
+            group: Some(group),
+            value: AnyProperty::Text(label.to_string()),
+            parameters: None,
+        }),
+    }
+    true
+}
+
+/// Set the PHOTO property with a data URI
+pub fn set_photo(card: &mut Vcard, data_uri: &str) {
+    use vcard4::property::{TextOrUriProperty, UriProperty};
+    
+    // Clear existing photos
+    card.photo.clear();
+    
+    // Parse as URI - data URIs are valid URIs
+    if let Ok(uri) = data_uri.parse::<Uri>() {
+        card.photo.push(TextOrUriProperty::Uri(UriProperty {
+            group: None,
+            value: uri,
+            parameters: None,
+        }));
+    }
+}
+
+/// JPEG bytes as a data URI
+pub fn photo_data_uri(jpeg: &[u8]) -> String {
+    use base64::Engine;
+
+    let base64_data = base64::engine::general_purpose::STANDARD.encode(jpeg);
+    format!("data:image/jpeg;base64,{}", base64_data)
+}
+
+/// Where `store_photo` put a photo
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PhotoPlacement {
+    /// In the card, as a data URI
+    Embedded,
+    /// In this managed file, referenced from the card by a `rldx-photo:` URI
+    External(PathBuf),
+}
+
+/// Scheme of a PHOTO that refers to a managed photo file: `rldx-photo:<name>`
+/// is `<vdir>/.photos/<name>.jpg.age` (`.jpg` without encryption), wherever
+/// the vdir lives
+pub const PHOTO_REF_SCHEME: &str = "rldx-photo";
+
+/// PHOTO value referring to the managed photo called `name`
+pub fn photo_ref(name: &str) -> String {
+    format!("{}:{}", PHOTO_REF_SCHEME, name)
+}
+
+/// Name of the managed photo a PHOTO value refers to. Names are plain ASCII
+/// letters, digits, `-` and `_`, so a reference never leads out of the
+/// photo directory.
+pub fn photo_ref_name(value: &str) -> Option<&str> {
+    let name = value.trim().strip_prefix(PHOTO_REF_SCHEME)?.strip_prefix(':')?;
+    let plain = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'));
+    plain.then_some(name)
+}
+
+fn photo_value(prop: &TextOrUriProperty) -> String {
+    match prop {
+        TextOrUriProperty::Uri(uri) => uri.value.to_string(),
+        TextOrUriProperty::Text(text) => text.value.clone(),
+    }
+}
+
+/// Point a PHOTO at `value`, keeping its group and parameters
+fn replace_photo_value(prop: &mut TextOrUriProperty, value: Uri) {
+    let (group, parameters) = match prop {
+        TextOrUriProperty::Uri(uri) => (uri.group.take(), uri.parameters.take()),
+        TextOrUriProperty::Text(text) => (text.group.take(), text.parameters.take()),
+    };
+    *prop = TextOrUriProperty::Uri(vcard4::property::UriProperty { group, value, parameters });
+}
+
+/// Names of the managed photos the card refers to
+pub fn managed_photo_names(card: &Vcard) -> Vec<String> {
+    card.photo
+        .iter()
+        .filter_map(|prop| photo_ref_name(&photo_value(prop)).map(str::to_string))
+        .collect()
+}
+
+/// Managed photo name for the card: its UID made safe for a file name, and a
+/// hash of the UID itself, since UIDs differing only in unsafe characters
+/// (`a.b`, `a_b`) would otherwise share a file
+fn photo_name(card: &Vcard) -> String {
+    use sha2::{Digest, Sha256};
+
+    let uid = card
+        .uid
+        .as_ref()
+        .map(|uid| uid.to_string())
+        .filter(|uid| !uid.is_empty())
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let safe: String = uid
+        .trim_start_matches("urn:uuid:")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    let hash: String = Sha256::digest(uid.as_bytes())[..6]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    format!("{}-{}", safe, hash)
+}
+
+/// Make `jpeg` the card's only PHOTO. It is embedded unless it is larger than
+/// `max_embedded` bytes; then it is written to a managed file under `root`
+/// (named after the card's UID) and the card refers to it. Returns where the
+/// photo went and the managed photos the card no longer refers to, which the
+/// caller removes once the card is written.
+pub fn store_photo(
+    card: &mut Vcard,
+    jpeg: &[u8],
+    root: &Path,
+    max_embedded: Option<usize>,
+    provider: &dyn CryptoProvider,
+) -> Result<(PhotoPlacement, Vec<String>)> {
+    use crate::vdir;
+
+    let mut previous = managed_photo_names(card);
+    let placement = if max_embedded.is_some_and(|max| jpeg.len() > max) {
+        let name = photo_name(card);
+        let path = vdir::photo_file_path(root, &name, provider.encryption_type());
+        vdir::write_photo_file(&path, jpeg, provider)?;
+        set_photo(card, &photo_ref(&name));
+        previous.retain(|old| *old != name);
+        PhotoPlacement::External(path)
+    } else {
+        set_photo(card, &photo_data_uri(jpeg));
+        PhotoPlacement::Embedded
+    };
+    Ok((placement, previous))
+}
+
+/// Put the managed photos a card refers to back into it as data URIs, for a
+/// card leaving the vdir (upload, export). A photo that cannot be read is
+/// dropped rather than sent as a reference nobody else can follow. Returns
+/// whether the card changed.
+pub fn inline_managed_photos(card: &mut Vcard, root: &Path, provider: &dyn CryptoProvider) -> bool {
+    use crate::vdir;
+
+    let mut changed = false;
+    card.photo.retain_mut(|prop| {
+        let Some(name) = photo_ref_name(&photo_value(prop)).map(str::to_string) else {
+            return true;
+        };
+        changed = true;
+        let path = vdir::photo_file_path(root, &name, provider.encryption_type());
+        let inlined = vdir::read_photo_file(&path, provider)
+            .and_then(|jpeg| photo_data_uri(&jpeg).parse::<Uri>().map_err(|err| anyhow!("{}", err)));
+        match inlined {
+            Ok(uri) => {
+                replace_photo_value(prop, uri);
+                true
+            }
+            Err(err) => {
+                log::warn!("dropping photo {}: {:#}", path.display(), err);
+                false
+            }
+        }
+    });
+    changed
+}
+
+/// Give a copied card copies of the managed photos it refers to, named after
+/// its own UID, so deleting either contact leaves the other's photo alone
+pub fn copy_managed_photos(card: &mut Vcard, root: &Path, provider: &dyn CryptoProvider) -> Result<()> {
+    use crate::vdir;
+
+    let encryption_type = provider.encryption_type();
+    let base = photo_name(card);
+    let mut copied = 0;
+    for prop in &mut card.photo {
+        let Some(name) = photo_ref_name(&photo_value(prop)).map(str::to_string) else {
+            continue;
+        };
+        let new_name = match copied {
+            0 => base.clone(),
+            n => format!("{}-{}", base, n),
+        };
+        copied += 1;
+        let from = vdir::photo_file_path(root, &name, encryption_type);
+        let to = vdir::photo_file_path(root, &new_name, encryption_type);
+        fs::copy(&from, &to).with_context(|| format!("failed to copy photo {}", from.display()))?;
+        let uri = photo_ref(&new_name).parse::<Uri>().map_err(|err| anyhow!("{}", err))?;
+        replace_photo_value(prop, uri);
     }
+    Ok(())
 }
 
-/// Set the PHOTO property with a data URI
-pub fn set_photo(card: &mut Vcard, data_uri: &str) {
-    use vcard4::property::{TextOrUriProperty, UriProperty};
-    
-    // Clear existing photos
-    card.photo.clear();
-    
-    // Parse as URI - data URIs are valid URIs
-    if let Ok(uri) = data_uri.parse::<Uri>() {
-        card.photo.push(TextOrUriProperty::Uri(UriProperty {
-            group: None,
-            value: uri,
-            parameters: None,
-        }));
+/// Load an image file, shrink it to at most 128x128 (preserving aspect ratio),
+/// and encode it as JPEG
+pub fn photo_jpeg_from_path(path: &Path) -> Result<Vec<u8>> {
+    use image::imageops::FilterType;
+    use image::GenericImageView;
+
+    let img = image::open(path)
+        .with_context(|| format!("failed to load image {}", path.display()))?;
+
+    // Resize to max 128x128 preserving aspect ratio
+    let (width, height) = img.dimensions();
+    let max_dim = 128u32;
+    let (new_width, new_height) = if width > max_dim || height > max_dim {
+        let ratio = f64::min(max_dim as f64 / width as f64, max_dim as f64 / height as f64);
+        let new_w = (width as f64 * ratio).round() as u32;
+        let new_h = (height as f64 * ratio).round() as u32;
+        (new_w.max(1), new_h.max(1))
+    } else {
+        (width, height)
+    };
+
+    let resized = img.resize_exact(new_width, new_height, FilterType::Lanczos3);
+
+    // Encode as JPEG
+    let mut jpeg_data = Vec::new();
+    {
+        let mut cursor = std::io::Cursor::new(&mut jpeg_data);
+        resized
+            .write_to(&mut cursor, image::ImageFormat::Jpeg)
+            .context("failed to encode image as JPEG")?;
     }
+
+    Ok(jpeg_data)
 }
 
 /// Delete all PHOTO properties
@@ -909,7 +2311,7 @@ fn transliterate_n(card: &mut Vcard, next_altid: &mut u32) -> bool {
     };
 
     // Combine all name components to check if transliteration is needed
-    let combined: String = name_prop.value.iter().cloned().collect::<Vec<_>>().join(" ");
+    let combined: String = name_prop.value.to_vec().join(" ");
     if !translit::needs_transliteration(&combined) {
         return false;
     }
@@ -1119,7 +2521,7 @@ VERSION:4.0
 FN:Иван Петров
 END:VCARD"#;
 
-        let parsed = parse_str(vcard_str, None).unwrap();
+        let parsed = parse_str(vcard_str, PhoneOptions::default()).unwrap();
         let mut card = parsed.cards.into_iter().next().unwrap();
 
         let changed = transliterate_card(&mut card);
@@ -1152,7 +2554,7 @@ VERSION:4.0
 FN:John Doe
 END:VCARD"#;
 
-        let parsed = parse_str(vcard_str, None).unwrap();
+        let parsed = parse_str(vcard_str, PhoneOptions::default()).unwrap();
         let mut card = parsed.cards.into_iter().next().unwrap();
 
         let changed = transliterate_card(&mut card);
@@ -1167,7 +2569,7 @@ VERSION:4.0
 FN:John Иванов
 END:VCARD"#;
 
-        let parsed = parse_str(vcard_str, None).unwrap();
+        let parsed = parse_str(vcard_str, PhoneOptions::default()).unwrap();
         let mut card = parsed.cards.into_iter().next().unwrap();
 
         let changed = transliterate_card(&mut card);
@@ -1192,7 +2594,7 @@ VERSION:4.0
 FN:Иван Петров
 END:VCARD"#;
 
-        let parsed = parse_str(vcard_str, None).unwrap();
+        let parsed = parse_str(vcard_str, PhoneOptions::default()).unwrap();
         let mut card = parsed.cards.into_iter().next().unwrap();
 
         // First transliteration
@@ -1205,6 +2607,267 @@ END:VCARD"#;
         assert!(!changed2, "second run should not change anything");
         assert_eq!(card.formatted_name.len(), 2);
     }
+
+    #[test]
+    fn test_parse_str_normalizes_phone_by_default() {
+        let vcard_str =
+            "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane\r\nTEL:(202) 555-0143\r\nEND:VCARD\r\n";
+
+        let parsed = parse_str(vcard_str, PhoneOptions::new(Some("US"), true)).unwrap();
+        assert!(parsed.changed);
+        match &parsed.cards[0].tel[0] {
+            TextOrUriProperty::Text(text) => assert_eq!(text.value, "+12025550143"),
+            other => panic!("unexpected TEL property: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_str_keeps_phone_when_normalization_disabled() {
+        let vcard_str =
+            "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane\r\nTEL:(202) 555-0143\r\nEND:VCARD\r\n";
+        let phone = PhoneOptions::new(Some("US"), false);
+
+        let parsed = parse_str(vcard_str, phone).unwrap();
+        assert!(
+            !parsed.changed,
+            "disabled normalization must not report changes"
+        );
+        match &parsed.cards[0].tel[0] {
+            TextOrUriProperty::Text(text) => assert_eq!(text.value, "(202) 555-0143"),
+            other => panic!("unexpected TEL property: {:?}", other),
+        }
+
+        assert_eq!(
+            phone_display_value("(202) 555-0143", phone),
+            "(202) 555-0143"
+        );
+        assert_eq!(
+            phone_display_value("tel:(202) 555-0143", phone),
+            "(202) 555-0143"
+        );
+    }
+
+    #[test]
+    fn test_update_tel_value_verbatim_when_normalization_disabled() {
+        let vcard_str =
+            "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane\r\nTEL:+12025550143\r\nEND:VCARD\r\n";
+        let phone = PhoneOptions::new(Some("US"), false);
+        let mut card = parse_str(vcard_str, phone).unwrap().cards.remove(0);
+
+        let updated = update_card_field(&mut card, "TEL", 0, None, " 555 0199 ", phone).unwrap();
+        assert!(updated);
+        match &card.tel[0] {
+            TextOrUriProperty::Text(text) => assert_eq!(text.value, "555 0199"),
+            other => panic!("unexpected TEL property: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_duplicate_card_gets_new_uid_and_clears_fields() {
+        let vcard_str = "BEGIN:VCARD\r\nVERSION:4.0\r\nUID:9b1f0a52-4f5e-4b43-9a57-2d4b0f3c8e11\r\n\
+FN:Jane Doe\r\nN:Doe;Jane;;;\r\nORG:Acme\r\nEMAIL:jane@acme.example\r\nTEL:+12025550143\r\n\
+REV:20200101T000000Z\r\nEND:VCARD\r\n";
+        let card = parse_str(vcard_str, PhoneOptions::default()).unwrap().cards.remove(0);
+
+        let (copy, uuid) = duplicate_card(&card, &[ClearedField::Name, ClearedField::Email]).unwrap();
+        assert_eq!(card_uid(&copy), Some(uuid.to_string()));
+        assert_ne!(card_uid(&copy), card_uid(&card));
+        assert_ne!(rev_sort_key(&copy), rev_sort_key(&card));
+        assert!(copy.email.is_empty());
+        assert!(copy.name.is_none());
+        assert_eq!(copy.tel.len(), 1);
+        assert_eq!(copy.org, card.org);
+
+        // The copy still has to survive a round trip through a file
+        let reparsed = parse_str(&cards_to_string(&[copy]), PhoneOptions::default()).unwrap();
+        assert_eq!(reparsed.cards[0].formatted_name[0].value, "");
+    }
+
+    #[test]
+    fn test_icloud_card_round_trips() {
+        let icloud = "BEGIN:VCARD\r\nVERSION:4.0\r\n\
+PRODID:-//Apple Inc.//iPhone OS 17.0//EN\r\n\
+UID:7d2b3f0e-1c4a-4a9e-9b1d-2f6c8e0a1b2c\r\n\
+FN:Jane Appleseed\r\nN:Appleseed;Jane;;;\r\n\
+item1.EMAIL;TYPE=HOME;PREF=1:jane@icloud.com\r\n\
+item1.X-ABLabel:_$!<Other>!$_\r\n\
+item2.TEL;TYPE=CELL,VOICE:+14155552671\r\n\
+item2.X-ABLabel:mobile\r\n\
+item3.ADR;TYPE=Work:;;1 Infinite Loop;Cupertino;CA;95014;USA\r\n\
+item3.X-ABADR:us\r\n\
+CLIENTPIDMAP:1;urn:uuid:53e374d9-337e-4727-8803-a1e9c14e0556\r\n\
+EMAIL;PID=1.1;TYPE=WORK:ops@work.example\r\n\
+X-ABShowAs:COMPANY\r\n\
+REV:20240102T030405Z\r\nEND:VCARD\r\n";
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("jane.vcf");
+        let provider = crate::crypto::PlainProvider;
+        std::fs::write(&path, icloud).unwrap();
+
+        let parsed = parse_file(&path, PhoneOptions::default(), &provider).unwrap();
+        assert!(!parsed.changed);
+        write_cards(&path, &parsed.cards, &provider).unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        for line in [
+            "item1.EMAIL;PREF=1;TYPE=home:jane@icloud.com",
+            "item1.X-ABLabel:_$!<Other>!$_",
+            "item2.TEL;TYPE=cell,voice:+14155552671",
+            "item3.ADR;TYPE=work:;;1 Infinite Loop;Cupertino;CA;95014;USA",
+            "item3.X-ABADR:us",
+            "CLIENTPIDMAP:1;urn:uuid:53e374d9-337e-4727-8803-a1e9c14e0556",
+            "X-ABShowAs:COMPANY",
+        ] {
+            assert!(written.contains(&format!("{line}\r\n")), "missing {line} in:\n{written}");
+        }
+        assert!(written.contains("PID=1.1"));
+        assert!(!written.contains("X-HOME") && !written.contains("X-CELL"));
+
+        // Once written, the card is canonical and stays byte-stable
+        let reparsed = parse_file(&path, PhoneOptions::default(), &provider).unwrap();
+        write_cards(&path, &reparsed.cards, &provider).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), written);
+    }
+
+    #[test]
+    fn test_store_photo_spills_over_limit_and_cleans_up() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path().join("contacts dir");
+        let provider = crate::crypto::PlainProvider;
+        let (mut card, uuid) = new_card("Ada", Kind::Individual).unwrap();
+        let jpeg = vec![0xFFu8; 64];
+
+        let (placement, stale) = store_photo(&mut card, &jpeg, &root, Some(16), &provider).unwrap();
+        assert!(stale.is_empty());
+        let name = managed_photo_names(&card).pop().unwrap();
+        assert!(name.starts_with(&format!("{}-", uuid)), "{name}");
+        let expected = root.join(".photos").join(format!("{}.jpg", name));
+        assert_eq!(placement, PhotoPlacement::External(expected.clone()));
+        assert_eq!(std::fs::read(&expected).unwrap(), jpeg);
+        let TextOrUriProperty::Uri(uri) = &card.photo[0] else { panic!("expected a URI photo") };
+        assert_eq!(uri.value.to_string(), format!("rldx-photo:{}", name));
+
+        // A small photo is embedded; the old external file is only reported,
+        // for the caller to remove once the card is written
+        let (placement, stale) = store_photo(&mut card, &jpeg[..8], &root, Some(16), &provider).unwrap();
+        assert_eq!(placement, PhotoPlacement::Embedded);
+        assert_eq!(card.photo.len(), 1);
+        assert!(card.photo[0].to_string().contains("data:image/jpeg;base64,"));
+        assert_eq!(stale, vec![name]);
+        assert!(expected.exists());
+
+        // Without a limit everything is embedded
+        let (placement, _) = store_photo(&mut card, &jpeg, &root, None, &provider).unwrap();
+        assert_eq!(placement, PhotoPlacement::Embedded);
+    }
+
+    #[test]
+    fn test_photo_names_keep_similar_uids_apart() {
+        let named = |uid: &str| {
+            let (mut card, _) = new_card("Ada", Kind::Individual).unwrap();
+            card.uid = Some(TextOrUriProperty::Text(TextProperty {
+                group: None,
+                value: uid.to_string(),
+                parameters: None,
+            }));
+            photo_name(&card)
+        };
+        assert_ne!(named("a.b"), named("a_b"));
+        assert_eq!(named("a.b"), named("a.b"));
+        assert!(photo_ref_name(&photo_ref(&named("urn:uuid:a.b/c"))).is_some());
+    }
+
+    #[test]
+    fn test_photo_ref_stays_inside_photo_dir() {
+        assert_eq!(photo_ref_name("rldx-photo:abc-123_x"), Some("abc-123_x"));
+        for bad in ["rldx-photo:", "rldx-photo:../card", "rldx-photo:a/b", "rldx-photo:.x", "file:///tmp/a.jpg"] {
+            assert_eq!(photo_ref_name(bad), None, "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_managed_photos_are_inlined_and_copied() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        let provider = crate::crypto::PlainProvider;
+        let (mut card, _) = new_card("Ada", Kind::Individual).unwrap();
+        let jpeg = vec![0xFFu8; 64];
+        store_photo(&mut card, &jpeg, root, Some(16), &provider).unwrap();
+
+        // A duplicate gets its own file
+        let (mut copy, copy_uuid) = duplicate_card(&card, &[]).unwrap();
+        copy_managed_photos(&mut copy, root, &provider).unwrap();
+        let copy_name = managed_photo_names(&copy).pop().unwrap();
+        assert!(copy_name.starts_with(&copy_uuid.to_string()), "{copy_name}");
+        let copied = crate::vdir::photo_file_path(root, &copy_name, crate::config::EncryptionType::None);
+        assert_eq!(std::fs::read(&copied).unwrap(), jpeg);
+
+        // Leaving the vdir, the reference becomes the photo itself
+        let mut outgoing = card.clone();
+        assert!(inline_managed_photos(&mut outgoing, root, &provider));
+        assert_eq!(photo_value(&outgoing.photo[0]), photo_data_uri(&jpeg));
+        assert!(!inline_managed_photos(&mut outgoing, root, &provider));
+
+        // A photo that is gone is dropped, not sent as a dangling reference
+        let names = managed_photo_names(&card);
+        crate::vdir::remove_photo_files(root, &names, crate::config::EncryptionType::None);
+        assert!(!crate::vdir::photo_file_path(root, &names[0], crate::config::EncryptionType::None).exists());
+        assert!(copied.exists());
+        let mut outgoing = card.clone();
+        assert!(inline_managed_photos(&mut outgoing, root, &provider));
+        assert!(outgoing.photo.is_empty());
+    }
+
+    #[test]
+    fn test_new_card_from_search_text() {
+        let (card, uuid) = new_card("Ada; Lovelace", Kind::Individual).unwrap();
+        assert_eq!(card_uid(&card), Some(uuid.to_string()));
+        assert!(card.rev.is_some());
+
+        let reparsed = parse_str(&cards_to_string(&[card]), PhoneOptions::default()).unwrap();
+        assert_eq!(reparsed.cards[0].formatted_name[0].value, "Ada; Lovelace");
+        assert_eq!(reparsed.cards[0].kind.as_ref().map(|k| k.value.clone()), Some(Kind::Individual));
+        assert!(reparsed.cards[0].org.is_empty());
+
+        let (org, _) = new_card("Acme", Kind::Org).unwrap();
+        let text = cards_to_string(&[org]);
+        assert!(text.contains("KIND:org\r\n") && text.contains("ORG:Acme\r\n"), "{text}");
+    }
+
+    #[test]
+    fn test_canonical_file_round_trips_byte_for_byte() {
+        let canonical = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nTEL:+12025550143\r\n\
+EMAIL:jane@example.com\r\nNOTE:first line\\nsecond line\r\nEND:VCARD\r\n\
+BEGIN:VCARD\r\nVERSION:4.0\r\nFN:John Roe\r\nEND:VCARD\r\n";
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("contact.vcf");
+        let provider = crate::crypto::PlainProvider;
+        std::fs::write(&path, canonical).unwrap();
+
+        let parsed = parse_file(&path, PhoneOptions::default(), &provider).unwrap();
+        assert!(!parsed.changed);
+        write_cards(&path, &parsed.cards, &provider).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), canonical);
+
+        // LF endings and a gap between cards both come back canonical
+        let sloppy = canonical
+            .replace("\r\n", "\n")
+            .replace("END:VCARD\nBEGIN", "END:VCARD\n\nBEGIN");
+        let cards = parse_str(&sloppy, PhoneOptions::default()).unwrap().cards;
+        assert_eq!(cards_to_string(&cards), canonical);
+    }
+
+    #[test]
+    fn test_cards_to_string_keeps_space_at_fold() {
+        // The 74th byte of the NOTE line is a space, so the fold ends a line with it
+        let note = format!("{} {}", "a".repeat(68), "b".repeat(20));
+        let vcard_str = format!("BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane\r\nNOTE:{note}\r\nEND:VCARD\r\n");
+        let cards = parse_str(&vcard_str, PhoneOptions::default()).unwrap().cards;
+
+        let text = cards_to_string(&cards);
+        assert!(text.contains(" \r\n b"), "{text:?}");
+        let reparsed = parse_str(&text, PhoneOptions::default()).unwrap().cards;
+        assert_eq!(reparsed[0].note[0].value, note);
+    }
 }
 
 // =============================================================================
@@ -1369,7 +3032,7 @@ fn add_nickname_if_unique(card: &mut Vcard, nickname: &str) {
 fn name_to_display_string(name: &vcard4::property::TextListProperty) -> String {
     // N components: [0]=family, [1]=given, [2]=additional, [3]=prefix, [4]=suffix
     // Display order: prefix given additional family suffix
-    let family = name.value.get(0).map(|s| s.trim()).unwrap_or("");
+    let family = name.value.first().map(|s| s.trim()).unwrap_or("");
     let given = name.value.get(1).map(|s| s.trim()).unwrap_or("");
     let additional = name.value.get(2).map(|s| s.trim()).unwrap_or("");
     let prefix = name.value.get(3).map(|s| s.trim()).unwrap_or("");
@@ -1392,40 +3055,70 @@ pub struct MergeResult {
     pub path: std::path::PathBuf,
 }
 
-/// Merge multiple vCard files into a single encrypted file.
-/// 
-/// - Parses all input files
-/// - Merges cards (first card is base)
-/// - Generates new UID and REV
-/// - Writes to target directory with correct encrypted extension
-/// - Returns the merged card and output path
-pub fn merge_vcard_files(
+/// Parse `paths` and merge their first cards the way `merge_vcard_files`
+/// does, without writing anything or assigning a new UID and REV
+pub fn plan_merge(
     paths: &[std::path::PathBuf],
-    target_dir: &std::path::Path,
     provider: &dyn CryptoProvider,
-    phone_region: Option<&str>,
-) -> Result<MergeResult> {
-    use crate::vdir;
-    
+    phone: PhoneOptions<'_>,
+) -> Result<Vcard> {
     if paths.len() < 2 {
         anyhow::bail!("need at least 2 files to merge");
     }
-    
-    // Parse all cards
+
     let mut cards: Vec<Vcard> = Vec::new();
     for path in paths {
-        let parsed = parse_file(path, phone_region, provider)?;
+        let parsed = parse_file(path, phone, provider)?;
         if let Some(card) = parsed.cards.into_iter().next() {
             cards.push(card);
         }
     }
-    
+
     if cards.len() < 2 {
         anyhow::bail!("could not parse at least 2 cards");
     }
+
+    merge_cards(cards).ok_or_else(|| anyhow!("merge failed"))
+}
+
+/// Key fields of a merge result for review: FN, every EMAIL and TEL, and ORG
+pub fn merge_preview(card: &Vcard) -> Vec<String> {
+    let mut lines = Vec::new();
+    if let Some(name) = card.formatted_name.first() {
+        lines.push(format!("FN: {}", name.value));
+    }
+    for email in &card.email {
+        lines.push(format!("EMAIL: {}", email.value));
+    }
+    for tel in &card.tel {
+        let value = match tel {
+            TextOrUriProperty::Text(text) => text.value.clone(),
+            TextOrUriProperty::Uri(uri) => uri.value.to_string(),
+        };
+        lines.push(format!("TEL: {}", value));
+    }
+    for org in &card.org {
+        lines.push(format!("ORG: {}", org.value.join(", ")));
+    }
+    lines
+}
+
+/// Merge multiple vCard files into a single encrypted file.
+/// 
+/// - Parses all input files
+/// - Merges cards (first card is base)
+/// - Generates new UID and REV
+/// - Writes to target directory with correct encrypted extension
+/// - Returns the merged card and output path
+pub fn merge_vcard_files(
+    paths: &[std::path::PathBuf],
+    target_dir: &std::path::Path,
+    provider: &dyn CryptoProvider,
+    phone: PhoneOptions<'_>,
+) -> Result<MergeResult> {
+    use crate::vdir;
     
-    // Merge
-    let mut merged = merge_cards(cards).ok_or_else(|| anyhow!("merge failed"))?;
+    let mut merged = plan_merge(paths, provider, phone)?;
     
     // Ensure UID and REV
     let uuid = ensure_uuid_uid(&mut merged)?;
@@ -1475,7 +3168,12 @@ UID:{}
             email.map(|e| format!("EMAIL:{}\r\n", e)).unwrap_or_default(),
             tel.map(|t| format!("TEL:{}\r\n", t)).unwrap_or_default(),
         );
-        parse_str(&vcard_str, None).unwrap().cards.into_iter().next().unwrap()
+        parse_str(&vcard_str, PhoneOptions::default())
+            .unwrap()
+            .cards
+            .into_iter()
+            .next()
+            .unwrap()
     }
 
     #[test]
@@ -1557,7 +3255,7 @@ UID:{}
             &[path1.clone(), path2.clone()],
             vdir,
             &provider,
-            None,
+            PhoneOptions::default(),
         ).unwrap();
 
         // Verify: output file has .vcf.age extension
@@ -1568,7 +3266,7 @@ UID:{}
         );
 
         // Verify: output file can be decrypted
-        let parsed = parse_file(&result.path, None, &provider).unwrap();
+        let parsed = parse_file(&result.path, PhoneOptions::default(), &provider).unwrap();
         assert_eq!(parsed.cards.len(), 1);
 
         // Verify: merged card has both emails
@@ -1576,6 +3274,26 @@ UID:{}
         assert_eq!(merged_card.email.len(), 2);
     }
 
+    #[test]
+    fn test_plan_merge_previews_without_writing() {
+        let temp_dir = TempDir::new().unwrap();
+        let vdir = temp_dir.path();
+        let provider = AgeProvider::new_ephemeral(vdir).unwrap();
+
+        let path1 = crate::vdir::vcf_target_path(vdir, "one", provider.encryption_type());
+        let path2 = crate::vdir::vcf_target_path(vdir, "two", provider.encryption_type());
+        write_cards(&path1, &[make_card("Ann Lee", Some("ann@a.example"), Some("+111"))], &provider).unwrap();
+        write_cards(&path2, &[make_card("Ann", Some("ann@b.example"), None)], &provider).unwrap();
+        let files_before = std::fs::read_dir(vdir).unwrap().count();
+
+        let merged = plan_merge(&[path1, path2], &provider, PhoneOptions::default()).unwrap();
+        assert_eq!(std::fs::read_dir(vdir).unwrap().count(), files_before);
+        assert_eq!(
+            merge_preview(&merged),
+            ["FN: Ann Lee", "EMAIL: ann@a.example", "EMAIL: ann@b.example", "TEL: +111"]
+        );
+    }
+
     #[test]
     fn test_merge_vcard_files_preserves_data() {
         let temp_dir = TempDir::new().unwrap();
@@ -1599,10 +3317,11 @@ UID:{}
         write_cards(&path1, &[card1], &provider).unwrap();
         write_cards(&path2, &[card2], &provider).unwrap();
 
-        let result = merge_vcard_files(&[path1, path2], vdir, &provider, None).unwrap();
+        let result =
+            merge_vcard_files(&[path1, path2], vdir, &provider, PhoneOptions::default()).unwrap();
 
         // Parse and verify
-        let parsed = parse_file(&result.path, None, &provider).unwrap();
+        let parsed = parse_file(&result.path, PhoneOptions::default(), &provider).unwrap();
         let merged = &parsed.cards[0];
 
         // Main FN preserved
@@ -1705,7 +3424,12 @@ END:VCARD"#,
             n_value,
             uuid::Uuid::new_v4(),
         );
-        parse_str(&vcard_str, None).unwrap().cards.into_iter().next().unwrap()
+        parse_str(&vcard_str, PhoneOptions::default())
+            .unwrap()
+            .cards
+            .into_iter()
+            .next()
+            .unwrap()
     }
 
     #[test]
@@ -1718,7 +3442,7 @@ END:VCARD"#,
 
         // Should have both family and given filled
         let name = merged.name.as_ref().unwrap();
-        assert_eq!(name.value.get(0).map(|s| s.as_str()), Some("Smith")); // family from B
+        assert_eq!(name.value.first().map(|s| s.as_str()), Some("Smith")); // family from B
         assert_eq!(name.value.get(1).map(|s| s.as_str()), Some("John"));  // given from A
     }
 
@@ -1730,7 +3454,7 @@ END:VCARD"#,
         let merged = merge_two_cards(card1, card2);
 
         let name = merged.name.as_ref().unwrap();
-        assert_eq!(name.value.get(0).map(|s| s.as_str()), Some("Smith"));   // family
+        assert_eq!(name.value.first().map(|s| s.as_str()), Some("Smith"));   // family
         assert_eq!(name.value.get(1).map(|s| s.as_str()), Some("John"));    // given
         assert_eq!(name.value.get(2).map(|s| s.as_str()), Some("William")); // additional from B
         assert_eq!(name.value.get(3).map(|s| s.as_str()), Some("Dr."));     // prefix from B
@@ -1779,7 +3503,7 @@ END:VCARD"#,
 
         // A's values kept where present (A is base for N merge)
         let name = merged.name.as_ref().unwrap();
-        assert_eq!(name.value.get(0).map(|s| s.as_str()), Some("Smith")); // A's family
+        assert_eq!(name.value.first().map(|s| s.as_str()), Some("Smith")); // A's family
         assert_eq!(name.value.get(1).map(|s| s.as_str()), Some("John"));  // A's given
 
         // B's values used for empty components
@@ -1815,7 +3539,7 @@ END:VCARD"#,
 
         // Same names case-insensitively, no conflict
         let name = merged.name.as_ref().unwrap();
-        assert_eq!(name.value.get(0).map(|s| s.as_str()), Some("Smith")); // A's case preserved
+        assert_eq!(name.value.first().map(|s| s.as_str()), Some("Smith")); // A's case preserved
         assert_eq!(name.value.get(1).map(|s| s.as_str()), Some("John"));
 
         // No alias added
@@ -1831,7 +3555,7 @@ END:VCARD"#,
 
         // A's name preserved
         let name = merged.name.as_ref().unwrap();
-        assert_eq!(name.value.get(0).map(|s| s.as_str()), Some("Smith"));
+        assert_eq!(name.value.first().map(|s| s.as_str()), Some("Smith"));
         assert_eq!(name.value.get(1).map(|s| s.as_str()), Some("John"));
     }
 
@@ -1844,7 +3568,7 @@ END:VCARD"#,
 
         // B's name used entirely
         let name = merged.name.as_ref().unwrap();
-        assert_eq!(name.value.get(0).map(|s| s.as_str()), Some("Smith"));
+        assert_eq!(name.value.first().map(|s| s.as_str()), Some("Smith"));
         assert_eq!(name.value.get(1).map(|s| s.as_str()), Some("John"));
         assert_eq!(name.value.get(2).map(|s| s.as_str()), Some("W"));
         assert_eq!(name.value.get(3).map(|s| s.as_str()), Some("Dr."));
@@ -1889,4 +3613,588 @@ END:VCARD"#,
         let display = name_to_display_string(&name);
         assert_eq!(display, "John Smith");
     }
+
+    #[test]
+    fn test_phone_tel_uri_uses_e164() {
+        assert_eq!(phone_tel_uri("(415) 555-2671", Some("US")), "tel:+14155552671");
+        assert_eq!(phone_tel_uri("tel:+44 20 7946 0958", None), "tel:+442079460958");
+        // Unparseable numbers are kept, minus whitespace
+        assert_eq!(phone_tel_uri("ext 12 34", None), "tel:ext1234");
+    }
+
+    #[test]
+    fn test_normalize_card_phone_numbers_counts_changes() {
+        let vcard_str = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane\r\n\
+TEL:020 7946 0958\r\n\
+TEL:+442079460958\r\n\
+END:VCARD\r\n";
+        let as_stored = PhoneOptions::new(None, false);
+        let card = parse_str(vcard_str, as_stored).unwrap().cards.remove(0);
+
+        // Without a region the national number stays ambiguous
+        assert_eq!(normalize_card_phone_numbers(&mut card.clone(), None), 0);
+
+        let mut card = card;
+        assert_eq!(normalize_card_phone_numbers(&mut card, Some("GB")), 1);
+        let values: Vec<String> = card
+            .tel
+            .iter()
+            .map(|prop| match prop {
+                TextOrUriProperty::Text(text) => text.value.clone(),
+                TextOrUriProperty::Uri(uri) => uri.value.to_string(),
+            })
+            .collect();
+        assert_eq!(values, ["+442079460958", "+442079460958"]);
+        assert_eq!(normalize_card_phone_numbers(&mut card, Some("GB")), 0);
+    }
+
+    #[test]
+    fn test_phone_digits() {
+        assert_eq!(phone_digits("(415) 555-2671"), "4155552671");
+        assert_eq!(phone_digits("tel:+44 20 7946 0958"), "+442079460958");
+        assert_eq!(phone_digits("no number"), "");
+    }
+
+    #[test]
+    fn test_phone_validity() {
+        assert_eq!(phone_validity("(201) 555-0123", Some("US")), PhoneValidity::Valid);
+        assert_eq!(phone_validity("tel:+44 20 7946 0958", None), PhoneValidity::Valid);
+        // Right length, but no such area code
+        assert_eq!(phone_validity("+1 200 555 0123", None), PhoneValidity::Possible);
+        assert_eq!(phone_validity("call me", Some("US")), PhoneValidity::Unrecognized);
+        assert_eq!(phone_validity("12", Some("US")), PhoneValidity::Unrecognized);
+    }
+
+    #[test]
+    fn test_clean_email_paste_mistakes() {
+        assert_eq!(clean_email("  jane@example.com \t"), "jane@example.com");
+        assert_eq!(clean_email("mailto:jane@example.com"), "jane@example.com");
+        assert_eq!(clean_email("MAILTO:jane@example.com?subject=Hi"), "jane@example.com");
+        assert_eq!(clean_email("Jane Doe <jane@example.com>"), "jane@example.com");
+        assert_eq!(clean_email("<mailto:jane@example.com>"), "jane@example.com");
+        assert_eq!(clean_email("\"jane@example.com\","), "jane@example.com");
+        assert_eq!(clean_email("jane@example.com;"), "jane@example.com");
+        // A multibyte character across byte 7 is not a scheme
+        assert_eq!(clean_email("ééééx@y.z"), "ééééx@y.z");
+        assert_eq!(clean_email("MaIlTo:ééééx@y.z"), "ééééx@y.z");
+
+        assert!(email_looks_valid("jane@example.com"));
+        assert!(!email_looks_valid("jane.example.com"));
+        assert!(!email_looks_valid("@example.com"));
+        assert!(!email_looks_valid("jane@"));
+        assert!(!email_looks_valid("jane@@example.com"));
+        assert!(!email_looks_valid("jane doe@example.com"));
+    }
+
+    #[test]
+    fn test_clean_url_adds_missing_scheme() {
+        assert_eq!(clean_url("example.com"), "https://example.com");
+        assert_eq!(clean_url("  <www.example.com/a b>  "), "https://www.example.com/a%20b");
+        assert_eq!(clean_url("example.com:8080/path"), "https://example.com:8080/path");
+        assert_eq!(clean_url("localhost:8080"), "https://localhost:8080");
+        assert_eq!(clean_url("intranet:443/wiki?page=1"), "https://intranet:443/wiki?page=1");
+        assert_eq!(clean_url("tel:+15551234567"), "tel:+15551234567");
+        assert_eq!(clean_url("http://example.com"), "http://example.com");
+        assert_eq!(clean_url("ftp://files.example.com/pub"), "ftp://files.example.com/pub");
+        assert_eq!(clean_url("mailto:jane@example.com"), "mailto:jane@example.com");
+        assert_eq!(clean_url("   "), "");
+
+        assert!(url_looks_valid(&clean_url("example.com")));
+        assert!(url_looks_valid("ftp://files.example.com/pub"));
+        assert!(url_looks_valid("mailto:jane@example.com"));
+        assert!(url_looks_valid("http://localhost:8080/"));
+        assert!(url_looks_valid(&clean_url("localhost:8080")));
+        assert!(!url_looks_valid(&clean_url("garbage")));
+        assert!(!url_looks_valid(&clean_url("not a url")));
+        assert!(!url_looks_valid(&clean_url("https://exa_mple..com")));
+    }
+
+    #[test]
+    fn test_url_edit_cleans_value_and_keeps_type() {
+        let vcard_str = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane\r\nURL;TYPE=work:https://old.example\r\nEND:VCARD\r\n";
+        let mut card = parse_str(vcard_str, PhoneOptions::default()).unwrap().cards.remove(0);
+
+        assert!(update_card_field(&mut card, "URL", 0, None, "jane.example.org", PhoneOptions::default()).unwrap());
+        assert!(card.to_string().contains("URL;TYPE=work:https://jane.example.org"));
+        assert!(!update_card_field(&mut card, "URL", 3, None, "x.example", PhoneOptions::default()).unwrap());
+        assert!(!update_card_field(&mut card, "URL", 0, None, "https://[bad", PhoneOptions::default()).unwrap());
+
+        assert!(add_card_field(&mut card, "URL", "blog.example.org", None));
+        assert_eq!(card.url[1].value.to_string(), "https://blog.example.org/");
+    }
+
+    #[test]
+    fn test_card_without_fn_parses_and_fills_from_n() {
+        let vcard_str = "BEGIN:VCARD\r\nVERSION:4.0\r\nN:Doe;Jane;;;\r\nEND:VCARD\r\n\
+BEGIN:VCARD\r\nVERSION:4.0\r\nitem1.FN:John Roe\r\nEND:VCARD\r\n";
+        let mut parsed = parse_str(vcard_str, PhoneOptions::default()).unwrap();
+        assert!(!parsed.changed);
+        assert_eq!(parsed.cards[0].formatted_name[0].value, "");
+        assert_eq!(parsed.cards[1].formatted_name.len(), 1);
+        assert_eq!(name_from_n(&parsed.cards[0]).as_deref(), Some("Jane Doe"));
+
+        assert!(fill_fn_from_name(&mut parsed.cards[0]));
+        assert_eq!(parsed.cards[0].formatted_name[0].value, "Jane Doe");
+        // A card with a real FN is left alone
+        assert!(!fill_fn_from_name(&mut parsed.cards[1]));
+    }
+
+    #[test]
+    fn test_tag_source_keeps_first_origin() {
+        let vcard_str = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane\r\n\
+EMAIL;X-RLDX-SOURCE=maildir:jane@example.com\r\nTEL;TYPE=cell:+12025550143\r\n\
+ORG:Acme\r\nEND:VCARD\r\n";
+        let mut card = parse_str(vcard_str, PhoneOptions::default()).unwrap().cards.remove(0);
+        assert_eq!(tag_source(&mut card, "google"), 2);
+        assert_eq!(tag_source(&mut card, "google"), 0);
+
+        let reparsed = parse_str(&card.to_string(), PhoneOptions::default()).unwrap().cards.remove(0);
+        let text = reparsed.to_string();
+        assert!(text.contains("EMAIL;X-RLDX-SOURCE=\"maildir\":"));
+        assert!(text.contains("X-RLDX-SOURCE=\"google\":+12025550143"));
+        assert!(text.contains("ORG;X-RLDX-SOURCE=\"google\":Acme"));
+        // FN is not a value that gets merged from elsewhere
+        assert!(text.contains("FN:Jane"));
+
+        // Stripped before upload, leaving the other parameters alone
+        let mut card = reparsed;
+        assert!(strip_source_tags(&mut card));
+        assert!(!strip_source_tags(&mut card));
+        let text = card.to_string();
+        assert!(!text.contains("X-RLDX-SOURCE"), "{text}");
+        assert!(text.contains("EMAIL:jane@example.com"), "{text}");
+        assert!(text.contains("TEL;TYPE=cell:+12025550143"), "{text}");
+    }
+
+    #[test]
+    fn test_split_formatted_name() {
+        let split = |s: &str| split_formatted_name(s).to_vec();
+        assert_eq!(split("John Smith"), ["Smith", "John", "", "", ""]);
+        assert_eq!(split("Smith, John Quincy"), ["Smith", "John", "Quincy", "", ""]);
+        assert_eq!(split("Dr. Jane Q. Doe Jr."), ["Doe", "Jane", "Q.", "Dr.", "Jr."]);
+        assert_eq!(split("John Smith, PhD"), ["Smith", "John", "", "", "PhD"]);
+        assert_eq!(split("Madonna"), ["", "Madonna", "", "", ""]);
+    }
+
+    #[test]
+    fn test_update_and_promote_address() {
+        let vcard_str = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane\r\n\
+ADR;TYPE=home:;;1 Main St;Springfield;;12345;USA\r\n\
+ADR;TYPE=work;LABEL=\"9 Office Rd\":;;9 Office Rd;Shelbyville;;;\r\n\
+END:VCARD\r\n";
+        let parsed = parse_str(vcard_str, PhoneOptions::default()).unwrap();
+        let mut card = parsed.cards.into_iter().next().unwrap();
+
+        let components = address_components(&card, 1).unwrap();
+        assert_eq!(components[2], "9 Office Rd");
+        assert_eq!(components.len(), ADR_COMPONENTS);
+
+        assert!(update_card_field(&mut card, "ADR", 1, Some(2), "10 Office Rd", PhoneOptions::default()).unwrap());
+        assert_eq!(card.address[1].value.street_address.as_deref(), Some("10 Office Rd"));
+        // Editing a component keeps the LABEL the user wrote
+        assert_eq!(
+            card.address[1].parameters.as_ref().unwrap().label.as_deref(),
+            Some("9 Office Rd")
+        );
+
+        assert!(promote_address_entry(&mut card, 1));
+        assert_eq!(card.address[0].value.locality.as_deref(), Some("Shelbyville"));
+        assert!(!promote_address_entry(&mut card, 5));
+    }
+
+    #[test]
+    fn test_ensure_leading_category() {
+        let vcard_str = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane\r\nCATEGORIES:friends,work\r\nEND:VCARD\r\n";
+        let parsed = parse_str(vcard_str, PhoneOptions::default()).unwrap();
+        let mut card = parsed.cards.into_iter().next().unwrap();
+
+        assert!(!ensure_leading_category(&mut card, "friends"));
+        assert!(ensure_leading_category(&mut card, "work"));
+        assert_eq!(categories(&card).collect::<Vec<_>>(), ["work", "friends"]);
+
+        let mut bare = parse_str("BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Bob\r\nEND:VCARD\r\n", PhoneOptions::default())
+            .unwrap()
+            .cards
+            .remove(0);
+        assert!(ensure_leading_category(&mut bare, "family"));
+        assert!(bare.to_string().contains("CATEGORIES:family"));
+    }
+
+    #[test]
+    fn test_add_category_appends_once() {
+        let vcard_str = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane\r\nCATEGORIES:friends\r\nEND:VCARD\r\n";
+        let mut card = parse_str(vcard_str, PhoneOptions::default()).unwrap().cards.remove(0);
+
+        assert!(add_category(&mut card, "leads-2024"));
+        assert!(!add_category(&mut card, "Friends"));
+        assert_eq!(categories(&card).collect::<Vec<_>>(), ["friends", "leads-2024"]);
+
+        let mut bare = parse_str("BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Bob\r\nEND:VCARD\r\n", PhoneOptions::default())
+            .unwrap()
+            .cards
+            .remove(0);
+        assert!(add_category(&mut bare, "leads-2024"));
+        assert!(bare.to_string().contains("CATEGORIES:leads-2024"));
+    }
+
+    #[test]
+    fn test_remove_category_drops_empty_property() {
+        let vcard_str = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane\r\nCATEGORIES:friends,Conference-2024\r\nEND:VCARD\r\n";
+        let mut card = parse_str(vcard_str, PhoneOptions::default()).unwrap().cards.remove(0);
+
+        assert!(remove_category(&mut card, "conference-2024"));
+        assert!(!remove_category(&mut card, "conference-2024"));
+        assert_eq!(categories(&card).collect::<Vec<_>>(), ["friends"]);
+
+        assert!(remove_category(&mut card, "friends"));
+        assert!(!card.to_string().contains("CATEGORIES"));
+    }
+
+    #[test]
+    fn test_edits_keep_existing_parameters() {
+        let vcard_str = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane\r\n\
+TEL;PREF=1;X-FOO=bar:+1 555 123 4567\r\n\
+TEL;VALUE=uri;TYPE=work;X-FOO=baz:tel:+15557654321\r\n\
+EMAIL;PREF=2;X-FOO=qux:jane@example.com\r\n\
+END:VCARD\r\n";
+        // Normalization on parse and on edit must only touch the value
+        let phone = PhoneOptions::default();
+        let parsed = parse_str(vcard_str, phone).unwrap();
+        let mut card = parsed.cards.into_iter().next().unwrap();
+
+        assert!(update_card_field(&mut card, "TEL", 0, None, "+1 (555) 000-0000", phone).unwrap());
+        assert!(update_card_field(&mut card, "TEL", 1, None, "+15551111111", phone).unwrap());
+        assert!(update_card_field(&mut card, "EMAIL", 0, None, "jd@example.com", phone).unwrap());
+
+        let written = String::from_utf8(card_to_bytes(&card)).unwrap();
+        let card = parse_str(&written, phone).unwrap().cards.remove(0);
+
+        let tel_params = |index: usize| match &card.tel[index] {
+            TextOrUriProperty::Text(prop) => prop.parameters.clone().unwrap(),
+            TextOrUriProperty::Uri(prop) => prop.parameters.clone().unwrap(),
+        };
+        let x_foo = |params: &vcard4::parameter::Parameters| {
+            params
+                .extensions
+                .as_ref()
+                .and_then(|ext| ext.iter().find(|(name, _)| name.eq_ignore_ascii_case("X-FOO")))
+                .map(|(_, values)| values.join(","))
+        };
+
+        let first = tel_params(0);
+        assert_eq!(first.pref, Some(1));
+        assert_eq!(x_foo(&first).as_deref(), Some("bar"));
+        let second = tel_params(1);
+        assert_eq!(second.types.as_ref().map(Vec::len), Some(1));
+        assert_eq!(x_foo(&second).as_deref(), Some("baz"));
+
+        let email = card.email[0].parameters.clone().unwrap();
+        assert_eq!(card.email[0].value, "jd@example.com");
+        assert_eq!(email.pref, Some(2));
+        assert_eq!(x_foo(&email).as_deref(), Some("qux"));
+        assert!(written.contains("+15550000000"));
+    }
+
+    #[test]
+    fn test_no_sync_flag() {
+        let vcard_str = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Local\r\nX-RLDX-NOSYNC:0\r\nEND:VCARD\r\n";
+        let mut card = parse_str(vcard_str, PhoneOptions::default()).unwrap().cards.remove(0);
+        assert!(!is_no_sync(&card));
+
+        assert!(set_no_sync(&mut card, true));
+        assert!(!set_no_sync(&mut card, true));
+        assert!(is_no_sync(&card));
+        assert!(card.to_string().contains("X-RLDX-NOSYNC:1"));
+
+        assert!(set_no_sync(&mut card, false));
+        assert!(!card.to_string().contains("X-RLDX-NOSYNC"));
+    }
+
+    #[test]
+    fn test_sort_position() {
+        let vcard_str = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Ada\r\nX-RLDX-SORT:abc\r\nEND:VCARD\r\n";
+        let mut card = parse_str(vcard_str, PhoneOptions::default()).unwrap().cards.remove(0);
+        assert_eq!(sort_position(&card), None);
+
+        assert!(set_sort_position(&mut card, Some(3)));
+        assert!(!set_sort_position(&mut card, Some(3)));
+        assert_eq!(sort_position(&card), Some(3));
+        assert_eq!(card.to_string().matches("X-RLDX-SORT").count(), 1);
+
+        assert!(set_sort_position(&mut card, None));
+        assert!(!card.to_string().contains("X-RLDX-SORT"));
+    }
+
+    #[test]
+    fn test_ab_labels() {
+        let vcard_str = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane\r\n\
+item1.EMAIL:jane@home.example\r\n\
+item1.X-ABLabel:_$!<Home>!$_\r\n\
+item2.TEL:+15551234567\r\n\
+item2.X-ABLabel:Boat\r\n\
+EMAIL:jane@work.example\r\n\
+END:VCARD\r\n";
+        let mut card = parse_str(vcard_str, PhoneOptions::default()).unwrap().cards.remove(0);
+        let labels = ab_labels(&card);
+        assert_eq!(labels.get("item1").map(String::as_str), Some("Home"));
+        assert_eq!(labels.get("item2").map(String::as_str), Some("Boat"));
+        assert_eq!(ab_label_display("Gym"), "Gym");
+
+        // Unchanged labels keep Apple's wrapping
+        assert!(set_ab_label(&mut card, "EMAIL", 0, "Home"));
+        assert!(card.to_string().contains("item1.X-ABLabel:_$!<Home>!$_"));
+
+        // An unlabelled property gets the next free group
+        assert!(set_ab_label(&mut card, "EMAIL", 1, "Office"));
+        let text = card.to_string();
+        assert!(text.contains("item3.EMAIL:jane@work.example"));
+        assert!(text.contains("item3.X-ABLabel:Office"));
+
+        assert!(set_ab_label(&mut card, "TEL", 0, ""));
+        assert!(!card.to_string().contains("Boat"));
+        assert!(!set_ab_label(&mut card, "TEL", 5, "Nope"));
+    }
+
+    #[test]
+    fn test_dedupe_emails() {
+        let vcard_str = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Foo\r\n\
+EMAIL;TYPE=work: Foo@X.com \r\n\
+EMAIL;TYPE=home;PREF=1:foo@x.com\r\n\
+EMAIL:Other@Example.COM\r\n\
+END:VCARD\r\n";
+        let card = parse_str(vcard_str, PhoneOptions::default()).unwrap().cards.remove(0);
+
+        let mut all = card.clone();
+        assert!(dedupe_emails(&mut all, EmailCase::All));
+        let values: Vec<_> = all.email.iter().map(|p| p.value.as_str()).collect();
+        assert_eq!(values, ["foo@x.com", "other@example.com"]);
+        // The surviving first entry keeps its place, takes the PREF and both types
+        let params = all.email[0].parameters.as_ref().unwrap();
+        assert_eq!(params.pref, Some(1));
+        assert_eq!(params.types.as_ref().unwrap().len(), 2);
+        assert!(!dedupe_emails(&mut all, EmailCase::All));
+
+        // Domain-only lowercasing keeps distinct local parts apart
+        let mut domain = card.clone();
+        assert!(dedupe_emails(&mut domain, EmailCase::Domain));
+        let values: Vec<_> = domain.email.iter().map(|p| p.value.as_str()).collect();
+        assert_eq!(values, ["Foo@x.com", "foo@x.com", "Other@example.com"]);
+    }
+
+    #[test]
+    fn test_removed_field_takes_its_label() {
+        let vcard_str = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Foo\r\n\
+EMAIL:foo@x.com\r\n\
+item1.EMAIL:FOO@x.com\r\n\
+item1.X-ABLabel:Old\r\n\
+item2.TEL:+1 415 555 2671\r\n\
+item2.X-ABLabel:Boat\r\n\
+item3.X-ABRELATEDNAMES:Bar\r\n\
+item3.X-ABLabel:_$!<Friend>!$_\r\n\
+END:VCARD\r\n";
+        let mut card = parse_str(vcard_str, PhoneOptions::default()).unwrap().cards.remove(0);
+
+        assert!(dedupe_emails(&mut card, EmailCase::All));
+        assert!(delete_card_field(&mut card, "TEL", 0));
+        let text = card.to_string();
+        assert!(!text.contains("Old"), "{text}");
+        assert!(!text.contains("Boat"), "{text}");
+        // Labels of groups that lost nothing stay
+        assert!(text.contains("item3.X-ABLabel"), "{text}");
+    }
+
+    #[test]
+    fn test_promote_found_entry_with_pref() {
+        let vcard_str = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Foo\r\n\
+EMAIL;PREF=1:first@x.com\r\n\
+EMAIL:Second@X.com\r\n\
+TEL:+1 415 555 2671\r\n\
+TEL:+44 20 7946 0958\r\n\
+END:VCARD\r\n";
+        let mut card = parse_str(vcard_str, PhoneOptions::default()).unwrap().cards.remove(0);
+
+        assert_eq!(find_email_entry(&card, " second@x.com"), Some(1));
+        assert_eq!(find_email_entry(&card, "third@x.com"), None);
+        assert_eq!(find_tel_entry(&card, "tel:+442079460958"), Some(1));
+        assert_eq!(find_tel_entry(&card, "(415) 555-0000"), None);
+
+        assert!(promote_email_entry(&mut card, 1));
+        prefer_first_email(&mut card);
+        assert_eq!(card.email[0].value, "Second@X.com");
+        assert_eq!(card.email[0].parameters.as_ref().unwrap().pref, Some(1));
+        assert_eq!(card.email[1].parameters.as_ref().unwrap().pref, None);
+        // TEL is left alone
+        assert!(card.tel.iter().all(|prop| match prop {
+            TextOrUriProperty::Text(text) => text.parameters.is_none(),
+            TextOrUriProperty::Uri(uri) => uri.parameters.is_none(),
+        }));
+    }
+
+    #[test]
+    fn test_reorder_entry_keeps_pref_on_top() {
+        let vcard_str = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Foo\r\n\
+EMAIL;PREF=1:a@x.com\r\n\
+EMAIL:b@x.com\r\n\
+EMAIL:c@x.com\r\n\
+TEL:+1 415 555 2671\r\n\
+TEL:+44 20 7946 0958\r\n\
+END:VCARD\r\n";
+        let mut card = parse_str(vcard_str, PhoneOptions::default()).unwrap().cards.remove(0);
+        let emails = |card: &Vcard| card.email.iter().map(|p| p.value.clone()).collect::<Vec<_>>();
+        let pref = |card: &Vcard, index: usize| card.email[index].parameters.as_ref().and_then(|p| p.pref);
+
+        // Below the top the PREF mark stays where it is
+        assert!(reorder_entry(&mut card, "email", 2, 1));
+        assert_eq!(emails(&card), ["a@x.com", "c@x.com", "b@x.com"]);
+        assert_eq!(pref(&card, 0), Some(1));
+
+        // A new first entry takes PREF=1 along
+        assert!(reorder_entry(&mut card, "EMAIL", 0, 1));
+        assert_eq!(emails(&card), ["c@x.com", "a@x.com", "b@x.com"]);
+        assert_eq!(pref(&card, 0), Some(1));
+        assert_eq!(pref(&card, 1), None);
+
+        // Fields without PREF are not given one
+        assert!(reorder_entry(&mut card, "TEL", 1, 0));
+        assert!(card.to_string().contains("TEL:+44"));
+        assert!(!card.to_string().contains("TEL;PREF"));
+
+        assert!(!reorder_entry(&mut card, "EMAIL", 0, 3));
+        assert!(!reorder_entry(&mut card, "NOTE", 0, 0));
+    }
+
+    #[test]
+    fn test_promote_photo_entry_with_pref() {
+        let vcard_str = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Foo\r\n\
+PHOTO;PREF=1:https://example.com/old.jpg\r\n\
+PHOTO:https://example.com/new.jpg\r\n\
+END:VCARD\r\n";
+        let mut card = parse_str(vcard_str, PhoneOptions::default()).unwrap().cards.remove(0);
+        let photo_uri = |prop: &TextOrUriProperty| match prop {
+            TextOrUriProperty::Text(text) => (text.value.clone(), text.parameters.as_ref().and_then(|p| p.pref)),
+            TextOrUriProperty::Uri(uri) => (uri.value.to_string(), uri.parameters.as_ref().and_then(|p| p.pref)),
+        };
+
+        assert!(!promote_photo_entry(&mut card, 2));
+        assert!(promote_photo_entry(&mut card, 1));
+        prefer_first_photo(&mut card);
+        assert_eq!(photo_uri(&card.photo[0]), ("https://example.com/new.jpg".to_string(), Some(1)));
+        assert_eq!(photo_uri(&card.photo[1]), ("https://example.com/old.jpg".to_string(), None));
+    }
+
+    #[test]
+    fn test_set_adr_label() {
+        let vcard_str = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane\r\n\
+ADR:;;1 Main St;Springfield;;12345;USA\r\n\
+END:VCARD\r\n";
+        let mut card = parse_str(vcard_str, PhoneOptions::default()).unwrap().cards.remove(0);
+
+        assert!(set_adr_label(&mut card, 0, " Jane Doe \n\n1 Main St\nSpringfield 12345 "));
+        assert_eq!(
+            adr_label(&card, 0).as_deref(),
+            Some("Jane Doe\n1 Main St\nSpringfield 12345")
+        );
+        // Components stay intact, and the label survives a write/parse round trip
+        let reparsed = parse_str(&card.to_string(), PhoneOptions::default())
+            .unwrap()
+            .cards
+            .remove(0);
+        assert_eq!(address_components(&reparsed, 0).unwrap()[3], "Springfield");
+        assert_eq!(adr_label(&reparsed, 0), adr_label(&card, 0));
+
+        // Editing a component keeps the label; the label component replaces it
+        assert!(update_adr_value(&mut card, 0, Some(3), "Shelbyville"));
+        assert_eq!(
+            adr_label(&card, 0).as_deref(),
+            Some("Jane Doe\n1 Main St\nSpringfield 12345")
+        );
+        assert!(update_adr_value(&mut card, 0, Some(ADR_LABEL_COMPONENT), "Jane\nShelbyville"));
+        assert_eq!(adr_label(&card, 0).as_deref(), Some("Jane\nShelbyville"));
+
+        assert!(set_adr_label(&mut card, 0, "  "));
+        assert_eq!(adr_label(&card, 0), None);
+        assert!(!set_adr_label(&mut card, 1, "Nope"));
+    }
+
+    #[test]
+    fn test_future_rev() {
+        let vcard_str = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane\r\n\
+REV:20990101T000000Z\r\nEND:VCARD\r\n";
+        let mut card = parse_str(vcard_str, PhoneOptions::default()).unwrap().cards.remove(0);
+        let now = time::OffsetDateTime::now_utc();
+        assert_eq!(rev_sort_key(&card).as_deref(), Some("20990101T000000Z"));
+        assert!(future_rev(&card, now).is_some());
+
+        // A REV just ahead of now is clock skew, not an error
+        card.rev.as_mut().unwrap().value = (now + time::Duration::minutes(1)).into();
+        assert!(future_rev(&card, now).is_none());
+
+        touch_rev(&mut card);
+        assert!(future_rev(&card, time::OffsetDateTime::now_utc()).is_none());
+    }
+
+    #[test]
+    fn test_rev_sort_key_uses_utc() {
+        let vcard_str = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane\r\n\
+REV:20240101T030000+0200\r\nEND:VCARD\r\n";
+        let card = parse_str(vcard_str, PhoneOptions::default()).unwrap().cards.remove(0);
+        assert_eq!(rev_sort_key(&card).as_deref(), Some("20240101T010000Z"));
+        assert_eq!(rev_text_sort_key("20240101T030000+0200").as_deref(), Some("20240101T010000Z"));
+        assert_eq!(rev_text_sort_key("20240101T010000Z").as_deref(), Some("20240101T010000Z"));
+        assert_eq!(rev_text_sort_key("garbage"), None);
+    }
+
+    #[test]
+    fn test_card_to_dialect_string() {
+        let vcard_str = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane\r\n\
+TEL;TYPE=cell:+15551234567\r\n\
+TEL;TYPE=boat:+15557654321\r\n\
+EMAIL;TYPE=work:jane@example.com\r\n\
+URL;TYPE=x-portfolio:https://example.com/jane\r\n\
+END:VCARD\r\n";
+        let card = parse_str(vcard_str, PhoneOptions::default()).unwrap().cards.remove(0);
+
+        let plain = card_to_dialect_string(&card, ExportDialect::Rfc, Some("Friends"), false);
+        assert_eq!(plain, card.to_string());
+        let with_book = card_to_dialect_string(&card, ExportDialect::Rfc, Some("Friends"), true);
+        assert!(with_book.contains("CATEGORIES:Friends"));
+
+        let apple = card_to_dialect_string(&card, ExportDialect::Apple, Some("Friends"), false);
+        assert!(!apple.contains("CATEGORIES"));
+        assert!(apple.contains("item1.TEL:+15557654321"), "{apple}");
+        assert!(apple.contains("item1.X-ABLabel:boat"), "{apple}");
+        assert!(apple.contains("item2.URL:https://example.com/jane"), "{apple}");
+        assert!(apple.contains("item2.X-ABLabel:portfolio"), "{apple}");
+        // Standard types stay as TYPE parameters
+        assert!(apple.contains("TEL;TYPE=cell:+15551234567"), "{apple}");
+        assert!(apple.contains("EMAIL;TYPE=work:jane@example.com"), "{apple}");
+
+        let google = card_to_dialect_string(&card, ExportDialect::Google, Some("Friends"), false);
+        assert!(google.contains("CATEGORIES:Friends"));
+        assert!(google.contains("item1.X-ABLabel:boat"));
+
+        // The source card is untouched
+        assert!(card.extensions.is_empty());
+    }
+
+    #[test]
+    fn test_new_label_groups_skip_groups_in_use() {
+        let vcard_str = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane\r\n\
+ITEM1.TEL:+15551234567\r\n\
+item2.IMPP:xmpp:jane@example.com\r\n\
+EMAIL;TYPE=boat:jane@example.com\r\n\
+URL:https://example.com/jane\r\n\
+END:VCARD\r\n";
+        let card = parse_str(vcard_str, PhoneOptions::default()).unwrap().cards.remove(0);
+
+        let apple = card_to_dialect_string(&card, ExportDialect::Apple, None, false);
+        assert!(apple.contains("item3.EMAIL:jane@example.com"), "{apple}");
+        assert!(apple.contains("item3.X-ABLabel:boat"), "{apple}");
+
+        let mut card = card;
+        assert!(set_ab_label(&mut card, "URL", 0, "Portfolio"));
+        let text = card.to_string();
+        assert!(text.contains("item3.URL:https://example.com/jane"), "{text}");
+        assert!(text.contains("item3.X-ABLabel:Portfolio"), "{text}");
+    }
 }
@@ -1,5 +1,6 @@
 use std::collections::HashSet;
 use std::fs;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
@@ -8,8 +9,8 @@ use sha1::{Digest, Sha1};
 use uuid::Uuid;
 
 use crate::config::EncryptionType;
-use crate::crypto::CryptoProvider;
-use crate::vcard_io::{self, CardWithSource};
+use crate::crypto::{self, CryptoProvider};
+use crate::vcard_io::{self, CardWithSource, PhoneOptions};
 
 const NORMALIZED_MARKER: &str = ".rldx_normalized";
 
@@ -25,8 +26,9 @@ pub fn marker_path(vdir: &Path) -> PathBuf {
 
 pub fn normalize(
     vdir: &Path,
-    default_region: Option<&str>,
+    phone: PhoneOptions<'_>,
     provider: &dyn CryptoProvider,
+    ignore: &IgnoreList,
 ) -> Result<NormalizationReport> {
     let mut report = NormalizationReport::default();
 
@@ -42,7 +44,7 @@ pub fn normalize(
     let mut used_names = existing_stems(vdir)?;
     let mut files_to_remove: Vec<PathBuf> = Vec::new();
 
-    let mut entries = list_vcf_files(vdir)?;
+    let mut entries = list_vcf_files(vdir, ignore, provider.encryption_type())?;
     entries.sort();
 
     for path in entries {
@@ -55,7 +57,7 @@ pub fn normalize(
         let content = String::from_utf8(decrypted)
             .with_context(|| format!("vCard file {} contains invalid UTF-8", path.display()))?;
 
-        match vcard_io::parse_str_with_source(&content, default_region) {
+        match vcard_io::parse_str_with_source(&content, phone) {
             Ok(cards) => {
                 process_cards(
                     vdir,
@@ -68,10 +70,7 @@ pub fn normalize(
                 )?;
             }
             Err(err) => {
-                eprintln!(
-                    "warning: unable to parse vCard file {}: {err}",
-                    path.display()
-                );
+                log::warn!("unable to parse vCard file {}: {err}", path.display());
             }
         }
     }
@@ -79,18 +78,15 @@ pub fn normalize(
     for path in files_to_remove {
         if path.exists() {
             if let Err(err) = fs::remove_file(&path) {
-                eprintln!(
-                    "warning: failed to remove original file {}: {err}",
-                    path.display()
-                );
+                log::warn!("failed to remove original file {}: {err}", path.display());
             }
         }
     }
 
     if !marker.exists() {
         if let Err(err) = fs::write(&marker, b"") {
-            eprintln!(
-                "warning: failed to create normalization marker {}: {err}",
+            log::warn!(
+                "failed to create normalization marker {}: {err}",
                 marker.display()
             );
         } else {
@@ -139,7 +135,7 @@ fn process_cards(
         let encrypted = provider
             .encrypt(&bytes)
             .with_context(|| format!("failed to encrypt vCard for {}", target.display()))?;
-        write_atomic(&target, &encrypted)?;
+        write_atomic(&target, &encrypted, provider.edit_backup())?;
         wrote_any = true;
 
         if target != *original_path {
@@ -194,10 +190,11 @@ pub(crate) fn select_filename(
     }
 }
 
-/// Get all existing file stems in vdir (for both encryption types)
+/// Get all existing file stems in vdir (for every encryption type).
+/// Ignored files count too: a new file must not take their name.
 pub(crate) fn existing_stems(vdir: &Path) -> Result<HashSet<String>> {
     let mut stems = HashSet::new();
-    let mut files = list_vcf_files(vdir)?;
+    let mut files = list_vcf_files(vdir, &IgnoreList::default(), EncryptionType::None)?;
     files.sort();
     for path in files {
         if let Some(stem) = vcf_base_stem(&path) {
@@ -207,25 +204,94 @@ pub(crate) fn existing_stems(vdir: &Path) -> Result<HashSet<String>> {
     Ok(stems)
 }
 
-/// List all vCard files (both .vcf.gpg and .vcf.age)
-pub fn list_vcf_files(root: &Path) -> Result<Vec<PathBuf>> {
+/// Files and directories under the vdir that are never indexed, synced or
+/// rewritten, from the `vdir_ignore` glob patterns. A pattern containing `/`
+/// is matched against the path relative to the vdir; any other pattern
+/// against each file or directory name, so `templates` skips every
+/// directory of that name and `*.tmpl.vcf.age` matches in any book.
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreList {
+    vdir: PathBuf,
+    patterns: Vec<glob::Pattern>,
+    /// Individual files skipped for this run, see `skip_files`
+    files: HashSet<PathBuf>,
+}
+
+impl IgnoreList {
+    pub fn new(vdir: &Path, patterns: &[String]) -> Result<Self> {
+        let patterns = patterns
+            .iter()
+            .map(|pattern| {
+                glob::Pattern::new(pattern.trim().trim_end_matches('/'))
+                    .with_context(|| format!("invalid vdir_ignore pattern `{}`", pattern))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { vdir: vdir.to_path_buf(), patterns, files: HashSet::new() })
+    }
+
+    /// Also ignore these files, e.g. ones that cannot be read with the
+    /// configured encryption
+    pub fn skip_files(&mut self, files: impl IntoIterator<Item = PathBuf>) {
+        self.files.extend(files);
+    }
+
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        if self.files.contains(path) {
+            return true;
+        }
+        if self.patterns.is_empty() {
+            return false;
+        }
+        let relative = path.strip_prefix(&self.vdir).unwrap_or(path);
+        let relative = relative.to_string_lossy().replace(std::path::MAIN_SEPARATOR, "/");
+        let name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+        let options = glob::MatchOptions {
+            require_literal_separator: true,
+            ..glob::MatchOptions::new()
+        };
+        self.patterns.iter().any(|pattern| {
+            if pattern.as_str().contains('/') {
+                pattern.matches_with(&relative, options)
+            } else {
+                pattern.matches_with(&name, options)
+            }
+        })
+    }
+}
+
+/// List all vCard files `encryption` reads (.vcf.gpg and .vcf.age, and plain
+/// .vcf without encryption), skipping `ignore`d files and directories
+pub fn list_vcf_files(root: &Path, ignore: &IgnoreList, encryption: EncryptionType) -> Result<Vec<PathBuf>> {
     let mut files = Vec::new();
-    collect_all_vcf(root, &mut files)?;
+    let wanted = |name: &str| {
+        name.ends_with(".vcf.gpg")
+            || name.ends_with(".vcf.age")
+            || (encryption == EncryptionType::None && name.ends_with(".vcf"))
+    };
+    collect_files(root, ignore, &wanted, &mut files)?;
     Ok(files)
 }
 
-fn collect_all_vcf(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+/// Collect files under `dir` whose lowercased name passes `wanted`
+fn collect_files(
+    dir: &Path,
+    ignore: &IgnoreList,
+    wanted: &dyn Fn(&str) -> bool,
+    files: &mut Vec<PathBuf>,
+) -> Result<()> {
     for entry in
         fs::read_dir(dir).with_context(|| format!("failed to read directory {}", dir.display()))?
     {
         let entry = entry?;
         let path = entry.path();
+        if ignore.is_ignored(&path) {
+            continue;
+        }
         if path.is_dir() {
-            collect_all_vcf(&path, files)?;
+            collect_files(&path, ignore, wanted, files)?;
         } else {
             let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-            let name_lower = name.to_ascii_lowercase();
-            if name_lower.ends_with(".vcf.gpg") || name_lower.ends_with(".vcf.age") {
+            if wanted(&name.to_ascii_lowercase()) {
                 files.push(path);
             }
         }
@@ -233,6 +299,102 @@ fn collect_all_vcf(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
     Ok(())
 }
 
+/// A vCard file whose name or contents do not fit the configured encryption
+#[derive(Debug, Clone)]
+pub struct FormatMismatch {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+/// Find `.vcf`, `.vcf.gpg` and `.vcf.age` files that `encryption` cannot
+/// read: encrypted setups name every card `.vcf.age`, so a plain `.vcf` there
+/// is a stray from another setup, and a file whose header belongs to a
+/// different backend points at a misconfiguration. Only the first bytes of
+/// each file are read.
+pub fn find_format_mismatches(
+    root: &Path,
+    ignore: &IgnoreList,
+    encryption: EncryptionType,
+) -> Result<Vec<FormatMismatch>> {
+    let mut files = Vec::new();
+    let wanted = |name: &str| {
+        name.ends_with(".vcf") || name.ends_with(".vcf.gpg") || name.ends_with(".vcf.age")
+    };
+    collect_files(root, ignore, &wanted, &mut files)?;
+    files.sort();
+
+    let backend = |format: EncryptionType| match format {
+        EncryptionType::Gpg => "gpg-encrypted",
+        EncryptionType::Age => "age-encrypted",
+        EncryptionType::None => "cleartext",
+    };
+    let mut mismatches = Vec::new();
+    for path in files {
+        let name = path.file_name().map(|n| n.to_string_lossy().to_ascii_lowercase()).unwrap_or_default();
+        // .vcf.gpg cards from older setups are still read, so both encrypted
+        // names are judged by their header, as are plain .vcf cards without
+        // encryption
+        let reason = if name.ends_with(".vcf") && encryption != EncryptionType::None {
+            "is a plain .vcf file, which rldx never reads (cards are named .vcf.age)".to_string()
+        } else {
+            let mut head = Vec::with_capacity(64);
+            fs::File::open(&path)
+                .and_then(|file| file.take(64).read_to_end(&mut head))
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            match crypto::detect_format(&head) {
+                Some(format) if format == encryption => continue,
+                Some(format) => format!(
+                    "holds a {} card, but encryption is {:?}",
+                    backend(format),
+                    encryption
+                )
+                .to_lowercase(),
+                None => "is neither a vCard nor encrypted by rldx".to_string(),
+            }
+        };
+        mismatches.push(FormatMismatch { path, reason });
+    }
+    Ok(mismatches)
+}
+
+/// Remove address-book directories under `root` that hold no files at all,
+/// deepest first. The root itself is never removed, and a directory holding
+/// anything besides other empty directories (a `.vcf*` file or otherwise) is
+/// left alone. Returns the pruned directories.
+pub fn prune_empty_books(root: &Path) -> Result<Vec<PathBuf>> {
+    let mut pruned = Vec::new();
+    for entry in
+        fs::read_dir(root).with_context(|| format!("failed to read directory {}", root.display()))?
+    {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            prune_dir(&entry.path(), &mut pruned)?;
+        }
+    }
+    Ok(pruned)
+}
+
+/// Returns true when `dir` was empty after pruning its children and has been removed.
+fn prune_dir(dir: &Path, pruned: &mut Vec<PathBuf>) -> Result<bool> {
+    let mut empty = true;
+    for entry in
+        fs::read_dir(dir).with_context(|| format!("failed to read directory {}", dir.display()))?
+    {
+        let entry = entry?;
+        // file_type() does not follow symlinks, so linked directories count as content
+        if !(entry.file_type()?.is_dir() && prune_dir(&entry.path(), pruned)?) {
+            empty = false;
+        }
+    }
+    if empty {
+        log::debug!("removing empty directory {}", dir.display());
+        fs::remove_dir(dir)
+            .with_context(|| format!("failed to remove directory {}", dir.display()))?;
+        pruned.push(dir.to_path_buf());
+    }
+    Ok(empty)
+}
+
 /// Get the base stem of a vCard file (without any .vcf, .gpg, .age extensions)
 pub fn vcf_base_stem(path: &Path) -> Option<String> {
     let name = path.file_name()?.to_str()?;
@@ -273,7 +435,12 @@ pub fn compute_file_state(path: &Path) -> Result<FileState> {
     Ok(FileState { sha1, mtime })
 }
 
-pub fn write_atomic(target: &Path, data: &[u8]) -> Result<()> {
+/// Replace `target` with `data` through a temporary file and a rename. The
+/// old file is backed up first when `backup` is set; writers pass their
+/// provider's, so edits, imports and sync downloads all leave a copy behind.
+pub fn write_atomic(target: &Path, data: &[u8], backup: Option<&EditBackup>) -> Result<()> {
+    log::debug!("writing {} ({} bytes)", target.display(), data.len());
+    backup_before_change(target, backup)?;
     let parent = target
         .parent()
         .ok_or_else(|| anyhow!("target path has no parent: {}", target.display()))?;
@@ -350,6 +517,7 @@ pub fn read_vcf_file(path: &Path, provider: &dyn CryptoProvider) -> Result<Strin
         .with_context(|| format!("failed to read {}", path.display()))?;
 
     // Decrypt if encryption is enabled
+    log::trace!("decrypting {} ({} bytes)", path.display(), data.len());
     let plaintext = provider.decrypt(&data)
         .with_context(|| format!("failed to decrypt {}", path.display()))?;
 
@@ -362,12 +530,318 @@ pub fn write_vcf_file(path: &Path, data: &[u8], provider: &dyn CryptoProvider) -
     let encrypted = provider.encrypt(data)
         .with_context(|| format!("failed to encrypt data for {}", path.display()))?;
 
-    write_atomic(path, &encrypted)
+    write_atomic(path, &encrypted, provider.edit_backup())
+}
+
+/// Turn a category or group name into an address-book directory name.
+/// Path separators are replaced; names that would escape the vdir are rejected.
+pub fn book_dir_name(name: &str) -> Option<String> {
+    let cleaned: String = name
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    if cleaned.is_empty() || cleaned.starts_with('.') {
+        return None;
+    }
+    Some(cleaned)
+}
+
+/// Validate a possibly nested address-book path such as `work/clients/acme`.
+/// Every component must be a valid book directory name, so the path cannot be
+/// absolute or climb out of the vdir with `..`. Returns the path without
+/// trailing slashes.
+pub fn book_path(name: &str) -> Option<String> {
+    let trimmed = name.trim().trim_end_matches('/');
+    if trimmed.is_empty() {
+        return None;
+    }
+    let valid = trimmed
+        .split('/')
+        .all(|part| book_dir_name(part).is_some_and(|dir| dir == part));
+    valid.then(|| trimmed.to_string())
+}
+
+/// Address book (first directory below `root`) that `path` is filed in
+pub fn book_of(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let mut components = relative.components();
+    let first = components.next()?;
+    // A file directly in `root` is not in a book
+    components.next()?;
+    first.as_os_str().to_str().map(str::to_string)
+}
+
+/// Full address book path of `path` below `root`, nested books joined with `/`
+pub fn book_path_of(root: &Path, path: &Path) -> Option<String> {
+    let parent = path.parent()?.strip_prefix(root).ok()?;
+    let parts = parent
+        .components()
+        .map(|c| c.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()?;
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+/// Directory under the vdir that `edit_backup` copies contact files into
+pub const BACKUP_DIR: &str = ".backups";
+
+/// The `edit_backup` setting: copy every contact file under `root` before it
+/// is overwritten or deleted, keeping the newest `keep` copies of each
+#[derive(Debug, Clone)]
+pub struct EditBackup {
+    pub root: PathBuf,
+    pub keep: usize,
+}
+
+/// Back up `path` before it is overwritten or deleted, when `backup` is set
+/// and the file exists
+pub fn backup_before_change(path: &Path, backup: Option<&EditBackup>) -> Result<()> {
+    match backup {
+        Some(backup) if path.is_file() => backup_file(&backup.root, path, backup.keep).map(|_| ()),
+        _ => Ok(()),
+    }
+}
+
+/// Copy the (still encrypted) bytes of `path` to a timestamped file under
+/// `<root>/.backups`, mirroring its place in the vdir, and drop all but the
+/// newest `keep` copies of it
+pub fn backup_file(root: &Path, path: &Path, keep: usize) -> Result<PathBuf> {
+    let relative = path
+        .strip_prefix(root)
+        .ok()
+        .filter(|rel| !rel.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new(path.file_name().unwrap_or(path.as_os_str())));
+    let name = relative
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| anyhow!("cannot back up {}", path.display()))?;
+    let dir = root.join(BACKUP_DIR).join(relative.parent().unwrap_or(Path::new("")));
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("failed to create backup dir {}", dir.display()))?;
+
+    let format = time::macros::format_description!(
+        "[year][month][day]T[hour][minute][second][subsecond digits:6]Z"
+    );
+    let stamp = time::OffsetDateTime::now_utc()
+        .format(&format)
+        .context("failed to format backup timestamp")?;
+    let backup = dir.join(format!("{name}.{stamp}"));
+    fs::copy(path, &backup)
+        .with_context(|| format!("failed to back up {} to {}", path.display(), backup.display()))?;
+    log::debug!("backed up {} to {}", path.display(), backup.display());
+
+    let prefix = format!("{name}.");
+    let mut copies: Vec<PathBuf> = fs::read_dir(&dir)
+        .with_context(|| format!("failed to read directory {}", dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .and_then(|file| file.strip_prefix(&prefix))
+                .is_some_and(|rest| !rest.contains('.') && rest.ends_with('Z'))
+        })
+        .map(|entry| entry.path())
+        .collect();
+    copies.sort();
+    for old in &copies[..copies.len().saturating_sub(keep.max(1))] {
+        if let Err(err) = fs::remove_file(old) {
+            log::warn!("could not remove old backup {}: {}", old.display(), err);
+        }
+    }
+    Ok(backup)
+}
+
+/// Directory under the vdir for photos too large to embed in their card
+pub const PHOTO_DIR: &str = ".photos";
+
+/// Where the external photo called `name` is kept: `<root>/.photos/<name>.jpg.age`,
+/// or a plain `.jpg` without encryption
+pub fn photo_file_path(root: &Path, name: &str, encryption_type: EncryptionType) -> PathBuf {
+    match encryption_type {
+        EncryptionType::None => root.join(PHOTO_DIR).join(format!("{}.jpg", name)),
+        EncryptionType::Gpg | EncryptionType::Age => root.join(PHOTO_DIR).join(format!("{}.jpg.age", name)),
+    }
+}
+
+/// Delete the managed photos called `names` under `root`; one already gone is
+/// not an error
+pub fn remove_photo_files(root: &Path, names: &[String], encryption_type: EncryptionType) {
+    for name in names {
+        let path = photo_file_path(root, name, encryption_type);
+        match fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => log::warn!("could not remove photo {}: {}", path.display(), err),
+        }
+    }
+}
+
+/// Write an external photo, encrypted like the cards
+pub fn write_photo_file(path: &Path, jpeg: &[u8], provider: &dyn CryptoProvider) -> Result<()> {
+    let encrypted = provider
+        .encrypt(jpeg)
+        .with_context(|| format!("failed to encrypt photo {}", path.display()))?;
+    write_atomic(path, &encrypted, provider.edit_backup())
+}
+
+/// Read and decrypt an external photo
+pub fn read_photo_file(path: &Path, provider: &dyn CryptoProvider) -> Result<Vec<u8>> {
+    let data = fs::read(path).with_context(|| format!("failed to read photo {}", path.display()))?;
+    provider
+        .decrypt(&data)
+        .with_context(|| format!("failed to decrypt photo {}", path.display()))
 }
 
 /// Get the target path for a vCard file with the correct extension.
-/// Both GPG and Age encryption types now use Age format, so all files use .vcf.age extension.
-pub fn vcf_target_path(vdir: &Path, stem: &str, _encryption_type: EncryptionType) -> PathBuf {
-    // Both encryption types use Age format internally, so use .vcf.age for all
-    vdir.join(format!("{}.vcf.age", stem))
+/// Both GPG and Age encryption types use the .vcf.age extension; cleartext
+/// cards are plain .vcf files.
+pub fn vcf_target_path(vdir: &Path, stem: &str, encryption_type: EncryptionType) -> PathBuf {
+    match encryption_type {
+        EncryptionType::None => vdir.join(format!("{}.vcf", stem)),
+        EncryptionType::Gpg | EncryptionType::Age => vdir.join(format!("{}.vcf.age", stem)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_book_path_allows_nesting_inside_vdir() {
+        assert_eq!(book_path("work/clients/acme").as_deref(), Some("work/clients/acme"));
+        assert_eq!(book_path(" Work/ ").as_deref(), Some("Work"));
+        for bad in ["", "/", "/etc", "../outside", "work/../../etc", "work//acme", ".hidden", "work/.git", "C:\\x"] {
+            assert_eq!(book_path(bad), None, "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_book_path_of_keeps_nested_books() {
+        let root = Path::new("/vdir");
+        let card = Path::new("/vdir/work/clients/acme/a.vcf");
+        assert_eq!(book_path_of(root, card).as_deref(), Some("work/clients/acme"));
+        assert_eq!(book_of(root, card).as_deref(), Some("work"));
+        assert_eq!(book_path_of(root, Path::new("/vdir/a.vcf")), None);
+        assert_eq!(book_path_of(root, Path::new("/elsewhere/work/a.vcf")), None);
+    }
+
+    #[test]
+    fn test_backup_file_mirrors_book_and_prunes() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        let contact = root.join("Work/ann.vcf.age");
+        fs::create_dir_all(contact.parent().unwrap()).unwrap();
+        fs::write(&contact, b"v1").unwrap();
+        fs::create_dir_all(root.join(".backups/Work")).unwrap();
+        fs::write(root.join(".backups/Work/ann.vcf.age.notes"), b"unrelated").unwrap();
+
+        let first = backup_file(root, &contact, 1).unwrap();
+        assert!(first.starts_with(root.join(".backups/Work")));
+        assert_eq!(fs::read(&first).unwrap(), b"v1");
+
+        fs::write(&contact, b"v2").unwrap();
+        let second = backup_file(root, &contact, 1).unwrap();
+        assert!(!first.exists());
+        assert_eq!(fs::read(&second).unwrap(), b"v2");
+        assert!(root.join(".backups/Work/ann.vcf.age.notes").exists());
+    }
+
+    #[test]
+    fn test_prune_empty_books() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("Old/Nested/Deeper")).unwrap();
+        fs::create_dir_all(root.join("Work/Empty")).unwrap();
+        fs::write(root.join("Work/a.vcf.age"), b"x").unwrap();
+        fs::create_dir_all(root.join("Notes")).unwrap();
+        fs::write(root.join("Notes/readme.txt"), b"x").unwrap();
+
+        let mut pruned = prune_empty_books(root).unwrap();
+        pruned.sort();
+        assert_eq!(
+            pruned,
+            vec![
+                root.join("Old"),
+                root.join("Old/Nested"),
+                root.join("Old/Nested/Deeper"),
+                root.join("Work/Empty"),
+            ]
+        );
+        assert!(root.is_dir());
+        assert!(root.join("Work/a.vcf.age").exists());
+        assert!(root.join("Notes").is_dir());
+        assert!(prune_empty_books(root).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_list_vcf_files_honors_ignore_list() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("Work/templates")).unwrap();
+        fs::create_dir_all(root.join("Home")).unwrap();
+        for file in [
+            "a.vcf.age",
+            "blank.tmpl.vcf.age",
+            "Work/b.vcf.age",
+            "Work/templates/c.vcf.age",
+            "Home/d.vcf.age",
+        ] {
+            fs::write(root.join(file), b"x").unwrap();
+        }
+
+        let patterns = ["templates/".to_string(), "*.tmpl.vcf.age".to_string(), "Home/*".to_string()];
+        let ignore = IgnoreList::new(root, &patterns).unwrap();
+        let mut files = list_vcf_files(root, &ignore, EncryptionType::Age).unwrap();
+        files.sort();
+        assert_eq!(files, vec![root.join("Work/b.vcf.age"), root.join("a.vcf.age")]);
+
+        // Listing a single book still matches patterns against the vdir
+        assert!(list_vcf_files(&root.join("Home"), &ignore, EncryptionType::Age).unwrap().is_empty());
+        assert_eq!(list_vcf_files(root, &IgnoreList::default(), EncryptionType::Age).unwrap().len(), 5);
+        assert!(IgnoreList::new(root, &["[".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_format_mismatches_read_gpg_names_by_header() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        fs::write(root.join("old.vcf.gpg"), b"RLDX-GPG-CHACHA20\nAAAA\n").unwrap();
+        fs::write(root.join("new.vcf.age"), b"RLDX-GPG-CHACHA20\nAAAA\n").unwrap();
+        fs::write(root.join("pgp.vcf.gpg"), b"-----BEGIN PGP MESSAGE-----\n").unwrap();
+        fs::write(root.join("plain.vcf"), b"BEGIN:VCARD\r\n").unwrap();
+
+        let mismatches = find_format_mismatches(root, &IgnoreList::default(), EncryptionType::Gpg).unwrap();
+        let flagged: Vec<_> = mismatches.iter().map(|m| m.path.clone()).collect();
+        assert_eq!(flagged, vec![root.join("pgp.vcf.gpg"), root.join("plain.vcf")]);
+
+        let mismatches = find_format_mismatches(root, &IgnoreList::default(), EncryptionType::Age).unwrap();
+        assert_eq!(mismatches.len(), 4);
+        assert!(mismatches[1].reason.contains("gpg-encrypted"), "{}", mismatches[1].reason);
+    }
+
+    #[test]
+    fn test_plain_vcf_cards_without_encryption() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        assert_eq!(vcf_target_path(root, "a", EncryptionType::None), root.join("a.vcf"));
+        assert_eq!(vcf_target_path(root, "a", EncryptionType::Age), root.join("a.vcf.age"));
+        fs::write(root.join("plain.vcf"), b"BEGIN:VCARD\r\n").unwrap();
+        fs::write(root.join("sealed.vcf"), b"age-encryption.org/v1\n").unwrap();
+
+        let mut files = list_vcf_files(root, &IgnoreList::default(), EncryptionType::None).unwrap();
+        files.sort();
+        assert_eq!(files, vec![root.join("plain.vcf"), root.join("sealed.vcf")]);
+        assert!(list_vcf_files(root, &IgnoreList::default(), EncryptionType::Age).unwrap().is_empty());
+
+        // Without encryption a .vcf name is judged by its header like the others
+        let mismatches = find_format_mismatches(root, &IgnoreList::default(), EncryptionType::None).unwrap();
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].path, root.join("sealed.vcf"));
+        assert!(mismatches[0].reason.contains("age-encrypted"), "{}", mismatches[0].reason);
+    }
 }
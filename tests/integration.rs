@@ -14,7 +14,7 @@ use tempfile::TempDir;
 
 /// Test environment with initialized rldx config and vdir
 struct TestEnv {
-    temp_dir: TempDir,
+    _temp_dir: TempDir,
     config_path: PathBuf,
     vdir_path: PathBuf,
     gnupg_home: Option<PathBuf>,
@@ -47,7 +47,7 @@ impl TestEnv {
         fs::write(&config_path, updated_config).unwrap();
 
         Self {
-            temp_dir,
+            _temp_dir: temp_dir,
             config_path,
             vdir_path,
             gnupg_home: None,
@@ -86,7 +86,7 @@ impl TestEnv {
         fs::write(&config_path, updated_config).unwrap();
 
         Self {
-            temp_dir,
+            _temp_dir: temp_dir,
             config_path,
             vdir_path,
             gnupg_home: Some(gnupg_home),
@@ -107,7 +107,7 @@ impl TestEnv {
 
 /// Get the rldx binary command
 fn rldx_cmd() -> AssertCommand {
-    AssertCommand::cargo_bin("rldx").unwrap()
+    AssertCommand::new(assert_cmd::cargo::cargo_bin!("rldx"))
 }
 
 /// Check if gpg is available
@@ -248,6 +248,51 @@ fn test_init_age_creates_config_and_vdir() {
     assert!(fs::read_dir(&vdir_path).unwrap().count() == 0);
 }
 
+#[test]
+fn test_init_leaves_key_bindings_to_the_keymap() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let vdir_path = temp_dir.path().join("vdir");
+
+    rldx_cmd()
+        .args(["init", "--config", config_path.to_str().unwrap()])
+        .args(["--encryption", "age", vdir_path.to_str().unwrap()])
+        .assert()
+        .success();
+
+    // Every binding under [keys.*] is commented out, so a keymap preset applies
+    let config_content = fs::read_to_string(&config_path).unwrap();
+    let mut in_keys = false;
+    for line in config_content.lines() {
+        if line.starts_with('[') {
+            in_keys = line.starts_with("[keys.");
+        } else if in_keys {
+            assert!(
+                line.is_empty() || line.starts_with('#'),
+                "uncommented key binding in init config: {line}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_init_with_relative_config_path() {
+    let temp_dir = TempDir::new().unwrap();
+
+    rldx_cmd()
+        .current_dir(temp_dir.path())
+        .args(["init", "--config", "rldx/config.toml", "--encryption", "age", "vdir"])
+        .assert()
+        .success();
+
+    // The written paths still hold when the config is read from elsewhere
+    rldx_cmd()
+        .args(["--config", temp_dir.path().join("rldx/config.toml").to_str().unwrap()])
+        .args(["remote", "list"])
+        .assert()
+        .success();
+}
+
 #[test]
 fn test_init_gpg_creates_config() {
     if !gpg_available() {
@@ -284,6 +329,65 @@ fn test_init_gpg_creates_config() {
     assert!(config_content.contains(&key_id));
 }
 
+#[test]
+fn test_init_none_stores_cleartext() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let vdir_path = temp_dir.path().join("vdir");
+    let db_path = temp_dir.path().join("index.db");
+
+    rldx_cmd()
+        .args([
+            "init",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--encryption",
+            "none",
+            vdir_path.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Initialized rldx with no encryption"));
+
+    let config_content = fs::read_to_string(&config_path).unwrap();
+    assert!(config_content.contains(r#"type = "none""#));
+    assert!(!temp_dir.path().join("age-identity.txt").exists());
+    fs::write(&config_path, update_db_path_in_config(&config_content, &db_path)).unwrap();
+
+    rldx_cmd()
+        .args([
+            "--config",
+            config_path.to_str().unwrap(),
+            "import",
+            "--format",
+            "google",
+            test_contacts_vcf_path().to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+    rldx_cmd()
+        .args(["--config", config_path.to_str().unwrap(), "query", "zane"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("zane.miller@blueskycorp.com"));
+
+    // Imported cards are written as plain vCards, named .vcf
+    let files: Vec<_> = fs::read_dir(&vdir_path)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .filter(|path| path.to_string_lossy().contains(".vcf"))
+        .collect();
+    assert!(files.iter().all(|path| path.extension().is_some_and(|ext| ext == "vcf")));
+    assert!(!files.is_empty());
+    for path in files {
+        assert!(fs::read_to_string(&path).unwrap().starts_with("BEGIN:VCARD"));
+    }
+
+    // The index is ordinary SQLite
+    let header = fs::read(&db_path).unwrap();
+    assert!(header.starts_with(b"SQLite format 3"));
+}
+
 #[test]
 fn test_init_fails_if_config_exists_without_force() {
     let temp_dir = TempDir::new().unwrap();
@@ -484,6 +588,44 @@ fn test_import_google_contacts_with_gpg() {
     }
 }
 
+#[test]
+fn test_gpg_key_lists_are_refused() {
+    if !gpg_available() {
+        eprintln!("Skipping test: gpg not available");
+        return;
+    }
+
+    let env = TestEnv::new_with_gpg();
+    let config = fs::read_to_string(&env.config_path).unwrap();
+    let line = config
+        .lines()
+        .find(|l| l.starts_with("gpg_key_id ="))
+        .unwrap()
+        .to_string();
+    let key_id = line.split('"').nth(1).unwrap();
+
+    // A book keyed from one GPG key cannot be shared with a second one
+    let shared = config.replace(
+        &line,
+        &format!("gpg_key_ids = [\"{}\", \"second@example.com\"]", key_id),
+    );
+    fs::write(&env.config_path, shared).unwrap();
+    env.rldx()
+        .args(["query", "zane"])
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("gpg_key_ids is not supported"));
+
+    let other = env.vdir_path.parent().unwrap().join("other");
+    rldx_cmd()
+        .env("GNUPGHOME", env.gnupg_home.as_ref().unwrap())
+        .args(["init", "--config", other.join("config.toml").to_str().unwrap()])
+        .args(["--encryption", "gpg", "--key", key_id, "--key", key_id, other.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used multiple times"));
+}
+
 #[test]
 fn test_import_maildir() {
     let env = TestEnv::new_with_age();
@@ -534,6 +676,26 @@ fn test_import_to_address_book() {
     assert_eq!(entries.len(), 50);
 }
 
+#[test]
+fn test_import_to_nested_address_book() {
+    let env = TestEnv::new_with_age();
+    let input = test_contacts_vcf_path();
+
+    env.rldx()
+        .args(["import", "--format", "google", "--book", "work/clients/acme", input.to_str().unwrap()])
+        .assert()
+        .success();
+    let acme = env.vdir_path.join("work/clients/acme");
+    assert_eq!(fs::read_dir(&acme).unwrap().count(), 50);
+
+    env.rldx()
+        .args(["import", "--format", "google", "--book", "../outside", input.to_str().unwrap()])
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("invalid address book"));
+    assert!(!env.vdir_path.parent().unwrap().join("outside").exists());
+}
+
 #[test]
 fn test_query_after_import() {
     let env = TestEnv::new_with_age();
@@ -557,6 +719,260 @@ fn test_query_after_import() {
         .stdout(predicate::str::contains("zane.miller@blueskycorp.com"));
 }
 
+#[test]
+fn test_list_flat_format() {
+    let env = TestEnv::new_with_age();
+    env.rldx()
+        .args([
+            "import",
+            "--format",
+            "google",
+            "--book",
+            "Friends",
+            test_contacts_vcf_path().to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    let output = env.rldx().args(["list", "--format", "flat"]).output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 50);
+    let zane = lines.iter().find(|l| l.contains("zane.miller@blueskycorp.com")).unwrap();
+    let columns: Vec<&str> = zane.split('\t').collect();
+    assert_eq!(columns.len(), 5);
+    assert_eq!(columns[4], "Friends");
+}
+
+#[test]
+fn test_set_default_email() {
+    let env = TestEnv::new_with_age();
+    env.rldx()
+        .args([
+            "import",
+            "--format",
+            "google",
+            test_contacts_vcf_path().to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    let output = env.rldx().args(["list", "--format", "flat"]).output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let zane = stdout.lines().find(|l| l.contains("zane.miller@blueskycorp.com")).unwrap();
+    let uuid = zane.split('\t').next().unwrap();
+
+    env.rldx()
+        .args(["set-default", uuid, "--email", "Zane.Miller@BlueSkyCorp.com"])
+        .assert()
+        .success();
+    let output = env.rldx().args(["query", "--format", "vcf", "zane.miller"]).output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("EMAIL;PREF=1:zane.miller@blueskycorp.com"));
+
+    // Values the contact does not have, and unknown UIDs, are not found
+    env.rldx()
+        .args(["set-default", uuid, "--email", "nobody@example.com"])
+        .assert()
+        .code(2);
+    env.rldx()
+        .args(["set-default", "no-such-uid", "--phone", "+1 415 555 2671"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_new_contact_sets_kind() {
+    let env = TestEnv::new_with_age();
+    let output = env.rldx().args(["-q", "new", "Acme Corp", "--kind", "org", "--book", "Work"]).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let uuid = String::from_utf8(output.stdout).unwrap().trim().to_string();
+    env.rldx().args(["-q", "new", "Ada Lovelace"]).assert().success();
+
+    let output = env.rldx().args(["list", "--format", "flat"]).output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.lines().any(|line| line.starts_with(&uuid) && line.ends_with("\tWork")), "{stdout}");
+
+    let output = env.rldx().args(["query", "--format", "vcf", "acme"]).output().unwrap();
+    let card = String::from_utf8(output.stdout).unwrap();
+    assert!(card.contains("KIND:org") && card.contains("ORG:Acme Corp"), "{card}");
+    let output = env.rldx().args(["query", "--format", "vcf", "ada"]).output().unwrap();
+    assert!(String::from_utf8(output.stdout).unwrap().contains("KIND:individual"));
+
+    env.rldx().args(["new", "Bad", "--book", "../outside"]).assert().code(3);
+}
+
+#[test]
+fn test_edit_backup_keeps_newest_copies() {
+    let env = TestEnv::new_with_age();
+    let config = fs::read_to_string(&env.config_path).unwrap();
+    fs::write(&env.config_path, format!("edit_backup = true\nedit_backup_keep = 2\n{config}")).unwrap();
+    env.rldx()
+        .args(["import", "--format", "google", test_contacts_vcf_path().to_str().unwrap()])
+        .assert()
+        .success();
+    // Importing only creates files, there is nothing to back up yet
+    assert!(!env.vdir_path.join(".backups").exists());
+
+    let output = env.rldx().args(["list", "--format", "flat"]).output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let zane = stdout.lines().find(|l| l.contains("zane.miller@blueskycorp.com")).unwrap();
+    let uuid = zane.split('\t').next().unwrap();
+    // Each run rewrites the file; only the newest two copies are kept
+    for _ in 0..3 {
+        env.rldx()
+            .args(["set-default", uuid, "--email", "zane.miller@blueskycorp.com"])
+            .assert()
+            .success();
+    }
+
+    let backups: Vec<String> = fs::read_dir(env.vdir_path.join(".backups"))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(backups.len(), 2, "{backups:?}");
+    assert!(backups.iter().all(|name| name.contains(".vcf.age.")));
+    // Backups are not contacts
+    let output = env.rldx().args(["list", "--format", "flat"]).output().unwrap();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), stdout);
+}
+
+#[test]
+fn test_duplicate_uid_is_reported_and_fixed() {
+    let env = TestEnv::new_with_age();
+    env.rldx()
+        .args([
+            "import",
+            "--format",
+            "google",
+            test_contacts_vcf_path().to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    // A stray copy of a contact file carries the same UID
+    let mut dirs = vec![env.vdir_path.clone()];
+    let mut original = None;
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path.to_string_lossy().ends_with(".vcf.age") {
+                original = Some(path);
+            }
+        }
+    }
+    let original = original.unwrap();
+    fs::copy(&original, original.with_file_name("copy.vcf.age")).unwrap();
+
+    let count = |output: &std::process::Output| String::from_utf8_lossy(&output.stdout).lines().count();
+    let output = env.rldx().arg("--prune-empty-books").output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--fix-dupe-uids"));
+    let output = env.rldx().args(["list", "--format", "flat"]).output().unwrap();
+    assert_eq!(count(&output), 50);
+
+    let output = env.rldx().args(["--fix-dupe-uids", "--prune-empty-books"]).output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("gave it the new UID"));
+
+    let output = env.rldx().args(["list", "--format", "flat"]).output().unwrap();
+    assert_eq!(count(&output), 51);
+    let output = env.rldx().arg("--prune-empty-books").output().unwrap();
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("share UID"));
+}
+
+#[test]
+fn test_undecryptable_file_aborts_or_is_skipped() {
+    let env = TestEnv::new_with_age();
+    env.rldx()
+        .args(["import", "--format", "google", test_contacts_vcf_path().to_str().unwrap()])
+        .assert()
+        .success();
+    // A truncated card still looks like age to the format check but fails to decrypt
+    let mut dirs = vec![env.vdir_path.clone()];
+    let mut original = None;
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path.to_string_lossy().ends_with(".vcf.age") {
+                original = Some(path);
+            }
+        }
+    }
+    let data = fs::read(original.unwrap()).unwrap();
+    fs::write(env.vdir_path.join("0-corrupt.vcf.age"), &data[..data.len() / 2]).unwrap();
+
+    env.rldx().arg("--prune-empty-books").assert().failure();
+
+    let config = fs::read_to_string(&env.config_path).unwrap();
+    fs::write(&env.config_path, format!("on_decrypt_error = \"skip\"\n{config}")).unwrap();
+    let output = env.rldx().arg("--prune-empty-books").output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("skipped 1 file(s)") && stderr.contains("0-corrupt.vcf.age"), "{stderr}");
+    let output = env.rldx().args(["list", "--format", "flat"]).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout).lines().count(), 50);
+}
+
+#[test]
+fn test_db_override_leaves_configured_index_alone() {
+    let env = TestEnv::new_with_age();
+    let scratch = env.vdir_path.with_file_name("scratch").join("index.db");
+    env.rldx()
+        .args(["--db", scratch.to_str().unwrap()])
+        .args([
+            "import",
+            "--format",
+            "google",
+            test_contacts_vcf_path().to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+    assert!(scratch.exists());
+
+    let count = |args: &[&str]| {
+        let output = env.rldx().args(args).args(["list", "--format", "flat"]).output().unwrap();
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).lines().count()
+    };
+    assert_eq!(count(&["--db", scratch.to_str().unwrap()]), 50);
+    // The configured index has not been built yet
+    assert_eq!(count(&[]), 0);
+}
+
+#[test]
+fn test_query_vcf_format() {
+    let env = TestEnv::new_with_age();
+    env.rldx()
+        .args([
+            "import",
+            "--format",
+            "google",
+            test_contacts_vcf_path().to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    let output = env.rldx().args(["query", "--format", "vcf", "zane"]).output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("BEGIN:VCARD"));
+    assert!(stdout.contains("EMAIL"));
+    assert!(stdout.contains("zane.miller@blueskycorp.com"));
+    assert!(!stdout.contains("Found"));
+
+    env.rldx()
+        .args(["query", "--format", "vcf", "nobody-by-this-name"])
+        .assert()
+        .code(2)
+        .stdout(predicate::str::is_empty());
+}
+
 #[test]
 fn test_import_google_no_errors() {
     let env = TestEnv::new_with_age();
@@ -573,6 +989,56 @@ fn test_import_google_no_errors() {
         .stderr(predicate::str::is_empty()); // No errors
 }
 
+#[test]
+fn test_import_google_with_threads() {
+    let env = TestEnv::new_with_age();
+
+    env.rldx()
+        .args([
+            "import",
+            "--format",
+            "google",
+            "--threads",
+            "2",
+            test_contacts_vcf_path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 50 contacts"));
+}
+
+#[test]
+fn test_verbose_logs_file_writes() {
+    let env = TestEnv::new_with_age();
+
+    env.rldx()
+        .args([
+            "-vv",
+            "import",
+            "--format",
+            "google",
+            test_contacts_vcf_path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("[debug rldx::db] opening index"))
+        .stderr(predicate::str::contains("[debug rldx::vdir] writing"));
+
+    // RLDX_LOG sets the level when -v is not given
+    env.rldx()
+        .env("RLDX_LOG", "info")
+        .args([
+            "import",
+            "--format",
+            "google",
+            test_contacts_vcf_path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("[info rldx::import::google] 50 record(s)"))
+        .stderr(predicate::str::contains("[debug").not());
+}
+
 #[test]
 fn test_import_maildir_no_errors() {
     let env = TestEnv::new_with_age();
@@ -590,3 +1056,945 @@ fn test_import_maildir_no_errors() {
         .success()
         .stderr(predicate::str::contains("Error").not());
 }
+
+// =============================================================================
+// Photo Import Tests
+// =============================================================================
+
+#[test]
+fn test_photos_import_matches_primary_email() {
+    let env = TestEnv::new_with_age();
+
+    env.rldx()
+        .args([
+            "import",
+            "--format",
+            "google",
+            test_contacts_vcf_path().to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    let photos_dir = env.vdir_path.parent().unwrap().join("photos");
+    fs::create_dir_all(&photos_dir).unwrap();
+    let image = image::RgbaImage::from_pixel(300, 200, image::Rgba([10, 20, 30, 255]));
+    image
+        .save(photos_dir.join("zane.miller@blueskycorp.com.png"))
+        .unwrap();
+    image.save(photos_dir.join("nobody@example.com.png")).unwrap();
+    fs::write(photos_dir.join("notes.txt"), "not an image").unwrap();
+
+    env.rldx()
+        .args(["photos", "import", photos_dir.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Set 1 photo(s)."))
+        .stdout(predicate::str::contains("No matching contact for 1 file(s):"))
+        .stdout(predicate::str::contains("nobody@example.com.png"))
+        .stdout(predicate::str::contains("Failed").not());
+}
+
+#[test]
+fn test_external_photo_is_inlined_on_export() {
+    let env = TestEnv::new_with_age();
+    let config = fs::read_to_string(&env.config_path).unwrap();
+    fs::write(&env.config_path, format!("max_embedded_photo_bytes = 16\n{}", config)).unwrap();
+
+    env.rldx()
+        .args(["import", "--format", "google", test_contacts_vcf_path().to_str().unwrap()])
+        .assert()
+        .success();
+    let photos_dir = env.vdir_path.parent().unwrap().join("photos");
+    fs::create_dir_all(&photos_dir).unwrap();
+    image::RgbaImage::from_pixel(300, 200, image::Rgba([10, 20, 30, 255]))
+        .save(photos_dir.join("zane.miller@blueskycorp.com.png"))
+        .unwrap();
+    env.rldx()
+        .args(["photos", "import", photos_dir.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 photo(s) exceeded max_embedded_photo_bytes"));
+    let stored: Vec<_> = fs::read_dir(env.vdir_path.join(".photos")).unwrap().collect();
+    assert_eq!(stored.len(), 1);
+
+    let output = env.rldx().args(["export"]).output().unwrap();
+    assert!(output.status.success());
+    let exported = String::from_utf8(output.stdout).unwrap();
+    assert!(exported.contains("PHOTO:data:image/jpeg;base64,"));
+    assert!(!exported.contains("rldx-photo:"));
+}
+
+// =============================================================================
+// Remote Tests
+// =============================================================================
+
+#[test]
+fn test_remote_add_escapes_values() {
+    let env = TestEnv::new_with_age();
+    let before_add = fs::read_to_string(&env.config_path).unwrap();
+    assert!(before_add.contains("\n# "));
+
+    env.rldx()
+        .args([
+            "remote",
+            "add",
+            "work",
+            "--type",
+            "carddav",
+            "--url",
+            "https://dav.example.com/remote.php/dav",
+            "--user",
+            r#"jane "jd" \doe"#,
+            "--address-book",
+            "contacts",
+        ])
+        .assert()
+        .success();
+
+    env.rldx()
+        .args(["remote", "-v"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#"User: jane "jd" \doe"#));
+
+    // The rest of the file, comments included, is left as it was
+    let config = fs::read_to_string(&env.config_path).unwrap();
+    let (before, added) = config.split_once("[[remotes]]").unwrap();
+    assert_eq!(before.trim_end(), before_add.trim_end());
+    assert!(added.contains(r#"username = 'jane "jd" \doe'"#), "{added}");
+}
+
+#[test]
+fn test_remote_add_rejects_non_http_url() {
+    let env = TestEnv::new_with_age();
+    let before = fs::read_to_string(&env.config_path).unwrap();
+
+    env.rldx()
+        .args([
+            "remote",
+            "add",
+            "work",
+            "--type",
+            "carddav",
+            "--url",
+            "ftp://dav.example.com",
+            "--user",
+            "jane",
+            "--address-book",
+            "contacts",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("expected an http:// or https:// URL"));
+
+    assert_eq!(fs::read_to_string(&env.config_path).unwrap(), before);
+}
+
+/// Add a throwaway CardDAV remote named `work`
+fn add_work_remote(env: &TestEnv) {
+    env.rldx()
+        .args([
+            "remote",
+            "add",
+            "work",
+            "--type",
+            "carddav",
+            "--url",
+            "https://dav.example.com",
+            "--user",
+            "jane",
+            "--address-book",
+            "contacts",
+        ])
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_remote_remove_purge_requires_confirmation() {
+    let env = TestEnv::new_with_age();
+    add_work_remote(&env);
+
+    env.rldx()
+        .args(["remote", "remove", "work", "--purge"])
+        .write_stdin("n\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Aborted"));
+    env.rldx()
+        .args(["remote", "list"])
+        .assert()
+        .stdout(predicate::str::contains("work"));
+
+    env.rldx()
+        .args(["remote", "remove", "work", "--purge", "--yes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Purged 0 sync record(s) for 'work'"));
+}
+
+#[test]
+fn test_remote_status_before_first_sync() {
+    let env = TestEnv::new_with_age();
+    add_work_remote(&env);
+
+    env.rldx()
+        .args(["remote", "status", "work"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Last Sync: never"))
+        .stdout(predicate::str::contains("Tracked Cards: 0"));
+
+    env.rldx()
+        .args(["remote", "status", "missing"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_serve_answers_query_over_http() {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpStream;
+    use std::process::Stdio;
+
+    let env = TestEnv::new_with_age();
+    env.rldx()
+        .args([
+            "import",
+            "--format",
+            "google",
+            test_contacts_vcf_path().to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    let mut child = Command::new(assert_cmd::cargo::cargo_bin!("rldx"))
+        .args(["--config", env.config_path.to_str().unwrap()])
+        .args(["serve", "--addr", "127.0.0.1:0"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to start rldx serve");
+
+    let mut banner = String::new();
+    BufReader::new(child.stdout.take().unwrap())
+        .read_line(&mut banner)
+        .unwrap();
+    let addr = banner
+        .trim()
+        .strip_prefix("Listening on http://")
+        .expect("unexpected serve banner")
+        .to_string();
+
+    let request = |host: &str| {
+        let mut stream = TcpStream::connect(&addr).unwrap();
+        write!(stream, "GET /query?q=zane HTTP/1.1\r\nHost: {}\r\n\r\n", host).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    };
+
+    // A client that never sends its request does not hold up the others
+    let _idle = TcpStream::connect(&addr).unwrap();
+    let response = request(&addr);
+    // A page on a rebound domain is refused
+    let rebound = request(&addr.replace("127.0.0.1", "attacker.example"));
+    child.kill().ok();
+    child.wait().ok();
+
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.contains("\"email\":\"zane.miller@blueskycorp.com\""));
+    assert!(rebound.starts_with("HTTP/1.1 403 Forbidden"), "{rebound}");
+    assert!(!rebound.contains("zane"));
+}
+
+#[test]
+fn test_serve_refuses_non_loopback_address() {
+    let env = TestEnv::new_with_age();
+    env.rldx()
+        .args(["serve", "--addr", "0.0.0.0:8787"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--allow-remote"));
+}
+
+#[test]
+fn test_exit_codes_and_quiet_mode() {
+    let env = TestEnv::new_with_age();
+
+    env.rldx()
+        .args(["query", "nobody-by-this-name"])
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains("No matches"));
+    env.rldx()
+        .args(["sync", "missing"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("remote 'missing' not found"));
+    rldx_cmd()
+        .args(["--config", "/nonexistent/rldx/config.toml", "query", "x"])
+        .assert()
+        .code(3);
+
+    env.rldx()
+        .args([
+            "--quiet",
+            "remote",
+            "add",
+            "work",
+            "--type",
+            "carddav",
+            "--url",
+            "https://dav.example.com",
+            "--user",
+            "jane",
+            "--address-book",
+            "contacts",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+    env.rldx()
+        .args(["-q", "remote", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("work"));
+}
+
+#[test]
+fn test_sync_without_name_needs_a_terminal_to_pick() {
+    let env = TestEnv::new_with_age();
+    env.rldx()
+        .args(["sync"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("no remotes configured"));
+
+    for name in ["work", "home"] {
+        env.rldx()
+            .args([
+                "-q", "remote", "add", name, "--type", "carddav", "--url", "https://dav.example.com",
+                "--user", "jane", "--address-book", "contacts",
+            ])
+            .assert()
+            .success();
+    }
+    // stdin is not a terminal here, so there is nothing to pick from
+    env.rldx()
+        .args(["remote", "test"])
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("name one or pass --all"));
+    env.rldx()
+        .args(["sync", "work", "--all"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_remote_test_all_tries_every_remote() {
+    let env = TestEnv::new_with_age();
+    for name in ["work", "home"] {
+        env.rldx()
+            .args([
+                "-q", "remote", "add", name, "--type", "carddav", "--url", "http://127.0.0.1:1",
+                "--user", "jane", "--address-book", "contacts", "--password-cmd", "echo secret",
+            ])
+            .assert()
+            .success();
+    }
+    // Both unreachable remotes are tried and named before the run fails
+    env.rldx()
+        .args(["remote", "test", "--all"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("'work' failed"))
+        .stderr(predicate::str::contains("'home' failed"))
+        .stderr(predicate::str::contains("failed for 2 of 2 remotes"));
+}
+
+#[test]
+fn test_export_dialects() {
+    let env = TestEnv::new_with_age();
+    let input = env.vdir_path.parent().unwrap().join("export.vcf");
+    fs::write(
+        &input,
+        "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Ada Lovelace\r\nEMAIL:ada@example.com\r\nEND:VCARD\r\n\
+         BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Alan Turing\r\nEMAIL:alan@example.com\r\nEND:VCARD\r\n",
+    )
+    .unwrap();
+    env.rldx()
+        .args(["import", "--format", "google", "--book", "Friends", input.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let output = env.rldx().args(["export"]).output().unwrap();
+    assert!(output.status.success());
+    let plain = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(plain.matches("BEGIN:VCARD").count(), 2);
+    assert!(plain.contains("FN:Ada Lovelace"));
+    assert!(!plain.contains("CATEGORIES"));
+
+    let target = env.vdir_path.parent().unwrap().join("google.vcf");
+    env.rldx()
+        .args(["export", "--dialect", "google", "-o", target.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Exported 2 contacts"));
+    let google = fs::read_to_string(&target).unwrap();
+    assert_eq!(google.matches("CATEGORIES:Friends").count(), 2);
+
+    env.rldx()
+        .args(["export", "--book", "Missing"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("address book not found"));
+}
+
+#[test]
+fn test_export_csv_quotes_fields() {
+    let env = TestEnv::new_with_age();
+    let input = env.vdir_path.parent().unwrap().join("sheet.vcf");
+    fs::write(
+        &input,
+        "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Lovelace\\, Ada\r\nN:Lovelace;Ada;;;\r\n\
+         EMAIL:ada@example.com\r\nEMAIL:ada@work.example\r\nTEL:+44 20 7946 0000\r\n\
+         ORG:Analytical Engines;R&D\r\nTITLE:Chief \"Enchantress\" of Numbers\r\n\
+         CATEGORIES:math,poetry\r\nEND:VCARD\r\n",
+    )
+    .unwrap();
+    env.rldx()
+        .args(["import", "--format", "google", "--book", "Friends", input.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let output = env.rldx().args(["export", "--format", "csv"]).output().unwrap();
+    assert!(output.status.success());
+    let csv = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = csv.split_terminator("\r\n").collect();
+    assert_eq!(lines[0], "FN,Given,Family,Emails,Phones,Org,Title,Categories,Book");
+    assert_eq!(lines.len(), 2);
+    assert!(lines[1].starts_with("\"Lovelace, Ada\",Ada,Lovelace,ada@example.com; ada@work.example,"));
+    assert!(lines[1].contains(",Analytical Engines,\"Chief \"\"Enchantress\"\" of Numbers\",math; poetry,Friends"));
+
+    env.rldx()
+        .args(["export", "--format", "csv", "--book", "Missing"])
+        .assert()
+        .code(2);
+
+    // Nested books keep their whole path
+    let nested = env.vdir_path.join("Friends/Close");
+    fs::create_dir_all(&nested).unwrap();
+    for entry in fs::read_dir(env.vdir_path.join("Friends")).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_file() {
+            fs::rename(&path, nested.join(path.file_name().unwrap())).unwrap();
+        }
+    }
+    env.rldx().args(["--reindex", "--prune-empty-books"]).assert().success();
+    let output = env.rldx().args(["export", "--format", "csv"]).output().unwrap();
+    let csv = String::from_utf8(output.stdout).unwrap();
+    assert!(csv.contains(",math; poetry,Friends/Close\r\n"), "{csv}");
+
+    for flag in [&["--dialect", "google"][..], &["--categories-from-books"][..]] {
+        env.rldx()
+            .args(["export", "--format", "csv"])
+            .args(flag)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("only supported with --format vcard"));
+    }
+}
+
+#[test]
+fn test_import_google_skips_unchanged_records() {
+    let env = TestEnv::new_with_age();
+    let input = env.vdir_path.parent().unwrap().join("nightly.vcf");
+    let ada = "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Ada Lovelace\r\nEMAIL:ada@example.com\r\nEND:VCARD\r\n";
+    let alan = "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Alan Turing\r\nEMAIL:alan@example.com\r\nEND:VCARD\r\n";
+    fs::write(&input, ada).unwrap();
+
+    let import = |extra: &[&str]| {
+        let mut cmd = env.rldx();
+        cmd.args(["import", "--format", "google"])
+            .args(extra)
+            .arg(input.to_str().unwrap());
+        cmd
+    };
+
+    import(&[])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 1 contacts"));
+
+    // Only the new record is processed on the next run
+    fs::write(&input, format!("{ada}{alan}")).unwrap();
+    import(&[])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 1 contacts"))
+        .stdout(predicate::str::contains("1 contacts unchanged"))
+        .stdout(predicate::str::contains("Skipped").not());
+
+    import(&[])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 0 contacts"))
+        .stdout(predicate::str::contains("2 contacts unchanged"));
+
+    // --full forgets the manifest; existing emails are skipped as before
+    import(&["--full"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Skipped 2 contacts"))
+        .stdout(predicate::str::contains("unchanged").not());
+}
+
+#[test]
+fn test_import_into_default_book() {
+    let env = TestEnv::new_with_age();
+    let config = fs::read_to_string(&env.config_path).unwrap();
+    fs::write(&env.config_path, format!("default_book = \"Inbox\"\n{config}")).unwrap();
+
+    env.rldx()
+        .args([
+            "import",
+            "--format",
+            "google",
+            test_contacts_vcf_path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 50 contacts"));
+
+    let count_vcf = |dir: &Path| {
+        fs::read_dir(dir)
+            .unwrap()
+            .filter_map(Result::ok)
+            .filter(|e| e.file_name().to_string_lossy().ends_with(".vcf.age"))
+            .count()
+    };
+    assert_eq!(count_vcf(&env.vdir_path.join("Inbox")), 50);
+    assert_eq!(count_vcf(&env.vdir_path), 0);
+}
+
+#[test]
+fn test_prune_empty_books() {
+    let env = TestEnv::new_with_age();
+    env.rldx()
+        .args([
+            "import",
+            "--format",
+            "google",
+            "--book",
+            "Friends",
+            test_contacts_vcf_path().to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+    fs::create_dir_all(env.vdir_path.join("Moved/Old")).unwrap();
+
+    env.rldx()
+        .arg("--prune-empty-books")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Pruned 2 empty address book(s)"));
+
+    assert!(!env.vdir_path.join("Moved").exists());
+    assert!(env.vdir_path.join("Friends").is_dir());
+    assert!(env.vdir_path.is_dir());
+}
+
+#[test]
+fn test_import_categories_as_books() {
+    let env = TestEnv::new_with_age();
+    let input = env.vdir_path.parent().unwrap().join("grouped.vcf");
+    fs::write(
+        &input,
+        "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Ada Lovelace\r\nEMAIL:ada@example.com\r\n\
+         CATEGORIES:myContacts,Friends,Work\r\nEND:VCARD\r\n\
+         BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Alan Turing\r\nEMAIL:alan@example.com\r\n\
+         CATEGORIES:myContacts\r\nEND:VCARD\r\n",
+    )
+    .unwrap();
+
+    env.rldx()
+        .args([
+            "import",
+            "--format",
+            "google",
+            "--categories-as-books",
+            input.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    let friends = env.vdir_path.join("Friends");
+    assert_eq!(fs::read_dir(&friends).unwrap().count(), 1);
+    let root_cards = fs::read_dir(&env.vdir_path)
+        .unwrap()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_file() && e.file_name().to_string_lossy().ends_with(".vcf.age"))
+        .count();
+    assert_eq!(root_cards, 1);
+}
+
+#[test]
+fn test_import_google_merges_duplicate_email() {
+    let env = TestEnv::new_with_age();
+    let input = env.vdir_path.parent().unwrap().join("update.vcf");
+    fs::write(
+        &input,
+        "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Ada Lovelace\r\nEMAIL:ada@example.com\r\nEND:VCARD\r\n",
+    )
+    .unwrap();
+    env.rldx()
+        .args(["import", "--format", "google", input.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 1 contacts"));
+
+    fs::write(
+        &input,
+        "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Ada King\r\nEMAIL:ADA@example.com\r\n\
+         EMAIL:countess@example.com\r\nTEL:+44 20 7946 0958\r\nORG:Analytical Engines\r\nEND:VCARD\r\n",
+    )
+    .unwrap();
+    env.rldx()
+        .args(["import", "--format", "google", "--on-duplicate", "merge", input.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 0 contacts"))
+        .stdout(predicate::str::contains("Ada King <ADA@example.com> -> Ada Lovelace"));
+
+    let output = env.rldx().args(["export"]).output().unwrap();
+    assert!(output.status.success());
+    let exported = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(exported.matches("BEGIN:VCARD").count(), 1);
+    assert!(exported.contains("FN:Ada Lovelace"));
+    assert!(exported.contains("countess@example.com"));
+    assert!(exported.contains("+442079460958"));
+    assert!(exported.contains("ORG:Analytical Engines"));
+
+    env.rldx()
+        .args(["import", "--format", "maildir", "--on-duplicate", "merge", input.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("only supported with --format google"));
+}
+
+#[test]
+fn test_import_google_adds_category() {
+    let env = TestEnv::new_with_age();
+    let input = env.vdir_path.parent().unwrap().join("leads.vcf");
+    fs::write(
+        &input,
+        "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Ada Lovelace\r\nEMAIL:ada@example.com\r\nEND:VCARD\r\n\
+         BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Alan Turing\r\nCATEGORIES:math\r\nEMAIL:alan@example.com\r\nEND:VCARD\r\n",
+    )
+    .unwrap();
+    env.rldx()
+        .args(["import", "--format", "google", "--category", "leads-2024", input.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 2 contacts"));
+
+    let output = env.rldx().args(["export"]).output().unwrap();
+    assert!(output.status.success());
+    let exported = String::from_utf8(output.stdout).unwrap();
+    assert!(exported.contains("CATEGORIES:leads-2024\r\n"));
+    assert!(exported.contains("CATEGORIES:math,leads-2024\r\n"));
+
+    env.rldx()
+        .args(["import", "--format", "google", "--category", " ", input.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--category must not be empty"));
+}
+
+#[test]
+fn test_status_reports_checks() {
+    let env = TestEnv::new_with_age();
+    env.rldx()
+        .args(["import", "--format", "google", test_contacts_vcf_path().to_str().unwrap()])
+        .assert()
+        .success();
+
+    env.rldx()
+        .arg("status")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(ok, 50 cards)"))
+        .stdout(predicate::str::contains("encryption  age (ok)"))
+        .stdout(predicate::str::contains("(ok, 50 contacts indexed)"))
+        .stdout(predicate::str::contains("remotes     (none)"));
+
+    let missing = env.vdir_path.parent().unwrap().join("missing.db");
+    env.rldx()
+        .args(["--db", missing.to_str().unwrap(), "status"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("database    "))
+        .stdout(predicate::str::contains("FAILED: not created yet"))
+        .stderr(predicate::str::contains("1 check(s) failed"));
+    assert!(!missing.exists());
+
+    // A missing vdir is reported by status; other commands refuse to run
+    fs::remove_dir_all(&env.vdir_path).unwrap();
+    env.rldx()
+        .arg("status")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("FAILED: not a directory"));
+    env.rldx()
+        .args(["list"])
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("configured vdir does not exist"));
+}
+
+#[test]
+fn test_upgrade_check_lists_and_converts_v3_cards() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let vdir_path = temp_dir.path().join("vdir");
+    rldx_cmd()
+        .args(["init", "--config", config_path.to_str().unwrap(), "--encryption", "none", vdir_path.to_str().unwrap()])
+        .assert()
+        .success();
+    let config_content = fs::read_to_string(&config_path).unwrap();
+    fs::write(&config_path, update_db_path_in_config(&config_content, &temp_dir.path().join("index.db"))).unwrap();
+    let rldx = || {
+        let mut cmd = rldx_cmd();
+        cmd.args(["--config", config_path.to_str().unwrap()]);
+        cmd
+    };
+
+    fs::write(
+        vdir_path.join("ada.vcf.age"),
+        "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Ada Lovelace\r\nTEL;HOME;VOICE:+44 20 7946 0958\r\n\
+         EMAIL;TYPE=INTERNET,PREF:ada@example.com\r\nEND:VCARD\r\n",
+    )
+    .unwrap();
+    fs::write(
+        vdir_path.join("alan.vcf.age"),
+        "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Alan Turing\r\nLABEL;TYPE=HOME:Bletchley Park\r\nEND:VCARD\r\n",
+    )
+    .unwrap();
+
+    rldx()
+        .arg("upgrade-check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("ada.vcf.age\tAda Lovelace"))
+        .stdout(predicate::str::contains("alan.vcf.age\tAlan Turing"))
+        .stderr(predicate::str::contains("2 file(s) hold cards older than vCard 4.0"));
+
+    rldx()
+        .args(["upgrade-check", "--upgrade"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("upgraded\t"))
+        .stdout(predicate::str::contains("manual\t"))
+        .stdout(predicate::str::contains("vCard 4.0 dropped: LABEL"))
+        .stderr(predicate::str::contains("1 file(s) need a manual upgrade"));
+
+    let ada = fs::read_to_string(vdir_path.join("ada.vcf.age")).unwrap();
+    assert!(ada.contains("VERSION:4.0"));
+    assert!(ada.contains("TEL;TYPE="));
+    assert!(ada.contains("PREF=1"));
+    assert!(ada.contains("UID:"));
+    assert!(fs::read_to_string(vdir_path.join("alan.vcf.age")).unwrap().contains("VERSION:3.0"));
+
+    // Once the dropped property is removed by hand the rest converts too
+    fs::write(
+        vdir_path.join("alan.vcf.age"),
+        "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Alan Turing\r\nEND:VCARD\r\n",
+    )
+    .unwrap();
+    rldx()
+        .args(["upgrade-check", "--upgrade"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("upgraded\t").count(1));
+    rldx()
+        .arg("upgrade-check")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("All cards are vCard 4.0"));
+
+    rldx()
+        .args(["query", "ada"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ada@example.com"));
+}
+
+#[test]
+fn test_card_without_fn_uses_n() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let vdir_path = temp_dir.path().join("vdir");
+    rldx_cmd()
+        .args(["init", "--config", config_path.to_str().unwrap(), "--encryption", "none", vdir_path.to_str().unwrap()])
+        .assert()
+        .success();
+    let config_content = fs::read_to_string(&config_path).unwrap();
+    let config_content = update_db_path_in_config(&config_content, &temp_dir.path().join("index.db"));
+    fs::write(&config_path, &config_content).unwrap();
+    let rldx = || {
+        let mut cmd = rldx_cmd();
+        cmd.args(["--config", config_path.to_str().unwrap()]);
+        cmd
+    };
+
+    fs::write(
+        vdir_path.join("ada.vcf.age"),
+        "BEGIN:VCARD\r\nVERSION:4.0\r\nUID:urn:uuid:5d7c1e2a-3b4f-4c6d-8e9f-0a1b2c3d4e5f\r\n\
+         N:Lovelace;Ada;;;\r\nEMAIL:ada@example.com\r\nEND:VCARD\r\n",
+    )
+    .unwrap();
+
+    rldx().args(["--reindex", "--prune-empty-books"]).assert().success();
+    rldx()
+        .args(["query", "lovelace"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ada@example.com\tAda Lovelace"));
+    // Reindexing renames the file after its UID, as a plain .vcf
+    let card_path = vdir_path.join("5d7c1e2a3b4f.vcf");
+    // Without the flag the card itself keeps an empty FN
+    assert!(!fs::read_to_string(&card_path).unwrap().contains("FN:Ada"));
+
+    fs::write(&config_path, format!("fill_missing_fn = true\n{}", config_content)).unwrap();
+    rldx().args(["--reindex", "--prune-empty-books"]).assert().success();
+    assert!(fs::read_to_string(&card_path).unwrap().contains("FN:Ada Lovelace"));
+}
+
+#[test]
+fn test_import_tags_field_sources() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let vdir_path = temp_dir.path().join("vdir");
+    rldx_cmd()
+        .args(["init", "--config", config_path.to_str().unwrap(), "--encryption", "none", vdir_path.to_str().unwrap()])
+        .assert()
+        .success();
+    let config_content = fs::read_to_string(&config_path).unwrap();
+    let config_content = update_db_path_in_config(&config_content, &temp_dir.path().join("index.db"));
+    fs::write(&config_path, format!("track_field_sources = true\n{}", config_content)).unwrap();
+
+    rldx_cmd()
+        .args([
+            "--config",
+            config_path.to_str().unwrap(),
+            "import",
+            "--format",
+            "google",
+            test_contacts_vcf_path().to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    let zane = fs::read_dir(&vdir_path)
+        .unwrap()
+        .map(|e| fs::read_to_string(e.unwrap().path()).unwrap_or_default())
+        .find(|card| card.contains("zane.miller@blueskycorp.com"))
+        .unwrap();
+    assert!(zane.contains("EMAIL;X-RLDX-SOURCE=\"google\":zane.miller@blueskycorp.com"));
+    assert!(zane.contains("ORG;X-RLDX-SOURCE=\"google\":BlueSky Corp"));
+}
+
+#[test]
+fn test_wrong_age_identity_fails_before_reindex() {
+    let env = TestEnv::new_with_age();
+    env.rldx()
+        .args(["import", "--format", "google", test_contacts_vcf_path().to_str().unwrap()])
+        .assert()
+        .success();
+
+    // Swap in the identity of an unrelated setup
+    let other = TestEnv::new_with_age();
+    let identity = |env: &TestEnv| env.config_path.with_file_name("age-identity.txt");
+    fs::copy(identity(&other), identity(&env)).unwrap();
+
+    env.rldx()
+        .args(["--reindex", "--prune-empty-books"])
+        .assert()
+        .code(5)
+        .stderr(predicate::str::contains("with the configured age identity"))
+        .stderr(predicate::str::contains("recipient mismatch?"));
+}
+
+#[test]
+fn test_damaged_card_is_not_blamed_on_the_key() {
+    let env = TestEnv::new_with_age();
+    env.rldx()
+        .args(["import", "--format", "google", test_contacts_vcf_path().to_str().unwrap()])
+        .assert()
+        .success();
+
+    // Damage the card listed first; the others still decrypt with the key
+    let damaged = fs::read_dir(&env.vdir_path)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .find(|path| path.to_string_lossy().ends_with(".vcf.age"))
+        .unwrap();
+    let data = fs::read_to_string(&damaged).unwrap();
+    fs::write(&damaged, &data[..data.len() / 2]).unwrap();
+
+    let output = env.rldx().args(["--reindex", "--prune-empty-books"]).output().unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains(&*damaged.file_name().unwrap().to_string_lossy()), "{stderr}");
+    assert!(!stderr.contains("recipient mismatch?"), "{stderr}");
+}
+
+#[test]
+fn test_completions_need_no_config() {
+    rldx_cmd()
+        .args(["--config", "/nonexistent/rldx/config.toml", "completions", "bash"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("upgrade-check"));
+    rldx_cmd()
+        .args(["completions", "fish"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("complete -c rldx"));
+}
+
+#[test]
+fn test_vdir_format_mismatches_warn_or_fail_with_strict() {
+    let env = TestEnv::new_with_age();
+    env.rldx()
+        .args(["import", "--format", "google", test_contacts_vcf_path().to_str().unwrap()])
+        .assert()
+        .success();
+    fs::write(env.vdir_path.join("stray.vcf.gpg"), b"-----BEGIN PGP MESSAGE-----\n").unwrap();
+    fs::write(
+        env.vdir_path.join("plain.vcf.age"),
+        "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Plain\r\nEND:VCARD\r\n",
+    )
+    .unwrap();
+
+    env.rldx()
+        .args(["--reindex", "--prune-empty-books"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("stray.vcf.gpg is neither a vCard nor encrypted by rldx"))
+        .stderr(predicate::str::contains("plain.vcf.age holds a cleartext card, but encryption is age"))
+        .stderr(predicate::str::contains("skipping 2 unreadable file(s)"));
+    env.rldx()
+        .args(["query", "zane"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("zane.miller@blueskycorp.com"));
+
+    env.rldx()
+        .args(["--strict", "--reindex", "--prune-empty-books"])
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("2 file(s) in the vdir do not match"));
+}